@@ -0,0 +1,226 @@
+//! Validation against the reference answers shipped with DuckDB's `tpch` extension.
+//!
+//! `tpch_answers()` stores one pipe-separated answer per query number and scale factor. The first
+//! line holds the column names, every following line one result row in ORDER BY order.
+
+use std::fmt::Debug;
+use std::str::FromStr;
+
+use duckdb::{params, Connection};
+
+use super::scale_factor::ScaleFactor;
+use super::verify::{compare, Report, Tolerance};
+use super::{
+    query_1, query_10, query_12, query_13, query_14, query_16, query_17, query_18, query_19, query_2, query_20,
+    query_21, query_22, query_3, query_4, query_5, query_6,
+};
+
+pub trait FromAnswer: Sized {
+    fn from_answer(fields: &[&str]) -> Self;
+}
+
+/// Parse one column of an answer row. Panics if the column is missing or malformed.
+pub fn parse_field<T: FromStr>(fields: &[&str], index: usize) -> T
+where
+    T::Err: Debug,
+{
+    let field = fields
+        .get(index)
+        .unwrap_or_else(|| panic!("Answer row {:?} has no column {}", fields, index));
+    field
+        .parse()
+        .unwrap_or_else(|e| panic!("Error parsing answer column {} ({:?}): {:?}", index, field, e))
+}
+
+pub fn parse_answer<T: FromAnswer>(answer: &str) -> Vec<T> {
+    answer
+        .lines()
+        // Skip the header with the column names
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let fields: Vec<&str> = line.split('|').collect();
+            T::from_answer(&fields)
+        })
+        .collect()
+}
+
+/// Fetch the raw reference answer. Panics if DuckDB does not ship an answer for this combination.
+pub fn expected_answer(conn: &Connection, query_nr: u32, scale_factor: impl Into<ScaleFactor>) -> String {
+    let scale_factor = scale_factor.into();
+    conn.query_row(
+        "SELECT answer FROM tpch_answers() WHERE query_nr = ? AND scale_factor = ?;",
+        params![query_nr, scale_factor.value()],
+        |row| row.get(0),
+    )
+    .unwrap_or_else(|e| {
+        panic!(
+            "No reference answer for query {} at scale factor {}: {}",
+            query_nr, scale_factor, e
+        )
+    })
+}
+
+pub fn expected<T: FromAnswer>(conn: &Connection, query_nr: u32, scale_factor: impl Into<ScaleFactor>) -> Vec<T> {
+    parse_answer(&expected_answer(conn, query_nr, scale_factor))
+}
+
+/// The reference answers round money and averages to two decimals.
+pub fn answer_tolerance() -> Tolerance {
+    Tolerance {
+        absolute: 0.01,
+        relative: 1e-9,
+    }
+}
+
+pub fn validate_query_1(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_1::Q1Row>> {
+    let expected = expected(conn, 1, scale_factor);
+    let baseline = query_1::query(query_1::load(conn).expect("Error loading Q1"), &query_1::QueryParams::default());
+
+    vec![compare("Q1", "tpch_answers", "baseline", &expected, &baseline, &answer_tolerance())]
+}
+
+pub fn validate_query_2(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_2::Q2Row>> {
+    let expected = expected(conn, 2, scale_factor);
+    let (part, supplier, partsupp, nation, region) = query_2::load(conn);
+    let baseline = query_2::query(part, supplier, partsupp, nation, region);
+
+    vec![compare("Q2", "tpch_answers", "baseline", &expected, &baseline, &answer_tolerance())]
+}
+
+pub fn validate_query_3(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_3::Q3Row>> {
+    let expected = expected(conn, 3, scale_factor);
+    let (customer, orders, line_items) = query_3::load(conn);
+    let baseline = query_3::query(customer, orders, line_items);
+
+    vec![compare("Q3", "tpch_answers", "baseline", &expected, &baseline, &answer_tolerance())]
+}
+
+pub fn validate_query_4(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_4::Q4Row>> {
+    let expected = expected(conn, 4, scale_factor);
+    let (line_items, orders) = query_4::load(conn).expect("Error loading Q4");
+    let baseline = query_4::query(line_items, orders, &query_4::QueryParams::default());
+
+    vec![compare("Q4", "tpch_answers", "baseline", &expected, &baseline, &answer_tolerance())]
+}
+
+pub fn validate_query_5(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_5::Q5Row>> {
+    let expected = expected(conn, 5, scale_factor);
+    let (customer, orders, line_items, supplier, nation, region) = query_5::load(conn);
+    let baseline = query_5::query(customer, orders, line_items, supplier, nation, region);
+
+    vec![compare("Q5", "tpch_answers", "baseline", &expected, &baseline, &answer_tolerance())]
+}
+
+pub fn validate_query_6(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_6::Q6Row>> {
+    let expected = expected(conn, 6, scale_factor);
+    let baseline = query_6::query(query_6::load(conn));
+
+    vec![compare("Q6", "tpch_answers", "baseline", &expected, &baseline, &answer_tolerance())]
+}
+
+pub fn validate_query_10(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_10::Q10Row>> {
+    let expected = expected(conn, 10, scale_factor);
+    let (customer, orders, line_items, nation) = query_10::load(conn);
+    let baseline = query_10::query(customer, orders, line_items, nation);
+
+    vec![compare("Q10", "tpch_answers", "baseline", &expected, &baseline, &answer_tolerance())]
+}
+
+pub fn validate_query_12(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_12::Q12Row>> {
+    let expected = expected(conn, 12, scale_factor);
+    let (orders, line_items) = query_12::load(conn);
+    let baseline = query_12::query(orders, line_items);
+
+    vec![compare("Q12", "tpch_answers", "baseline", &expected, &baseline, &answer_tolerance())]
+}
+
+pub fn validate_query_13(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_13::Q13Row>> {
+    let expected = expected(conn, 13, scale_factor);
+    let (customer, orders) = query_13::load(conn);
+    let baseline = query_13::query(customer, orders);
+
+    vec![compare("Q13", "tpch_answers", "baseline", &expected, &baseline, &answer_tolerance())]
+}
+
+pub fn validate_query_14(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_14::Q14Row>> {
+    let expected = expected(conn, 14, scale_factor);
+    let (line_items, part) = query_14::load(conn);
+    let baseline = query_14::query(line_items, part);
+
+    vec![compare("Q14", "tpch_answers", "baseline", &expected, &baseline, &answer_tolerance())]
+}
+
+pub fn validate_query_16(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_16::Q16Row>> {
+    let expected = expected(conn, 16, scale_factor);
+    let (partsupp, part, supplier) = query_16::load(conn);
+    let baseline = query_16::query(partsupp, part, supplier);
+
+    vec![compare("Q16", "tpch_answers", "baseline", &expected, &baseline, &answer_tolerance())]
+}
+
+pub fn validate_query_17(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_17::Q17Row>> {
+    let expected = expected(conn, 17, scale_factor);
+    let (line_items, part) = query_17::load(conn);
+    let baseline = query_17::query(line_items, part);
+
+    vec![compare("Q17", "tpch_answers", "baseline", &expected, &baseline, &answer_tolerance())]
+}
+
+pub fn validate_query_18(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_18::Q18Row>> {
+    let expected = expected(conn, 18, scale_factor);
+    let (customer, orders, line_items) = query_18::load(conn);
+    let baseline = query_18::query(customer, orders, line_items);
+
+    vec![compare("Q18", "tpch_answers", "baseline", &expected, &baseline, &answer_tolerance())]
+}
+
+pub fn validate_query_19(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_19::Q19Row>> {
+    let expected = expected(conn, 19, scale_factor);
+    let (line_items, part) = query_19::load(conn).expect("Error loading Q19");
+    let baseline = query_19::query(line_items, part, &query_19::QueryParams::default());
+
+    vec![compare("Q19", "tpch_answers", "baseline", &expected, &baseline, &answer_tolerance())]
+}
+
+pub fn validate_query_20(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_20::Q20Row>> {
+    let expected = expected(conn, 20, scale_factor);
+    let (supplier, nation, partsupp, part, line_items) = query_20::load(conn);
+    let baseline = query_20::query(supplier, nation, partsupp, part, line_items);
+
+    vec![compare("Q20", "tpch_answers", "baseline", &expected, &baseline, &answer_tolerance())]
+}
+
+pub fn validate_query_21(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_21::Q21Row>> {
+    let expected = expected(conn, 21, scale_factor);
+    let (supplier, line_items, orders, nation) = query_21::load(conn);
+    let baseline = query_21::query(supplier, line_items, orders, nation);
+
+    vec![compare("Q21", "tpch_answers", "baseline", &expected, &baseline, &answer_tolerance())]
+}
+
+pub fn validate_query_22(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_22::Q22Row>> {
+    let expected = expected(conn, 22, scale_factor);
+    let (customer, orders) = query_22::load(conn);
+    let baseline = query_22::query(customer, orders);
+
+    vec![compare("Q22", "tpch_answers", "baseline", &expected, &baseline, &answer_tolerance())]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answer() {
+        let answer = "o_orderpriority|order_count\n1-URGENT|10594\n2-HIGH|10476\n";
+        let rows: Vec<query_4::Q4Row> = parse_answer(answer);
+        assert_eq!(
+            rows,
+            vec![
+                query_4::Q4Row { o_orderpriority: "1-URGENT".to_string(), order_count: 10594 },
+                query_4::Q4Row { o_orderpriority: "2-HIGH".to_string(), order_count: 10476 },
+            ]
+        );
+    }
+}
@@ -0,0 +1,90 @@
+//! Bloom filters for sideways information passing.
+//!
+//! A join whose build side is much smaller than its probe side, e.g. the filtered parts of Query 19
+//! or the orders of one quarter in Query 4, still probes every tuple of the probe side. A Bloom
+//! filter of the build keys is passed to the probe side before the join and drops most tuples
+//! without a match early, before they are hashed into the join or sent over the network. The filter
+//! has no false negatives, so the join still sees every matching tuple and removes the false
+//! positives.
+
+use std::collections::hash_map::DefaultHasher;
+use std::f64::consts::LN_2;
+use std::hash::{Hash, Hasher};
+
+/// The share of probe keys without a match that pass the filter.
+pub const DEFAULT_FALSE_POSITIVE_RATE: f64 = 0.01;
+
+#[derive(Clone, Debug)]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    hashes: u32,
+}
+
+impl BloomFilter {
+    /// An empty filter sized for `keys` keys at `false_positive_rate`.
+    pub fn new(keys: usize, false_positive_rate: f64) -> Self {
+        assert!(false_positive_rate > 0.0 && false_positive_rate < 1.0, "False positive rate out of (0, 1): {}", false_positive_rate);
+        let keys = keys.max(1) as f64;
+        let bits = (-keys * false_positive_rate.ln() / (LN_2 * LN_2)).ceil() as usize;
+        let words = bits.div_ceil(64).max(1);
+        let hashes = ((words * 64) as f64 / keys * LN_2).round().clamp(1.0, 16.0) as u32;
+        BloomFilter { bits: vec![0; words], hashes }
+    }
+
+    /// A filter of `keys` at `false_positive_rate`.
+    pub fn from_keys<K: Hash>(keys: &[K], false_positive_rate: f64) -> Self {
+        let mut filter = BloomFilter::new(keys.len(), false_positive_rate);
+        for key in keys {
+            filter.insert(key);
+        }
+        filter
+    }
+
+    pub fn insert<K: Hash + ?Sized>(&mut self, key: &K) {
+        for position in self.positions(key) {
+            self.bits[position / 64] |= 1 << (position % 64);
+        }
+    }
+
+    /// False if `key` was never inserted, true if it probably was.
+    pub fn contains<K: Hash + ?Sized>(&self, key: &K) -> bool {
+        self.positions(key).all(|position| self.bits[position / 64] & (1 << (position % 64)) != 0)
+    }
+
+    /// The size of the filter in bytes, e.g. to account for broadcasting it.
+    pub fn size(&self) -> usize {
+        self.bits.len() * 8
+    }
+
+    /// Double hashing: the positions of `key` derive from one 64 bit hash.
+    fn positions<K: Hash + ?Sized>(&self, key: &K) -> impl Iterator<Item = usize> {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        let hash = hasher.finish();
+        let (h1, h2) = (hash, hash.rotate_left(32) | 1);
+        let bits = self.bits.len() as u64 * 64;
+        (0..self.hashes as u64).map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % bits) as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bloom_filter() {
+        let keys: Vec<i64> = (0..10_000).map(|x| x * 2).collect();
+        let filter = BloomFilter::from_keys(&keys, DEFAULT_FALSE_POSITIVE_RATE);
+
+        // No false negatives.
+        assert!(keys.iter().all(|key| filter.contains(key)));
+
+        // The false positives stay close to the rate the filter is sized for.
+        let false_positives = (0..10_000i64).map(|x| x * 2 + 1).filter(|key| filter.contains(key)).count();
+        assert!(false_positives < 300, "{} false positives", false_positives);
+
+        let empty = BloomFilter::new(0, DEFAULT_FALSE_POSITIVE_RATE);
+        assert!(!empty.contains(&1i64));
+        assert!(empty.size() > 0);
+    }
+}
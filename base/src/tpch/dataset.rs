@@ -0,0 +1,151 @@
+//! On-disk TPC-H datasets cached per scale factor.
+//!
+//! Generating the tables with `dbgen` takes minutes at SF10, so `DatasetCache` generates them once
+//! into `<dir>/tpch_sf_<ScaleFactor::name>.duckdb` and every later `open` attaches that file READ_ONLY
+//! to a fresh in-memory connection. Each file records the generator it was built with, i.e. the
+//! DuckDB version (which ships the `tpch` extension) and `DATASET_VERSION`. A file with a different
+//! generator is built again.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use duckdb::{params, AccessMode, Config, Connection};
+
+use super::scale_factor::ScaleFactor;
+
+/// Bump when the layout of the generated files changes.
+pub const DATASET_VERSION: u32 = 1;
+
+/// Overrides the directory of the cached datasets.
+pub const DATASET_DIR_VAR: &str = "TPCH_DATASET_DIR";
+
+/// Serializes generation within the process, tests open the same scale factor concurrently.
+static GENERATE: Mutex<()> = Mutex::new(());
+
+pub struct DatasetCache {
+    dir: PathBuf,
+}
+
+impl Default for DatasetCache {
+    /// `$TPCH_DATASET_DIR`, or `hydro_benchmarks_tpch` in the temp directory.
+    fn default() -> Self {
+        let dir = std::env::var_os(DATASET_DIR_VAR)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| std::env::temp_dir().join("hydro_benchmarks_tpch"));
+        DatasetCache::new(dir)
+    }
+}
+
+impl DatasetCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        DatasetCache { dir: dir.into() }
+    }
+
+    pub fn path(&self, scale_factor: ScaleFactor) -> PathBuf {
+        self.dir.join(format!("tpch_sf_{}.duckdb", scale_factor.name()))
+    }
+
+    /// The cached dataset of `scale_factor`, generated first if it is missing or stale.
+    pub fn ensure(&self, scale_factor: ScaleFactor) -> PathBuf {
+        let path = self.path(scale_factor);
+        let _guard = GENERATE.lock().unwrap_or_else(|e| e.into_inner());
+        if !is_current(&path) {
+            generate(&path, scale_factor);
+        }
+        path
+    }
+
+    /// An in-memory connection with the dataset of `scale_factor` attached READ_ONLY. Unqualified
+    /// table names resolve to the schema `ScaleFactor::schema` of the dataset.
+    pub fn open(&self, scale_factor: ScaleFactor) -> Connection {
+        let path = self.ensure(scale_factor);
+        let conn = Connection::open_in_memory().expect("Error creating in-memory database");
+        conn.execute_batch(&format!(
+            "ATTACH '{}' AS tpch (READ_ONLY); USE tpch.{};",
+            path.display(),
+            scale_factor.schema()
+        ))
+        .unwrap_or_else(|e| panic!("Error attaching {}: {}", path.display(), e));
+        conn
+    }
+}
+
+/// The DuckDB version and `DATASET_VERSION`, a dataset is only reused by the same generator.
+pub fn generator(conn: &Connection) -> String {
+    let (version, source_id): (String, String) = conn
+        .query_row("SELECT library_version, source_id FROM pragma_version();", [], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .expect("Error querying DuckDB version");
+    format!("duckdb {} ({}), dataset {}", version, source_id, DATASET_VERSION)
+}
+
+fn is_current(path: &Path) -> bool {
+    if !path.exists() {
+        return false;
+    }
+    let config = Config::default().access_mode(AccessMode::ReadOnly).expect("Error configuring DuckDB");
+    let Ok(conn) = Connection::open_with_flags(path, config) else {
+        return false;
+    };
+    let stored: Result<String, _> = conn.query_row("SELECT generator FROM main.dataset_meta;", [], |row| row.get(0));
+    stored.map(|stored| stored == generator(&conn)).unwrap_or(false)
+}
+
+/// Generate into a temporary file and move it into place, so readers never see a partial dataset.
+fn generate(path: &Path, scale_factor: ScaleFactor) {
+    let dir = path.parent().expect("Dataset path without directory");
+    fs::create_dir_all(dir).unwrap_or_else(|e| panic!("Error creating {}: {}", dir.display(), e));
+    let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+    let _ = fs::remove_file(&tmp);
+
+    {
+        let conn = Connection::open(&tmp).unwrap_or_else(|e| panic!("Error creating {}: {}", tmp.display(), e));
+        conn.execute_batch(&format!(
+            "CREATE SCHEMA {schema}; USE {schema}; CALL dbgen(sf = {sf});",
+            schema = scale_factor.schema(),
+            sf = scale_factor
+        ))
+        .expect("Error loading data via TPCH extension");
+        conn.execute_batch("CREATE TABLE main.dataset_meta (generator VARCHAR);")
+            .expect("Error creating dataset_meta");
+        conn.execute("INSERT INTO main.dataset_meta VALUES (?);", params![generator(&conn)])
+            .expect("Error writing dataset_meta");
+        conn.execute_batch("CHECKPOINT;").expect("Error checkpointing dataset");
+    }
+
+    fs::rename(&tmp, path)
+        .unwrap_or_else(|e| panic!("Error moving {} to {}: {}", tmp.display(), path.display(), e));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dataset_cache() {
+        let dir = std::env::temp_dir().join(format!("hydro_benchmarks_tpch_test_{}", std::process::id()));
+        let cache = DatasetCache::new(&dir);
+
+        // SF 0 generates the schema without rows.
+        let scale_factor = ScaleFactor::from(0);
+        let path = cache.ensure(scale_factor);
+        assert!(is_current(&path));
+        let conn = cache.open(scale_factor);
+        let count: i64 = conn.query_row("SELECT count(*) FROM lineitem;", [], |row| row.get(0)).unwrap();
+        assert_eq!(count, 0);
+        drop(conn);
+
+        // A dataset of another generator is generated again.
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute("UPDATE main.dataset_meta SET generator = 'stale';", []).unwrap();
+        }
+        assert!(!is_current(&path));
+        cache.ensure(scale_factor);
+        assert!(is_current(&path));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
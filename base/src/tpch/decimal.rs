@@ -0,0 +1,184 @@
+//! Exact arithmetic for the DECIMAL columns of TPC-H.
+//!
+//! The money columns are DECIMAL(15,2) in DuckDB, which computes Q1 and Q19 in exact fixed-point
+//! arithmetic. With `f64` the sums drift from DuckDB's results and can only be compared with a
+//! tolerance. `Decimal` is a scaled integer with `Decimal::SCALE` fractional digits, enough for the
+//! products of up to three DECIMAL(15,2) values that the queries compute, so the sums match DuckDB
+//! exactly. The query structs are generic over `Numeric` and default to `f64`.
+
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Sub};
+use std::str::FromStr;
+
+/// The arithmetic the queries need from a numeric column type.
+pub trait Numeric:
+    Copy + Default + PartialOrd + fmt::Debug + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + AddAssign + Sum
+{
+    fn one() -> Self;
+
+    fn from_i32(value: i32) -> Self;
+
+    fn to_f64(self) -> f64;
+}
+
+impl Numeric for f64 {
+    fn one() -> Self {
+        1.0
+    }
+
+    fn from_i32(value: i32) -> Self {
+        value as f64
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Decimal(i128);
+
+impl Decimal {
+    /// The number of fractional digits.
+    pub const SCALE: u32 = 6;
+    const UNIT: i128 = 10i128.pow(Decimal::SCALE);
+
+    /// The value in units of 10^-SCALE.
+    pub fn from_units(units: i128) -> Self {
+        Decimal(units)
+    }
+
+    /// `value` * 10^-`scale`, e.g. `from_scaled(1234, 2)` is 12.34.
+    pub fn from_scaled(value: i128, scale: u32) -> Self {
+        assert!(scale <= Decimal::SCALE, "Scale {} exceeds {}", scale, Decimal::SCALE);
+        Decimal(value * 10i128.pow(Decimal::SCALE - scale))
+    }
+
+    pub fn units(self) -> i128 {
+        self.0
+    }
+}
+
+impl Numeric for Decimal {
+    fn one() -> Self {
+        Decimal(Decimal::UNIT)
+    }
+
+    fn from_i32(value: i32) -> Self {
+        Decimal(value as i128 * Decimal::UNIT)
+    }
+
+    fn to_f64(self) -> f64 {
+        self.0 as f64 / Decimal::UNIT as f64
+    }
+}
+
+impl Add for Decimal {
+    type Output = Decimal;
+
+    fn add(self, other: Decimal) -> Decimal {
+        Decimal(self.0 + other.0)
+    }
+}
+
+impl AddAssign for Decimal {
+    fn add_assign(&mut self, other: Decimal) {
+        self.0 += other.0;
+    }
+}
+
+impl Sub for Decimal {
+    type Output = Decimal;
+
+    fn sub(self, other: Decimal) -> Decimal {
+        Decimal(self.0 - other.0)
+    }
+}
+
+/// Truncates digits beyond `SCALE`, exact as long as the factors have at most `SCALE` fractional
+/// digits together.
+impl Mul for Decimal {
+    type Output = Decimal;
+
+    fn mul(self, other: Decimal) -> Decimal {
+        Decimal(self.0 * other.0 / Decimal::UNIT)
+    }
+}
+
+impl Sum for Decimal {
+    fn sum<I: Iterator<Item = Decimal>>(iter: I) -> Decimal {
+        iter.fold(Decimal::default(), |a, b| a + b)
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let units = self.0.unsigned_abs();
+        let unit = Decimal::UNIT as u128;
+        write!(f, "{}{}.{:0width$}", sign, units / unit, units % unit, width = Decimal::SCALE as usize)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDecimalError(String);
+
+impl fmt::Display for ParseDecimalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid decimal {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseDecimalError {}
+
+/// Parses `[-]digits[.digits]` with at most `SCALE` fractional digits, as DuckDB prints DECIMALs.
+impl FromStr for Decimal {
+    type Err = ParseDecimalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseDecimalError(s.to_string());
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s),
+        };
+        let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        let is_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if integer.is_empty() || !is_digits(integer) || !is_digits(fraction) || fraction.len() > Decimal::SCALE as usize {
+            return Err(error());
+        }
+        let integer: i128 = integer.parse().map_err(|_| error())?;
+        let units = match fraction {
+            "" => integer * Decimal::UNIT,
+            _ => integer * Decimal::UNIT + Decimal::from_scaled(fraction.parse().map_err(|_| error())?, fraction.len() as u32).0,
+        };
+        Ok(Decimal(if negative { -units } else { units }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decimal() {
+        let price: Decimal = "21168.23".parse().unwrap();
+        let discount: Decimal = "0.04".parse().unwrap();
+        let tax: Decimal = "0.02".parse().unwrap();
+        assert_eq!(price, Decimal::from_scaled(2116823, 2));
+
+        // 21168.23 * 0.96 * 1.02 = 20727.930816
+        let charge = price * (Decimal::one() - discount) * (Decimal::one() + tax);
+        assert_eq!(charge, Decimal::from_units(20_727_930_816));
+        assert_eq!(charge.to_string(), "20727.930816");
+
+        assert_eq!("17".parse::<Decimal>().unwrap(), Decimal::from_i32(17));
+        assert_eq!("-0.5".parse::<Decimal>().unwrap().to_string(), "-0.500000");
+        assert_eq!([Decimal::one(), Decimal::one()].into_iter().sum::<Decimal>(), Decimal::from_i32(2));
+        assert_eq!(Decimal::from_scaled(25, 1).to_f64(), 2.5);
+
+        for invalid in ["", ".5", "1.2345678", "1e5", "1.-2", "abc"] {
+            assert!(invalid.parse::<Decimal>().is_err(), "{}", invalid);
+        }
+    }
+}
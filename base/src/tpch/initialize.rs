@@ -1,22 +1,34 @@
 use duckdb::Connection;
 
+use super::dataset::DatasetCache;
+use super::scale_factor::ScaleFactor;
+
+/**
+ * Using DuckDB to load the data. The tables are generated once per scale factor into the on-disk
+ * dataset cache and attached READ_ONLY, see `DatasetCache`.
+ * */
+pub fn initialize_database(scale_factor: impl Into<ScaleFactor>) -> Connection {
+    DatasetCache::default().open(scale_factor.into())
+}
+
 /**
- * Using DuckDB to load the data
- * */ 
-pub fn initialize_database(scale_factor: u32) -> Connection {
+ * Generate the data into a fresh in-memory database that can be modified.
+ * */
+pub fn initialize_in_memory(scale_factor: impl Into<ScaleFactor>) -> Connection {
+    let scale_factor = scale_factor.into();
 
     // Create a in-memory database
     let conn = Connection::open_in_memory().expect("Error creating in-memory database");
     conn.execute(
-        &format!("CREATE OR REPLACE SCHEMA SF_{};", scale_factor),
+        &format!("CREATE OR REPLACE SCHEMA {};", scale_factor.schema()),
         [],
     )
     .expect("Error creating schema");
-    conn.execute(&format!("USE SF_{};", scale_factor), [])
+    conn.execute(&format!("USE {};", scale_factor.schema()), [])
         .expect("Error using schema");
     // Load the data via TPCH extension
     conn.execute(&format!("CALL dbgen(sf ={});", scale_factor), [])
         .expect("Error loading data via TPCH extension");
 
     conn
-}
\ No newline at end of file
+}
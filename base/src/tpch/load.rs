@@ -0,0 +1,79 @@
+//! Errors and modes of the TPC-H loaders.
+//!
+//! A loader scans a table with DuckDB and converts every row to the query's row struct. If a
+//! value does not fit its field, e.g. a BIGINT key that overflows an `i32`, the conversion fails.
+//! In `LoadMode::Strict` the first such row fails the load with a `LoadError`, in
+//! `LoadMode::Lenient` the row is dropped and counted in `Loaded::dropped`.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LoadMode {
+    /// Fail on the first row that cannot be converted.
+    #[default]
+    Strict,
+    /// Drop rows that cannot be converted and count them.
+    Lenient,
+}
+
+/// The rows of a load and the number of rows dropped in `LoadMode::Lenient`.
+#[derive(Debug, Clone)]
+pub struct Loaded<T> {
+    pub rows: Vec<T>,
+    pub dropped: usize,
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    /// Preparing or running the scan of `table` failed.
+    Query { table: String, error: duckdb::Error },
+    /// The value of `column` in row `row` of the scan could not be converted. `column` is `None` if
+    /// DuckDB does not report which column failed.
+    Conversion {
+        table: String,
+        column: Option<String>,
+        row: usize,
+        error: duckdb::Error,
+    },
+}
+
+impl LoadError {
+    /// Attribute a conversion error of row `row` to one of the scanned `columns`.
+    pub(crate) fn conversion(table: &str, columns: &[&str], row: usize, error: duckdb::Error) -> Self {
+        let index = match &error {
+            duckdb::Error::FromSqlConversionFailure(i, _, _)
+            | duckdb::Error::IntegralValueOutOfRange(i, _)
+            | duckdb::Error::InvalidColumnIndex(i)
+            | duckdb::Error::InvalidColumnType(i, _, _) => Some(*i),
+            _ => None,
+        };
+        LoadError::Conversion {
+            table: table.to_string(),
+            column: index.and_then(|i| columns.get(i)).map(|c| c.to_string()),
+            row,
+            error,
+        }
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Query { table, error } => write!(f, "Error querying {}: {}", table, error),
+            LoadError::Conversion { table, column: Some(column), row, error } => {
+                write!(f, "Error converting {}.{} in row {}: {}", table, column, row, error)
+            }
+            LoadError::Conversion { table, column: None, row, error } => {
+                write!(f, "Error converting row {} of {}: {}", row, table, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::Query { error, .. } | LoadError::Conversion { error, .. } => Some(error),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+//! The merge stage of sort-merge joins.
+//!
+//! Both inputs are sorted by their key, e.g. with `sort_unstable_by_key` or Hydroflow's
+//! `sort_by_key`, and merged in one pass without a hash table. The sort is cheap where `dbgen`
+//! already generates a table in key order, e.g. orders and lineitem by order key, and costs a full
+//! sort where it does not, e.g. lineitem by part key. The output has the shape of Hydroflow's
+//! `join_multiset()`.
+
+/**
+ * Join `left` and `right`, both sorted by key. Every right tuple is paired with every left tuple of
+ * the same key, so duplicate keys on both sides produce their cross product, in key order.
+ */
+pub fn merge_join<K, L, R>(left: Vec<(K, L)>, right: Vec<(K, R)>) -> Vec<(K, (L, R))>
+where
+    K: Ord + Clone,
+    L: Clone,
+    R: Clone,
+{
+    let mut result = vec![];
+    let mut left = left.into_iter().peekable();
+    // The left values of the key of the previous right tuple.
+    let mut run: Vec<L> = vec![];
+    let mut run_key: Option<K> = None;
+    for (key, right_val) in right {
+        if run_key.as_ref() != Some(&key) {
+            while left.next_if(|(left_key, _)| *left_key < key).is_some() {}
+            run.clear();
+            while let Some((_, left_val)) = left.next_if(|(left_key, _)| *left_key == key) {
+                run.push(left_val);
+            }
+            run_key = Some(key.clone());
+        }
+        result.extend(run.iter().map(|left_val| (key.clone(), (left_val.clone(), right_val.clone()))));
+    }
+    result
+}
+
+/// The tuples of `probe` whose key is in `build`, both sorted by key, see `super::semi_join`.
+pub fn merge_semi_join<K: Ord, V>(probe: Vec<(K, V)>, build: Vec<K>) -> Vec<(K, V)> {
+    let mut build = build.into_iter().peekable();
+    probe
+        .into_iter()
+        .filter(|(key, _)| {
+            while build.next_if(|build_key| build_key < key).is_some() {}
+            build.peek() == Some(key)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_join() {
+        let left = vec![(1, 'a'), (2, 'b'), (2, 'c'), (4, 'd')];
+        let right = vec![(0, 10), (2, 20), (2, 21), (3, 30), (4, 40), (5, 50)];
+        assert_eq!(
+            merge_join(left, right),
+            vec![(2, ('b', 20)), (2, ('c', 20)), (2, ('b', 21)), (2, ('c', 21)), (4, ('d', 40))]
+        );
+
+        assert!(merge_join(Vec::<(i32, char)>::new(), vec![(1, 1)]).is_empty());
+        assert!(merge_join(vec![(1, 'a')], Vec::<(i32, i32)>::new()).is_empty());
+    }
+
+    #[test]
+    fn test_merge_semi_join() {
+        let orders = vec![(1, "a"), (2, "b"), (3, "c"), (3, "d"), (5, "e")];
+        let line_items = vec![1, 1, 3, 4, 5, 5, 6];
+        assert_eq!(merge_semi_join(orders.clone(), line_items), vec![(1, "a"), (3, "c"), (3, "d"), (5, "e")]);
+        assert!(merge_semi_join(orders, vec![]).is_empty());
+    }
+}
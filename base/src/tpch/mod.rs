@@ -1,5 +1,36 @@
 pub mod query_1;
+pub mod query_2;
+pub mod query_3;
 pub mod query_4;
+pub mod query_5;
+pub mod query_6;
+pub mod query_10;
+pub mod query_12;
+pub mod query_13;
+pub mod query_14;
+pub mod query_16;
+pub mod query_17;
+pub mod query_18;
 pub mod query_19;
+pub mod query_20;
+pub mod query_21;
+pub mod query_22;
+pub mod top_k;
+pub mod qgen;
+pub mod load;
+pub mod decimal;
+pub mod tbl;
+pub mod parallel;
+pub mod refresh;
+pub mod scan;
+pub mod shared_scan;
+pub mod semi_join;
+pub mod merge_join;
+pub mod bloom;
+pub mod plan;
+pub mod dataset;
+pub mod scale_factor;
 pub mod initialize;
+pub mod answers;
+pub mod verify;
 mod util;
\ No newline at end of file
@@ -0,0 +1,96 @@
+//! Morsel-driven parallelism for the baselines.
+//!
+//! The input is cut into morsels of `Parallelism::morsel_size` rows. Every worker thread takes the
+//! next unprocessed morsel from a shared counter and folds it into its own partial state, so a slow
+//! thread does not hold up the others. The partial states, e.g. partial hash aggregates, are
+//! returned to the caller to be merged.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Rows per morsel. Large enough to amortize the counter, small enough to balance the threads.
+pub const DEFAULT_MORSEL_SIZE: usize = 16 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Parallelism {
+    pub threads: usize,
+    pub morsel_size: usize,
+}
+
+impl Parallelism {
+    pub fn new(threads: usize) -> Self {
+        assert!(threads > 0, "Need at least one thread");
+        Parallelism { threads, morsel_size: DEFAULT_MORSEL_SIZE }
+    }
+
+    pub fn with_morsel_size(self, morsel_size: usize) -> Self {
+        assert!(morsel_size > 0, "Need at least one row per morsel");
+        Parallelism { morsel_size, ..self }
+    }
+}
+
+/// All available cores.
+impl Default for Parallelism {
+    fn default() -> Self {
+        Parallelism::new(thread::available_parallelism().map(|n| n.get()).unwrap_or(1))
+    }
+}
+
+/**
+ * Fold the morsels of `items` on `parallelism.threads` scoped threads. Every thread starts from
+ * `init()` and returns its partial state, one per thread in thread order. The partial states may
+ * borrow from `items`.
+ */
+pub fn morsels<'a, T, A, I, F>(items: &'a [T], parallelism: &Parallelism, init: I, fold: F) -> Vec<A>
+where
+    T: Sync,
+    A: Send,
+    I: Fn() -> A + Sync,
+    F: Fn(&mut A, &'a [T]) + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let worker = || {
+        let mut acc = init();
+        loop {
+            let start = next.fetch_add(parallelism.morsel_size, Ordering::Relaxed);
+            if start >= items.len() {
+                break acc;
+            }
+            let end = items.len().min(start + parallelism.morsel_size);
+            fold(&mut acc, &items[start..end]);
+        }
+    };
+
+    thread::scope(|s| {
+        let handles: Vec<_> = (0..parallelism.threads).map(|_| s.spawn(worker)).collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("Morsel worker panicked"))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_morsels() {
+        let items: Vec<u64> = (0..100_000).collect();
+        let expected: u64 = items.iter().sum();
+
+        for threads in [1, 2, 4, 7] {
+            let parallelism = Parallelism::new(threads).with_morsel_size(1000);
+            let partials = morsels(&items, &parallelism, || (0u64, 0usize), |acc, morsel| {
+                acc.0 += morsel.iter().sum::<u64>();
+                acc.1 += morsel.len();
+            });
+            assert_eq!(partials.len(), threads);
+            assert_eq!(partials.iter().map(|p| p.0).sum::<u64>(), expected);
+            assert_eq!(partials.iter().map(|p| p.1).sum::<usize>(), items.len());
+        }
+
+        let partials = morsels(&[] as &[u64], &Parallelism::new(3), || 0u64, |acc, morsel| *acc += morsel.len() as u64);
+        assert_eq!(partials, vec![0, 0, 0]);
+    }
+}
@@ -0,0 +1,342 @@
+//! Cardinality estimates and the choice of the build side and distribution of a join.
+//!
+//! The estimates come from DuckDB: the rows of a table from its catalog, the selectivity of the
+//! pushed down filters from a sample, and the distinct join keys that pass the filters from
+//! `approx_count_distinct`. `JoinPlan::choose` builds on the smaller filtered input and decides
+//! whether a distributed join broadcasts the build side to every node or shuffles both sides by key.
+//! The queries estimate their inputs in their `plan` functions, e.g. `query_4::plan`. The rows are
+//! routed to the nodes with `partition` and `Distribution::probe_node`, which the distributed
+//! Hydroflow+ join of `flow::tpch::query_4_distributed` uses as well, so the cost is that of the
+//! implementation. A semi-join that counts the rows of one side once, e.g. the EXISTS of Query 4,
+//! is planned with `JoinPlan::choose_semi_join`.
+
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use duckdb::{Connection, ToSql};
+
+/// Rows sampled to estimate the selectivity of a filter.
+pub const SAMPLE_ROWS: usize = 10_000;
+
+/// Seed of the sample, so the same database gets the same plan.
+const SAMPLE_SEED: u32 = 42;
+
+/// The rows of `table` from DuckDB's catalog, without a scan.
+pub fn table_rows(conn: &Connection, table: &str) -> u64 {
+    let rows: i64 = conn
+        .query_row("SELECT estimated_size FROM duckdb_tables() WHERE table_name = ?;", duckdb::params![table], |row| row.get(0))
+        .unwrap_or_else(|e| panic!("Error reading the size of {}: {}", table, e));
+    rows as u64
+}
+
+/// The share of the rows of `table` that satisfy the SQL `predicate`, on a sample of `SAMPLE_ROWS`.
+pub fn selectivity(conn: &Connection, table: &str, predicate: &str, params: &[&dyn ToSql]) -> f64 {
+    let query = format!(
+        "SELECT count(*) FILTER (WHERE {}), count(*) FROM (SELECT * FROM {} USING SAMPLE reservoir({} ROWS) REPEATABLE ({}));",
+        predicate, table, SAMPLE_ROWS, SAMPLE_SEED
+    );
+    let (selected, sampled): (i64, i64) = conn
+        .query_row(&query, params, |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap_or_else(|e| panic!("Error sampling {}: {}", table, e));
+    if sampled == 0 {
+        0.0
+    } else {
+        selected as f64 / sampled as f64
+    }
+}
+
+/// The approximate distinct values of `column` in the rows of `table` that satisfy `predicate`.
+pub fn distinct_values(conn: &Connection, table: &str, column: &str, predicate: &str, params: &[&dyn ToSql]) -> u64 {
+    let query = format!("SELECT approx_count_distinct({}) FROM {} WHERE {};", column, table, predicate);
+    let distinct: i64 = conn
+        .query_row(&query, params, |row| row.get(0))
+        .unwrap_or_else(|e| panic!("Error counting the distinct {} of {}: {}", column, table, e));
+    distinct as u64
+}
+
+/// One input of a join: a table, its pushed down filter and its join key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate {
+    pub table: String,
+    pub rows: u64,
+    pub selectivity: f64,
+    pub distinct_keys: u64,
+}
+
+impl Estimate {
+    /// Estimate the rows of `table` that satisfy `predicate`, joined on `key`.
+    pub fn filtered(conn: &Connection, table: &str, key: &str, predicate: &str, params: &[&dyn ToSql]) -> Self {
+        Estimate {
+            table: table.to_string(),
+            rows: table_rows(conn, table),
+            selectivity: selectivity(conn, table, predicate, params),
+            distinct_keys: distinct_values(conn, table, key, predicate, params),
+        }
+    }
+
+    /// The rows after the filter.
+    pub fn cardinality(&self) -> f64 {
+        self.rows as f64 * self.selectivity
+    }
+}
+
+impl fmt::Display for Estimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (~{:.0} of {} rows, ~{} keys)", self.table, self.cardinality(), self.rows, self.distinct_keys)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// The node of the `nodes` that a shuffle sends a row with join key `key` to.
+pub fn partition<K: Hash>(key: &K, nodes: usize) -> u32 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    (hasher.finish() % nodes as u64) as u32
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Distribution {
+    /// Send the build side to every node and spread the probe side round robin over them.
+    Broadcast,
+    /// Hash partition both sides on the join key.
+    Shuffle,
+}
+
+impl Distribution {
+    /// The node of the `i`-th probe row, with join key `key`.
+    pub fn probe_node<K: Hash>(self, i: usize, key: &K, nodes: usize) -> u32 {
+        match self {
+            Distribution::Broadcast => (i % nodes) as u32,
+            Distribution::Shuffle => partition(key, nodes),
+        }
+    }
+
+    /**
+     * The rows that move between the `nodes` for the join. Both inputs start out spread round robin
+     * over the nodes, like the probe side of a broadcast, which costs the same for every plan. A
+     * broadcast copies every build row to the other nodes and leaves the probe side where it is. A
+     * shuffle moves the rows of both sides that are not on the node of their key, all but
+     * `1 / nodes` of them.
+     */
+    pub fn sent_rows(self, build_rows: f64, probe_rows: f64, nodes: usize) -> f64 {
+        let others = (nodes - 1) as f64;
+        match self {
+            Distribution::Broadcast => build_rows * others,
+            Distribution::Shuffle => (build_rows + probe_rows) * others / nodes as f64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JoinPlan {
+    pub left: Estimate,
+    pub right: Estimate,
+    pub build: Side,
+    pub distribution: Distribution,
+    pub nodes: usize,
+}
+
+impl JoinPlan {
+    /**
+     * Build on the smaller filtered input, and distribute it so the fewest rows are sent, see
+     * `Distribution::sent_rows`. A shuffle on fewer distinct keys than nodes leaves nodes idle and
+     * is never chosen.
+     */
+    pub fn choose(left: Estimate, right: Estimate, nodes: usize) -> Self {
+        assert!(nodes > 0, "Need at least one node");
+        let build = if left.cardinality() <= right.cardinality() { Side::Left } else { Side::Right };
+
+        let (build_rows, probe_rows) = match build {
+            Side::Left => (left.cardinality(), right.cardinality()),
+            Side::Right => (right.cardinality(), left.cardinality()),
+        };
+        let broadcast = Distribution::Broadcast.sent_rows(build_rows, probe_rows, nodes);
+        let shuffle = Distribution::Shuffle.sent_rows(build_rows, probe_rows, nodes);
+        let keys = left.distinct_keys.min(right.distinct_keys);
+        let distribution = if broadcast <= shuffle || keys < nodes as u64 { Distribution::Broadcast } else { Distribution::Shuffle };
+
+        JoinPlan { left, right, build, distribution, nodes }
+    }
+
+    /**
+     * `choose` for a semi-join that counts every row of `left` once, e.g. the orders of Query 4. A
+     * broadcast of `left` spreads the rows of `right` round robin, so the `right` rows of one key
+     * match its `left` row on several nodes, which all count it. Instead, `right` is broadcast and
+     * `left` probes it, or both sides are shuffled, whichever sends fewer rows.
+     */
+    pub fn choose_semi_join(left: Estimate, right: Estimate, nodes: usize) -> Self {
+        let plan = JoinPlan::choose(left, right, nodes);
+        if plan.build == Side::Right || plan.distribution == Distribution::Shuffle {
+            return plan;
+        }
+        // On one node a shuffle sends nothing either and keeps the build side.
+        if nodes == 1 {
+            return JoinPlan { distribution: Distribution::Shuffle, ..plan };
+        }
+
+        let broadcast = Distribution::Broadcast.sent_rows(plan.right.cardinality(), plan.left.cardinality(), nodes);
+        let shuffle = Distribution::Shuffle.sent_rows(plan.left.cardinality(), plan.right.cardinality(), nodes);
+        let keys = plan.left.distinct_keys.min(plan.right.distinct_keys);
+        if broadcast <= shuffle || keys < nodes as u64 {
+            JoinPlan { build: Side::Right, ..plan }
+        } else {
+            JoinPlan { distribution: Distribution::Shuffle, ..plan }
+        }
+    }
+
+    pub fn build_side(&self) -> &Estimate {
+        match self.build {
+            Side::Left => &self.left,
+            Side::Right => &self.right,
+        }
+    }
+
+    pub fn probe_side(&self) -> &Estimate {
+        match self.build {
+            Side::Left => &self.right,
+            Side::Right => &self.left,
+        }
+    }
+}
+
+impl fmt::Display for JoinPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let distribution = match self.distribution {
+            Distribution::Broadcast => "broadcast",
+            Distribution::Shuffle => "shuffle",
+        };
+        write!(f, "build {}, probe {}, {} on {} nodes", self.build_side(), self.probe_side(), distribution, self.nodes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn estimate(table: &str, rows: u64, selectivity: f64, distinct_keys: u64) -> Estimate {
+        Estimate { table: table.to_string(), rows, selectivity, distinct_keys }
+    }
+
+    #[test]
+    fn test_join_plan() {
+        let orders = estimate("orders", 1_500_000, 0.038, 57_000);
+        let line_items = estimate("lineitem", 6_000_000, 0.63, 1_400_000);
+
+        // The orders of one quarter are the build side, small enough to broadcast.
+        let plan = JoinPlan::choose(orders.clone(), line_items.clone(), 4);
+        assert_eq!(plan.build, Side::Left);
+        assert_eq!(plan.distribution, Distribution::Broadcast);
+        assert_eq!(plan.build_side().table, "orders");
+
+        let plan = JoinPlan::choose(line_items.clone(), orders.clone(), 4);
+        assert_eq!(plan.build, Side::Right);
+
+        // Build sides of similar size are shuffled.
+        let all_orders = estimate("orders", 1_500_000, 1.0, 1_500_000);
+        let plan = JoinPlan::choose(all_orders.clone(), line_items.clone(), 4);
+        assert_eq!(plan.distribution, Distribution::Shuffle);
+
+        // A shuffle on fewer keys than nodes leaves nodes idle, so the build side is broadcast.
+        let plan = JoinPlan::choose(estimate("orders", 1_500_000, 1.0, 2), line_items.clone(), 4);
+        assert_eq!(plan.distribution, Distribution::Broadcast);
+
+        // On one node nothing moves.
+        assert_eq!(JoinPlan::choose(all_orders, line_items, 1).distribution, Distribution::Broadcast);
+    }
+
+    /// The build and probe rows of every node.
+    type Received = Vec<(Vec<(u64, char)>, Vec<(u64, usize)>)>;
+
+    /// Route `build` and `probe` like the distributed join, the rows each node receives.
+    fn route(distribution: Distribution, build: &[(u64, char)], probe: &[(u64, usize)], nodes: usize) -> Received {
+        let mut received = vec![(vec![], vec![]); nodes];
+        for row in build {
+            match distribution {
+                Distribution::Broadcast => received.iter_mut().for_each(|(node_build, _)| node_build.push(*row)),
+                Distribution::Shuffle => received[partition(&row.0, nodes) as usize].0.push(*row),
+            }
+        }
+        for (i, row) in probe.iter().enumerate() {
+            received[distribution.probe_node(i, &row.0, nodes) as usize].1.push(*row);
+        }
+        received
+    }
+
+    #[test]
+    fn test_sent_rows() {
+        let nodes = 3;
+        let build: Vec<(u64, char)> = (0..3_000).map(|key| (key, 'b')).collect();
+        let probe: Vec<(u64, usize)> = (0..9_000).map(|i| (i as u64 % 4_500, i)).collect();
+
+        for distribution in [Distribution::Broadcast, Distribution::Shuffle] {
+            let received = route(distribution, &build, &probe, nodes);
+
+            // The rows that are not on their round robin node have moved.
+            let moved: usize = received
+                .iter()
+                .enumerate()
+                .map(|(node, (node_build, node_probe))| {
+                    let build_moved = node_build.iter().filter(|(key, _)| *key as usize % nodes != node).count();
+                    let probe_moved = node_probe.iter().filter(|(_, i)| i % nodes != node).count();
+                    build_moved + probe_moved
+                })
+                .sum();
+            let expected = distribution.sent_rows(build.len() as f64, probe.len() as f64, nodes);
+            assert!((moved as f64 - expected).abs() <= 0.05 * expected, "{:?} moved {} rows, expected {}", distribution, moved, expected);
+
+            // Every probe row with a key in the build side finds it on its node.
+            let joined: usize = received
+                .iter()
+                .map(|(node_build, node_probe)| node_probe.iter().filter(|(key, _)| node_build.iter().any(|(k, _)| k == key)).count())
+                .sum();
+            assert_eq!(joined, 6_000);
+        }
+    }
+
+    #[test]
+    fn test_semi_join_plan() {
+        // Order 1 has qualifying line items at the first two positions, which a broadcast of the
+        // orders sends to two nodes.
+        let nodes = 2;
+        let orders: Vec<(u64, char)> = vec![(1, 'a'), (2, 'b'), (3, 'c')];
+        let line_items: Vec<(u64, usize)> = [1, 1, 2, 4].into_iter().enumerate().map(|(i, key)| (key, i)).collect();
+
+        // The orders with a line item, counted on every node with a match.
+        let count = |received: Received| -> usize {
+            received
+                .iter()
+                .map(|(node_orders, node_line_items)| node_orders.iter().filter(|(key, _)| node_line_items.iter().any(|(k, _)| k == key)).count())
+                .sum()
+        };
+        assert_eq!(count(route(Distribution::Broadcast, &orders, &line_items, nodes)), 3);
+        assert_eq!(count(route(Distribution::Shuffle, &orders, &line_items, nodes)), 2);
+
+        // Broadcasting the line items instead: every order is on one node.
+        let received = route(Distribution::Broadcast, &line_items.iter().map(|(key, _)| (*key, 'l')).collect::<Vec<_>>(), &orders.iter().map(|(key, _)| (*key, 0)).collect::<Vec<_>>(), nodes);
+        let counted: usize = received
+            .iter()
+            .map(|(node_line_items, node_orders)| node_orders.iter().filter(|(key, _)| node_line_items.iter().any(|(k, _)| k == key)).count())
+            .sum();
+        assert_eq!(counted, 2);
+
+        // So the semi-join never broadcasts the orders, which `choose` would.
+        let quarter = estimate("orders", 1_500_000, 0.038, 57_000);
+        let line_items = estimate("lineitem", 6_000_000, 0.63, 1_400_000);
+        assert_eq!(JoinPlan::choose(quarter.clone(), line_items.clone(), 4).distribution, Distribution::Broadcast);
+        let plan = JoinPlan::choose_semi_join(quarter.clone(), line_items.clone(), 4);
+        assert_eq!((plan.build, plan.distribution), (Side::Left, Distribution::Shuffle));
+
+        let few_keys = estimate("orders", 1_500_000, 1.0, 2);
+        let plan = JoinPlan::choose_semi_join(few_keys, line_items.clone(), 4);
+        assert_eq!((plan.build, plan.distribution), (Side::Right, Distribution::Broadcast));
+
+        let plan = JoinPlan::choose_semi_join(quarter, line_items, 1);
+        assert_eq!((plan.build, plan.distribution), (Side::Left, Distribution::Shuffle));
+    }
+}
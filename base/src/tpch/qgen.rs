@@ -0,0 +1,96 @@
+//! Substitution parameters in the style of `qgen`.
+//!
+//! `QGen` draws the parameters of the parameterized queries from the value ranges of the TPC-H
+//! specification (clause 2.4), so benchmarks can cover several selectivities. The generator is
+//! seeded, the same seed always yields the same parameters.
+
+use chrono::{Months, NaiveDate};
+
+use super::{query_1, query_19, query_4};
+
+pub struct QGen {
+    state: u64,
+}
+
+impl QGen {
+    pub fn new(seed: u64) -> Self {
+        QGen { state: seed }
+    }
+
+    /// SplitMix64.
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniformly distributed in [low, high].
+    pub fn uniform(&mut self, low: i64, high: i64) -> i64 {
+        assert!(low <= high, "empty range [{}, {}]", low, high);
+        let range = (high - low) as u64 + 1;
+        low + (self.next_u64() % range) as i64
+    }
+
+    /// DELTA is randomly selected within [60, 120].
+    pub fn query_1(&mut self) -> query_1::QueryParams {
+        query_1::QueryParams {
+            delta: self.uniform(60, 120),
+        }
+    }
+
+    /// DATE is the first day of a randomly selected month between January 1993 and October 1997.
+    pub fn query_4(&mut self) -> query_4::QueryParams {
+        let months = self.uniform(0, 57) as u32;
+        query_4::QueryParams::new(NaiveDate::from_ymd_opt(1993, 1, 1).unwrap() + Months::new(months))
+    }
+
+    /// QUANTITY1 within [1, 10], QUANTITY2 within [10, 20], QUANTITY3 within [20, 30] and BRANDn =
+    /// 'Brand#MN' where M and N are two single character strings within [1, 5].
+    pub fn query_19(&mut self) -> query_19::QueryParams {
+        query_19::QueryParams {
+            quantity1: self.uniform(1, 10) as i32,
+            quantity2: self.uniform(10, 20) as i32,
+            quantity3: self.uniform(20, 30) as i32,
+            brand1: self.brand(),
+            brand2: self.brand(),
+            brand3: self.brand(),
+        }
+    }
+
+    fn brand(&mut self) -> String {
+        format!("Brand#{}{}", self.uniform(1, 5), self.uniform(1, 5))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qgen() {
+        assert_eq!(QGen::new(7).query_19(), QGen::new(7).query_19());
+
+        let mut qgen = QGen::new(42);
+        for _ in 0..1000 {
+            let q1 = qgen.query_1();
+            assert!((60..=120).contains(&q1.delta));
+
+            let q4 = qgen.query_4();
+            assert!(q4.date >= NaiveDate::from_ymd_opt(1993, 1, 1).unwrap());
+            assert!(q4.date <= NaiveDate::from_ymd_opt(1997, 10, 1).unwrap());
+            assert_eq!(q4.date.format("%d").to_string(), "01");
+
+            let q19 = qgen.query_19();
+            assert!((1..=10).contains(&q19.quantity1));
+            assert!((10..=20).contains(&q19.quantity2));
+            assert!((20..=30).contains(&q19.quantity3));
+            for brand in [&q19.brand1, &q19.brand2, &q19.brand3] {
+                let digits: Vec<char> = brand.trim_start_matches("Brand#").chars().collect();
+                assert_eq!(digits.len(), 2, "{}", brand);
+                assert!(digits.iter().all(|d| ('1'..='5').contains(d)), "{}", brand);
+            }
+        }
+    }
+}
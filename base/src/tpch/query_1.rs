@@ -1,62 +1,250 @@
 use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
 
-use chrono::NaiveDate;
-use duckdb::Connection;
+use chrono::{Duration, NaiveDate};
+use duckdb::arrow::datatypes::{Date32Type, Float64Type};
+use duckdb::arrow::record_batch::RecordBatch;
+use duckdb::{params, Connection};
 use itertools::Itertools;
 
-use super::util::to_date;
+use super::decimal::{Decimal, Numeric};
+use super::load::{LoadError, LoadMode, Loaded};
+use super::parallel::{morsels, Parallelism};
+use super::refresh::{self, Delta};
+use super::scan::FromBatch;
+use super::tbl::{self, Fields, FromTbl, TblError};
+use super::util::{char_value, decimal_column, get_char, load_batches, primitive_column, string_column, to_date, try_load_rows};
+use super::answers::{parse_field, FromAnswer};
+use super::verify::{ApproxEq, Tolerance};
 
-pub struct LineItem {
+/// Substitution parameters of Query 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryParams {
+    /// l_shipdate <= date '1998-12-01' - interval DELTA day
+    pub delta: i64,
+}
+
+impl QueryParams {
+    pub fn shipdate(&self) -> NaiveDate {
+        NaiveDate::from_ymd_opt(1998, 12, 1).unwrap() - Duration::days(self.delta)
+    }
+}
+
+/// The validation parameters, the reference answers are computed with these.
+impl Default for QueryParams {
+    fn default() -> Self {
+        QueryParams { delta: 90 }
+    }
+}
+
+impl fmt::Display for QueryParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "delta={}", self.delta)
+    }
+}
+
+/// The money columns are `f64` by default and `Decimal` for exact arithmetic.
+#[derive(Clone)]
+pub struct LineItem<N = f64> {
     pub l_returnflag: char,
     pub l_linestatus: char,
-    pub l_quantity: f64,
-    pub l_extendedprice: f64,
-    pub l_discount: f64,
-    pub l_tax: f64,
+    pub l_quantity: N,
+    pub l_extendedprice: N,
+    pub l_discount: N,
+    pub l_tax: N,
     pub l_shipdate: NaiveDate,
 }
 
 impl LineItem {
-    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
-        // Create iterator for LineItem
-        let query = match limit {
-            Some(limit) => format!("SELECT l_returnflag, l_linestatus, l_quantity, l_extendedprice, l_discount, l_tax, l_shipdate FROM lineitem LIMIT {};", limit),
-            None => "SELECT l_returnflag, l_linestatus, l_quantity, l_extendedprice, l_discount, l_tax, l_shipdate FROM lineitem;".to_string(),
-        };
-        let mut stmt = conn
-            .prepare(&query)
-            .expect("Error preparing query for LineItem");
-        let line_items = stmt
-            .query_map([], |row| {
-                Ok(LineItem {
-                    l_returnflag: row.get::<_, String>(0)?.chars().next().unwrap(),
-                    l_linestatus: row.get::<_, String>(1)?.chars().next().unwrap(),
-                    l_quantity: row.get(2)?,
-                    l_extendedprice: row.get(3)?,
-                    l_discount: row.get(4)?,
-                    l_tax: row.get(5)?,
-                    l_shipdate: to_date(row.get(6)?),
-                })
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Result<Vec<Self>, LoadError> {
+        Ok(LineItem::load_with(conn, limit, LoadMode::Strict)?.rows)
+    }
+
+    pub fn load_with(conn: &Connection, limit: Option<u32>, mode: LoadMode) -> Result<Loaded<Self>, LoadError> {
+        LineItem::load_table_with(conn, "lineitem", limit, mode)
+    }
+
+    /// Same as `load`, from another table with the columns of lineitem, e.g. a refresh staging table.
+    pub fn load_table(conn: &Connection, table: &str) -> Result<Vec<Self>, LoadError> {
+        Ok(LineItem::load_table_with(conn, table, None, LoadMode::Strict)?.rows)
+    }
+
+    fn load_table_with(conn: &Connection, table: &str, limit: Option<u32>, mode: LoadMode) -> Result<Loaded<Self>, LoadError> {
+        let columns = ["l_returnflag", "l_linestatus", "l_quantity", "l_extendedprice", "l_discount", "l_tax", "l_shipdate"];
+        try_load_rows(conn, table, &columns, limit, mode, |row| {
+            Ok(LineItem {
+                l_returnflag: get_char(row, 0)?,
+                l_linestatus: get_char(row, 1)?,
+                l_quantity: row.get(2)?,
+                l_extendedprice: row.get(3)?,
+                l_discount: row.get(4)?,
+                l_tax: row.get(5)?,
+                l_shipdate: to_date(row.get(6)?),
             })
-            .expect("Error querying LineItem");
+        })
+    }
 
-        let line_items = line_items.filter(|x| x.is_ok()).map(|x| x.unwrap());
+    /// Same as `load`, but converts whole Arrow record batches instead of one row at a time.
+    pub fn load_arrow(conn: &Connection, limit: Option<u32>) -> Result<Vec<Self>, LoadError> {
+        load_batches(conn, Self::TABLE, Self::COLUMNS, limit, Self::from_batch)
+    }
+}
 
-        line_items.collect()
+impl FromBatch for LineItem {
+    fn from_batch(batch: &RecordBatch, line_items: &mut Vec<Self>) -> duckdb::Result<()> {
+        let l_returnflag = string_column(batch, 0)?;
+        let l_linestatus = string_column(batch, 1)?;
+        let l_quantity = primitive_column::<Float64Type>(batch, 2)?;
+        let l_extendedprice = primitive_column::<Float64Type>(batch, 3)?;
+        let l_discount = primitive_column::<Float64Type>(batch, 4)?;
+        let l_tax = primitive_column::<Float64Type>(batch, 5)?;
+        let l_shipdate = primitive_column::<Date32Type>(batch, 6)?;
+        for i in 0..batch.num_rows() {
+            line_items.push(LineItem {
+                l_returnflag: char_value(l_returnflag.value(i), 0)?,
+                l_linestatus: char_value(l_linestatus.value(i), 1)?,
+                l_quantity: l_quantity.value(i),
+                l_extendedprice: l_extendedprice.value(i),
+                l_discount: l_discount.value(i),
+                l_tax: l_tax.value(i),
+                l_shipdate: to_date(l_shipdate.value(i)),
+            });
+        }
+        Ok(())
     }
 }
 
-pub struct LineItem2 {
+impl LineItem<Decimal> {
+    /// Same as `load_arrow`, with the DECIMAL columns as `Decimal`.
+    pub fn load_decimal(conn: &Connection, limit: Option<u32>) -> Result<Vec<Self>, LoadError> {
+        let columns = ["l_returnflag", "l_linestatus", "l_quantity", "l_extendedprice", "l_discount", "l_tax", "l_shipdate"];
+        load_batches(conn, "lineitem", &columns, limit, |batch, line_items| {
+            let l_returnflag = string_column(batch, 0)?;
+            let l_linestatus = string_column(batch, 1)?;
+            let l_quantity = decimal_column(batch, 2)?;
+            let l_extendedprice = decimal_column(batch, 3)?;
+            let l_discount = decimal_column(batch, 4)?;
+            let l_tax = decimal_column(batch, 5)?;
+            let l_shipdate = primitive_column::<Date32Type>(batch, 6)?;
+            for i in 0..batch.num_rows() {
+                line_items.push(LineItem {
+                    l_returnflag: char_value(l_returnflag.value(i), 0)?,
+                    l_linestatus: char_value(l_linestatus.value(i), 1)?,
+                    l_quantity: Decimal::from_units(l_quantity.value(i)),
+                    l_extendedprice: Decimal::from_units(l_extendedprice.value(i)),
+                    l_discount: Decimal::from_units(l_discount.value(i)),
+                    l_tax: Decimal::from_units(l_tax.value(i)),
+                    l_shipdate: to_date(l_shipdate.value(i)),
+                });
+            }
+            Ok(())
+        })
+    }
+}
+
+impl<N: Numeric + FromStr> FromTbl for LineItem<N>
+where
+    N::Err: fmt::Display,
+{
+    const TABLE: &'static str = "lineitem";
+    const COLUMNS: &'static [&'static str] = &["l_returnflag", "l_linestatus", "l_quantity", "l_extendedprice", "l_discount", "l_tax", "l_shipdate"];
+
+    fn from_tbl(fields: &Fields<'_>) -> Result<Self, TblError> {
+        Ok(LineItem {
+            l_returnflag: fields.char(0)?,
+            l_linestatus: fields.char(1)?,
+            l_quantity: fields.parse(2)?,
+            l_extendedprice: fields.parse(3)?,
+            l_discount: fields.parse(4)?,
+            l_tax: fields.parse(5)?,
+            l_shipdate: fields.parse(6)?,
+        })
+    }
+}
+
+/// Struct-of-arrays layout of `LineItem`, one `Vec` per column.
+#[derive(Debug, Clone, Default)]
+pub struct LineItemColumns {
+    pub l_returnflag: Vec<char>,
+    pub l_linestatus: Vec<char>,
+    pub l_quantity: Vec<f64>,
+    pub l_extendedprice: Vec<f64>,
+    pub l_discount: Vec<f64>,
+    pub l_tax: Vec<f64>,
+    pub l_shipdate: Vec<NaiveDate>,
+}
+
+/// A batch of rows of `LineItemColumns`, one slice per column.
+#[derive(Debug, Clone, Copy)]
+pub struct LineItemChunk<'a> {
+    pub l_returnflag: &'a [char],
+    pub l_linestatus: &'a [char],
+    pub l_quantity: &'a [f64],
+    pub l_extendedprice: &'a [f64],
+    pub l_discount: &'a [f64],
+    pub l_tax: &'a [f64],
+    pub l_shipdate: &'a [NaiveDate],
+}
+
+impl LineItemColumns {
+    pub fn len(&self) -> usize {
+        self.l_shipdate.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.l_shipdate.is_empty()
+    }
+
+    pub fn push(&mut self, x: LineItem) {
+        self.l_returnflag.push(x.l_returnflag);
+        self.l_linestatus.push(x.l_linestatus);
+        self.l_quantity.push(x.l_quantity);
+        self.l_extendedprice.push(x.l_extendedprice);
+        self.l_discount.push(x.l_discount);
+        self.l_tax.push(x.l_tax);
+        self.l_shipdate.push(x.l_shipdate);
+    }
+
+    /// The rows in batches of `chunk_size`, the last one may be shorter.
+    pub fn chunks(&self, chunk_size: usize) -> impl Iterator<Item = LineItemChunk<'_>> {
+        (0..self.len()).step_by(chunk_size).map(move |start| {
+            let rows = start..(start + chunk_size).min(self.len());
+            LineItemChunk {
+                l_returnflag: &self.l_returnflag[rows.clone()],
+                l_linestatus: &self.l_linestatus[rows.clone()],
+                l_quantity: &self.l_quantity[rows.clone()],
+                l_extendedprice: &self.l_extendedprice[rows.clone()],
+                l_discount: &self.l_discount[rows.clone()],
+                l_tax: &self.l_tax[rows.clone()],
+                l_shipdate: &self.l_shipdate[rows],
+            }
+        })
+    }
+}
+
+impl FromIterator<LineItem> for LineItemColumns {
+    fn from_iter<I: IntoIterator<Item = LineItem>>(iter: I) -> Self {
+        let mut columns = LineItemColumns::default();
+        for x in iter {
+            columns.push(x);
+        }
+        columns
+    }
+}
+
+pub struct LineItem2<N = f64> {
     pub l_returnflag: char,
     pub l_linestatus: char,
-    pub l_quantity: f64,
-    pub l_extendedprice: f64,
-    pub l_discount: f64,
-    pub l_tax: f64,
+    pub l_quantity: N,
+    pub l_extendedprice: N,
+    pub l_discount: N,
+    pub l_tax: N,
 }
 
-impl Into<LineItem2> for LineItem {
-    fn into(self) -> LineItem2 {
+impl<N> Into<LineItem2<N>> for LineItem<N> {
+    fn into(self) -> LineItem2<N> {
         LineItem2 {
             l_returnflag: self.l_returnflag,
             l_linestatus: self.l_linestatus,
@@ -68,103 +256,246 @@ impl Into<LineItem2> for LineItem {
     }
 }
 
-pub struct LineItemAgg1 {
-    pub sum_qty: f64,
-    pub sum_base_price: f64,
-    pub sum_disc_price: f64,
-    pub sum_charge: f64,
+#[derive(Debug, Clone)]
+pub struct LineItemAgg1<N = f64> {
+    pub sum_qty: N,
+    pub sum_base_price: N,
+    pub sum_disc_price: N,
+    pub sum_charge: N,
+    pub sum_discount: N,
     pub count_order: u64,
 }
 
-impl Default for LineItemAgg1 {
+impl<N: Numeric> Default for LineItemAgg1<N> {
     fn default() -> Self {
         LineItemAgg1 {
-            sum_qty: 0.0,
-            sum_base_price: 0.0,
-            sum_disc_price: 0.0,
-            sum_charge: 0.0,
+            sum_qty: N::default(),
+            sum_base_price: N::default(),
+            sum_disc_price: N::default(),
+            sum_charge: N::default(),
+            sum_discount: N::default(),
             count_order: 0,
         }
     }
 }
 
+impl<N: Numeric> LineItemAgg1<N> {
+    /// Add a line item that passed the filter, with its disc_price and charge.
+    pub fn add(&mut self, x: &LineItem<N>) {
+        // l_extendedprice * (1 - l_discount) AS disc_price,
+        let disc_price = x.l_extendedprice * (N::one() - x.l_discount);
+        // l_extendedprice * (1 - l_discount) * (1 + l_tax) AS charge,
+        let charge = disc_price * (N::one() + x.l_tax);
+        self.sum_qty += x.l_quantity;
+        self.sum_base_price += x.l_extendedprice;
+        self.sum_disc_price += disc_price;
+        self.sum_charge += charge;
+        self.sum_discount += x.l_discount;
+        self.count_order += 1;
+    }
+
+    /// Take back a line item added before, e.g. one deleted by a refresh.
+    pub fn remove(&mut self, x: &LineItem<N>) {
+        let disc_price = x.l_extendedprice * (N::one() - x.l_discount);
+        let charge = disc_price * (N::one() + x.l_tax);
+        self.sum_qty = self.sum_qty - x.l_quantity;
+        self.sum_base_price = self.sum_base_price - x.l_extendedprice;
+        self.sum_disc_price = self.sum_disc_price - disc_price;
+        self.sum_charge = self.sum_charge - charge;
+        self.sum_discount = self.sum_discount - x.l_discount;
+        self.count_order -= 1;
+    }
+
+    /// Merge the partial aggregate of another thread.
+    pub fn merge(&mut self, other: LineItemAgg1<N>) {
+        self.sum_qty += other.sum_qty;
+        self.sum_base_price += other.sum_base_price;
+        self.sum_disc_price += other.sum_disc_price;
+        self.sum_charge += other.sum_charge;
+        self.sum_discount += other.sum_discount;
+        self.count_order += other.count_order;
+    }
+}
+
+/// The averages are `f64` like DuckDB's `avg` of a DECIMAL.
 #[derive(Debug)]
-pub struct LineItemAgg2 {
-    pub sum_qty: f64,
-    pub sum_base_price: f64,
-    pub sum_disc_price: f64,
-    pub sum_charge: f64,
+pub struct LineItemAgg2<N = f64> {
+    pub sum_qty: N,
+    pub sum_base_price: N,
+    pub sum_disc_price: N,
+    pub sum_charge: N,
     pub avg_qty: f64,
     pub avg_price: f64,
     pub avg_disc: f64,
     pub count_order: u64,
 }
 
-impl From<LineItemAgg1> for LineItemAgg2 {
-    fn from(x: LineItemAgg1) -> LineItemAgg2 {
+impl<N: Numeric> From<LineItemAgg1<N>> for LineItemAgg2<N> {
+    fn from(x: LineItemAgg1<N>) -> LineItemAgg2<N> {
         LineItemAgg2 {
             sum_qty: x.sum_qty,
             sum_base_price: x.sum_base_price,
             sum_disc_price: x.sum_disc_price,
             sum_charge: x.sum_charge,
-            avg_qty: x.sum_qty / x.count_order as f64,
-            avg_price: x.sum_base_price / x.count_order as f64,
-            avg_disc: x.sum_disc_price / x.count_order as f64,
+            avg_qty: x.sum_qty.to_f64() / x.count_order as f64,
+            avg_price: x.sum_base_price.to_f64() / x.count_order as f64,
+            avg_disc: x.sum_discount.to_f64() / x.count_order as f64,
+            count_order: x.count_order,
+        }
+    }
+}
+
+/// Result row of Query 1, ordered by (l_returnflag, l_linestatus).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Q1Row<N = f64> {
+    pub l_returnflag: char,
+    pub l_linestatus: char,
+    pub sum_qty: N,
+    pub sum_base_price: N,
+    pub sum_disc_price: N,
+    pub sum_charge: N,
+    pub avg_qty: f64,
+    pub avg_price: f64,
+    pub avg_disc: f64,
+    pub count_order: u64,
+}
+
+impl<N> From<((char, char), LineItemAgg2<N>)> for Q1Row<N> {
+    fn from(((l_returnflag, l_linestatus), x): ((char, char), LineItemAgg2<N>)) -> Q1Row<N> {
+        Q1Row {
+            l_returnflag,
+            l_linestatus,
+            sum_qty: x.sum_qty,
+            sum_base_price: x.sum_base_price,
+            sum_disc_price: x.sum_disc_price,
+            sum_charge: x.sum_charge,
+            avg_qty: x.avg_qty,
+            avg_price: x.avg_price,
+            avg_disc: x.avg_disc,
             count_order: x.count_order,
         }
     }
 }
 
-pub fn load(conn: &Connection) -> Vec<LineItem> {
+impl ApproxEq for Q1Row {
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+        self.l_returnflag == other.l_returnflag
+            && self.l_linestatus == other.l_linestatus
+            && tolerance.eq_f64(self.sum_qty, other.sum_qty)
+            && tolerance.eq_f64(self.sum_base_price, other.sum_base_price)
+            && tolerance.eq_f64(self.sum_disc_price, other.sum_disc_price)
+            && tolerance.eq_f64(self.sum_charge, other.sum_charge)
+            && tolerance.eq_f64(self.avg_qty, other.avg_qty)
+            && tolerance.eq_f64(self.avg_price, other.avg_price)
+            && tolerance.eq_f64(self.avg_disc, other.avg_disc)
+            && self.count_order == other.count_order
+    }
+}
+
+/// The sums are exact, the averages are `f64` in DuckDB as well.
+impl ApproxEq for Q1Row<Decimal> {
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+        self.l_returnflag == other.l_returnflag
+            && self.l_linestatus == other.l_linestatus
+            && self.sum_qty == other.sum_qty
+            && self.sum_base_price == other.sum_base_price
+            && self.sum_disc_price == other.sum_disc_price
+            && self.sum_charge == other.sum_charge
+            && tolerance.eq_f64(self.avg_qty, other.avg_qty)
+            && tolerance.eq_f64(self.avg_price, other.avg_price)
+            && tolerance.eq_f64(self.avg_disc, other.avg_disc)
+            && self.count_order == other.count_order
+    }
+}
+
+impl FromAnswer for Q1Row {
+    fn from_answer(fields: &[&str]) -> Self {
+        Q1Row {
+            l_returnflag: parse_field(fields, 0),
+            l_linestatus: parse_field(fields, 1),
+            sum_qty: parse_field(fields, 2),
+            sum_base_price: parse_field(fields, 3),
+            sum_disc_price: parse_field(fields, 4),
+            sum_charge: parse_field(fields, 5),
+            avg_qty: parse_field(fields, 6),
+            avg_price: parse_field(fields, 7),
+            avg_disc: parse_field(fields, 8),
+            count_order: parse_field(fields, 9),
+        }
+    }
+}
+
+pub fn load(conn: &Connection) -> Result<Vec<LineItem>, LoadError> {
     LineItem::load(conn, None)
 }
 
-pub fn query(line_items: Vec<LineItem>) {
+pub fn load_arrow(conn: &Connection) -> Result<Vec<LineItem>, LoadError> {
+    LineItem::load_arrow(conn, None)
+}
+
+pub fn load_decimal(conn: &Connection) -> Result<Vec<LineItem<Decimal>>, LoadError> {
+    LineItem::load_decimal(conn, None)
+}
+
+/// Reads `lineitem.tbl` in `dir` instead of scanning DuckDB.
+pub fn load_tbl(dir: &Path) -> Result<Vec<LineItem>, TblError> {
+    tbl::read(dir)
+}
+
+/// The line items the staged refresh pair inserts and deletes, see `refresh::RefreshStream`.
+pub fn load_refresh(conn: &Connection) -> Result<Delta<LineItem>, LoadError> {
+    Ok(Delta {
+        inserted: LineItem::load_table(conn, refresh::RF1_LINEITEM)?,
+        deleted: LineItem::load_table(conn, refresh::RF2_LINEITEM)?,
+    })
+}
+
+pub fn load_columns(conn: &Connection) -> Result<LineItemColumns, LoadError> {
+    Ok(LineItem::load_arrow(conn, None)?.into_iter().collect())
+}
+
+pub fn query<N: Numeric>(line_items: Vec<LineItem<N>>, params: &QueryParams) -> Vec<Q1Row<N>> {
+    let shipdate = params.shipdate();
 
     // 1. Scan from lineitem: "l_returnflag", "l_linestatus", "l_quantity", "l_extendedprice", "l_discount", "l_tax", "l_shipdate"
     let line_items = line_items.into_iter();
 
-    // 2. Filter l_shipdate <= date '1998-12-01' - interval '90' day i.e., l_shipdate <= '1998-09-02'.
+    // 2. Filter l_shipdate <= date '1998-12-01' - interval DELTA day, i.e., l_shipdate <= '1998-09-02' for DELTA = 90.
     // (2.1 Evaluate expression.)
     // 2.2. Filter on the expression. Need everything apart from l_shipdate.
-    let line_items_filtered = line_items.filter(|x| x.l_shipdate <= NaiveDate::from_ymd_opt(1998, 9, 2).unwrap());
+    let line_items_filtered = line_items.filter(|x| x.l_shipdate <= shipdate);
 
     // 3. Evaluate expressions for the aggregations.
     // XXX: Skipping projection of l_tax, cloud drop that column.
     let line_items_proj = line_items_filtered.map(|x| {
         // Project the fields.
-        let x: LineItem2 = x.into();
+        let x: LineItem2<N> = x.into();
         // l_extendedprice * (1 - l_discount) AS disc_price,
-        let disc_price = x.l_extendedprice * (1.0 - x.l_discount);
+        let disc_price = x.l_extendedprice * (N::one() - x.l_discount);
         // l_extendedprice * (1 - l_discount) * (1 + l_tax) AS charge,
-        let charge = disc_price * (1.0 + x.l_tax);
+        let charge = disc_price * (N::one() + x.l_tax);
         return (x, disc_price, charge)
     });
 
     // 4. Group by l_returnflag, l_linestatus & compute aggregates.
     let agg = line_items_proj.map(|(x, disc_price, charge)| {
         // Group by l_returnflag, l_linestatus
-        ((x.l_returnflag, x.l_linestatus), x.l_quantity, x.l_extendedprice, disc_price, charge)
+        ((x.l_returnflag, x.l_linestatus), x.l_quantity, x.l_extendedprice, x.l_discount, disc_price, charge)
     }).fold(HashMap::new(), |mut acc, x| {
         // Hash aggregate without average
-        let (key, l_quantity, l_extendedprice, disc_price, charge) = x;
-        let entry = acc.entry(key).or_insert(LineItemAgg1 {
-            sum_qty: 0.0,
-            sum_base_price: 0.0,
-            sum_disc_price: 0.0,
-            sum_charge: 0.0,
-            count_order: 0,
-        });
+        let (key, l_quantity, l_extendedprice, l_discount, disc_price, charge) = x;
+        let entry: &mut LineItemAgg1<N> = acc.entry(key).or_default();
         entry.sum_qty += l_quantity;
         entry.sum_base_price += l_extendedprice;
         entry.sum_disc_price += disc_price;
         entry.sum_charge += charge;
+        entry.sum_discount += l_discount;
         entry.count_order += 1;
         acc
     }).into_iter().map(|(key, value)| {
         // Finalize aggregation with average
         let (l_returnflag, l_linestatus) = key;
-        let value: LineItemAgg2 = value.into();
+        let value: LineItemAgg2<N> = value.into();
         ((l_returnflag, l_linestatus), value)
     });
 
@@ -172,25 +503,100 @@ pub fn query(line_items: Vec<LineItem>) {
     // XXX: InkFuse is skipping this step.
     let ordered = agg.sorted_by_key(|x| x.0);
 
-    // Attach the sink for printing.
-    ordered.for_each(|x| {
-        let ((l_returnflag, l_linestatus), x) = x;
-        println!("{}, {}, {}, {}, {}, {}, {}, {}, {}, {}",
-                 l_returnflag,
-                 l_linestatus,
-                 x.sum_qty,
-                 x.sum_base_price,
-                 x.sum_disc_price,
-                 x.sum_charge,
-                 x.avg_qty,
-                 x.avg_price,
-                 x.avg_disc,
-                 x.count_order,
-        );
+    // Collect the typed result rows.
+    ordered.map(Q1Row::from).collect()
+}
+
+/**
+ * Morsel-driven: every thread filters and aggregates morsels of the line items into a partial hash
+ * aggregate, the partial aggregates are merged before the averages are finalized.
+ */
+pub fn query_parallel<N: Numeric + Send + Sync>(line_items: Vec<LineItem<N>>, params: &QueryParams, parallelism: &Parallelism) -> Vec<Q1Row<N>> {
+    let shipdate = params.shipdate();
+
+    // 1. - 4. Scan, filter and aggregate the morsels into one partial aggregate per thread.
+    let partials = morsels(&line_items, parallelism, HashMap::new, |groups: &mut HashMap<(char, char), LineItemAgg1<N>>, morsel| {
+        for x in morsel.iter().filter(|x| x.l_shipdate <= shipdate) {
+            groups.entry((x.l_returnflag, x.l_linestatus)).or_default().add(x);
+        }
     });
+
+    // 5. Merge the partial aggregates.
+    let mut groups: HashMap<(char, char), LineItemAgg1<N>> = HashMap::new();
+    for (key, value) in partials.into_iter().flatten() {
+        groups.entry(key).or_default().merge(value);
+    }
+
+    // 6. Finalize the averages and sort by l_returnflag, l_linestatus.
+    groups
+        .into_iter()
+        .map(|(key, value)| (key, LineItemAgg2::from(value)))
+        .sorted_by_key(|x| x.0)
+        .map(Q1Row::from)
+        .collect()
+}
+
+/**
+ * The selection vector of a chunk: the positions of the rows with l_shipdate <= `shipdate`.
+ */
+pub fn select_chunk(chunk: &LineItemChunk<'_>, shipdate: NaiveDate) -> Vec<usize> {
+    let mut selection = Vec::with_capacity(chunk.l_shipdate.len());
+    selection.extend((0..chunk.l_shipdate.len()).filter(|&i| chunk.l_shipdate[i] <= shipdate));
+    selection
+}
+
+/**
+ * Aggregate the selected rows of a chunk into `groups`. The expressions are evaluated one column
+ * at a time over the selection, only the group by looks at whole rows.
+ */
+pub fn aggregate_chunk(chunk: &LineItemChunk<'_>, selection: &[usize], groups: &mut HashMap<(char, char), LineItemAgg1>) {
+    // l_extendedprice * (1 - l_discount) AS disc_price,
+    let disc_price: Vec<f64> = selection
+        .iter()
+        .map(|&i| chunk.l_extendedprice[i] * (1.0 - chunk.l_discount[i]))
+        .collect();
+    // l_extendedprice * (1 - l_discount) * (1 + l_tax) AS charge,
+    let charge: Vec<f64> = selection
+        .iter()
+        .zip(&disc_price)
+        .map(|(&i, disc_price)| disc_price * (1.0 + chunk.l_tax[i]))
+        .collect();
+
+    for (j, &i) in selection.iter().enumerate() {
+        let entry = groups.entry((chunk.l_returnflag[i], chunk.l_linestatus[i])).or_default();
+        entry.sum_qty += chunk.l_quantity[i];
+        entry.sum_base_price += chunk.l_extendedprice[i];
+        entry.sum_disc_price += disc_price[j];
+        entry.sum_charge += charge[j];
+        entry.sum_discount += chunk.l_discount[i];
+        entry.count_order += 1;
+    }
+}
+
+/**
+ * Columnar: the line items are processed in column chunks of `CHUNK_SIZE` rows with a selection
+ * vector, see `select_chunk` and `aggregate_chunk`.
+ */
+pub fn query_columns<const CHUNK_SIZE: usize>(line_items: LineItemColumns, params: &QueryParams) -> Vec<Q1Row> {
+    let shipdate = params.shipdate();
+
+    // 1. - 4. Scan, filter and aggregate chunk by chunk.
+    let mut groups = HashMap::new();
+    for chunk in line_items.chunks(CHUNK_SIZE) {
+        let selection = select_chunk(&chunk, shipdate);
+        aggregate_chunk(&chunk, &selection, &mut groups);
+    }
+
+    // 5. Finalize the averages and sort by l_returnflag, l_linestatus.
+    groups
+        .into_iter()
+        .map(|(key, value)| (key, LineItemAgg2::from(value)))
+        .sorted_by_key(|x| x.0)
+        .map(Q1Row::from)
+        .collect()
 }
 
-pub fn query_duckdb(conn: &Connection, limit: Option<u32>) {
+pub fn query_duckdb(conn: &Connection, limit: Option<u32>, params: &QueryParams) -> Vec<Q1Row> {
     let table = match limit {
         Some(limit) => format!("(SELECT * FROM lineitem LIMIT {})", limit),
         None => "lineitem".to_string(),
@@ -210,7 +616,58 @@ pub fn query_duckdb(conn: &Connection, limit: Option<u32>) {
         FROM
             {}
         WHERE
-            l_shipdate <= CAST('1998-09-02' AS date)
+            l_shipdate <= CAST(? AS date)
+        GROUP BY
+            l_returnflag,
+            l_linestatus
+        ORDER BY
+            l_returnflag,
+            l_linestatus;
+    "#, table)).expect("Error preparing query for LineItem");
+    let mut rows = stmt
+        .query(params![params.shipdate().to_string()])
+        .expect("Error executing Query 1");
+    let mut result = vec![];
+    while let Some(row) = rows.next().unwrap() {
+        result.push(Q1Row {
+            l_returnflag: get_char(row, 0).unwrap(),
+            l_linestatus: get_char(row, 1).unwrap(),
+            sum_qty: row.get(2).unwrap(),
+            sum_base_price: row.get(3).unwrap(),
+            sum_disc_price: row.get(4).unwrap(),
+            sum_charge: row.get(5).unwrap(),
+            avg_qty: row.get(6).unwrap(),
+            avg_price: row.get(7).unwrap(),
+            avg_disc: row.get(8).unwrap(),
+            count_order: row.get(9).unwrap(),
+        });
+    }
+    result
+}
+
+/// Same as `query_duckdb`, with the exact DECIMAL sums. They are fetched as VARCHAR, duckdb-rs
+/// converts DECIMAL values to `f64`.
+pub fn query_duckdb_decimal(conn: &Connection, limit: Option<u32>, params: &QueryParams) -> Vec<Q1Row<Decimal>> {
+    let table = match limit {
+        Some(limit) => format!("(SELECT * FROM lineitem LIMIT {})", limit),
+        None => "lineitem".to_string(),
+    };
+    let mut stmt = conn.prepare(&format!(r#"
+        SELECT
+            l_returnflag,
+            l_linestatus,
+            CAST(sum(l_quantity) AS VARCHAR) AS sum_qty,
+            CAST(sum(l_extendedprice) AS VARCHAR) AS sum_base_price,
+            CAST(sum(l_extendedprice * (1 - l_discount)) AS VARCHAR) AS sum_disc_price,
+            CAST(sum(l_extendedprice * (1 - l_discount) * (1 + l_tax)) AS VARCHAR) AS sum_charge,
+            avg(l_quantity) AS avg_qty,
+            avg(l_extendedprice) AS avg_price,
+            avg(l_discount) AS avg_disc,
+            count(*) AS count_order
+        FROM
+            {}
+        WHERE
+            l_shipdate <= CAST(? AS date)
         GROUP BY
             l_returnflag,
             l_linestatus
@@ -218,48 +675,205 @@ pub fn query_duckdb(conn: &Connection, limit: Option<u32>) {
             l_returnflag,
             l_linestatus;
     "#, table)).expect("Error preparing query for LineItem");
-    let mut rows = stmt.query([]).expect("Error executing Query 1");
+    let mut rows = stmt
+        .query(params![params.shipdate().to_string()])
+        .expect("Error executing Query 1");
+    let decimal = |value: String| value.parse::<Decimal>().unwrap_or_else(|e| panic!("{}", e));
+    let mut result = vec![];
     while let Some(row) = rows.next().unwrap() {
-        let l_returnflag: char = row.get::<_, String>(0).unwrap().chars().next().unwrap();
-        let l_linestatus: char = row.get::<_, String>(1).unwrap().chars().next().unwrap();
-        let sum_qty: f64 = row.get(2).unwrap();
-        let sum_base_price: f64 = row.get(3).unwrap();
-        let sum_disc_price: f64 = row.get(4).unwrap();
-        let sum_charge: f64 = row.get(5).unwrap();
-        let avg_qty: f64 = row.get(6).unwrap();
-        let avg_price: f64 = row.get(7).unwrap();
-        let avg_disc: f64 = row.get(8).unwrap();
-        let count_order: u64 = row.get(9).unwrap();
-        println!("{}, {}, {}, {}, {}, {}, {}, {}, {}, {}",
-                 l_returnflag,
-                 l_linestatus,
-                 sum_qty,
-                 sum_base_price,
-                 sum_disc_price,
-                 sum_charge,
-                 avg_qty,
-                 avg_price,
-                 avg_disc,
-                 count_order,
-        );
+        result.push(Q1Row {
+            l_returnflag: get_char(row, 0).unwrap(),
+            l_linestatus: get_char(row, 1).unwrap(),
+            sum_qty: decimal(row.get(2).unwrap()),
+            sum_base_price: decimal(row.get(3).unwrap()),
+            sum_disc_price: decimal(row.get(4).unwrap()),
+            sum_charge: decimal(row.get(5).unwrap()),
+            avg_qty: row.get(6).unwrap(),
+            avg_price: row.get(7).unwrap(),
+            avg_disc: row.get(8).unwrap(),
+            count_order: row.get(9).unwrap(),
+        });
     }
+    result
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::tpch::initialize::initialize_database;
+    use crate::tpch::answers::{answer_tolerance, expected};
+    use crate::tpch::verify::{assert_match, compare};
 
     #[test]
     fn test_query_1() {
-        let conn = initialize_database(1);
-        let line_items = LineItem::load(&conn, Some(1000));
-        query(line_items);
+        let conn = initialize_database(0.01);
+        let line_items = LineItem::load(&conn, None).unwrap();
+        let result = query(line_items, &QueryParams::default());
+
+        let expected = query_duckdb(&conn, None, &QueryParams::default());
+        assert_match(&[compare("Q1", "duckdb", "baseline", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_load_mode() {
+        // The second row has an empty l_returnflag, which is no char.
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            r#"
+            CREATE TABLE lineitem (l_returnflag VARCHAR, l_linestatus VARCHAR, l_quantity DOUBLE, l_extendedprice DOUBLE, l_discount DOUBLE, l_tax DOUBLE, l_shipdate DATE);
+            INSERT INTO lineitem VALUES
+                ('N', 'O', 1, 10, 0.1, 0.01, DATE '1996-03-13'),
+                ('', 'O', 2, 20, 0.1, 0.01, DATE '1996-04-12'),
+                ('R', 'F', 3, 30, 0.1, 0.01, DATE '1994-02-02');
+            "#,
+        )
+        .unwrap();
+
+        match LineItem::load(&conn, None) {
+            Err(LoadError::Conversion { table, column, row, .. }) => {
+                assert_eq!(table, "lineitem");
+                assert_eq!(column.as_deref(), Some("l_returnflag"));
+                assert_eq!(row, 1);
+            }
+            other => panic!("Expected a conversion error, got {:?}", other.map(|line_items| line_items.len())),
+        }
+
+        let loaded = LineItem::load_with(&conn, None, LoadMode::Lenient).unwrap();
+        assert_eq!(loaded.rows.iter().map(|l| l.l_returnflag).collect::<Vec<_>>(), vec!['N', 'R']);
+        assert_eq!(loaded.dropped, 1);
+
+        // The Arrow loaders fail on the same row.
+        let loads = [LineItem::load_arrow(&conn, None).map(|line_items| line_items.len()), LineItem::load_decimal(&conn, None).map(|line_items| line_items.len())];
+        for load in loads {
+            match load {
+                Err(LoadError::Conversion { column, row, .. }) => {
+                    assert_eq!(column.as_deref(), Some("l_returnflag"));
+                    assert_eq!(row, 1);
+                }
+                other => panic!("Expected a conversion error, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_select_chunk() {
+        let line_item = |l_returnflag, l_shipdate: &str| LineItem {
+            l_returnflag,
+            l_linestatus: 'F',
+            l_quantity: 2.0,
+            l_extendedprice: 100.0,
+            l_discount: 0.1,
+            l_tax: 0.5,
+            l_shipdate: l_shipdate.parse().unwrap(),
+        };
+        let columns: LineItemColumns =
+            [line_item('A', "1998-01-01"), line_item('R', "1998-12-01"), line_item('A', "1994-01-01")].into_iter().collect();
+        let chunks: Vec<_> = columns.chunks(2).collect();
+        assert_eq!(chunks.iter().map(|chunk| chunk.l_shipdate.len()).collect::<Vec<_>>(), vec![2, 1]);
+
+        let shipdate = QueryParams::default().shipdate();
+        assert_eq!(select_chunk(&chunks[0], shipdate), vec![0]);
+        assert_eq!(select_chunk(&chunks[1], shipdate), vec![0]);
+
+        let mut groups = HashMap::new();
+        for chunk in &chunks {
+            aggregate_chunk(chunk, &select_chunk(chunk, shipdate), &mut groups);
+        }
+        let agg = &groups[&('A', 'F')];
+        assert_eq!((agg.count_order, agg.sum_qty, agg.sum_disc_price, agg.sum_charge), (2, 4.0, 180.0, 270.0));
+        assert!(!groups.contains_key(&('R', 'F')));
+    }
+
+    #[test]
+    fn test_query_1_columns() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+        let line_items = load_columns(&conn).unwrap();
+        assert_eq!(line_items.chunks(1024).map(|chunk| chunk.l_tax.len()).sum::<usize>(), line_items.len());
+
+        let result = query_columns::<1024>(line_items, &params);
+
+        let expected = query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q1", "duckdb", "baseline-columns", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_1_parallel() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+        let expected = query_duckdb(&conn, None, &params);
+        let expected_decimal = query_duckdb_decimal(&conn, None, &params);
+
+        for threads in [1, 2, 4] {
+            let parallelism = Parallelism::new(threads).with_morsel_size(1000);
+            let result = query_parallel(load_arrow(&conn).unwrap(), &params, &parallelism);
+            assert_match(&[compare("Q1", "duckdb", "baseline-parallel", &expected, &result, &Tolerance::default())]);
+
+            // The merge order of the partial aggregates does not change exact sums.
+            let result = query_parallel(load_decimal(&conn).unwrap(), &params, &parallelism);
+            assert_match(&[compare("Q1", "duckdb", "baseline-parallel-decimal", &expected_decimal, &result, &Tolerance::default())]);
+        }
+    }
+
+    #[test]
+    fn test_query_1_decimal() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+        let result = query(load_decimal(&conn).unwrap(), &params);
+
+        // Exact sums, only the averages are compared with a tolerance.
+        let expected = query_duckdb_decimal(&conn, None, &params);
+        assert_match(&[compare("Q1", "duckdb", "baseline-decimal", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_1_load_arrow() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+        let result = query(load_arrow(&conn).unwrap(), &params);
+
+        let expected = query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q1", "duckdb", "baseline-arrow", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_1_tbl() {
+        let conn = initialize_database(0.01);
+        let dir = std::env::temp_dir().join(format!("hydro_benchmarks_tbl_q1_{}", std::process::id()));
+        tbl::export(&conn, "lineitem", &dir);
+        let params = QueryParams::default();
+        let result = query(load_tbl(&dir).unwrap(), &params);
+
+        let expected = query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q1", "duckdb", "baseline-tbl", &expected, &result, &Tolerance::default())]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_query_1_params() {
+        assert_eq!(QueryParams::default().shipdate(), NaiveDate::from_ymd_opt(1998, 9, 2).unwrap());
+
+        let conn = initialize_database(0.01);
+        let params = QueryParams { delta: 120 };
+        let result = query(LineItem::load(&conn, None).unwrap(), &params);
+
+        let expected = query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q1", "duckdb", "baseline", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_1_answer() {
+        let conn = initialize_database(0.01);
+        let result = query(load(&conn).unwrap(), &QueryParams::default());
+
+        let expected = expected(&conn, 1, 0.01);
+        assert_match(&[compare("Q1", "tpch_answers", "baseline", &expected, &result, &answer_tolerance())]);
     }
 
     #[test]
     fn test_query_1_duckdb() {
-        let conn = initialize_database(1);
-        query_duckdb(&conn, Some(1000));
+        let conn = initialize_database(0.01);
+        let result = query_duckdb(&conn, None, &QueryParams::default());
+        assert!(!result.is_empty());
     }
 }
\ No newline at end of file
@@ -0,0 +1,325 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use duckdb::Connection;
+
+use super::answers::{parse_field, FromAnswer};
+use super::top_k::{Strategy, TopK};
+use super::util::{get_char, load_rows, to_date};
+use super::verify::{ApproxEq, Tolerance};
+
+#[derive(Debug, Clone)]
+pub struct Customer {
+    pub c_custkey: i64,
+    pub c_name: String,
+    pub c_address: String,
+    pub c_nationkey: i32,
+    pub c_phone: String,
+    pub c_acctbal: f64,
+    pub c_comment: String,
+}
+
+impl Customer {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(
+            conn,
+            "customer",
+            &["c_custkey", "c_name", "c_address", "c_nationkey", "c_phone", "c_acctbal", "c_comment"],
+            limit,
+            |row| {
+                Ok(Customer {
+                    c_custkey: row.get(0)?,
+                    c_name: row.get(1)?,
+                    c_address: row.get(2)?,
+                    c_nationkey: row.get(3)?,
+                    c_phone: row.get(4)?,
+                    c_acctbal: row.get(5)?,
+                    c_comment: row.get(6)?,
+                })
+            },
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Order {
+    pub o_orderkey: i64,
+    pub o_custkey: i64,
+    pub o_orderdate: NaiveDate,
+}
+
+impl Order {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "orders", &["o_orderkey", "o_custkey", "o_orderdate"], limit, |row| {
+            Ok(Order {
+                o_orderkey: row.get(0)?,
+                o_custkey: row.get(1)?,
+                o_orderdate: to_date(row.get(2)?),
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LineItem {
+    pub l_orderkey: i64,
+    pub l_extendedprice: f64,
+    pub l_discount: f64,
+    pub l_returnflag: char,
+}
+
+impl LineItem {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "lineitem", &["l_orderkey", "l_extendedprice", "l_discount", "l_returnflag"], limit, |row| {
+            Ok(LineItem {
+                l_orderkey: row.get(0)?,
+                l_extendedprice: row.get(1)?,
+                l_discount: row.get(2)?,
+                l_returnflag: get_char(row, 3)?,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Nation {
+    pub n_nationkey: i32,
+    pub n_name: String,
+}
+
+impl Nation {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "nation", &["n_nationkey", "n_name"], limit, |row| {
+            Ok(Nation {
+                n_nationkey: row.get(0)?,
+                n_name: row.get(1)?,
+            })
+        })
+    }
+}
+
+/// Result row of Query 10, ordered by revenue descending.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Q10Row {
+    pub c_custkey: i64,
+    pub c_name: String,
+    pub revenue: f64,
+    pub c_acctbal: f64,
+    pub n_name: String,
+    pub c_address: String,
+    pub c_phone: String,
+    pub c_comment: String,
+}
+
+impl Q10Row {
+    pub fn new(customer: Customer, revenue: f64, n_name: String) -> Q10Row {
+        Q10Row {
+            c_custkey: customer.c_custkey,
+            c_name: customer.c_name,
+            revenue,
+            c_acctbal: customer.c_acctbal,
+            n_name,
+            c_address: customer.c_address,
+            c_phone: customer.c_phone,
+            c_comment: customer.c_comment,
+        }
+    }
+
+    /// ORDER BY revenue DESC
+    pub fn order_by(a: &Q10Row, b: &Q10Row) -> Ordering {
+        b.revenue.total_cmp(&a.revenue)
+    }
+}
+
+impl ApproxEq for Q10Row {
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+        self.c_custkey == other.c_custkey
+            && self.c_name == other.c_name
+            && tolerance.eq_f64(self.revenue, other.revenue)
+            && tolerance.eq_f64(self.c_acctbal, other.c_acctbal)
+            && self.n_name == other.n_name
+            && self.c_address == other.c_address
+            && self.c_phone == other.c_phone
+            && self.c_comment == other.c_comment
+    }
+}
+
+impl FromAnswer for Q10Row {
+    fn from_answer(fields: &[&str]) -> Self {
+        Q10Row {
+            c_custkey: parse_field(fields, 0),
+            c_name: parse_field(fields, 1),
+            revenue: parse_field(fields, 2),
+            c_acctbal: parse_field(fields, 3),
+            n_name: parse_field(fields, 4),
+            c_address: parse_field(fields, 5),
+            c_phone: parse_field(fields, 6),
+            c_comment: parse_field(fields, 7),
+        }
+    }
+}
+
+pub fn load(conn: &Connection) -> (Vec<Customer>, Vec<Order>, Vec<LineItem>, Vec<Nation>) {
+    (
+        Customer::load(conn, None),
+        Order::load(conn, None),
+        LineItem::load(conn, None),
+        Nation::load(conn, None),
+    )
+}
+
+pub fn query(customer: Vec<Customer>, orders: Vec<Order>, line_items: Vec<LineItem>, nation: Vec<Nation>) -> Vec<Q10Row> {
+    query_with(Strategy::Heap, customer, orders, line_items, nation)
+}
+
+/// Query 10 with the given top-k strategy for ORDER BY ... LIMIT 20.
+pub fn query_with(
+    strategy: Strategy,
+    customer: Vec<Customer>,
+    orders: Vec<Order>,
+    line_items: Vec<LineItem>,
+    nation: Vec<Nation>,
+) -> Vec<Q10Row> {
+    // All customer columns in the GROUP BY are functionally dependent on c_custkey, so we
+    // aggregate on c_custkey first and join customer and nation on the (much smaller) result.
+
+    // 1. Scan orders, filter on o_orderdate in [1993-10-01, 1994-01-01).
+    // Keys: o_orderkey
+    // Payload: o_custkey
+    let orders_build: HashMap<i64, i64> = orders
+        .into_iter()
+        .filter(|o| {
+            o.o_orderdate >= NaiveDate::from_ymd_opt(1993, 10, 1).unwrap()
+                && o.o_orderdate < NaiveDate::from_ymd_opt(1994, 1, 1).unwrap()
+        })
+        .map(|o| (o.o_orderkey, o.o_custkey))
+        .collect();
+
+    // 2. Scan lineitem, filter on l_returnflag = 'R' and probe orders.
+    let joined = line_items
+        .into_iter()
+        .filter(|l| l.l_returnflag == 'R')
+        .filter_map(|l| {
+            orders_build
+                .get(&l.l_orderkey)
+                .map(|o_custkey| (*o_custkey, l.l_extendedprice * (1.0 - l.l_discount)))
+        });
+
+    // 3. Group by c_custkey and sum the revenue.
+    let agg = joined.fold(HashMap::new(), |mut acc, (c_custkey, revenue)| {
+        *acc.entry(c_custkey).or_insert(0.0) += revenue;
+        acc
+    });
+
+    // 4. Join the aggregate with customer and nation.
+    let nation_build: HashMap<i32, String> = nation.into_iter().map(|n| (n.n_nationkey, n.n_name)).collect();
+    let joined = customer.into_iter().filter_map(|c| {
+        let revenue = *agg.get(&c.c_custkey)?;
+        let n_name = nation_build.get(&c.c_nationkey)?.clone();
+        Some(Q10Row::new(c, revenue, n_name))
+    });
+
+    // 5. Keep the first 20 rows by revenue desc.
+    let mut top_k = TopK::new(strategy, 20, Q10Row::order_by);
+    top_k.extend(joined);
+    top_k.into_sorted_vec()
+}
+
+pub fn query_duckdb(conn: &Connection, limit: Option<u32>) -> Vec<Q10Row> {
+    let table = |name: &str| match limit {
+        Some(limit) => format!("(SELECT * FROM {} LIMIT {}) AS {}", name, limit, name),
+        None => name.to_string(),
+    };
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"
+        SELECT
+            c_custkey,
+            c_name,
+            sum(l_extendedprice * (1 - l_discount)) AS revenue,
+            c_acctbal,
+            n_name,
+            c_address,
+            c_phone,
+            c_comment
+        FROM
+            {},
+            {},
+            {},
+            {}
+        WHERE
+            c_custkey = o_custkey
+            AND l_orderkey = o_orderkey
+            AND o_orderdate >= CAST('1993-10-01' AS date)
+            AND o_orderdate < CAST('1994-01-01' AS date)
+            AND l_returnflag = 'R'
+            AND c_nationkey = n_nationkey
+        GROUP BY
+            c_custkey,
+            c_name,
+            c_acctbal,
+            c_phone,
+            n_name,
+            c_address,
+            c_comment
+        ORDER BY
+            revenue DESC
+        LIMIT 20;
+    "#,
+            table("customer"),
+            table("orders"),
+            table("lineitem"),
+            table("nation")
+        ))
+        .expect("Error preparing query for DuckDB");
+
+    let mut rows = stmt.query([]).expect("Error executing Query 10");
+    let mut result = vec![];
+    while let Some(row) = rows.next().unwrap() {
+        result.push(Q10Row {
+            c_custkey: row.get(0).unwrap(),
+            c_name: row.get(1).unwrap(),
+            revenue: row.get(2).unwrap(),
+            c_acctbal: row.get(3).unwrap(),
+            n_name: row.get(4).unwrap(),
+            c_address: row.get(5).unwrap(),
+            c_phone: row.get(6).unwrap(),
+            c_comment: row.get(7).unwrap(),
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tpch::answers::{answer_tolerance, expected};
+    use crate::tpch::initialize::initialize_database;
+    use crate::tpch::verify::{assert_match, compare};
+
+    #[test]
+    fn test_query_10() {
+        let conn = initialize_database(0.01);
+        let (customer, orders, line_items, nation) = load(&conn);
+        let result = query(customer, orders, line_items, nation);
+        assert!(result.len() <= 20);
+
+        let expected = query_duckdb(&conn, None);
+        assert_match(&[compare("Q10", "duckdb", "baseline", &expected, &result, &Tolerance::default())]);
+
+        let (customer, orders, line_items, nation) = load(&conn);
+        let result = query_with(Strategy::Sort, customer, orders, line_items, nation);
+        assert_match(&[compare("Q10", "duckdb", "baseline-sort", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_10_answer() {
+        let conn = initialize_database(0.01);
+        let (customer, orders, line_items, nation) = load(&conn);
+        let result = query(customer, orders, line_items, nation);
+
+        let expected = expected(&conn, 10, 0.01);
+        assert_match(&[compare("Q10", "tpch_answers", "baseline", &expected, &result, &answer_tolerance())]);
+    }
+}
@@ -0,0 +1,288 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::NaiveDate;
+use duckdb::Connection;
+
+use super::answers::{parse_field, FromAnswer};
+use super::util::{load_rows, to_date};
+use super::verify::{ApproxEq, Tolerance};
+
+#[derive(Debug, Clone)]
+pub struct Order {
+    pub o_orderkey: i64,
+    pub o_orderpriority: String,
+}
+
+impl Order {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "orders", &["o_orderkey", "o_orderpriority"], limit, |row| {
+            Ok(Order {
+                o_orderkey: row.get(0)?,
+                o_orderpriority: row.get(1)?,
+            })
+        })
+    }
+
+    /// o_orderpriority = '1-URGENT' OR o_orderpriority = '2-HIGH'
+    pub fn is_high_priority(&self) -> bool {
+        self.o_orderpriority == "1-URGENT" || self.o_orderpriority == "2-HIGH"
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LineItem {
+    pub l_orderkey: i64,
+    pub l_shipmode: String,
+    pub l_shipdate: NaiveDate,
+    pub l_commitdate: NaiveDate,
+    pub l_receiptdate: NaiveDate,
+}
+
+impl LineItem {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(
+            conn,
+            "lineitem",
+            &["l_orderkey", "l_shipmode", "l_shipdate", "l_commitdate", "l_receiptdate"],
+            limit,
+            |row| {
+                Ok(LineItem {
+                    l_orderkey: row.get(0)?,
+                    l_shipmode: row.get(1)?,
+                    l_shipdate: to_date(row.get(2)?),
+                    l_commitdate: to_date(row.get(3)?),
+                    l_receiptdate: to_date(row.get(4)?),
+                })
+            },
+        )
+    }
+
+    /**
+     * l_shipmode IN ('MAIL', 'SHIP') AND l_commitdate < l_receiptdate AND l_shipdate < l_commitdate
+     * AND l_receiptdate >= '1994-01-01' AND l_receiptdate < '1995-01-01'
+     */
+    pub fn filter(&self) -> bool {
+        (self.l_shipmode == "MAIL" || self.l_shipmode == "SHIP")
+            && self.l_commitdate < self.l_receiptdate
+            && self.l_shipdate < self.l_commitdate
+            && self.l_receiptdate >= NaiveDate::from_ymd_opt(1994, 1, 1).unwrap()
+            && self.l_receiptdate < NaiveDate::from_ymd_opt(1995, 1, 1).unwrap()
+    }
+}
+
+/// Result row of Query 12, ordered by l_shipmode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Q12Row {
+    pub l_shipmode: String,
+    pub high_line_count: i64,
+    pub low_line_count: i64,
+}
+
+impl From<(String, (i64, i64))> for Q12Row {
+    fn from((l_shipmode, (high_line_count, low_line_count)): (String, (i64, i64))) -> Self {
+        Q12Row {
+            l_shipmode,
+            high_line_count,
+            low_line_count,
+        }
+    }
+}
+
+impl ApproxEq for Q12Row {
+    fn approx_eq(&self, other: &Self, _tolerance: &Tolerance) -> bool {
+        self == other
+    }
+}
+
+impl FromAnswer for Q12Row {
+    fn from_answer(fields: &[&str]) -> Self {
+        Q12Row {
+            l_shipmode: parse_field(fields, 0),
+            high_line_count: parse_field(fields, 1),
+            low_line_count: parse_field(fields, 2),
+        }
+    }
+}
+
+pub fn load(conn: &Connection) -> (Vec<Order>, Vec<LineItem>) {
+    (Order::load(conn, None), LineItem::load(conn, None))
+}
+
+/// The CASE expressions of Query 12 as (high_line_count, low_line_count) increments.
+fn line_count(high_priority: bool) -> (i64, i64) {
+    if high_priority {
+        (1, 0)
+    } else {
+        (0, 1)
+    }
+}
+
+/**
+ * Row-wise: every line item is filtered, joined and aggregated on its own.
+ */
+pub fn query(orders: Vec<Order>, line_items: Vec<LineItem>) -> Vec<Q12Row> {
+    // 1. Scan orders. Build side of the join.
+    // Keys: o_orderkey
+    // Payload: o_orderpriority IN ('1-URGENT', '2-HIGH')
+    let orders_build: HashMap<i64, bool> = orders
+        .into_iter()
+        .map(|o| (o.o_orderkey, o.is_high_priority()))
+        .collect();
+
+    // 2. Scan lineitem, filter and probe orders.
+    // 3. Group by l_shipmode and sum the CASE expressions. The BTreeMap keeps the groups sorted.
+    let agg = line_items
+        .into_iter()
+        .filter(|l| l.filter())
+        .filter_map(|l| orders_build.get(&l.l_orderkey).map(|high| (l.l_shipmode, line_count(*high))))
+        .fold(BTreeMap::new(), |mut acc: BTreeMap<String, (i64, i64)>, (l_shipmode, (high, low))| {
+            let counts = acc.entry(l_shipmode).or_insert((0, 0));
+            counts.0 += high;
+            counts.1 += low;
+            acc
+        });
+
+    agg.into_iter().map(Q12Row::from).collect()
+}
+
+/**
+ * Evaluate the predicates of Query 12 on a chunk of at most `CHUNK_SIZE` line items, one predicate
+ * at a time over the whole chunk. Returns the selection mask of the chunk.
+ */
+pub fn filter_chunk<const CHUNK_SIZE: usize>(chunk: &[LineItem]) -> [bool; CHUNK_SIZE] {
+    let date_from = NaiveDate::from_ymd_opt(1994, 1, 1).unwrap();
+    let date_to = NaiveDate::from_ymd_opt(1995, 1, 1).unwrap();
+
+    let mut selection = [false; CHUNK_SIZE];
+    for (s, l) in selection.iter_mut().zip(chunk) {
+        *s = l.l_receiptdate >= date_from;
+    }
+    for (s, l) in selection.iter_mut().zip(chunk) {
+        *s &= l.l_receiptdate < date_to;
+    }
+    for (s, l) in selection.iter_mut().zip(chunk) {
+        *s &= l.l_commitdate < l.l_receiptdate;
+    }
+    for (s, l) in selection.iter_mut().zip(chunk) {
+        *s &= l.l_shipdate < l.l_commitdate;
+    }
+    // The string comparison is the most expensive predicate, only evaluate it on selected rows.
+    for (s, l) in selection.iter_mut().zip(chunk) {
+        *s = *s && (l.l_shipmode == "MAIL" || l.l_shipmode == "SHIP");
+    }
+    selection
+}
+
+/**
+ * Chunked: the line items are filtered in chunks of `CHUNK_SIZE`, see `filter_chunk`, and only the
+ * selected line items probe orders.
+ */
+pub fn query_chunks<const CHUNK_SIZE: usize>(orders: Vec<Order>, line_items: Vec<LineItem>) -> Vec<Q12Row> {
+    let orders_build: HashMap<i64, bool> = orders
+        .into_iter()
+        .map(|o| (o.o_orderkey, o.is_high_priority()))
+        .collect();
+
+    let mut agg: BTreeMap<String, (i64, i64)> = BTreeMap::new();
+    for chunk in line_items.chunks(CHUNK_SIZE) {
+        let selection = filter_chunk::<CHUNK_SIZE>(chunk);
+        for (_, l) in selection.iter().zip(chunk).filter(|(s, _)| **s) {
+            if let Some(high) = orders_build.get(&l.l_orderkey) {
+                let (high, low) = line_count(*high);
+                let counts = agg.entry(l.l_shipmode.clone()).or_insert((0, 0));
+                counts.0 += high;
+                counts.1 += low;
+            }
+        }
+    }
+
+    agg.into_iter().map(Q12Row::from).collect()
+}
+
+pub fn query_duckdb(conn: &Connection, limit: Option<u32>) -> Vec<Q12Row> {
+    let table = |name: &str| match limit {
+        Some(limit) => format!("(SELECT * FROM {} LIMIT {}) AS {}", name, limit, name),
+        None => name.to_string(),
+    };
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"
+        SELECT
+            l_shipmode,
+            sum(
+                CASE WHEN o_orderpriority = '1-URGENT'
+                    OR o_orderpriority = '2-HIGH' THEN
+                    1
+                ELSE
+                    0
+                END) AS high_line_count,
+            sum(
+                CASE WHEN o_orderpriority <> '1-URGENT'
+                    AND o_orderpriority <> '2-HIGH' THEN
+                    1
+                ELSE
+                    0
+                END) AS low_line_count
+        FROM
+            {},
+            {}
+        WHERE
+            o_orderkey = l_orderkey
+            AND l_shipmode IN ('MAIL', 'SHIP')
+            AND l_commitdate < l_receiptdate
+            AND l_shipdate < l_commitdate
+            AND l_receiptdate >= CAST('1994-01-01' AS date)
+            AND l_receiptdate < CAST('1995-01-01' AS date)
+        GROUP BY
+            l_shipmode
+        ORDER BY
+            l_shipmode;
+    "#,
+            table("orders"),
+            table("lineitem")
+        ))
+        .expect("Error preparing query for DuckDB");
+
+    let mut rows = stmt.query([]).expect("Error executing Query 12");
+    let mut result = vec![];
+    while let Some(row) = rows.next().unwrap() {
+        result.push(Q12Row {
+            l_shipmode: row.get(0).unwrap(),
+            high_line_count: row.get(1).unwrap(),
+            low_line_count: row.get(2).unwrap(),
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tpch::answers::{answer_tolerance, expected};
+    use crate::tpch::initialize::initialize_database;
+    use crate::tpch::verify::{assert_match, compare};
+
+    #[test]
+    fn test_query_12() {
+        let conn = initialize_database(0.01);
+        let expected = query_duckdb(&conn, None);
+
+        let (orders, line_items) = load(&conn);
+        let result = query(orders, line_items);
+        assert_match(&[compare("Q12", "duckdb", "baseline", &expected, &result, &Tolerance::default())]);
+
+        let (orders, line_items) = load(&conn);
+        let result = query_chunks::<1024>(orders, line_items);
+        assert_match(&[compare("Q12", "duckdb", "baseline-chunks", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_12_answer() {
+        let conn = initialize_database(0.01);
+        let (orders, line_items) = load(&conn);
+        let result = query(orders, line_items);
+
+        let expected = expected(&conn, 12, 0.01);
+        assert_match(&[compare("Q12", "tpch_answers", "baseline", &expected, &result, &answer_tolerance())]);
+    }
+}
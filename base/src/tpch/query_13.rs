@@ -0,0 +1,183 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use duckdb::Connection;
+
+use super::answers::{parse_field, FromAnswer};
+use super::util::{contains_in_order, load_rows};
+use super::verify::{ApproxEq, Tolerance};
+
+#[derive(Debug, Clone)]
+pub struct Customer {
+    pub c_custkey: i64,
+}
+
+impl Customer {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "customer", &["c_custkey"], limit, |row| {
+            Ok(Customer {
+                c_custkey: row.get(0)?,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Order {
+    pub o_orderkey: i64,
+    pub o_custkey: i64,
+    pub o_comment: String,
+}
+
+impl Order {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "orders", &["o_orderkey", "o_custkey", "o_comment"], limit, |row| {
+            Ok(Order {
+                o_orderkey: row.get(0)?,
+                o_custkey: row.get(1)?,
+                o_comment: row.get(2)?,
+            })
+        })
+    }
+
+    /// o_comment LIKE '%special%requests%'
+    pub fn is_special_request(&self) -> bool {
+        contains_in_order(&self.o_comment, &["special", "requests"])
+    }
+}
+
+/// Result row of Query 13, ordered by custdist descending and c_count descending.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Q13Row {
+    pub c_count: i64,
+    pub custdist: i64,
+}
+
+impl Q13Row {
+    /// ORDER BY custdist DESC, c_count DESC
+    pub fn order_by(a: &Q13Row, b: &Q13Row) -> Ordering {
+        b.custdist.cmp(&a.custdist).then(b.c_count.cmp(&a.c_count))
+    }
+}
+
+impl ApproxEq for Q13Row {
+    fn approx_eq(&self, other: &Self, _tolerance: &Tolerance) -> bool {
+        self == other
+    }
+}
+
+impl FromAnswer for Q13Row {
+    fn from_answer(fields: &[&str]) -> Self {
+        Q13Row {
+            c_count: parse_field(fields, 0),
+            custdist: parse_field(fields, 1),
+        }
+    }
+}
+
+pub fn load(conn: &Connection) -> (Vec<Customer>, Vec<Order>) {
+    (Customer::load(conn, None), Order::load(conn, None))
+}
+
+pub fn query(customer: Vec<Customer>, orders: Vec<Order>) -> Vec<Q13Row> {
+    // count(o_orderkey) only counts the matched side of the left outer join, so the join can be
+    // evaluated after aggregating orders: customers without a partner get a count of 0.
+
+    // 1. Scan orders, filter on o_comment NOT LIKE '%special%requests%'.
+    // 2. Group by o_custkey and count(o_orderkey).
+    let order_count = orders
+        .into_iter()
+        .filter(|o| !o.is_special_request())
+        .fold(HashMap::new(), |mut acc: HashMap<i64, i64>, o| {
+            *acc.entry(o.o_custkey).or_insert(0) += 1;
+            acc
+        });
+
+    // 3. Scan customer and probe the order counts. Left outer join: a missing partner counts 0.
+    // 4. Group by c_count and count(*).
+    let agg = customer
+        .into_iter()
+        .map(|c| order_count.get(&c.c_custkey).copied().unwrap_or(0))
+        .fold(HashMap::new(), |mut acc: HashMap<i64, i64>, c_count| {
+            *acc.entry(c_count).or_insert(0) += 1;
+            acc
+        });
+
+    // 5. Sort by custdist desc, c_count desc.
+    let mut result: Vec<Q13Row> = agg
+        .into_iter()
+        .map(|(c_count, custdist)| Q13Row { c_count, custdist })
+        .collect();
+    result.sort_by(Q13Row::order_by);
+    result
+}
+
+pub fn query_duckdb(conn: &Connection, limit: Option<u32>) -> Vec<Q13Row> {
+    let table = |name: &str| match limit {
+        Some(limit) => format!("(SELECT * FROM {} LIMIT {}) AS {}", name, limit, name),
+        None => name.to_string(),
+    };
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"
+        SELECT
+            c_count,
+            count(*) AS custdist
+        FROM (
+            SELECT
+                c_custkey,
+                count(o_orderkey)
+            FROM
+                {} LEFT OUTER JOIN {} ON c_custkey = o_custkey
+                AND o_comment NOT LIKE '%special%requests%'
+            GROUP BY
+                c_custkey) AS c_orders (c_custkey, c_count)
+        GROUP BY
+            c_count
+        ORDER BY
+            custdist DESC,
+            c_count DESC;
+    "#,
+            table("customer"),
+            table("orders")
+        ))
+        .expect("Error preparing query for DuckDB");
+
+    let mut rows = stmt.query([]).expect("Error executing Query 13");
+    let mut result = vec![];
+    while let Some(row) = rows.next().unwrap() {
+        result.push(Q13Row {
+            c_count: row.get(0).unwrap(),
+            custdist: row.get(1).unwrap(),
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tpch::answers::{answer_tolerance, expected};
+    use crate::tpch::initialize::initialize_database;
+    use crate::tpch::verify::{assert_match, compare};
+
+    #[test]
+    fn test_query_13() {
+        let conn = initialize_database(0.01);
+        let (customer, orders) = load(&conn);
+        let result = query(customer, orders);
+
+        let expected = query_duckdb(&conn, None);
+        assert_match(&[compare("Q13", "duckdb", "baseline", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_13_answer() {
+        let conn = initialize_database(0.01);
+        let (customer, orders) = load(&conn);
+        let result = query(customer, orders);
+
+        let expected = expected(&conn, 13, 0.01);
+        assert_match(&[compare("Q13", "tpch_answers", "baseline", &expected, &result, &answer_tolerance())]);
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use duckdb::Connection;
+
+use super::answers::{parse_field, FromAnswer};
+use super::util::{load_rows, to_date};
+use super::verify::{ApproxEq, Tolerance};
+
+#[derive(Debug, Clone)]
+pub struct LineItem {
+    pub l_partkey: i64,
+    pub l_extendedprice: f64,
+    pub l_discount: f64,
+    pub l_shipdate: NaiveDate,
+}
+
+impl LineItem {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "lineitem", &["l_partkey", "l_extendedprice", "l_discount", "l_shipdate"], limit, |row| {
+            Ok(LineItem {
+                l_partkey: row.get(0)?,
+                l_extendedprice: row.get(1)?,
+                l_discount: row.get(2)?,
+                l_shipdate: to_date(row.get(3)?),
+            })
+        })
+    }
+
+    /// l_shipdate >= '1995-09-01' AND l_shipdate < '1995-10-01'
+    pub fn filter(&self) -> bool {
+        self.l_shipdate >= NaiveDate::from_ymd_opt(1995, 9, 1).unwrap()
+            && self.l_shipdate < NaiveDate::from_ymd_opt(1995, 10, 1).unwrap()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Part {
+    pub p_partkey: i64,
+    pub p_type: String,
+}
+
+impl Part {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "part", &["p_partkey", "p_type"], limit, |row| {
+            Ok(Part {
+                p_partkey: row.get(0)?,
+                p_type: row.get(1)?,
+            })
+        })
+    }
+
+    /// p_type LIKE 'PROMO%'
+    pub fn is_promo(&self) -> bool {
+        self.p_type.starts_with("PROMO")
+    }
+}
+
+/// Result row of Query 14. `promo_revenue` is NULL if no line item qualifies.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Q14Row {
+    pub promo_revenue: Option<f64>,
+}
+
+impl Q14Row {
+    /// 100.00 * sum(promo revenue) / sum(revenue)
+    pub fn new(promo_revenue: f64, revenue: f64, count: u64) -> Q14Row {
+        Q14Row {
+            promo_revenue: if count > 0 { Some(100.0 * promo_revenue / revenue) } else { None },
+        }
+    }
+}
+
+impl ApproxEq for Q14Row {
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+        match (self.promo_revenue, other.promo_revenue) {
+            (Some(a), Some(b)) => tolerance.eq_f64(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl FromAnswer for Q14Row {
+    fn from_answer(fields: &[&str]) -> Self {
+        Q14Row {
+            promo_revenue: Some(parse_field(fields, 0)),
+        }
+    }
+}
+
+pub fn load(conn: &Connection) -> (Vec<LineItem>, Vec<Part>) {
+    (LineItem::load(conn, None), Part::load(conn, None))
+}
+
+/**
+ * Row-wise: every line item is filtered, joined and aggregated on its own.
+ */
+pub fn query(line_items: Vec<LineItem>, part: Vec<Part>) -> Vec<Q14Row> {
+    // 1. Scan part. Build side of the join.
+    // Keys: p_partkey
+    // Payload: p_type LIKE 'PROMO%'
+    let part_build: HashMap<i64, bool> = part.into_iter().map(|p| (p.p_partkey, p.is_promo())).collect();
+
+    // 2. Scan lineitem, filter and probe part.
+    // 3. Aggregate: sum(CASE WHEN promo THEN revenue ELSE 0 END), sum(revenue), count(*)
+    let (promo_revenue, revenue, count) = line_items
+        .into_iter()
+        .filter(|l| l.filter())
+        .filter_map(|l| {
+            part_build
+                .get(&l.l_partkey)
+                .map(|promo| (*promo, l.l_extendedprice * (1.0 - l.l_discount)))
+        })
+        .fold((0.0, 0.0, 0), |(promo_revenue, revenue, count), (promo, r)| {
+            (promo_revenue + if promo { r } else { 0.0 }, revenue + r, count + 1)
+        });
+
+    vec![Q14Row::new(promo_revenue, revenue, count)]
+}
+
+/**
+ * Evaluate the predicates of Query 14 on a chunk of at most `CHUNK_SIZE` line items, one predicate
+ * at a time over the whole chunk. Returns the selection mask of the chunk.
+ */
+pub fn filter_chunk<const CHUNK_SIZE: usize>(chunk: &[LineItem]) -> [bool; CHUNK_SIZE] {
+    let date_from = NaiveDate::from_ymd_opt(1995, 9, 1).unwrap();
+    let date_to = NaiveDate::from_ymd_opt(1995, 10, 1).unwrap();
+
+    let mut selection = [false; CHUNK_SIZE];
+    for (s, l) in selection.iter_mut().zip(chunk) {
+        *s = l.l_shipdate >= date_from;
+    }
+    for (s, l) in selection.iter_mut().zip(chunk) {
+        *s &= l.l_shipdate < date_to;
+    }
+    selection
+}
+
+/**
+ * Chunked: the line items are filtered in chunks of `CHUNK_SIZE`, see `filter_chunk`, and only the
+ * selected line items probe part.
+ */
+pub fn query_chunks<const CHUNK_SIZE: usize>(line_items: Vec<LineItem>, part: Vec<Part>) -> Vec<Q14Row> {
+    let part_build: HashMap<i64, bool> = part.into_iter().map(|p| (p.p_partkey, p.is_promo())).collect();
+
+    let (mut promo_revenue, mut revenue, mut count) = (0.0, 0.0, 0);
+    for chunk in line_items.chunks(CHUNK_SIZE) {
+        let selection = filter_chunk::<CHUNK_SIZE>(chunk);
+        for (_, l) in selection.iter().zip(chunk).filter(|(s, _)| **s) {
+            if let Some(promo) = part_build.get(&l.l_partkey) {
+                let r = l.l_extendedprice * (1.0 - l.l_discount);
+                promo_revenue += *promo as u8 as f64 * r;
+                revenue += r;
+                count += 1;
+            }
+        }
+    }
+
+    vec![Q14Row::new(promo_revenue, revenue, count)]
+}
+
+pub fn query_duckdb(conn: &Connection, limit: Option<u32>) -> Vec<Q14Row> {
+    let table = |name: &str| match limit {
+        Some(limit) => format!("(SELECT * FROM {} LIMIT {}) AS {}", name, limit, name),
+        None => name.to_string(),
+    };
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"
+        SELECT
+            100.00 * sum(
+                CASE WHEN p_type LIKE 'PROMO%' THEN
+                    l_extendedprice * (1 - l_discount)
+                ELSE
+                    0
+                END) / sum(l_extendedprice * (1 - l_discount)) AS promo_revenue
+        FROM
+            {},
+            {}
+        WHERE
+            l_partkey = p_partkey
+            AND l_shipdate >= CAST('1995-09-01' AS date)
+            AND l_shipdate < CAST('1995-10-01' AS date);
+    "#,
+            table("lineitem"),
+            table("part")
+        ))
+        .expect("Error preparing query for DuckDB");
+
+    let mut rows = stmt.query([]).expect("Error executing Query 14");
+    let mut result = vec![];
+    while let Some(row) = rows.next().unwrap() {
+        result.push(Q14Row {
+            promo_revenue: row.get(0).unwrap(),
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tpch::answers::{answer_tolerance, expected};
+    use crate::tpch::initialize::initialize_database;
+    use crate::tpch::verify::{assert_match, compare};
+
+    #[test]
+    fn test_query_14() {
+        let conn = initialize_database(0.01);
+        let expected = query_duckdb(&conn, None);
+
+        let (line_items, part) = load(&conn);
+        let result = query(line_items, part);
+        assert_match(&[compare("Q14", "duckdb", "baseline", &expected, &result, &Tolerance::default())]);
+
+        let (line_items, part) = load(&conn);
+        let result = query_chunks::<1024>(line_items, part);
+        assert_match(&[compare("Q14", "duckdb", "baseline-chunks", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_14_answer() {
+        let conn = initialize_database(0.01);
+        let (line_items, part) = load(&conn);
+        let result = query(line_items, part);
+
+        let expected = expected(&conn, 14, 0.01);
+        assert_match(&[compare("Q14", "tpch_answers", "baseline", &expected, &result, &answer_tolerance())]);
+    }
+}
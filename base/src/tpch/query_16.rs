@@ -0,0 +1,252 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use duckdb::Connection;
+
+use super::answers::{parse_field, FromAnswer};
+use super::util::{contains_in_order, load_rows};
+use super::verify::{ApproxEq, Tolerance};
+
+/// Part sizes selected by the validation parameters of Query 16.
+pub const SIZES: [i32; 8] = [49, 14, 23, 45, 19, 3, 36, 9];
+
+#[derive(Debug, Clone)]
+pub struct PartSupp {
+    pub ps_partkey: i64,
+    pub ps_suppkey: i64,
+}
+
+impl PartSupp {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "partsupp", &["ps_partkey", "ps_suppkey"], limit, |row| {
+            Ok(PartSupp {
+                ps_partkey: row.get(0)?,
+                ps_suppkey: row.get(1)?,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Part {
+    pub p_partkey: i64,
+    pub p_brand: String,
+    pub p_type: String,
+    pub p_size: i32,
+}
+
+impl Part {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "part", &["p_partkey", "p_brand", "p_type", "p_size"], limit, |row| {
+            Ok(Part {
+                p_partkey: row.get(0)?,
+                p_brand: row.get(1)?,
+                p_type: row.get(2)?,
+                p_size: row.get(3)?,
+            })
+        })
+    }
+
+    /// p_brand <> 'Brand#45' AND p_type NOT LIKE 'MEDIUM POLISHED%' AND p_size IN (...)
+    pub fn filter(&self) -> bool {
+        self.p_brand != "Brand#45" && !self.p_type.starts_with("MEDIUM POLISHED") && SIZES.contains(&self.p_size)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Supplier {
+    pub s_suppkey: i64,
+    pub s_comment: String,
+}
+
+impl Supplier {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "supplier", &["s_suppkey", "s_comment"], limit, |row| {
+            Ok(Supplier {
+                s_suppkey: row.get(0)?,
+                s_comment: row.get(1)?,
+            })
+        })
+    }
+
+    /// s_comment LIKE '%Customer%Complaints%'
+    pub fn has_complaints(&self) -> bool {
+        contains_in_order(&self.s_comment, &["Customer", "Complaints"])
+    }
+}
+
+/// Result row of Query 16, ordered by supplier_cnt descending, p_brand, p_type and p_size.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Q16Row {
+    pub p_brand: String,
+    pub p_type: String,
+    pub p_size: i32,
+    pub supplier_cnt: i64,
+}
+
+impl Q16Row {
+    /// ORDER BY supplier_cnt DESC, p_brand, p_type, p_size
+    pub fn order_by(a: &Q16Row, b: &Q16Row) -> Ordering {
+        b.supplier_cnt
+            .cmp(&a.supplier_cnt)
+            .then_with(|| a.p_brand.cmp(&b.p_brand))
+            .then_with(|| a.p_type.cmp(&b.p_type))
+            .then(a.p_size.cmp(&b.p_size))
+    }
+}
+
+impl ApproxEq for Q16Row {
+    fn approx_eq(&self, other: &Self, _tolerance: &Tolerance) -> bool {
+        self == other
+    }
+}
+
+impl FromAnswer for Q16Row {
+    fn from_answer(fields: &[&str]) -> Self {
+        Q16Row {
+            p_brand: parse_field(fields, 0),
+            p_type: parse_field(fields, 1),
+            p_size: parse_field(fields, 2),
+            supplier_cnt: parse_field(fields, 3),
+        }
+    }
+}
+
+pub fn load(conn: &Connection) -> (Vec<PartSupp>, Vec<Part>, Vec<Supplier>) {
+    (
+        PartSupp::load(conn, None),
+        Part::load(conn, None),
+        Supplier::load(conn, None),
+    )
+}
+
+pub fn query(partsupp: Vec<PartSupp>, part: Vec<Part>, supplier: Vec<Supplier>) -> Vec<Q16Row> {
+    // ps_suppkey NOT IN (...) is an anti join with supplier. s_suppkey is never NULL, so NOT IN and
+    // NOT EXISTS agree.
+
+    // 1. Scan supplier and filter on s_comment LIKE '%Customer%Complaints%'.
+    // Build side of the anti join: s_suppkey
+    let supplier_build: HashSet<i64> = supplier
+        .into_iter()
+        .filter(|s| s.has_complaints())
+        .map(|s| s.s_suppkey)
+        .collect();
+
+    // 2. Scan part and filter on p_brand, p_type and p_size.
+    // Keys: p_partkey
+    // Payload: p_brand, p_type, p_size
+    let part_build: HashMap<i64, (String, String, i32)> = part
+        .into_iter()
+        .filter(|p| p.filter())
+        .map(|p| (p.p_partkey, (p.p_brand, p.p_type, p.p_size)))
+        .collect();
+
+    // 3. Scan partsupp, anti join supplier and probe part.
+    // 4. Group by p_brand, p_type, p_size and count(DISTINCT ps_suppkey).
+    let agg = partsupp
+        .into_iter()
+        .filter(|ps| !supplier_build.contains(&ps.ps_suppkey))
+        .filter_map(|ps| part_build.get(&ps.ps_partkey).map(|key| (key, ps.ps_suppkey)))
+        .fold(HashMap::new(), |mut acc: HashMap<&(String, String, i32), HashSet<i64>>, (key, ps_suppkey)| {
+            acc.entry(key).or_default().insert(ps_suppkey);
+            acc
+        });
+
+    // 5. Sort by supplier_cnt desc, p_brand, p_type, p_size.
+    let mut result: Vec<Q16Row> = agg
+        .into_iter()
+        .map(|((p_brand, p_type, p_size), suppliers)| Q16Row {
+            p_brand: p_brand.clone(),
+            p_type: p_type.clone(),
+            p_size: *p_size,
+            supplier_cnt: suppliers.len() as i64,
+        })
+        .collect();
+    result.sort_by(Q16Row::order_by);
+    result
+}
+
+pub fn query_duckdb(conn: &Connection, limit: Option<u32>) -> Vec<Q16Row> {
+    let table = |name: &str| match limit {
+        Some(limit) => format!("(SELECT * FROM {} LIMIT {}) AS {}", name, limit, name),
+        None => name.to_string(),
+    };
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"
+        SELECT
+            p_brand,
+            p_type,
+            p_size,
+            count(DISTINCT ps_suppkey) AS supplier_cnt
+        FROM
+            {},
+            {}
+        WHERE
+            p_partkey = ps_partkey
+            AND p_brand <> 'Brand#45'
+            AND p_type NOT LIKE 'MEDIUM POLISHED%'
+            AND p_size IN (49, 14, 23, 45, 19, 3, 36, 9)
+            AND ps_suppkey NOT IN (
+                SELECT
+                    s_suppkey
+                FROM
+                    {}
+                WHERE
+                    s_comment LIKE '%Customer%Complaints%')
+        GROUP BY
+            p_brand,
+            p_type,
+            p_size
+        ORDER BY
+            supplier_cnt DESC,
+            p_brand,
+            p_type,
+            p_size;
+    "#,
+            table("partsupp"),
+            table("part"),
+            table("supplier")
+        ))
+        .expect("Error preparing query for DuckDB");
+
+    let mut rows = stmt.query([]).expect("Error executing Query 16");
+    let mut result = vec![];
+    while let Some(row) = rows.next().unwrap() {
+        result.push(Q16Row {
+            p_brand: row.get(0).unwrap(),
+            p_type: row.get(1).unwrap(),
+            p_size: row.get(2).unwrap(),
+            supplier_cnt: row.get(3).unwrap(),
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tpch::answers::{answer_tolerance, expected};
+    use crate::tpch::initialize::initialize_database;
+    use crate::tpch::verify::{assert_match, compare};
+
+    #[test]
+    fn test_query_16() {
+        let conn = initialize_database(0.01);
+        let (partsupp, part, supplier) = load(&conn);
+        let result = query(partsupp, part, supplier);
+
+        let expected = query_duckdb(&conn, None);
+        assert_match(&[compare("Q16", "duckdb", "baseline", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_16_answer() {
+        let conn = initialize_database(0.01);
+        let (partsupp, part, supplier) = load(&conn);
+        let result = query(partsupp, part, supplier);
+
+        let expected = expected(&conn, 16, 0.01);
+        assert_match(&[compare("Q16", "tpch_answers", "baseline", &expected, &result, &answer_tolerance())]);
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::{HashMap, HashSet};
+
+use duckdb::Connection;
+
+use super::answers::{parse_field, FromAnswer};
+use super::util::load_rows;
+use super::verify::{ApproxEq, Tolerance};
+
+#[derive(Debug, Clone)]
+pub struct Part {
+    pub p_partkey: i64,
+    pub p_brand: String,
+    pub p_container: String,
+}
+
+impl Part {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "part", &["p_partkey", "p_brand", "p_container"], limit, |row| {
+            Ok(Part {
+                p_partkey: row.get(0)?,
+                p_brand: row.get(1)?,
+                p_container: row.get(2)?,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LineItem {
+    pub l_partkey: i64,
+    pub l_quantity: f64,
+    pub l_extendedprice: f64,
+}
+
+impl LineItem {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "lineitem", &["l_partkey", "l_quantity", "l_extendedprice"], limit, |row| {
+            Ok(LineItem {
+                l_partkey: row.get(0)?,
+                l_quantity: row.get(1)?,
+                l_extendedprice: row.get(2)?,
+            })
+        })
+    }
+}
+
+/// Result row of Query 17. `avg_yearly` is NULL if no line item qualifies.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Q17Row {
+    pub avg_yearly: Option<f64>,
+}
+
+impl ApproxEq for Q17Row {
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+        match (self.avg_yearly, other.avg_yearly) {
+            (Some(a), Some(b)) => tolerance.eq_f64(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl FromAnswer for Q17Row {
+    fn from_answer(fields: &[&str]) -> Self {
+        Q17Row {
+            avg_yearly: Some(parse_field(fields, 0)),
+        }
+    }
+}
+
+pub fn load(conn: &Connection) -> (Vec<LineItem>, Vec<Part>) {
+    (LineItem::load(conn, None), Part::load(conn, None))
+}
+
+pub fn query(line_items: Vec<LineItem>, part: Vec<Part>) -> Vec<Q17Row> {
+    // The correlated subquery computes 0.2 * avg(l_quantity) over all line items of a part.
+    // Decorrelated, this is a group by l_partkey over lineitem joined back with the outer query on
+    // l_partkey. Only the parts of the outer query are needed, so the aggregate is computed on the
+    // join of part and lineitem.
+
+    // 1. Scan part and filter on p_brand = 'Brand#23' and p_container = 'MED BOX'.
+    let part_build: HashSet<i64> = part
+        .into_iter()
+        .filter(|p| p.p_brand == "Brand#23" && p.p_container == "MED BOX")
+        .map(|p| p.p_partkey)
+        .collect();
+
+    // 2. Scan lineitem and probe part.
+    let joined: Vec<LineItem> = line_items
+        .into_iter()
+        .filter(|l| part_build.contains(&l.l_partkey))
+        .collect();
+
+    // 3. Subquery: group by l_partkey and compute 0.2 * avg(l_quantity).
+    let threshold: HashMap<i64, f64> = joined
+        .iter()
+        .fold(HashMap::new(), |mut acc: HashMap<i64, (f64, u64)>, l| {
+            let (sum, count) = acc.entry(l.l_partkey).or_insert((0.0, 0));
+            *sum += l.l_quantity;
+            *count += 1;
+            acc
+        })
+        .into_iter()
+        .map(|(l_partkey, (sum, count))| (l_partkey, 0.2 * sum / count as f64))
+        .collect();
+
+    // 4. Filter on l_quantity < 0.2 * avg(l_quantity) and sum(l_extendedprice) / 7.0.
+    let sum = joined
+        .into_iter()
+        .filter(|l| l.l_quantity < threshold[&l.l_partkey])
+        .map(|l| l.l_extendedprice)
+        .reduce(|acc, x| acc + x);
+
+    vec![Q17Row {
+        avg_yearly: sum.map(|sum| sum / 7.0),
+    }]
+}
+
+pub fn query_duckdb(conn: &Connection, limit: Option<u32>) -> Vec<Q17Row> {
+    let table = |name: &str| match limit {
+        Some(limit) => format!("(SELECT * FROM {} LIMIT {}) AS {}", name, limit, name),
+        None => name.to_string(),
+    };
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"
+        SELECT
+            sum(l_extendedprice) / 7.0 AS avg_yearly
+        FROM
+            {lineitem},
+            {part}
+        WHERE
+            p_partkey = l_partkey
+            AND p_brand = 'Brand#23'
+            AND p_container = 'MED BOX'
+            AND l_quantity < (
+                SELECT
+                    0.2 * avg(l_quantity)
+                FROM
+                    {lineitem}
+                WHERE
+                    l_partkey = p_partkey);
+    "#,
+            lineitem = table("lineitem"),
+            part = table("part")
+        ))
+        .expect("Error preparing query for DuckDB");
+
+    let mut rows = stmt.query([]).expect("Error executing Query 17");
+    let mut result = vec![];
+    while let Some(row) = rows.next().unwrap() {
+        result.push(Q17Row {
+            avg_yearly: row.get(0).unwrap(),
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tpch::answers::{answer_tolerance, expected};
+    use crate::tpch::initialize::initialize_database;
+    use crate::tpch::verify::{assert_match, compare};
+
+    #[test]
+    fn test_query_17() {
+        let conn = initialize_database(0.01);
+        let (line_items, part) = load(&conn);
+        let result = query(line_items, part);
+
+        let expected = query_duckdb(&conn, None);
+        assert_match(&[compare("Q17", "duckdb", "baseline", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_17_answer() {
+        let conn = initialize_database(0.01);
+        let (line_items, part) = load(&conn);
+        let result = query(line_items, part);
+
+        let expected = expected(&conn, 17, 0.01);
+        assert_match(&[compare("Q17", "tpch_answers", "baseline", &expected, &result, &answer_tolerance())]);
+    }
+}
@@ -0,0 +1,264 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use duckdb::Connection;
+
+use super::answers::{parse_field, FromAnswer};
+use super::top_k::{Strategy, TopK};
+use super::util::{load_rows, to_date};
+use super::verify::{ApproxEq, Tolerance};
+
+#[derive(Debug, Clone)]
+pub struct Customer {
+    pub c_custkey: i64,
+    pub c_name: String,
+}
+
+impl Customer {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "customer", &["c_custkey", "c_name"], limit, |row| {
+            Ok(Customer {
+                c_custkey: row.get(0)?,
+                c_name: row.get(1)?,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Order {
+    pub o_orderkey: i64,
+    pub o_custkey: i64,
+    pub o_orderdate: NaiveDate,
+    pub o_totalprice: f64,
+}
+
+impl Order {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "orders", &["o_orderkey", "o_custkey", "o_orderdate", "o_totalprice"], limit, |row| {
+            Ok(Order {
+                o_orderkey: row.get(0)?,
+                o_custkey: row.get(1)?,
+                o_orderdate: to_date(row.get(2)?),
+                o_totalprice: row.get(3)?,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LineItem {
+    pub l_orderkey: i64,
+    pub l_quantity: f64,
+}
+
+impl LineItem {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "lineitem", &["l_orderkey", "l_quantity"], limit, |row| {
+            Ok(LineItem {
+                l_orderkey: row.get(0)?,
+                l_quantity: row.get(1)?,
+            })
+        })
+    }
+}
+
+/// HAVING sum(l_quantity) > 300
+pub const QUANTITY: f64 = 300.0;
+
+/// Result row of Query 18, ordered by o_totalprice descending and o_orderdate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Q18Row {
+    pub c_name: String,
+    pub c_custkey: i64,
+    pub o_orderkey: i64,
+    pub o_orderdate: NaiveDate,
+    pub o_totalprice: f64,
+    pub sum_quantity: f64,
+}
+
+impl Q18Row {
+    /// ORDER BY o_totalprice DESC, o_orderdate
+    pub fn order_by(a: &Q18Row, b: &Q18Row) -> Ordering {
+        b.o_totalprice
+            .total_cmp(&a.o_totalprice)
+            .then(a.o_orderdate.cmp(&b.o_orderdate))
+    }
+}
+
+impl ApproxEq for Q18Row {
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+        self.c_name == other.c_name
+            && self.c_custkey == other.c_custkey
+            && self.o_orderkey == other.o_orderkey
+            && self.o_orderdate == other.o_orderdate
+            && tolerance.eq_f64(self.o_totalprice, other.o_totalprice)
+            && tolerance.eq_f64(self.sum_quantity, other.sum_quantity)
+    }
+}
+
+impl FromAnswer for Q18Row {
+    fn from_answer(fields: &[&str]) -> Self {
+        Q18Row {
+            c_name: parse_field(fields, 0),
+            c_custkey: parse_field(fields, 1),
+            o_orderkey: parse_field(fields, 2),
+            o_orderdate: parse_field(fields, 3),
+            o_totalprice: parse_field(fields, 4),
+            sum_quantity: parse_field(fields, 5),
+        }
+    }
+}
+
+pub fn load(conn: &Connection) -> (Vec<Customer>, Vec<Order>, Vec<LineItem>) {
+    (
+        Customer::load(conn, None),
+        Order::load(conn, None),
+        LineItem::load(conn, None),
+    )
+}
+
+pub fn query(customer: Vec<Customer>, orders: Vec<Order>, line_items: Vec<LineItem>) -> Vec<Q18Row> {
+    query_with(Strategy::Heap, customer, orders, line_items)
+}
+
+/// Query 18 with the given top-k strategy for ORDER BY ... LIMIT 100.
+pub fn query_with(strategy: Strategy, customer: Vec<Customer>, orders: Vec<Order>, line_items: Vec<LineItem>) -> Vec<Q18Row> {
+    // The IN subquery groups lineitem by l_orderkey. The outer query groups by o_orderkey (the other
+    // columns are functionally dependent on it) and only sees line items of the same order, so
+    // sum(l_quantity) of the outer query is the sum of the subquery.
+
+    // 1. Subquery: scan lineitem, group by l_orderkey and sum(l_quantity).
+    let quantities = line_items.into_iter().fold(HashMap::new(), |mut acc, l| {
+        *acc.entry(l.l_orderkey).or_insert(0.0) += l.l_quantity;
+        acc
+    });
+
+    // 2. HAVING sum(l_quantity) > 300. Build side of the join with orders.
+    // Keys: l_orderkey
+    // Payload: sum(l_quantity)
+    let large_orders: HashMap<i64, f64> = quantities
+        .into_iter()
+        .filter(|(_l_orderkey, sum_quantity)| *sum_quantity > QUANTITY)
+        .collect();
+
+    // 3. Scan customer. Build side of the join with orders.
+    // Keys: c_custkey
+    // Payload: c_name
+    let customer_build: HashMap<i64, String> = customer.into_iter().map(|c| (c.c_custkey, c.c_name)).collect();
+
+    // 4. Scan orders, probe the large orders and customer.
+    let joined = orders.into_iter().filter_map(|o| {
+        let sum_quantity = *large_orders.get(&o.o_orderkey)?;
+        let c_name = customer_build.get(&o.o_custkey)?.clone();
+        Some(Q18Row {
+            c_name,
+            c_custkey: o.o_custkey,
+            o_orderkey: o.o_orderkey,
+            o_orderdate: o.o_orderdate,
+            o_totalprice: o.o_totalprice,
+            sum_quantity,
+        })
+    });
+
+    // 5. Keep the first 100 rows by o_totalprice desc, o_orderdate.
+    let mut top_k = TopK::new(strategy, 100, Q18Row::order_by);
+    top_k.extend(joined);
+    top_k.into_sorted_vec()
+}
+
+pub fn query_duckdb(conn: &Connection, limit: Option<u32>) -> Vec<Q18Row> {
+    let table = |name: &str| match limit {
+        Some(limit) => format!("(SELECT * FROM {} LIMIT {}) AS {}", name, limit, name),
+        None => name.to_string(),
+    };
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"
+        SELECT
+            c_name,
+            c_custkey,
+            o_orderkey,
+            o_orderdate,
+            o_totalprice,
+            sum(l_quantity)
+        FROM
+            {customer},
+            {orders},
+            {lineitem}
+        WHERE
+            o_orderkey IN (
+                SELECT
+                    l_orderkey
+                FROM
+                    {lineitem}
+                GROUP BY
+                    l_orderkey
+                HAVING
+                    sum(l_quantity) > 300)
+            AND c_custkey = o_custkey
+            AND o_orderkey = l_orderkey
+        GROUP BY
+            c_name,
+            c_custkey,
+            o_orderkey,
+            o_orderdate,
+            o_totalprice
+        ORDER BY
+            o_totalprice DESC,
+            o_orderdate
+        LIMIT 100;
+    "#,
+            customer = table("customer"),
+            orders = table("orders"),
+            lineitem = table("lineitem")
+        ))
+        .expect("Error preparing query for DuckDB");
+
+    let mut rows = stmt.query([]).expect("Error executing Query 18");
+    let mut result = vec![];
+    while let Some(row) = rows.next().unwrap() {
+        result.push(Q18Row {
+            c_name: row.get(0).unwrap(),
+            c_custkey: row.get(1).unwrap(),
+            o_orderkey: row.get(2).unwrap(),
+            o_orderdate: to_date(row.get(3).unwrap()),
+            o_totalprice: row.get(4).unwrap(),
+            sum_quantity: row.get(5).unwrap(),
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tpch::answers::{answer_tolerance, expected};
+    use crate::tpch::initialize::initialize_database;
+    use crate::tpch::verify::{assert_match, compare};
+
+    #[test]
+    fn test_query_18() {
+        let conn = initialize_database(0.01);
+        let expected = query_duckdb(&conn, None);
+
+        let (customer, orders, line_items) = load(&conn);
+        let result = query(customer, orders, line_items);
+        assert_match(&[compare("Q18", "duckdb", "baseline", &expected, &result, &Tolerance::default())]);
+
+        let (customer, orders, line_items) = load(&conn);
+        let result = query_with(Strategy::Sort, customer, orders, line_items);
+        assert_match(&[compare("Q18", "duckdb", "baseline-sort", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_18_answer() {
+        let conn = initialize_database(0.01);
+        let (customer, orders, line_items) = load(&conn);
+        let result = query(customer, orders, line_items);
+
+        let expected = expected(&conn, 18, 0.01);
+        assert_match(&[compare("Q18", "tpch_answers", "baseline", &expected, &result, &answer_tolerance())]);
+    }
+}
@@ -1,5 +1,61 @@
 use std::collections::HashMap;
-use duckdb::Connection;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use duckdb::arrow::datatypes::{Float64Type, Int32Type, Int64Type};
+use duckdb::arrow::record_batch::RecordBatch;
+use duckdb::types::FromSql;
+use duckdb::{params, Connection};
+
+use super::answers::{parse_field, FromAnswer};
+use super::bloom::{BloomFilter, DEFAULT_FALSE_POSITIVE_RATE};
+use super::decimal::{Decimal, Numeric};
+use super::load::{LoadError, LoadMode, Loaded};
+use super::merge_join::merge_join;
+use super::parallel::{morsels, Parallelism};
+use super::plan::{Estimate, JoinPlan, Side};
+use super::scan::FromBatch;
+use super::tbl::{self, Fields, FromTbl, TblError};
+use super::util::{decimal_column, load_batches, primitive_column, string_column, try_load_rows};
+use super::verify::{ApproxEq, Tolerance};
+
+/// Substitution parameters of Query 19. The containers and sizes of the three disjuncts are fixed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryParams {
+    /// l_quantity >= QUANTITY1 AND l_quantity <= QUANTITY1 + 10
+    pub quantity1: i32,
+    pub quantity2: i32,
+    pub quantity3: i32,
+    /// p_brand = BRAND1
+    pub brand1: String,
+    pub brand2: String,
+    pub brand3: String,
+}
+
+/// The validation parameters, the reference answers are computed with these.
+impl Default for QueryParams {
+    fn default() -> Self {
+        QueryParams {
+            quantity1: 1,
+            quantity2: 10,
+            quantity3: 20,
+            brand1: "Brand#12".to_string(),
+            brand2: "Brand#23".to_string(),
+            brand3: "Brand#34".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for QueryParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "quantity={}/{}/{},brand={}/{}/{}",
+            self.quantity1, self.quantity2, self.quantity3, self.brand1, self.brand2, self.brand3
+        )
+    }
+}
 
 pub struct Part {
     pub p_partkey: i64,
@@ -9,32 +65,24 @@ pub struct Part {
 }
 
 impl Part {
-    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
-        // Create iterator for Part
-        let query = match limit {
-            Some(limit) => format!(
-                "SELECT p_partkey, p_brand, p_container, p_size FROM part LIMIT {};",
-                limit
-            ),
-            None => "SELECT p_partkey, p_brand, p_container, p_size FROM part;".to_string(),
-        };
-        let mut stmt = conn
-            .prepare(&query)
-            .expect("Error preparing query for Part");
-        let parts = stmt
-            .query_map([], |row| {
-                Ok(Part {
-                    p_partkey: row.get(0)?,
-                    p_brand: row.get(1)?,
-                    p_container: row.get(2)?,
-                    p_size: row.get(3)?,
-                })
-            })
-            .expect("Error querying Part");
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Result<Vec<Self>, LoadError> {
+        Ok(Part::load_with(conn, limit, LoadMode::Strict)?.rows)
+    }
 
-        let parts = parts.filter(|x| x.is_ok()).map(|x| x.unwrap());
+    pub fn load_with(conn: &Connection, limit: Option<u32>, mode: LoadMode) -> Result<Loaded<Self>, LoadError> {
+        try_load_rows(conn, "part", &["p_partkey", "p_brand", "p_container", "p_size"], limit, mode, |row| {
+            Ok(Part {
+                p_partkey: row.get(0)?,
+                p_brand: row.get(1)?,
+                p_container: row.get(2)?,
+                p_size: row.get(3)?,
+            })
+        })
+    }
 
-        parts.collect()
+    /// Same as `load`, but converts whole Arrow record batches instead of one row at a time.
+    pub fn load_arrow(conn: &Connection, limit: Option<u32>) -> Result<Vec<Self>, LoadError> {
+        load_batches(conn, Self::TABLE, Self::COLUMNS, limit, Self::from_batch)
     }
 
     fn filter(brand_pred: &str, brand_val: &str, size_between: &(i32, i32), size_val: &i32, container_list: &[&str], container_val: &str) -> bool {
@@ -44,81 +92,293 @@ impl Part {
             && container_list.contains(&container_val);
     }
 
-    pub fn filter_1(brand_val: &str, size_val: &i32, container_val: &str) -> bool {
-        return Part::filter("Brand#12", brand_val, &(1, 5), size_val, &["SM CASE", "SM BOX", "SM PACK", "SM PKG"], container_val);
+    pub fn filter_1(params: &QueryParams, brand_val: &str, size_val: &i32, container_val: &str) -> bool {
+        return Part::filter(&params.brand1, brand_val, &(1, 5), size_val, &["SM CASE", "SM BOX", "SM PACK", "SM PKG"], container_val);
+    }
+
+    pub fn filter_2(params: &QueryParams, brand_val: &str, size_val: &i32, container_val: &str) -> bool {
+        return Part::filter(&params.brand2, brand_val, &(1, 10), size_val, &["MED BAG", "MED BOX", "MED PKG", "MED PACK"], container_val);
     }
 
-    pub fn filter_2(brand_val: &str, size_val: &i32, container_val: &str) -> bool {
-        return Part::filter("Brand#23", brand_val, &(1, 10), size_val, &["MED BAG", "MED BOX", "MED PKG", "MED PACK"], container_val);
+    pub fn filter_3(params: &QueryParams, brand_val: &str, size_val: &i32, container_val: &str) -> bool {
+        return Part::filter(&params.brand3, brand_val, &(1, 15), size_val, &["LG CASE", "LG BOX", "LG PACK", "LG PKG"], container_val);
     }
+}
+
+impl FromTbl for Part {
+    const TABLE: &'static str = "part";
+    const COLUMNS: &'static [&'static str] = &["p_partkey", "p_brand", "p_container", "p_size"];
 
-    pub fn filter_3(brand_val: &str, size_val: &i32, container_val: &str) -> bool {
-        return Part::filter("Brand#34", brand_val, &(1, 15), size_val, &["LG CASE", "LG BOX", "LG PACK", "LG PKG"], container_val);
+    fn from_tbl(fields: &Fields<'_>) -> Result<Self, TblError> {
+        Ok(Part {
+            p_partkey: fields.parse(0)?,
+            p_brand: fields.str(1).to_string(),
+            p_container: fields.str(2).to_string(),
+            p_size: fields.parse(3)?,
+        })
     }
 }
 
-pub struct LineItem {
+impl FromBatch for Part {
+    fn from_batch(batch: &RecordBatch, parts: &mut Vec<Self>) -> duckdb::Result<()> {
+        let p_partkey = primitive_column::<Int64Type>(batch, 0)?;
+        let p_brand = string_column(batch, 1)?;
+        let p_container = string_column(batch, 2)?;
+        let p_size = primitive_column::<Int32Type>(batch, 3)?;
+        for i in 0..batch.num_rows() {
+            parts.push(Part {
+                p_partkey: p_partkey.value(i),
+                p_brand: p_brand.value(i).to_string(),
+                p_container: p_container.value(i).to_string(),
+                p_size: p_size.value(i),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// The DECIMAL columns are `f64` by default and `Decimal` for exact arithmetic.
+pub struct LineItem<N = f64> {
     pub l_partkey: i64,
     pub l_shipmode: String,
-    pub l_quantity: f64,
+    pub l_quantity: N,
     pub l_shipinstruct: String,
-    pub l_discount: f64,
-    pub l_extendedprice: f64,
+    pub l_discount: N,
+    pub l_extendedprice: N,
 }
 
 impl LineItem {
-    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
-        // Create iterator for LineItem
-        let query = match limit {
-            Some(limit) => format!(
-                "SELECT l_partkey, l_shipmode, l_quantity, l_shipinstruct, l_discount, l_extendedprice FROM lineitem LIMIT {};",
-                limit
-            ),
-            None => "SELECT l_partkey, l_shipmode, l_quantity, l_shipinstruct, l_discount, l_extendedprice FROM lineitem;".to_string(),
-        };
-        let mut stmt = conn
-            .prepare(&query)
-            .expect("Error preparing query for LineItem");
-        let line_items = stmt
-            .query_map([], |row| {
-                Ok(LineItem {
-                    l_partkey: row.get(0)?,
-                    l_shipmode: row.get(1)?,
-                    l_quantity: row.get(2)?,
-                    l_shipinstruct: row.get(3)?,
-                    l_discount: row.get(4)?,
-                    l_extendedprice: row.get(5)?,
-                })
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Result<Vec<Self>, LoadError> {
+        Ok(LineItem::load_with(conn, limit, LoadMode::Strict)?.rows)
+    }
+
+    pub fn load_with(conn: &Connection, limit: Option<u32>, mode: LoadMode) -> Result<Loaded<Self>, LoadError> {
+        let columns = ["l_partkey", "l_shipmode", "l_quantity", "l_shipinstruct", "l_discount", "l_extendedprice"];
+        try_load_rows(conn, "lineitem", &columns, limit, mode, |row| {
+            Ok(LineItem {
+                l_partkey: row.get(0)?,
+                l_shipmode: row.get(1)?,
+                l_quantity: row.get(2)?,
+                l_shipinstruct: row.get(3)?,
+                l_discount: row.get(4)?,
+                l_extendedprice: row.get(5)?,
             })
-            .expect("Error querying LineItem");
+        })
+    }
 
-        let line_items = line_items.filter(|x| x.is_ok()).map(|x| x.unwrap());
+    /// Same as `load`, but converts whole Arrow record batches instead of one row at a time.
+    pub fn load_arrow(conn: &Connection, limit: Option<u32>) -> Result<Vec<Self>, LoadError> {
+        load_batches(conn, Self::TABLE, Self::COLUMNS, limit, Self::from_batch)
+    }
+}
 
-        line_items.collect()
+impl FromBatch for LineItem {
+    fn from_batch(batch: &RecordBatch, line_items: &mut Vec<Self>) -> duckdb::Result<()> {
+        let l_partkey = primitive_column::<Int64Type>(batch, 0)?;
+        let l_shipmode = string_column(batch, 1)?;
+        let l_quantity = primitive_column::<Float64Type>(batch, 2)?;
+        let l_shipinstruct = string_column(batch, 3)?;
+        let l_discount = primitive_column::<Float64Type>(batch, 4)?;
+        let l_extendedprice = primitive_column::<Float64Type>(batch, 5)?;
+        for i in 0..batch.num_rows() {
+            line_items.push(LineItem {
+                l_partkey: l_partkey.value(i),
+                l_shipmode: l_shipmode.value(i).to_string(),
+                l_quantity: l_quantity.value(i),
+                l_shipinstruct: l_shipinstruct.value(i).to_string(),
+                l_discount: l_discount.value(i),
+                l_extendedprice: l_extendedprice.value(i),
+            });
+        }
+        Ok(())
     }
+}
 
-    fn filter(quantity_between: &(f64, f64), quantity_val: &f64) -> bool {
+impl<N: Numeric> LineItem<N> {
+    fn filter(quantity_between: &(N, N), quantity_val: &N) -> bool {
         return quantity_between.0 <= *quantity_val && *quantity_val <= quantity_between.1;
     }
 
-    pub fn filter_1(quantity_val: &f64) -> bool {
-        return LineItem::filter(&(1.0, 11.0), quantity_val);
+    fn quantity_between(quantity: i32) -> (N, N) {
+        (N::from_i32(quantity), N::from_i32(quantity + 10))
+    }
+
+    pub fn filter_1(params: &QueryParams, quantity_val: &N) -> bool {
+        return LineItem::filter(&LineItem::quantity_between(params.quantity1), quantity_val);
+    }
+
+    pub fn filter_2(params: &QueryParams, quantity_val: &N) -> bool {
+        return LineItem::filter(&LineItem::quantity_between(params.quantity2), quantity_val);
+    }
+
+    pub fn filter_3(params: &QueryParams, quantity_val: &N) -> bool {
+        return LineItem::filter(&LineItem::quantity_between(params.quantity3), quantity_val);
+    }
+}
+
+/// Struct-of-arrays layout of `LineItem`, one `Vec` per column.
+#[derive(Debug, Clone, Default)]
+pub struct LineItemColumns {
+    pub l_partkey: Vec<i64>,
+    pub l_shipmode: Vec<String>,
+    pub l_quantity: Vec<f64>,
+    pub l_shipinstruct: Vec<String>,
+    pub l_discount: Vec<f64>,
+    pub l_extendedprice: Vec<f64>,
+}
+
+/// A batch of rows of `LineItemColumns`, one slice per column.
+#[derive(Debug, Clone, Copy)]
+pub struct LineItemChunk<'a> {
+    pub l_partkey: &'a [i64],
+    pub l_shipmode: &'a [String],
+    pub l_quantity: &'a [f64],
+    pub l_shipinstruct: &'a [String],
+    pub l_discount: &'a [f64],
+    pub l_extendedprice: &'a [f64],
+}
+
+impl LineItemColumns {
+    pub fn len(&self) -> usize {
+        self.l_partkey.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.l_partkey.is_empty()
+    }
+
+    pub fn push(&mut self, x: LineItem) {
+        self.l_partkey.push(x.l_partkey);
+        self.l_shipmode.push(x.l_shipmode);
+        self.l_quantity.push(x.l_quantity);
+        self.l_shipinstruct.push(x.l_shipinstruct);
+        self.l_discount.push(x.l_discount);
+        self.l_extendedprice.push(x.l_extendedprice);
+    }
+
+    /// The rows in batches of `chunk_size`, the last one may be shorter.
+    pub fn chunks(&self, chunk_size: usize) -> impl Iterator<Item = LineItemChunk<'_>> {
+        (0..self.len()).step_by(chunk_size).map(move |start| {
+            let rows = start..(start + chunk_size).min(self.len());
+            LineItemChunk {
+                l_partkey: &self.l_partkey[rows.clone()],
+                l_shipmode: &self.l_shipmode[rows.clone()],
+                l_quantity: &self.l_quantity[rows.clone()],
+                l_shipinstruct: &self.l_shipinstruct[rows.clone()],
+                l_discount: &self.l_discount[rows.clone()],
+                l_extendedprice: &self.l_extendedprice[rows],
+            }
+        })
+    }
+}
+
+impl FromIterator<LineItem> for LineItemColumns {
+    fn from_iter<I: IntoIterator<Item = LineItem>>(iter: I) -> Self {
+        let mut columns = LineItemColumns::default();
+        for x in iter {
+            columns.push(x);
+        }
+        columns
+    }
+}
+
+impl LineItem<Decimal> {
+    /// Same as `load_arrow`, with the DECIMAL columns as `Decimal`.
+    pub fn load_decimal(conn: &Connection, limit: Option<u32>) -> Result<Vec<Self>, LoadError> {
+        let columns = ["l_partkey", "l_shipmode", "l_quantity", "l_shipinstruct", "l_discount", "l_extendedprice"];
+        load_batches(conn, "lineitem", &columns, limit, |batch, line_items| {
+            let l_partkey = primitive_column::<Int64Type>(batch, 0)?;
+            let l_shipmode = string_column(batch, 1)?;
+            let l_quantity = decimal_column(batch, 2)?;
+            let l_shipinstruct = string_column(batch, 3)?;
+            let l_discount = decimal_column(batch, 4)?;
+            let l_extendedprice = decimal_column(batch, 5)?;
+            for i in 0..batch.num_rows() {
+                line_items.push(LineItem {
+                    l_partkey: l_partkey.value(i),
+                    l_shipmode: l_shipmode.value(i).to_string(),
+                    l_quantity: Decimal::from_units(l_quantity.value(i)),
+                    l_shipinstruct: l_shipinstruct.value(i).to_string(),
+                    l_discount: Decimal::from_units(l_discount.value(i)),
+                    l_extendedprice: Decimal::from_units(l_extendedprice.value(i)),
+                });
+            }
+            Ok(())
+        })
+    }
+}
+
+impl<N: Numeric + FromStr> FromTbl for LineItem<N>
+where
+    N::Err: fmt::Display,
+{
+    const TABLE: &'static str = "lineitem";
+    const COLUMNS: &'static [&'static str] = &["l_partkey", "l_shipmode", "l_quantity", "l_shipinstruct", "l_discount", "l_extendedprice"];
+
+    fn from_tbl(fields: &Fields<'_>) -> Result<Self, TblError> {
+        Ok(LineItem {
+            l_partkey: fields.parse(0)?,
+            l_shipmode: fields.str(1).to_string(),
+            l_quantity: fields.parse(2)?,
+            l_shipinstruct: fields.str(3).to_string(),
+            l_discount: fields.parse(4)?,
+            l_extendedprice: fields.parse(5)?,
+        })
     }
+}
 
-    pub fn filter_2(quantity_val: &f64) -> bool {
-        return LineItem::filter(&(10.0, 20.0), quantity_val);
+/// Result row of Query 19. The revenue is NULL if no line item qualifies.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Q19Row<N = f64> {
+    pub revenue: Option<N>,
+}
+
+impl ApproxEq for Q19Row {
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+        match (self.revenue, other.revenue) {
+            (Some(a), Some(b)) => tolerance.eq_f64(a, b),
+            (None, None) => true,
+            _ => false,
+        }
     }
+}
 
-    pub fn filter_3(quantity_val: &f64) -> bool {
-        return LineItem::filter(&(20.0, 30.0), quantity_val);
+impl ApproxEq for Q19Row<Decimal> {
+    fn approx_eq(&self, other: &Self, _tolerance: &Tolerance) -> bool {
+        self == other
     }
 }
 
-pub fn load(conn: &Connection) -> (Vec<LineItem>, Vec<Part>) {
-    (LineItem::load(conn, None), Part::load(conn, None))
+impl FromAnswer for Q19Row {
+    fn from_answer(fields: &[&str]) -> Self {
+        Q19Row {
+            revenue: Some(parse_field(fields, 0)),
+        }
+    }
 }
 
-pub fn query(line_items: Vec<LineItem>, part: Vec<Part>) {
+pub fn load(conn: &Connection) -> Result<(Vec<LineItem>, Vec<Part>), LoadError> {
+    Ok((LineItem::load(conn, None)?, Part::load(conn, None)?))
+}
+
+pub fn load_arrow(conn: &Connection) -> Result<(Vec<LineItem>, Vec<Part>), LoadError> {
+    Ok((LineItem::load_arrow(conn, None)?, Part::load_arrow(conn, None)?))
+}
+
+pub fn load_decimal(conn: &Connection) -> Result<(Vec<LineItem<Decimal>>, Vec<Part>), LoadError> {
+    Ok((LineItem::load_decimal(conn, None)?, Part::load(conn, None)?))
+}
+
+pub fn load_columns(conn: &Connection) -> Result<(LineItemColumns, Vec<Part>), LoadError> {
+    Ok((LineItem::load_arrow(conn, None)?.into_iter().collect(), Part::load_arrow(conn, None)?))
+}
+
+/// Reads `lineitem.tbl` and `part.tbl` in `dir` instead of scanning DuckDB.
+pub fn load_tbl(dir: &Path) -> Result<(Vec<LineItem>, Vec<Part>), TblError> {
+    Ok((tbl::read(dir)?, tbl::read(dir)?))
+}
+
+pub fn query<N: Numeric>(line_items: Vec<LineItem<N>>, part: Vec<Part>, params: &QueryParams) -> Vec<Q19Row<N>> {
     // XXX: InkFuse adds an early filter before the join. DuckDB does not
     
     // 1. Scan part.
@@ -126,9 +386,9 @@ pub fn query(line_items: Vec<LineItem>, part: Vec<Part>) {
         .into_iter()
     // 2. Pushed down filter on part.
         .filter(|part| {
-            Part::filter_1(&part.p_brand, &part.p_size, &part.p_container)
-            || Part::filter_2(&part.p_brand, &part.p_size, &part.p_container)
-            || Part::filter_3(&part.p_brand, &part.p_size, &part.p_container)
+            Part::filter_1(params, &part.p_brand, &part.p_size, &part.p_container)
+            || Part::filter_2(params, &part.p_brand, &part.p_size, &part.p_container)
+            || Part::filter_3(params, &part.p_brand, &part.p_size, &part.p_container)
         });
 
     // 3. Scan lineitem.
@@ -137,11 +397,11 @@ pub fn query(line_items: Vec<LineItem>, part: Vec<Part>) {
         // l_shipinstruct = "DELIVER IN PERSON"
         // l_shipmode = "AIR" or "AIR REG"
         .filter(|lineitem| lineitem.l_shipinstruct == "DELIVER IN PERSON" && (lineitem.l_shipmode == "AIR" || lineitem.l_shipmode == "AIR REG"))
-        .filter(|lineitem| LineItem::filter_1(&lineitem.l_quantity) || LineItem::filter_2(&lineitem.l_quantity) || LineItem::filter_3(&lineitem.l_quantity));
+        .filter(|lineitem| LineItem::filter_1(params, &lineitem.l_quantity) || LineItem::filter_2(params, &lineitem.l_quantity) || LineItem::filter_3(params, &lineitem.l_quantity));
     
     // 5. Join the two
-    // Build: Part?
-    // Probe: LineItem?
+    // Build: Part, see `query_with_plan` for the line items as the build side.
+    // Probe: LineItem
 
     // Keys left (p_partkey)
     // Payload left (p_brand, p_container, p_size)
@@ -165,25 +425,284 @@ pub fn query(line_items: Vec<LineItem>, part: Vec<Part>) {
 
     // 6. Filter again, we need to make sure the right tuples survived.
     let join_filtered = join.filter(|(p_brand, p_container, p_size, l_quantity, _l_discount, _l_extendedprice)| {
-        (Part::filter_1(&p_brand, &p_size, &p_container) && LineItem::filter_1(&l_quantity))
-        || (Part::filter_2(&p_brand, &p_size, &p_container) && LineItem::filter_2(&l_quantity))
-        || (Part::filter_3(&p_brand, &p_size, &p_container) && LineItem::filter_3(&l_quantity))
+        (Part::filter_1(params, &p_brand, &p_size, &p_container) && LineItem::filter_1(params, l_quantity))
+        || (Part::filter_2(params, &p_brand, &p_size, &p_container) && LineItem::filter_2(params, l_quantity))
+        || (Part::filter_3(params, &p_brand, &p_size, &p_container) && LineItem::filter_3(params, l_quantity))
     });
 
     // 7. Aggregate the result.
     // 7.1 Compute (l_extendedprice * (1 - l_discount))
     // 7.2. Aggregate sum
     let agg = join_filtered.map(|(_p_brand, _p_container, _p_size, _l_quantity, l_discount, l_extendedprice)| {
-        l_extendedprice * (1.0 - l_discount)
+        l_extendedprice * (N::one() - l_discount)
     }).reduce(|a, b| a + b);
 
+    // 8. Collect: "revenue"
+    vec![Q19Row { revenue: agg }]
+}
+
+/// The pushed down filter on part in SQL, with the brands as parameters.
+const PART_PREDICATE: &str = "(p_brand = ? AND p_container IN ('SM CASE', 'SM BOX', 'SM PACK', 'SM PKG') AND p_size BETWEEN 1 AND 5)
+    OR (p_brand = ? AND p_container IN ('MED BAG', 'MED BOX', 'MED PKG', 'MED PACK') AND p_size BETWEEN 1 AND 10)
+    OR (p_brand = ? AND p_container IN ('LG CASE', 'LG BOX', 'LG PACK', 'LG PKG') AND p_size BETWEEN 1 AND 15)";
 
-    // 8. Print
-    println!("{}", agg.unwrap());
+/// The pushed down filter on lineitem in SQL, with the quantities as parameters.
+const LINEITEM_PREDICATE: &str = "l_shipmode IN ('AIR', 'AIR REG') AND l_shipinstruct = 'DELIVER IN PERSON'
+    AND ((l_quantity >= ? AND l_quantity <= ? + 10) OR (l_quantity >= ? AND l_quantity <= ? + 10) OR (l_quantity >= ? AND l_quantity <= ? + 10))";
 
+/// Estimate the filtered parts (left) and line items (right) and plan the join, see `super::plan`.
+pub fn plan(conn: &Connection, params: &QueryParams, nodes: usize) -> JoinPlan {
+    let part = Estimate::filtered(conn, "part", "p_partkey", PART_PREDICATE, params![params.brand1, params.brand2, params.brand3]);
+    let line_items = Estimate::filtered(
+        conn,
+        "lineitem",
+        "l_partkey",
+        LINEITEM_PREDICATE,
+        params![params.quantity1, params.quantity1, params.quantity2, params.quantity2, params.quantity3, params.quantity3],
+    );
+    JoinPlan::choose(part, line_items, nodes)
 }
 
-pub fn query_duckdb(conn: &Connection, limit: Option<u32>) {
+/**
+ * `query` with the build side of the join chosen by `plan`. With the parts as the build side, see
+ * `query`. With the line items as the build side, their payloads are grouped by l_partkey and every
+ * filtered part probes its group.
+ */
+pub fn query_with_plan<N: Numeric>(line_items: Vec<LineItem<N>>, part: Vec<Part>, params: &QueryParams, plan: &JoinPlan) -> Vec<Q19Row<N>> {
+    if plan.build == Side::Left {
+        return query(line_items, part, params);
+    }
+
+    // 1. Build: scan lineitem, pushed down filters, by l_partkey with (l_quantity, l_discount, l_extendedprice).
+    let mut join_build: HashMap<i64, Vec<(N, N, N)>> = HashMap::new();
+    line_items.into_iter()
+        .filter(|lineitem| lineitem.l_shipinstruct == "DELIVER IN PERSON" && (lineitem.l_shipmode == "AIR" || lineitem.l_shipmode == "AIR REG"))
+        .filter(|lineitem| LineItem::filter_1(params, &lineitem.l_quantity) || LineItem::filter_2(params, &lineitem.l_quantity) || LineItem::filter_3(params, &lineitem.l_quantity))
+        .for_each(|l| join_build.entry(l.l_partkey).or_default().push((l.l_quantity, l.l_discount, l.l_extendedprice)));
+
+    // 2. Probe: scan part, pushed down filter, and 3. filter again and aggregate the revenue.
+    let agg = build_parts(part, params)
+        .into_iter()
+        .flat_map(|(p_partkey, (p_brand, p_container, p_size))| {
+            let line_items = join_build.remove(&p_partkey).unwrap_or_default();
+            line_items.into_iter()
+                .filter(move |(l_quantity, _l_discount, _l_extendedprice)| {
+                    (Part::filter_1(params, &p_brand, &p_size, &p_container) && LineItem::filter_1(params, l_quantity))
+                    || (Part::filter_2(params, &p_brand, &p_size, &p_container) && LineItem::filter_2(params, l_quantity))
+                    || (Part::filter_3(params, &p_brand, &p_size, &p_container) && LineItem::filter_3(params, l_quantity))
+                })
+                .map(|(_l_quantity, l_discount, l_extendedprice)| l_extendedprice * (N::one() - l_discount))
+        })
+        .reduce(|a, b| a + b);
+
+    // 4. Collect: "revenue"
+    vec![Q19Row { revenue: agg }]
+}
+
+/**
+ * Bloom filter pushdown, see `super::bloom`. The filtered parts are a few hundred, so a Bloom
+ * filter of their keys drops almost all line items before they probe the hash table.
+ */
+pub fn query_bloom<N: Numeric>(line_items: Vec<LineItem<N>>, part: Vec<Part>, params: &QueryParams) -> Vec<Q19Row<N>> {
+    // 1. Scan part, 2. pushed down filter on part.
+    let join_build: HashMap<i64, (String, String, i32)> = build_parts(part, params);
+
+    // 3. Bloom filter of the qualifying p_partkey.
+    let part_keys: Vec<i64> = join_build.keys().copied().collect();
+    let bloom = BloomFilter::from_keys(&part_keys, DEFAULT_FALSE_POSITIVE_RATE);
+
+    // 4. Scan lineitem, probe the Bloom filter first, then the pushed down filters.
+    let join_probe = line_items.into_iter()
+        .filter(|lineitem| bloom.contains(&lineitem.l_partkey))
+        .filter(|lineitem| lineitem.l_shipinstruct == "DELIVER IN PERSON" && (lineitem.l_shipmode == "AIR" || lineitem.l_shipmode == "AIR REG"))
+        .filter(|lineitem| LineItem::filter_1(params, &lineitem.l_quantity) || LineItem::filter_2(params, &lineitem.l_quantity) || LineItem::filter_3(params, &lineitem.l_quantity));
+
+    // 5. Join on p_partkey = l_partkey, 6. filter again and 7. aggregate the revenue.
+    let agg = join_probe
+        .filter(|lineitem| match join_build.get(&lineitem.l_partkey) {
+            Some((p_brand, p_container, p_size)) => {
+                (Part::filter_1(params, p_brand, p_size, p_container) && LineItem::filter_1(params, &lineitem.l_quantity))
+                || (Part::filter_2(params, p_brand, p_size, p_container) && LineItem::filter_2(params, &lineitem.l_quantity))
+                || (Part::filter_3(params, p_brand, p_size, p_container) && LineItem::filter_3(params, &lineitem.l_quantity))
+            }
+            None => false,
+        })
+        .map(|lineitem| lineitem.l_extendedprice * (N::one() - lineitem.l_discount))
+        .reduce(|a, b| a + b);
+
+    // 8. Collect: "revenue"
+    vec![Q19Row { revenue: agg }]
+}
+
+/**
+ * Sort-merge join instead of the hash join, see `super::merge_join`. Both inputs are sorted by part
+ * key after the pushed down filters, which leave few parts and line items.
+ */
+pub fn query_merge_join<N: Numeric>(line_items: Vec<LineItem<N>>, part: Vec<Part>, params: &QueryParams) -> Vec<Q19Row<N>> {
+    // 1. Scan part, 2. pushed down filter and sort by p_partkey.
+    // Payload (p_brand, p_container, p_size)
+    let mut part_sorted: Vec<(i64, (String, String, i32))> = part
+        .into_iter()
+        .filter(|part| {
+            Part::filter_1(params, &part.p_brand, &part.p_size, &part.p_container)
+            || Part::filter_2(params, &part.p_brand, &part.p_size, &part.p_container)
+            || Part::filter_3(params, &part.p_brand, &part.p_size, &part.p_container)
+        })
+        .map(|p| (p.p_partkey, (p.p_brand, p.p_container, p.p_size)))
+        .collect();
+    part_sorted.sort_unstable_by_key(|x| x.0);
+
+    // 3. Scan lineitem, 4. pushed down filter and sort by l_partkey.
+    // Payload (l_quantity, l_discount, l_extendedprice)
+    let mut lineitem_sorted: Vec<(i64, (N, N, N))> = line_items
+        .into_iter()
+        .filter(|lineitem| lineitem.l_shipinstruct == "DELIVER IN PERSON" && (lineitem.l_shipmode == "AIR" || lineitem.l_shipmode == "AIR REG"))
+        .filter(|lineitem| LineItem::filter_1(params, &lineitem.l_quantity) || LineItem::filter_2(params, &lineitem.l_quantity) || LineItem::filter_3(params, &lineitem.l_quantity))
+        .map(|l| (l.l_partkey, (l.l_quantity, l.l_discount, l.l_extendedprice)))
+        .collect();
+    lineitem_sorted.sort_unstable_by_key(|x| x.0);
+
+    // 5. Merge join on p_partkey = l_partkey.
+    let joined = merge_join(part_sorted, lineitem_sorted);
+
+    // 6. Filter again, 7. aggregate the revenue.
+    let agg = joined
+        .into_iter()
+        .filter(|(_key, ((p_brand, p_container, p_size), (l_quantity, _l_discount, _l_extendedprice)))| {
+            (Part::filter_1(params, p_brand, p_size, p_container) && LineItem::filter_1(params, l_quantity))
+            || (Part::filter_2(params, p_brand, p_size, p_container) && LineItem::filter_2(params, l_quantity))
+            || (Part::filter_3(params, p_brand, p_size, p_container) && LineItem::filter_3(params, l_quantity))
+        })
+        .map(|(_key, (_part, (_l_quantity, l_discount, l_extendedprice)))| l_extendedprice * (N::one() - l_discount))
+        .reduce(|a, b| a + b);
+
+    // 8. Collect: "revenue"
+    vec![Q19Row { revenue: agg }]
+}
+
+/// The build side of the join: the qualifying parts by p_partkey with (p_brand, p_container, p_size).
+pub fn build_parts(part: Vec<Part>, params: &QueryParams) -> HashMap<i64, (String, String, i32)> {
+    part.into_iter()
+        .filter(|part| {
+            Part::filter_1(params, &part.p_brand, &part.p_size, &part.p_container)
+            || Part::filter_2(params, &part.p_brand, &part.p_size, &part.p_container)
+            || Part::filter_3(params, &part.p_brand, &part.p_size, &part.p_container)
+        })
+        .map(|p| (p.p_partkey, (p.p_brand, p.p_container, p.p_size)))
+        .collect()
+}
+
+/**
+ * The selection vector of a chunk after the pushed down lineitem filters. Every predicate narrows
+ * the selection of the previous one and only looks at its own column.
+ */
+pub fn select_chunk(chunk: &LineItemChunk<'_>, params: &QueryParams) -> Vec<usize> {
+    // l_shipinstruct = "DELIVER IN PERSON"
+    let mut selection: Vec<usize> = (0..chunk.l_partkey.len())
+        .filter(|&i| chunk.l_shipinstruct[i] == "DELIVER IN PERSON")
+        .collect();
+    // l_shipmode = "AIR" or "AIR REG"
+    selection.retain(|&i| chunk.l_shipmode[i] == "AIR" || chunk.l_shipmode[i] == "AIR REG");
+    // l_quantity within one of the three ranges
+    selection.retain(|&i| {
+        let l_quantity = &chunk.l_quantity[i];
+        LineItem::filter_1(params, l_quantity) || LineItem::filter_2(params, l_quantity) || LineItem::filter_3(params, l_quantity)
+    });
+    selection
+}
+
+/**
+ * Probe the selected rows of a chunk against the parts of `build_parts` and sum
+ * l_extendedprice * (1 - l_discount) of the rows that satisfy one of the three disjuncts.
+ */
+pub fn revenue_chunk(chunk: &LineItemChunk<'_>, selection: &[usize], parts: &HashMap<i64, (String, String, i32)>, params: &QueryParams) -> Option<f64> {
+    selection
+        .iter()
+        .filter_map(|&i| parts.get(&chunk.l_partkey[i]).map(|part| (i, part)))
+        .filter(|(i, (p_brand, p_container, p_size))| {
+            let l_quantity = &chunk.l_quantity[*i];
+            (Part::filter_1(params, p_brand, p_size, p_container) && LineItem::filter_1(params, l_quantity))
+            || (Part::filter_2(params, p_brand, p_size, p_container) && LineItem::filter_2(params, l_quantity))
+            || (Part::filter_3(params, p_brand, p_size, p_container) && LineItem::filter_3(params, l_quantity))
+        })
+        .map(|(i, _)| chunk.l_extendedprice[i] * (1.0 - chunk.l_discount[i]))
+        .reduce(|a, b| a + b)
+}
+
+/**
+ * Columnar: part is built into a hash table, lineitem is probed in column chunks of `CHUNK_SIZE`
+ * rows with a selection vector, see `select_chunk` and `revenue_chunk`.
+ */
+pub fn query_columns<const CHUNK_SIZE: usize>(line_items: LineItemColumns, part: Vec<Part>, params: &QueryParams) -> Vec<Q19Row> {
+    let parts = build_parts(part, params);
+
+    let revenue = line_items
+        .chunks(CHUNK_SIZE)
+        .filter_map(|chunk| revenue_chunk(&chunk, &select_chunk(&chunk, params), &parts, params))
+        .reduce(|a, b| a + b);
+
+    vec![Q19Row { revenue }]
+}
+
+/**
+ * Morsel-driven: part is built into a hash table on the calling thread, it is small next to
+ * lineitem. Every thread probes morsels of lineitem and sums its own partial revenue, the partial
+ * sums are added at the end.
+ */
+pub fn query_parallel<N: Numeric + Send + Sync>(line_items: Vec<LineItem<N>>, part: Vec<Part>, params: &QueryParams, parallelism: &Parallelism) -> Vec<Q19Row<N>> {
+    // 1. - 2. Build the filtered parts by p_partkey.
+    let parts = build_parts(part, params);
+
+    // 3. - 7. Filter, probe and sum the morsels of lineitem into one partial revenue per thread.
+    let partials = morsels(&line_items, parallelism, || None, |revenue: &mut Option<N>, morsel| {
+        let morsel_revenue = morsel
+            .iter()
+            .filter(|l| l.l_shipinstruct == "DELIVER IN PERSON" && (l.l_shipmode == "AIR" || l.l_shipmode == "AIR REG"))
+            .filter(|l| LineItem::filter_1(params, &l.l_quantity) || LineItem::filter_2(params, &l.l_quantity) || LineItem::filter_3(params, &l.l_quantity))
+            .filter_map(|l| parts.get(&l.l_partkey).map(|part| (l, part)))
+            .filter(|(l, (p_brand, p_container, p_size))| {
+                (Part::filter_1(params, p_brand, p_size, p_container) && LineItem::filter_1(params, &l.l_quantity))
+                || (Part::filter_2(params, p_brand, p_size, p_container) && LineItem::filter_2(params, &l.l_quantity))
+                || (Part::filter_3(params, p_brand, p_size, p_container) && LineItem::filter_3(params, &l.l_quantity))
+            })
+            .map(|(l, _)| l.l_extendedprice * (N::one() - l.l_discount))
+            .reduce(|a, b| a + b);
+        *revenue = add_revenue(*revenue, morsel_revenue);
+    });
+
+    // 8. Add the partial sums, None if no row qualified on any thread.
+    let revenue = partials.into_iter().fold(None, add_revenue);
+    vec![Q19Row { revenue }]
+}
+
+/// SUM over partial sums, where `None` is the sum of no rows.
+fn add_revenue<N: Numeric>(a: Option<N>, b: Option<N>) -> Option<N> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a + b),
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
+
+pub fn query_duckdb(conn: &Connection, limit: Option<u32>, params: &QueryParams) -> Vec<Q19Row> {
+    query_duckdb_revenue(conn, limit, params, "sum(l_extendedprice * (1 - l_discount))")
+        .into_iter()
+        .map(|revenue| Q19Row { revenue })
+        .collect()
+}
+
+/// Same as `query_duckdb`, with the exact DECIMAL revenue. It is fetched as VARCHAR, duckdb-rs
+/// converts DECIMAL values to `f64`.
+pub fn query_duckdb_decimal(conn: &Connection, limit: Option<u32>, params: &QueryParams) -> Vec<Q19Row<Decimal>> {
+    query_duckdb_revenue::<String>(conn, limit, params, "CAST(sum(l_extendedprice * (1 - l_discount)) AS VARCHAR)")
+        .into_iter()
+        .map(|revenue| Q19Row {
+            revenue: revenue.map(|revenue| revenue.parse().unwrap_or_else(|e| panic!("{}", e))),
+        })
+        .collect()
+}
+
+fn query_duckdb_revenue<T: FromSql>(conn: &Connection, limit: Option<u32>, params: &QueryParams, revenue: &str) -> Vec<Option<T>> {
     let lineitem_table = match limit {
         Some(limit) => format!("(SELECT * FROM lineitem LIMIT {})", limit),
         None => "lineitem".to_string(),
@@ -196,62 +715,226 @@ pub fn query_duckdb(conn: &Connection, limit: Option<u32>) {
         .prepare(&format!(
             r#"
             SELECT
-            sum(l_extendedprice * (1 - l_discount)) AS revenue
+            {} AS revenue
         FROM
             {},
             {}
         WHERE (p_partkey = l_partkey
-            AND p_brand = 'Brand#12'
+            AND p_brand = ?
             AND p_container IN ('SM CASE', 'SM BOX', 'SM PACK', 'SM PKG')
-            AND l_quantity >= 1
-            AND l_quantity <= 1 + 10
+            AND l_quantity >= ?
+            AND l_quantity <= ? + 10
             AND p_size BETWEEN 1 AND 5
             AND l_shipmode IN ('AIR', 'AIR REG')
             AND l_shipinstruct = 'DELIVER IN PERSON')
             OR (p_partkey = l_partkey
-                AND p_brand = 'Brand#23'
+                AND p_brand = ?
                 AND p_container IN ('MED BAG', 'MED BOX', 'MED PKG', 'MED PACK')
-                AND l_quantity >= 10
-                AND l_quantity <= 10 + 10
+                AND l_quantity >= ?
+                AND l_quantity <= ? + 10
                 AND p_size BETWEEN 1 AND 10
                 AND l_shipmode IN ('AIR', 'AIR REG')
                 AND l_shipinstruct = 'DELIVER IN PERSON')
             OR (p_partkey = l_partkey
-                AND p_brand = 'Brand#34'
+                AND p_brand = ?
                 AND p_container IN ('LG CASE', 'LG BOX', 'LG PACK', 'LG PKG')
-                AND l_quantity >= 20
-                AND l_quantity <= 20 + 10
+                AND l_quantity >= ?
+                AND l_quantity <= ? + 10
                 AND p_size BETWEEN 1 AND 15
                 AND l_shipmode IN ('AIR', 'AIR REG')
                 AND l_shipinstruct = 'DELIVER IN PERSON');
     "#,
-            lineitem_table, part_table
+            revenue, lineitem_table, part_table
         ))
         .expect("Error preparing query for LineItem");
-    let mut rows = stmt.query([]).expect("Error executing Query 1");
+    let mut rows = stmt
+        .query(params![
+            params.brand1,
+            params.quantity1,
+            params.quantity1,
+            params.brand2,
+            params.quantity2,
+            params.quantity2,
+            params.brand3,
+            params.quantity3,
+            params.quantity3,
+        ])
+        .expect("Error executing Query 19");
+    let mut result = vec![];
     while let Some(row) = rows.next().unwrap() {
-        let revenue: f64 = row.get(0).unwrap();
-        println!("{}", revenue);
+        result.push(row.get(0).unwrap());
     }
+    result
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::tpch::initialize::initialize_database;
+    use crate::tpch::answers::{answer_tolerance, expected};
+    use crate::tpch::verify::{assert_match, compare};
 
     #[test]
     fn test_query_19() {
         let limit = None;
-        let conn = initialize_database(1);
-        let line_items = LineItem::load(&conn, limit);
-        let parts = Part::load(&conn, limit);
-        query(line_items, parts);
+        let conn = initialize_database(0.01);
+        let line_items = LineItem::load(&conn, limit).unwrap();
+        let parts = Part::load(&conn, limit).unwrap();
+        let result = query(line_items, parts, &QueryParams::default());
+
+        let expected = query_duckdb(&conn, limit, &QueryParams::default());
+        assert_match(&[compare("Q19", "duckdb", "baseline", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_19_columns() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+        let (line_items, parts) = load_columns(&conn).unwrap();
+        let result = query_columns::<1024>(line_items, parts, &params);
+
+        let expected = query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q19", "duckdb", "baseline-columns", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_19_merge_join() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+        let (line_items, parts) = load(&conn).unwrap();
+        let result = query_merge_join(line_items, parts, &params);
+
+        let expected = query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q19", "duckdb", "baseline-merge-join", &expected, &result, &Tolerance::default())]);
+
+        let (line_items, parts) = load_decimal(&conn).unwrap();
+        let result = query_merge_join(line_items, parts, &params);
+
+        let expected = query_duckdb_decimal(&conn, None, &params);
+        assert_match(&[compare("Q19", "duckdb", "baseline-merge-join", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_19_bloom() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+        let (line_items, parts) = load(&conn).unwrap();
+        let result = query_bloom(line_items, parts, &params);
+
+        let expected = query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q19", "duckdb", "baseline-bloom", &expected, &result, &Tolerance::default())]);
+
+        let (line_items, parts) = load_decimal(&conn).unwrap();
+        let result = query_bloom(line_items, parts, &params);
+
+        let expected = query_duckdb_decimal(&conn, None, &params);
+        assert_match(&[compare("Q19", "duckdb", "baseline-bloom", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_19_plan() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+
+        // The filtered parts are fewer than the filtered line items.
+        let plan = plan(&conn, &params, 1);
+        assert_eq!(plan.build, Side::Left);
+
+        let expected = query_duckdb(&conn, None, &params);
+        for build in [Side::Left, Side::Right] {
+            let (line_items, parts) = load(&conn).unwrap();
+            let result = query_with_plan(line_items, parts, &params, &JoinPlan { build, ..plan.clone() });
+            assert_match(&[compare("Q19", "duckdb", "baseline-plan", &expected, &result, &Tolerance::default())]);
+        }
+    }
+
+    #[test]
+    fn test_query_19_parallel() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+        let expected = query_duckdb(&conn, None, &params);
+
+        for threads in [1, 2, 4] {
+            let (line_items, parts) = load_arrow(&conn).unwrap();
+            let result = query_parallel(line_items, parts, &params, &Parallelism::new(threads));
+            assert_match(&[compare("Q19", "duckdb", "baseline-parallel", &expected, &result, &Tolerance::default())]);
+        }
+
+        // No qualifying row on any thread is NULL, not 0.
+        let (line_items, parts) = load_arrow(&conn).unwrap();
+        let result = query_parallel(line_items, parts, &QueryParams { quantity1: 100, quantity2: 100, quantity3: 100, ..params }, &Parallelism::new(4));
+        assert_eq!(result[0].revenue, None);
+    }
+
+    #[test]
+    fn test_query_19_decimal() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+        let (line_items, parts) = load_decimal(&conn).unwrap();
+        let result = query(line_items, parts, &params);
+
+        let expected = query_duckdb_decimal(&conn, None, &params);
+        assert_match(&[compare("Q19", "duckdb", "baseline-decimal", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_19_load_arrow() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+        let (line_items, parts) = load_arrow(&conn).unwrap();
+        let result = query(line_items, parts, &params);
+
+        let expected = query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q19", "duckdb", "baseline-arrow", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_19_tbl() {
+        let conn = initialize_database(0.01);
+        let dir = std::env::temp_dir().join(format!("hydro_benchmarks_tbl_q19_{}", std::process::id()));
+        tbl::export(&conn, "lineitem", &dir);
+        tbl::export(&conn, "part", &dir);
+        let params = QueryParams::default();
+        let (line_items, parts) = load_tbl(&dir).unwrap();
+        let result = query(line_items, parts, &params);
+
+        let expected = query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q19", "duckdb", "baseline-tbl", &expected, &result, &Tolerance::default())]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_query_19_params() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams {
+            quantity1: 5,
+            quantity2: 15,
+            quantity3: 25,
+            brand1: "Brand#55".to_string(),
+            brand2: "Brand#41".to_string(),
+            brand3: "Brand#13".to_string(),
+        };
+        let (line_items, parts) = load(&conn).unwrap();
+        let result = query(line_items, parts, &params);
+
+        let expected = query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q19", "duckdb", "baseline", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_19_answer() {
+        let conn = initialize_database(0.01);
+        let (line_items, parts) = load(&conn).unwrap();
+        let result = query(line_items, parts, &QueryParams::default());
+
+        let expected = expected(&conn, 19, 0.01);
+        assert_match(&[compare("Q19", "tpch_answers", "baseline", &expected, &result, &answer_tolerance())]);
     }
 
     #[test]
     fn test_query_19_duckdb() {
-        let conn = initialize_database(1);
-        query_duckdb(&conn, None);
+        let conn = initialize_database(0.01);
+        let result = query_duckdb(&conn, None, &QueryParams::default());
+        assert_eq!(result.len(), 1);
     }
 }
\ No newline at end of file
@@ -0,0 +1,378 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use duckdb::Connection;
+
+use super::answers::{parse_field, FromAnswer};
+use super::util::load_rows;
+use super::verify::{ApproxEq, Tolerance};
+
+#[derive(Debug, Clone)]
+pub struct Part {
+    pub p_partkey: i64,
+    pub p_mfgr: String,
+    pub p_type: String,
+    pub p_size: i32,
+}
+
+impl Part {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "part", &["p_partkey", "p_mfgr", "p_type", "p_size"], limit, |row| {
+            Ok(Part {
+                p_partkey: row.get(0)?,
+                p_mfgr: row.get(1)?,
+                p_type: row.get(2)?,
+                p_size: row.get(3)?,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Supplier {
+    pub s_suppkey: i64,
+    pub s_name: String,
+    pub s_address: String,
+    pub s_nationkey: i32,
+    pub s_phone: String,
+    pub s_acctbal: f64,
+    pub s_comment: String,
+}
+
+impl Supplier {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(
+            conn,
+            "supplier",
+            &["s_suppkey", "s_name", "s_address", "s_nationkey", "s_phone", "s_acctbal", "s_comment"],
+            limit,
+            |row| {
+                Ok(Supplier {
+                    s_suppkey: row.get(0)?,
+                    s_name: row.get(1)?,
+                    s_address: row.get(2)?,
+                    s_nationkey: row.get(3)?,
+                    s_phone: row.get(4)?,
+                    s_acctbal: row.get(5)?,
+                    s_comment: row.get(6)?,
+                })
+            },
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PartSupp {
+    pub ps_partkey: i64,
+    pub ps_suppkey: i64,
+    pub ps_supplycost: f64,
+}
+
+impl PartSupp {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "partsupp", &["ps_partkey", "ps_suppkey", "ps_supplycost"], limit, |row| {
+            Ok(PartSupp {
+                ps_partkey: row.get(0)?,
+                ps_suppkey: row.get(1)?,
+                ps_supplycost: row.get(2)?,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Nation {
+    pub n_nationkey: i32,
+    pub n_name: String,
+    pub n_regionkey: i32,
+}
+
+impl Nation {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "nation", &["n_nationkey", "n_name", "n_regionkey"], limit, |row| {
+            Ok(Nation {
+                n_nationkey: row.get(0)?,
+                n_name: row.get(1)?,
+                n_regionkey: row.get(2)?,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Region {
+    pub r_regionkey: i32,
+    pub r_name: String,
+}
+
+impl Region {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "region", &["r_regionkey", "r_name"], limit, |row| {
+            Ok(Region {
+                r_regionkey: row.get(0)?,
+                r_name: row.get(1)?,
+            })
+        })
+    }
+}
+
+/// Result row of Query 2, ordered by s_acctbal descending, n_name, s_name and p_partkey.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Q2Row {
+    pub s_acctbal: f64,
+    pub s_name: String,
+    pub n_name: String,
+    pub p_partkey: i64,
+    pub p_mfgr: String,
+    pub s_address: String,
+    pub s_phone: String,
+    pub s_comment: String,
+}
+
+impl Q2Row {
+    pub fn new(supplier: Supplier, n_name: String, p_partkey: i64, p_mfgr: String) -> Q2Row {
+        Q2Row {
+            s_acctbal: supplier.s_acctbal,
+            s_name: supplier.s_name,
+            n_name,
+            p_partkey,
+            p_mfgr,
+            s_address: supplier.s_address,
+            s_phone: supplier.s_phone,
+            s_comment: supplier.s_comment,
+        }
+    }
+
+    /// ORDER BY s_acctbal DESC, n_name, s_name, p_partkey
+    pub fn order_by(a: &Q2Row, b: &Q2Row) -> Ordering {
+        b.s_acctbal
+            .total_cmp(&a.s_acctbal)
+            .then_with(|| a.n_name.cmp(&b.n_name))
+            .then_with(|| a.s_name.cmp(&b.s_name))
+            .then(a.p_partkey.cmp(&b.p_partkey))
+    }
+}
+
+impl ApproxEq for Q2Row {
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+        tolerance.eq_f64(self.s_acctbal, other.s_acctbal)
+            && self.s_name == other.s_name
+            && self.n_name == other.n_name
+            && self.p_partkey == other.p_partkey
+            && self.p_mfgr == other.p_mfgr
+            && self.s_address == other.s_address
+            && self.s_phone == other.s_phone
+            && self.s_comment == other.s_comment
+    }
+}
+
+impl FromAnswer for Q2Row {
+    fn from_answer(fields: &[&str]) -> Self {
+        Q2Row {
+            s_acctbal: parse_field(fields, 0),
+            s_name: parse_field(fields, 1),
+            n_name: parse_field(fields, 2),
+            p_partkey: parse_field(fields, 3),
+            p_mfgr: parse_field(fields, 4),
+            s_address: parse_field(fields, 5),
+            s_phone: parse_field(fields, 6),
+            s_comment: parse_field(fields, 7),
+        }
+    }
+}
+
+pub type Tables = (Vec<Part>, Vec<Supplier>, Vec<PartSupp>, Vec<Nation>, Vec<Region>);
+
+pub fn load(conn: &Connection) -> Tables {
+    (
+        Part::load(conn, None),
+        Supplier::load(conn, None),
+        PartSupp::load(conn, None),
+        Nation::load(conn, None),
+        Region::load(conn, None),
+    )
+}
+
+pub fn query(
+    part: Vec<Part>,
+    supplier: Vec<Supplier>,
+    partsupp: Vec<PartSupp>,
+    nation: Vec<Nation>,
+    region: Vec<Region>,
+) -> Vec<Q2Row> {
+    // The correlated subquery computes min(ps_supplycost) over the European suppliers of a part.
+    // Decorrelated, this is a group by ps_partkey over the same join that the outer query uses,
+    // followed by a join of the outer rows with the minimum on ps_partkey and
+    // ps_supplycost = min(ps_supplycost).
+
+    // 1. Scan region and filter on r_name = 'EUROPE'.
+    let region_build: Vec<i32> = region
+        .into_iter()
+        .filter(|r| r.r_name == "EUROPE")
+        .map(|r| r.r_regionkey)
+        .collect();
+
+    // 2. Scan nation and probe region.
+    // Keys: n_nationkey
+    // Payload: n_name
+    let nation_build: HashMap<i32, String> = nation
+        .into_iter()
+        .filter(|n| region_build.contains(&n.n_regionkey))
+        .map(|n| (n.n_nationkey, n.n_name))
+        .collect();
+
+    // 3. Scan supplier and probe nation.
+    // Keys: s_suppkey
+    // Payload: supplier, n_name
+    let supplier_build: HashMap<i64, (Supplier, String)> = supplier
+        .into_iter()
+        .filter_map(|s| {
+            let n_name = nation_build.get(&s.s_nationkey)?.clone();
+            Some((s.s_suppkey, (s, n_name)))
+        })
+        .collect();
+
+    // 4. Scan part and filter on p_size = 15 and p_type LIKE '%BRASS'.
+    // Keys: p_partkey
+    // Payload: p_mfgr
+    let part_build: HashMap<i64, String> = part
+        .into_iter()
+        .filter(|p| p.p_size == 15 && p.p_type.ends_with("BRASS"))
+        .map(|p| (p.p_partkey, p.p_mfgr))
+        .collect();
+
+    // 5. Scan partsupp and probe part and supplier. Restricting the subquery to the parts of the
+    // outer query is safe as the subquery is only evaluated for those.
+    let joined: Vec<&PartSupp> = partsupp
+        .iter()
+        .filter(|ps| part_build.contains_key(&ps.ps_partkey) && supplier_build.contains_key(&ps.ps_suppkey))
+        .collect();
+
+    // 6. Subquery: group by ps_partkey and compute min(ps_supplycost).
+    let min_cost = joined.iter().fold(HashMap::new(), |mut acc: HashMap<i64, f64>, ps| {
+        let min = acc.entry(ps.ps_partkey).or_insert(ps.ps_supplycost);
+        *min = min.min(ps.ps_supplycost);
+        acc
+    });
+
+    // 7. Keep the rows where ps_supplycost equals the minimum of the part.
+    let mut result: Vec<Q2Row> = joined
+        .into_iter()
+        .filter(|ps| min_cost[&ps.ps_partkey] == ps.ps_supplycost)
+        .map(|ps| {
+            let (s, n_name) = supplier_build[&ps.ps_suppkey].clone();
+            Q2Row::new(s, n_name, ps.ps_partkey, part_build[&ps.ps_partkey].clone())
+        })
+        .collect();
+
+    // 8. Sort by s_acctbal desc, n_name, s_name, p_partkey and keep the first 100 rows.
+    result.sort_by(Q2Row::order_by);
+    result.truncate(100);
+    result
+}
+
+pub fn query_duckdb(conn: &Connection, limit: Option<u32>) -> Vec<Q2Row> {
+    let table = |name: &str| match limit {
+        Some(limit) => format!("(SELECT * FROM {} LIMIT {}) AS {}", name, limit, name),
+        None => name.to_string(),
+    };
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"
+        SELECT
+            s_acctbal,
+            s_name,
+            n_name,
+            p_partkey,
+            p_mfgr,
+            s_address,
+            s_phone,
+            s_comment
+        FROM
+            {part},
+            {supplier},
+            {partsupp},
+            {nation},
+            {region}
+        WHERE
+            p_partkey = ps_partkey
+            AND s_suppkey = ps_suppkey
+            AND p_size = 15
+            AND p_type LIKE '%BRASS'
+            AND s_nationkey = n_nationkey
+            AND n_regionkey = r_regionkey
+            AND r_name = 'EUROPE'
+            AND ps_supplycost = (
+                SELECT
+                    min(ps_supplycost)
+                FROM
+                    {partsupp},
+                    {supplier},
+                    {nation},
+                    {region}
+                WHERE
+                    p_partkey = ps_partkey
+                    AND s_suppkey = ps_suppkey
+                    AND s_nationkey = n_nationkey
+                    AND n_regionkey = r_regionkey
+                    AND r_name = 'EUROPE')
+        ORDER BY
+            s_acctbal DESC,
+            n_name,
+            s_name,
+            p_partkey
+        LIMIT 100;
+    "#,
+            part = table("part"),
+            supplier = table("supplier"),
+            partsupp = table("partsupp"),
+            nation = table("nation"),
+            region = table("region")
+        ))
+        .expect("Error preparing query for DuckDB");
+
+    let mut rows = stmt.query([]).expect("Error executing Query 2");
+    let mut result = vec![];
+    while let Some(row) = rows.next().unwrap() {
+        result.push(Q2Row {
+            s_acctbal: row.get(0).unwrap(),
+            s_name: row.get(1).unwrap(),
+            n_name: row.get(2).unwrap(),
+            p_partkey: row.get(3).unwrap(),
+            p_mfgr: row.get(4).unwrap(),
+            s_address: row.get(5).unwrap(),
+            s_phone: row.get(6).unwrap(),
+            s_comment: row.get(7).unwrap(),
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tpch::answers::{answer_tolerance, expected};
+    use crate::tpch::initialize::initialize_database;
+    use crate::tpch::verify::{assert_match, compare};
+
+    #[test]
+    fn test_query_2() {
+        let conn = initialize_database(0.01);
+        let (part, supplier, partsupp, nation, region) = load(&conn);
+        let result = query(part, supplier, partsupp, nation, region);
+        assert!(result.len() <= 100);
+
+        let expected = query_duckdb(&conn, None);
+        assert_match(&[compare("Q2", "duckdb", "baseline", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_2_answer() {
+        let conn = initialize_database(0.01);
+        let (part, supplier, partsupp, nation, region) = load(&conn);
+        let result = query(part, supplier, partsupp, nation, region);
+
+        let expected = expected(&conn, 2, 0.01);
+        assert_match(&[compare("Q2", "tpch_answers", "baseline", &expected, &result, &answer_tolerance())]);
+    }
+}
@@ -0,0 +1,295 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::NaiveDate;
+use duckdb::Connection;
+
+use super::answers::{parse_field, FromAnswer};
+use super::util::{load_rows, to_date};
+use super::verify::{ApproxEq, Tolerance};
+
+#[derive(Debug, Clone)]
+pub struct Supplier {
+    pub s_suppkey: i64,
+    pub s_name: String,
+    pub s_address: String,
+    pub s_nationkey: i32,
+}
+
+impl Supplier {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "supplier", &["s_suppkey", "s_name", "s_address", "s_nationkey"], limit, |row| {
+            Ok(Supplier {
+                s_suppkey: row.get(0)?,
+                s_name: row.get(1)?,
+                s_address: row.get(2)?,
+                s_nationkey: row.get(3)?,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Nation {
+    pub n_nationkey: i32,
+    pub n_name: String,
+}
+
+impl Nation {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "nation", &["n_nationkey", "n_name"], limit, |row| {
+            Ok(Nation {
+                n_nationkey: row.get(0)?,
+                n_name: row.get(1)?,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PartSupp {
+    pub ps_partkey: i64,
+    pub ps_suppkey: i64,
+    pub ps_availqty: i64,
+}
+
+impl PartSupp {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "partsupp", &["ps_partkey", "ps_suppkey", "ps_availqty"], limit, |row| {
+            Ok(PartSupp {
+                ps_partkey: row.get(0)?,
+                ps_suppkey: row.get(1)?,
+                ps_availqty: row.get(2)?,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Part {
+    pub p_partkey: i64,
+    pub p_name: String,
+}
+
+impl Part {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "part", &["p_partkey", "p_name"], limit, |row| {
+            Ok(Part {
+                p_partkey: row.get(0)?,
+                p_name: row.get(1)?,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LineItem {
+    pub l_partkey: i64,
+    pub l_suppkey: i64,
+    pub l_quantity: f64,
+    pub l_shipdate: NaiveDate,
+}
+
+impl LineItem {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "lineitem", &["l_partkey", "l_suppkey", "l_quantity", "l_shipdate"], limit, |row| {
+            Ok(LineItem {
+                l_partkey: row.get(0)?,
+                l_suppkey: row.get(1)?,
+                l_quantity: row.get(2)?,
+                l_shipdate: to_date(row.get(3)?),
+            })
+        })
+    }
+}
+
+/// Result row of Query 20, ordered by s_name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Q20Row {
+    pub s_name: String,
+    pub s_address: String,
+}
+
+impl ApproxEq for Q20Row {
+    fn approx_eq(&self, other: &Self, _tolerance: &Tolerance) -> bool {
+        self == other
+    }
+}
+
+impl FromAnswer for Q20Row {
+    fn from_answer(fields: &[&str]) -> Self {
+        Q20Row {
+            s_name: parse_field(fields, 0),
+            s_address: parse_field(fields, 1),
+        }
+    }
+}
+
+pub type Tables = (Vec<Supplier>, Vec<Nation>, Vec<PartSupp>, Vec<Part>, Vec<LineItem>);
+
+pub fn load(conn: &Connection) -> Tables {
+    (
+        Supplier::load(conn, None),
+        Nation::load(conn, None),
+        PartSupp::load(conn, None),
+        Part::load(conn, None),
+        LineItem::load(conn, None),
+    )
+}
+
+pub fn query(
+    supplier: Vec<Supplier>,
+    nation: Vec<Nation>,
+    partsupp: Vec<PartSupp>,
+    part: Vec<Part>,
+    line_items: Vec<LineItem>,
+) -> Vec<Q20Row> {
+    // The nested IN subqueries become semi joins. The correlated scalar subquery computes
+    // 0.5 * sum(l_quantity) per (l_partkey, l_suppkey), decorrelated it is a group by on both keys
+    // that is joined with partsupp. Partsupp rows without line items are dropped by the join, just
+    // like the NULL comparison drops them in SQL.
+
+    // 1. Scan part and filter on p_name LIKE 'forest%'.
+    let part_build: HashSet<i64> = part
+        .into_iter()
+        .filter(|p| p.p_name.starts_with("forest"))
+        .map(|p| p.p_partkey)
+        .collect();
+
+    // 2. Scan lineitem, filter on l_shipdate in [1994-01-01, 1995-01-01) and semi join part.
+    // 3. Subquery: group by l_partkey, l_suppkey and compute 0.5 * sum(l_quantity).
+    let threshold = line_items
+        .into_iter()
+        .filter(|l| {
+            l.l_shipdate >= NaiveDate::from_ymd_opt(1994, 1, 1).unwrap()
+                && l.l_shipdate < NaiveDate::from_ymd_opt(1995, 1, 1).unwrap()
+        })
+        .filter(|l| part_build.contains(&l.l_partkey))
+        .fold(HashMap::new(), |mut acc: HashMap<(i64, i64), f64>, l| {
+            *acc.entry((l.l_partkey, l.l_suppkey)).or_insert(0.0) += 0.5 * l.l_quantity;
+            acc
+        });
+
+    // 4. Scan partsupp, semi join part and join the subquery on ps_partkey, ps_suppkey.
+    // Filter on ps_availqty > 0.5 * sum(l_quantity) and keep the distinct ps_suppkey.
+    let suppkeys: HashSet<i64> = partsupp
+        .into_iter()
+        .filter(|ps| part_build.contains(&ps.ps_partkey))
+        .filter(|ps| {
+            threshold
+                .get(&(ps.ps_partkey, ps.ps_suppkey))
+                .is_some_and(|threshold| ps.ps_availqty as f64 > *threshold)
+        })
+        .map(|ps| ps.ps_suppkey)
+        .collect();
+
+    // 5. Scan nation and filter on n_name = 'CANADA'.
+    let nation_build: HashSet<i32> = nation
+        .into_iter()
+        .filter(|n| n.n_name == "CANADA")
+        .map(|n| n.n_nationkey)
+        .collect();
+
+    // 6. Scan supplier, probe nation and semi join the qualifying suppliers.
+    let mut result: Vec<Q20Row> = supplier
+        .into_iter()
+        .filter(|s| nation_build.contains(&s.s_nationkey) && suppkeys.contains(&s.s_suppkey))
+        .map(|s| Q20Row {
+            s_name: s.s_name,
+            s_address: s.s_address,
+        })
+        .collect();
+
+    // 7. Sort by s_name.
+    result.sort_by(|a, b| a.s_name.cmp(&b.s_name));
+    result
+}
+
+pub fn query_duckdb(conn: &Connection, limit: Option<u32>) -> Vec<Q20Row> {
+    let table = |name: &str| match limit {
+        Some(limit) => format!("(SELECT * FROM {} LIMIT {}) AS {}", name, limit, name),
+        None => name.to_string(),
+    };
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"
+        SELECT
+            s_name,
+            s_address
+        FROM
+            {supplier},
+            {nation}
+        WHERE
+            s_suppkey IN (
+                SELECT
+                    ps_suppkey
+                FROM
+                    {partsupp}
+                WHERE
+                    ps_partkey IN (
+                        SELECT
+                            p_partkey
+                        FROM
+                            {part}
+                        WHERE
+                            p_name LIKE 'forest%')
+                    AND ps_availqty > (
+                        SELECT
+                            0.5 * sum(l_quantity)
+                        FROM
+                            {lineitem}
+                        WHERE
+                            l_partkey = ps_partkey
+                            AND l_suppkey = ps_suppkey
+                            AND l_shipdate >= CAST('1994-01-01' AS date)
+                            AND l_shipdate < CAST('1995-01-01' AS date)))
+            AND s_nationkey = n_nationkey
+            AND n_name = 'CANADA'
+        ORDER BY
+            s_name;
+    "#,
+            supplier = table("supplier"),
+            nation = table("nation"),
+            partsupp = table("partsupp"),
+            part = table("part"),
+            lineitem = table("lineitem")
+        ))
+        .expect("Error preparing query for DuckDB");
+
+    let mut rows = stmt.query([]).expect("Error executing Query 20");
+    let mut result = vec![];
+    while let Some(row) = rows.next().unwrap() {
+        result.push(Q20Row {
+            s_name: row.get(0).unwrap(),
+            s_address: row.get(1).unwrap(),
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tpch::answers::{answer_tolerance, expected};
+    use crate::tpch::initialize::initialize_database;
+    use crate::tpch::verify::{assert_match, compare};
+
+    #[test]
+    fn test_query_20() {
+        let conn = initialize_database(0.01);
+        let (supplier, nation, partsupp, part, line_items) = load(&conn);
+        let result = query(supplier, nation, partsupp, part, line_items);
+
+        let expected = query_duckdb(&conn, None);
+        assert_match(&[compare("Q20", "duckdb", "baseline", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_20_answer() {
+        let conn = initialize_database(0.01);
+        let (supplier, nation, partsupp, part, line_items) = load(&conn);
+        let result = query(supplier, nation, partsupp, part, line_items);
+
+        let expected = expected(&conn, 20, 0.01);
+        assert_match(&[compare("Q20", "tpch_answers", "baseline", &expected, &result, &answer_tolerance())]);
+    }
+}
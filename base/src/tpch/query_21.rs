@@ -0,0 +1,335 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use chrono::NaiveDate;
+use duckdb::Connection;
+
+use super::answers::{parse_field, FromAnswer};
+use super::util::{get_char, load_rows, to_date};
+use super::verify::{ApproxEq, Tolerance};
+
+#[derive(Debug, Clone)]
+pub struct Supplier {
+    pub s_suppkey: i64,
+    pub s_name: String,
+    pub s_nationkey: i32,
+}
+
+impl Supplier {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "supplier", &["s_suppkey", "s_name", "s_nationkey"], limit, |row| {
+            Ok(Supplier {
+                s_suppkey: row.get(0)?,
+                s_name: row.get(1)?,
+                s_nationkey: row.get(2)?,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LineItem {
+    pub l_orderkey: i64,
+    pub l_suppkey: i64,
+    pub l_commitdate: NaiveDate,
+    pub l_receiptdate: NaiveDate,
+}
+
+impl LineItem {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "lineitem", &["l_orderkey", "l_suppkey", "l_commitdate", "l_receiptdate"], limit, |row| {
+            Ok(LineItem {
+                l_orderkey: row.get(0)?,
+                l_suppkey: row.get(1)?,
+                l_commitdate: to_date(row.get(2)?),
+                l_receiptdate: to_date(row.get(3)?),
+            })
+        })
+    }
+
+    /// l_receiptdate > l_commitdate
+    pub fn is_late(&self) -> bool {
+        self.l_receiptdate > self.l_commitdate
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Order {
+    pub o_orderkey: i64,
+    pub o_orderstatus: char,
+}
+
+impl Order {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "orders", &["o_orderkey", "o_orderstatus"], limit, |row| {
+            Ok(Order {
+                o_orderkey: row.get(0)?,
+                o_orderstatus: get_char(row, 1)?,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Nation {
+    pub n_nationkey: i32,
+    pub n_name: String,
+}
+
+impl Nation {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "nation", &["n_nationkey", "n_name"], limit, |row| {
+            Ok(Nation {
+                n_nationkey: row.get(0)?,
+                n_name: row.get(1)?,
+            })
+        })
+    }
+}
+
+/**
+ * The distinct suppliers of an order, reduced to what the subqueries of Query 21 need: whether
+ * there is more than one.
+ *
+ * `EXISTS (... l2.l_suppkey <> l1.l_suppkey)` holds iff the order has more than one distinct
+ * supplier. `NOT EXISTS (... l3.l_suppkey <> l1.l_suppkey AND late)` holds iff l1's supplier is
+ * the only supplier with late line items, as l1 is late itself.
+ */
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Suppliers {
+    first: Option<i64>,
+    multiple: bool,
+}
+
+impl Suppliers {
+    pub fn insert(&mut self, suppkey: i64) {
+        match self.first {
+            None => self.first = Some(suppkey),
+            Some(first) => self.multiple |= first != suppkey,
+        }
+    }
+
+    pub fn is_multiple(&self) -> bool {
+        self.multiple
+    }
+}
+
+/// Result row of Query 21, ordered by numwait descending and s_name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Q21Row {
+    pub s_name: String,
+    pub numwait: i64,
+}
+
+impl Q21Row {
+    /// ORDER BY numwait DESC, s_name
+    pub fn order_by(a: &Q21Row, b: &Q21Row) -> Ordering {
+        b.numwait.cmp(&a.numwait).then_with(|| a.s_name.cmp(&b.s_name))
+    }
+}
+
+impl ApproxEq for Q21Row {
+    fn approx_eq(&self, other: &Self, _tolerance: &Tolerance) -> bool {
+        self == other
+    }
+}
+
+impl FromAnswer for Q21Row {
+    fn from_answer(fields: &[&str]) -> Self {
+        Q21Row {
+            s_name: parse_field(fields, 0),
+            numwait: parse_field(fields, 1),
+        }
+    }
+}
+
+pub fn load(conn: &Connection) -> (Vec<Supplier>, Vec<LineItem>, Vec<Order>, Vec<Nation>) {
+    (
+        Supplier::load(conn, None),
+        LineItem::load(conn, None),
+        Order::load(conn, None),
+        Nation::load(conn, None),
+    )
+}
+
+pub fn query(supplier: Vec<Supplier>, line_items: Vec<LineItem>, orders: Vec<Order>, nation: Vec<Nation>) -> Vec<Q21Row> {
+    // Both subqueries are correlated on l_orderkey with an inequality on l_suppkey. Decorrelated,
+    // they become a group by l_orderkey that tracks the distinct suppliers of all and of the late
+    // line items (see `Suppliers`). The subqueries only look at line items of the same order, so
+    // the filter on o_orderstatus = 'F' can be applied before grouping.
+
+    // 1. Scan nation and filter on n_name = 'SAUDI ARABIA'.
+    let nation_build: HashSet<i32> = nation
+        .into_iter()
+        .filter(|n| n.n_name == "SAUDI ARABIA")
+        .map(|n| n.n_nationkey)
+        .collect();
+
+    // 2. Scan supplier and probe nation.
+    // Keys: s_suppkey
+    // Payload: s_name
+    let supplier_build: HashMap<i64, String> = supplier
+        .into_iter()
+        .filter(|s| nation_build.contains(&s.s_nationkey))
+        .map(|s| (s.s_suppkey, s.s_name))
+        .collect();
+
+    // 3. Scan orders and filter on o_orderstatus = 'F'.
+    let orders_build: HashSet<i64> = orders
+        .into_iter()
+        .filter(|o| o.o_orderstatus == 'F')
+        .map(|o| o.o_orderkey)
+        .collect();
+
+    // 4. Scan lineitem and probe orders.
+    // Subqueries: group by l_orderkey and track the suppliers of all and of the late line items.
+    // Outer query: keep the late line items of the suppliers from step 2 (l1).
+    let mut suppliers: HashMap<i64, (Suppliers, Suppliers)> = HashMap::new();
+    let mut candidates: Vec<(i64, &String)> = vec![];
+    for l in line_items.into_iter().filter(|l| orders_build.contains(&l.l_orderkey)) {
+        let (all, late) = suppliers.entry(l.l_orderkey).or_default();
+        all.insert(l.l_suppkey);
+        if l.is_late() {
+            late.insert(l.l_suppkey);
+            if let Some(s_name) = supplier_build.get(&l.l_suppkey) {
+                candidates.push((l.l_orderkey, s_name));
+            }
+        }
+    }
+
+    // 5. EXISTS: the order has another supplier. NOT EXISTS: no other supplier is late.
+    // 6. Group by s_name and count(*).
+    let agg = candidates
+        .into_iter()
+        .filter(|(l_orderkey, _)| {
+            let (all, late) = &suppliers[l_orderkey];
+            all.is_multiple() && !late.is_multiple()
+        })
+        .fold(HashMap::new(), |mut acc: HashMap<&String, i64>, (_, s_name)| {
+            *acc.entry(s_name).or_insert(0) += 1;
+            acc
+        });
+
+    // 7. Sort by numwait desc, s_name and keep the first 100 rows.
+    let mut result: Vec<Q21Row> = agg
+        .into_iter()
+        .map(|(s_name, numwait)| Q21Row {
+            s_name: s_name.clone(),
+            numwait,
+        })
+        .collect();
+    result.sort_by(Q21Row::order_by);
+    result.truncate(100);
+    result
+}
+
+pub fn query_duckdb(conn: &Connection, limit: Option<u32>) -> Vec<Q21Row> {
+    let table = |name: &str| match limit {
+        Some(limit) => format!("(SELECT * FROM {} LIMIT {}) AS {}", name, limit, name),
+        None => name.to_string(),
+    };
+    let lineitem = |alias: &str| match limit {
+        Some(limit) => format!("(SELECT * FROM lineitem LIMIT {}) AS {}", limit, alias),
+        None => format!("lineitem {}", alias),
+    };
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"
+        SELECT
+            s_name,
+            count(*) AS numwait
+        FROM
+            {},
+            {},
+            {},
+            {}
+        WHERE
+            s_suppkey = l1.l_suppkey
+            AND o_orderkey = l1.l_orderkey
+            AND o_orderstatus = 'F'
+            AND l1.l_receiptdate > l1.l_commitdate
+            AND EXISTS (
+                SELECT
+                    *
+                FROM
+                    {}
+                WHERE
+                    l2.l_orderkey = l1.l_orderkey
+                    AND l2.l_suppkey <> l1.l_suppkey)
+            AND NOT EXISTS (
+                SELECT
+                    *
+                FROM
+                    {}
+                WHERE
+                    l3.l_orderkey = l1.l_orderkey
+                    AND l3.l_suppkey <> l1.l_suppkey
+                    AND l3.l_receiptdate > l3.l_commitdate)
+            AND s_nationkey = n_nationkey
+            AND n_name = 'SAUDI ARABIA'
+        GROUP BY
+            s_name
+        ORDER BY
+            numwait DESC,
+            s_name
+        LIMIT 100;
+    "#,
+            table("supplier"),
+            lineitem("l1"),
+            table("orders"),
+            table("nation"),
+            lineitem("l2"),
+            lineitem("l3")
+        ))
+        .expect("Error preparing query for DuckDB");
+
+    let mut rows = stmt.query([]).expect("Error executing Query 21");
+    let mut result = vec![];
+    while let Some(row) = rows.next().unwrap() {
+        result.push(Q21Row {
+            s_name: row.get(0).unwrap(),
+            numwait: row.get(1).unwrap(),
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tpch::answers::{answer_tolerance, expected};
+    use crate::tpch::initialize::initialize_database;
+    use crate::tpch::verify::{assert_match, compare};
+
+    #[test]
+    fn test_suppliers() {
+        let mut suppliers = Suppliers::default();
+        assert!(!suppliers.is_multiple());
+        suppliers.insert(1);
+        suppliers.insert(1);
+        assert!(!suppliers.is_multiple());
+        suppliers.insert(2);
+        assert!(suppliers.is_multiple());
+    }
+
+    #[test]
+    fn test_query_21() {
+        let conn = initialize_database(0.01);
+        let (supplier, line_items, orders, nation) = load(&conn);
+        let result = query(supplier, line_items, orders, nation);
+        assert!(result.len() <= 100);
+
+        let expected = query_duckdb(&conn, None);
+        assert_match(&[compare("Q21", "duckdb", "baseline", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_21_answer() {
+        let conn = initialize_database(0.01);
+        let (supplier, line_items, orders, nation) = load(&conn);
+        let result = query(supplier, line_items, orders, nation);
+
+        let expected = expected(&conn, 21, 0.01);
+        assert_match(&[compare("Q21", "tpch_answers", "baseline", &expected, &result, &answer_tolerance())]);
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::{BTreeMap, HashSet};
+
+use duckdb::Connection;
+
+use super::answers::{parse_field, FromAnswer};
+use super::util::load_rows;
+use super::verify::{ApproxEq, Tolerance};
+
+/// Country codes selected by the validation parameters of Query 22.
+pub const COUNTRY_CODES: [&str; 7] = ["13", "31", "23", "29", "30", "18", "17"];
+
+#[derive(Debug, Clone)]
+pub struct Customer {
+    pub c_custkey: i64,
+    pub c_phone: String,
+    pub c_acctbal: f64,
+}
+
+impl Customer {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "customer", &["c_custkey", "c_phone", "c_acctbal"], limit, |row| {
+            Ok(Customer {
+                c_custkey: row.get(0)?,
+                c_phone: row.get(1)?,
+                c_acctbal: row.get(2)?,
+            })
+        })
+    }
+
+    /// substring(c_phone FROM 1 FOR 2)
+    pub fn cntrycode(&self) -> &str {
+        &self.c_phone[..2]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Order {
+    pub o_custkey: i64,
+}
+
+impl Order {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "orders", &["o_custkey"], limit, |row| {
+            Ok(Order {
+                o_custkey: row.get(0)?,
+            })
+        })
+    }
+}
+
+/// Result row of Query 22, ordered by cntrycode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Q22Row {
+    pub cntrycode: String,
+    pub numcust: i64,
+    pub totacctbal: f64,
+}
+
+impl ApproxEq for Q22Row {
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+        self.cntrycode == other.cntrycode
+            && self.numcust == other.numcust
+            && tolerance.eq_f64(self.totacctbal, other.totacctbal)
+    }
+}
+
+impl FromAnswer for Q22Row {
+    fn from_answer(fields: &[&str]) -> Self {
+        Q22Row {
+            cntrycode: parse_field(fields, 0),
+            numcust: parse_field(fields, 1),
+            totacctbal: parse_field(fields, 2),
+        }
+    }
+}
+
+pub fn load(conn: &Connection) -> (Vec<Customer>, Vec<Order>) {
+    (Customer::load(conn, None), Order::load(conn, None))
+}
+
+pub fn query(customer: Vec<Customer>, orders: Vec<Order>) -> Vec<Q22Row> {
+    // The scalar subquery is uncorrelated and is computed once as a global aggregate. NOT EXISTS
+    // is an anti join of customer with orders on c_custkey = o_custkey.
+
+    // 1. Scan customer and filter on the country code.
+    let customer_filtered: Vec<Customer> = customer
+        .into_iter()
+        .filter(|c| COUNTRY_CODES.contains(&c.cntrycode()))
+        .collect();
+
+    // 2. Subquery: avg(c_acctbal) over the customers with c_acctbal > 0.00.
+    let (sum, count) = customer_filtered
+        .iter()
+        .filter(|c| c.c_acctbal > 0.0)
+        .fold((0.0, 0u64), |(sum, count), c| (sum + c.c_acctbal, count + 1));
+    let avg_acctbal = sum / count as f64;
+
+    // 3. Scan orders. Build side of the anti join: o_custkey.
+    let orders_build: HashSet<i64> = orders.into_iter().map(|o| o.o_custkey).collect();
+
+    // 4. Filter on c_acctbal > avg(c_acctbal) and anti join orders.
+    // 5. Group by cntrycode, count(*) and sum(c_acctbal). The BTreeMap keeps the groups sorted.
+    let agg = customer_filtered
+        .into_iter()
+        .filter(|c| c.c_acctbal > avg_acctbal)
+        .filter(|c| !orders_build.contains(&c.c_custkey))
+        .fold(BTreeMap::new(), |mut acc: BTreeMap<String, (i64, f64)>, c| {
+            let (numcust, totacctbal) = acc.entry(c.cntrycode().to_string()).or_insert((0, 0.0));
+            *numcust += 1;
+            *totacctbal += c.c_acctbal;
+            acc
+        });
+
+    agg.into_iter()
+        .map(|(cntrycode, (numcust, totacctbal))| Q22Row {
+            cntrycode,
+            numcust,
+            totacctbal,
+        })
+        .collect()
+}
+
+pub fn query_duckdb(conn: &Connection, limit: Option<u32>) -> Vec<Q22Row> {
+    let table = |name: &str| match limit {
+        Some(limit) => format!("(SELECT * FROM {} LIMIT {}) AS {}", name, limit, name),
+        None => name.to_string(),
+    };
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"
+        SELECT
+            cntrycode,
+            count(*) AS numcust,
+            sum(c_acctbal) AS totacctbal
+        FROM (
+            SELECT
+                substring(c_phone FROM 1 FOR 2) AS cntrycode,
+                c_acctbal
+            FROM
+                {customer}
+            WHERE
+                substring(c_phone FROM 1 FOR 2) IN ('13', '31', '23', '29', '30', '18', '17')
+                AND c_acctbal > (
+                    SELECT
+                        avg(c_acctbal)
+                    FROM
+                        {customer}
+                    WHERE
+                        c_acctbal > 0.00
+                        AND substring(c_phone FROM 1 FOR 2) IN ('13', '31', '23', '29', '30', '18', '17'))
+                AND NOT EXISTS (
+                    SELECT
+                        *
+                    FROM
+                        {orders}
+                    WHERE
+                        o_custkey = c_custkey)) AS custsale
+        GROUP BY
+            cntrycode
+        ORDER BY
+            cntrycode;
+    "#,
+            customer = table("customer"),
+            orders = table("orders")
+        ))
+        .expect("Error preparing query for DuckDB");
+
+    let mut rows = stmt.query([]).expect("Error executing Query 22");
+    let mut result = vec![];
+    while let Some(row) = rows.next().unwrap() {
+        result.push(Q22Row {
+            cntrycode: row.get(0).unwrap(),
+            numcust: row.get(1).unwrap(),
+            totacctbal: row.get(2).unwrap(),
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tpch::answers::{answer_tolerance, expected};
+    use crate::tpch::initialize::initialize_database;
+    use crate::tpch::verify::{assert_match, compare};
+
+    #[test]
+    fn test_query_22() {
+        let conn = initialize_database(0.01);
+        let (customer, orders) = load(&conn);
+        let result = query(customer, orders);
+        assert_eq!(result.len(), COUNTRY_CODES.len());
+
+        let expected = query_duckdb(&conn, None);
+        assert_match(&[compare("Q22", "duckdb", "baseline", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_22_answer() {
+        let conn = initialize_database(0.01);
+        let (customer, orders) = load(&conn);
+        let result = query(customer, orders);
+
+        let expected = expected(&conn, 22, 0.01);
+        assert_match(&[compare("Q22", "tpch_answers", "baseline", &expected, &result, &answer_tolerance())]);
+    }
+}
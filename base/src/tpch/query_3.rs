@@ -0,0 +1,260 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use chrono::NaiveDate;
+use duckdb::Connection;
+
+use super::answers::{parse_field, FromAnswer};
+use super::top_k::{Strategy, TopK};
+use super::util::{load_rows, to_date};
+use super::verify::{ApproxEq, Tolerance};
+
+#[derive(Debug, Clone)]
+pub struct Customer {
+    pub c_custkey: i64,
+    pub c_mktsegment: String,
+}
+
+impl Customer {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "customer", &["c_custkey", "c_mktsegment"], limit, |row| {
+            Ok(Customer {
+                c_custkey: row.get(0)?,
+                c_mktsegment: row.get(1)?,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Order {
+    pub o_orderkey: i64,
+    pub o_custkey: i64,
+    pub o_orderdate: NaiveDate,
+    pub o_shippriority: i32,
+}
+
+impl Order {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "orders", &["o_orderkey", "o_custkey", "o_orderdate", "o_shippriority"], limit, |row| {
+            Ok(Order {
+                o_orderkey: row.get(0)?,
+                o_custkey: row.get(1)?,
+                o_orderdate: to_date(row.get(2)?),
+                o_shippriority: row.get(3)?,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LineItem {
+    pub l_orderkey: i64,
+    pub l_extendedprice: f64,
+    pub l_discount: f64,
+    pub l_shipdate: NaiveDate,
+}
+
+impl LineItem {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "lineitem", &["l_orderkey", "l_extendedprice", "l_discount", "l_shipdate"], limit, |row| {
+            Ok(LineItem {
+                l_orderkey: row.get(0)?,
+                l_extendedprice: row.get(1)?,
+                l_discount: row.get(2)?,
+                l_shipdate: to_date(row.get(3)?),
+            })
+        })
+    }
+}
+
+/// Result row of Query 3, ordered by revenue descending and o_orderdate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Q3Row {
+    pub l_orderkey: i64,
+    pub revenue: f64,
+    pub o_orderdate: NaiveDate,
+    pub o_shippriority: i32,
+}
+
+impl Q3Row {
+    /// ORDER BY revenue DESC, o_orderdate
+    pub fn order_by(a: &Q3Row, b: &Q3Row) -> Ordering {
+        b.revenue
+            .total_cmp(&a.revenue)
+            .then(a.o_orderdate.cmp(&b.o_orderdate))
+    }
+}
+
+impl ApproxEq for Q3Row {
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+        self.l_orderkey == other.l_orderkey
+            && tolerance.eq_f64(self.revenue, other.revenue)
+            && self.o_orderdate == other.o_orderdate
+            && self.o_shippriority == other.o_shippriority
+    }
+}
+
+impl FromAnswer for Q3Row {
+    fn from_answer(fields: &[&str]) -> Self {
+        Q3Row {
+            l_orderkey: parse_field(fields, 0),
+            revenue: parse_field(fields, 1),
+            o_orderdate: parse_field(fields, 2),
+            o_shippriority: parse_field(fields, 3),
+        }
+    }
+}
+
+pub fn load(conn: &Connection) -> (Vec<Customer>, Vec<Order>, Vec<LineItem>) {
+    (
+        Customer::load(conn, None),
+        Order::load(conn, None),
+        LineItem::load(conn, None),
+    )
+}
+
+pub fn query(customer: Vec<Customer>, orders: Vec<Order>, line_items: Vec<LineItem>) -> Vec<Q3Row> {
+    query_with(Strategy::Heap, customer, orders, line_items)
+}
+
+/// Query 3 with the given top-k strategy for ORDER BY ... LIMIT 10.
+pub fn query_with(strategy: Strategy, customer: Vec<Customer>, orders: Vec<Order>, line_items: Vec<LineItem>) -> Vec<Q3Row> {
+    let date = NaiveDate::from_ymd_opt(1995, 3, 15).unwrap();
+
+    // 1. Scan customer and filter on c_mktsegment = 'BUILDING'.
+    // 2. Build side of the first join: c_custkey, no payload.
+    let customer_build: HashSet<i64> = customer
+        .into_iter()
+        .filter(|c| c.c_mktsegment == "BUILDING")
+        .map(|c| c.c_custkey)
+        .collect();
+
+    // 3. Scan orders and filter on o_orderdate < '1995-03-15'.
+    // 4. Probe customer with o_custkey, the surviving orders are the build side of the second join.
+    // Keys: o_orderkey
+    // Payload: o_orderdate, o_shippriority
+    let orders_build: HashMap<i64, (NaiveDate, i32)> = orders
+        .into_iter()
+        .filter(|o| o.o_orderdate < date)
+        .filter(|o| customer_build.contains(&o.o_custkey))
+        .map(|o| (o.o_orderkey, (o.o_orderdate, o.o_shippriority)))
+        .collect();
+
+    // 5. Scan lineitem and filter on l_shipdate > '1995-03-15'.
+    // 6. Probe orders with l_orderkey.
+    let joined = line_items
+        .into_iter()
+        .filter(|l| l.l_shipdate > date)
+        .filter_map(|l| {
+            orders_build.get(&l.l_orderkey).map(|(o_orderdate, o_shippriority)| {
+                (
+                    (l.l_orderkey, *o_orderdate, *o_shippriority),
+                    l.l_extendedprice * (1.0 - l.l_discount),
+                )
+            })
+        });
+
+    // 7. Group by l_orderkey, o_orderdate, o_shippriority and sum the revenue.
+    let agg = joined.fold(HashMap::new(), |mut acc, (key, revenue)| {
+        *acc.entry(key).or_insert(0.0) += revenue;
+        acc
+    });
+
+    // 8. Keep the first 10 rows by revenue desc, o_orderdate.
+    let mut top_k = TopK::new(strategy, 10, Q3Row::order_by);
+    top_k.extend(
+        agg.into_iter()
+            .map(|((l_orderkey, o_orderdate, o_shippriority), revenue)| Q3Row {
+                l_orderkey,
+                revenue,
+                o_orderdate,
+                o_shippriority,
+            }),
+    );
+    top_k.into_sorted_vec()
+}
+
+pub fn query_duckdb(conn: &Connection, limit: Option<u32>) -> Vec<Q3Row> {
+    let table = |name: &str| match limit {
+        Some(limit) => format!("(SELECT * FROM {} LIMIT {}) AS {}", name, limit, name),
+        None => name.to_string(),
+    };
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"
+        SELECT
+            l_orderkey,
+            sum(l_extendedprice * (1 - l_discount)) AS revenue,
+            o_orderdate,
+            o_shippriority
+        FROM
+            {},
+            {},
+            {}
+        WHERE
+            c_mktsegment = 'BUILDING'
+            AND c_custkey = o_custkey
+            AND l_orderkey = o_orderkey
+            AND o_orderdate < CAST('1995-03-15' AS date)
+            AND l_shipdate > CAST('1995-03-15' AS date)
+        GROUP BY
+            l_orderkey,
+            o_orderdate,
+            o_shippriority
+        ORDER BY
+            revenue DESC,
+            o_orderdate
+        LIMIT 10;
+    "#,
+            table("customer"),
+            table("orders"),
+            table("lineitem")
+        ))
+        .expect("Error preparing query for DuckDB");
+
+    let mut rows = stmt.query([]).expect("Error executing Query 3");
+    let mut result = vec![];
+    while let Some(row) = rows.next().unwrap() {
+        result.push(Q3Row {
+            l_orderkey: row.get(0).unwrap(),
+            revenue: row.get(1).unwrap(),
+            o_orderdate: to_date(row.get(2).unwrap()),
+            o_shippriority: row.get(3).unwrap(),
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tpch::answers::{answer_tolerance, expected};
+    use crate::tpch::initialize::initialize_database;
+    use crate::tpch::verify::{assert_match, compare};
+
+    #[test]
+    fn test_query_3() {
+        let conn = initialize_database(0.01);
+        let (customer, orders, line_items) = load(&conn);
+        let result = query(customer, orders, line_items);
+        assert!(result.len() <= 10);
+
+        let expected = query_duckdb(&conn, None);
+        assert_match(&[compare("Q3", "duckdb", "baseline", &expected, &result, &Tolerance::default())]);
+
+        let (customer, orders, line_items) = load(&conn);
+        let result = query_with(Strategy::Sort, customer, orders, line_items);
+        assert_match(&[compare("Q3", "duckdb", "baseline-sort", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_3_answer() {
+        let conn = initialize_database(0.01);
+        let (customer, orders, line_items) = load(&conn);
+        let result = query(customer, orders, line_items);
+
+        let expected = expected(&conn, 3, 0.01);
+        assert_match(&[compare("Q3", "tpch_answers", "baseline", &expected, &result, &answer_tolerance())]);
+    }
+}
@@ -1,9 +1,61 @@
 use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
 
-use chrono::NaiveDate;
-use duckdb::Connection;
+use chrono::{Months, NaiveDate};
+use duckdb::arrow::datatypes::{Date32Type, Int32Type};
+use duckdb::arrow::record_batch::RecordBatch;
+use duckdb::{params, Connection};
 
-use super::util::to_date;
+use super::bloom::{BloomFilter, DEFAULT_FALSE_POSITIVE_RATE};
+use super::load::{LoadError, LoadMode, Loaded};
+use super::parallel::{morsels, Parallelism};
+use super::plan::{Estimate, JoinPlan, Side};
+use super::refresh::{self, Delta};
+use super::scan::FromBatch;
+use super::merge_join::merge_semi_join;
+use super::semi_join::semi_join;
+use super::tbl::{self, Fields, FromTbl, TblError};
+use super::util::{load_batches, primitive_column, string_column, to_date, try_load_rows};
+use super::answers::{parse_field, FromAnswer};
+use super::verify::{ApproxEq, Tolerance};
+
+/// Substitution parameters of Query 4.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QueryParams {
+    /// o_orderdate >= DATE AND o_orderdate < DATE + interval '3' month
+    pub date: NaiveDate,
+    /// DATE + interval '3' month, computed once in `new` instead of for every row in `filter`.
+    date_end: NaiveDate,
+}
+
+impl QueryParams {
+    pub fn new(date: NaiveDate) -> Self {
+        QueryParams { date, date_end: date.checked_add_months(Months::new(3)).unwrap() }
+    }
+
+    pub fn date_end(&self) -> NaiveDate {
+        self.date_end
+    }
+
+    /// o_orderdate >= DATE AND o_orderdate < DATE + interval '3' month
+    pub fn filter(&self, order_date: &NaiveDate) -> bool {
+        *order_date >= self.date && *order_date < self.date_end
+    }
+}
+
+/// The validation parameters, the reference answers are computed with these.
+impl Default for QueryParams {
+    fn default() -> Self {
+        QueryParams::new(NaiveDate::from_ymd_opt(1993, 7, 1).unwrap())
+    }
+}
+
+impl fmt::Display for QueryParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "date={}", self.date)
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct LineItem {
@@ -13,31 +65,61 @@ pub struct LineItem {
 }
 
 impl LineItem {
-    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
-        // Create iterator for LineItem
-        let query = match limit {
-            Some(limit) => format!(
-                "SELECT l_orderkey, l_receiptdate, l_commitdate FROM lineitem LIMIT {};",
-                limit
-            ),
-            None => "SELECT l_orderkey, l_receiptdate, l_commitdate FROM lineitem;".to_string(),
-        };
-        let mut stmt = conn
-            .prepare(&query)
-            .expect("Error preparing query for LineItem");
-        let line_items = stmt
-            .query_map([], |row| {
-                Ok(LineItem {
-                    order_key: row.get(0)?,
-                    receiptdate: to_date(row.get(1)?),
-                    commit_date: to_date(row.get(2)?),
-                })
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Result<Vec<Self>, LoadError> {
+        Ok(LineItem::load_with(conn, limit, LoadMode::Strict)?.rows)
+    }
+
+    pub fn load_with(conn: &Connection, limit: Option<u32>, mode: LoadMode) -> Result<Loaded<Self>, LoadError> {
+        LineItem::load_table_with(conn, "lineitem", limit, mode)
+    }
+
+    /// Same as `load`, from another table with the columns of lineitem, e.g. a refresh staging table.
+    pub fn load_table(conn: &Connection, table: &str) -> Result<Vec<Self>, LoadError> {
+        Ok(LineItem::load_table_with(conn, table, None, LoadMode::Strict)?.rows)
+    }
+
+    fn load_table_with(conn: &Connection, table: &str, limit: Option<u32>, mode: LoadMode) -> Result<Loaded<Self>, LoadError> {
+        try_load_rows(conn, table, &["l_orderkey", "l_receiptdate", "l_commitdate"], limit, mode, |row| {
+            Ok(LineItem {
+                order_key: row.get(0)?,
+                receiptdate: to_date(row.get(1)?),
+                commit_date: to_date(row.get(2)?),
             })
-            .expect("Error querying LineItem");
+        })
+    }
+
+    /// Same as `load`, but converts whole Arrow record batches instead of one row at a time.
+    pub fn load_arrow(conn: &Connection, limit: Option<u32>) -> Result<Vec<Self>, LoadError> {
+        load_batches(conn, Self::TABLE, Self::COLUMNS, limit, Self::from_batch)
+    }
+}
 
-        let line_items = line_items.filter(|x| x.is_ok()).map(|x| x.unwrap());
+impl FromBatch for LineItem {
+    fn from_batch(batch: &RecordBatch, line_items: &mut Vec<Self>) -> duckdb::Result<()> {
+        let order_key = primitive_column::<Int32Type>(batch, 0)?;
+        let receiptdate = primitive_column::<Date32Type>(batch, 1)?;
+        let commit_date = primitive_column::<Date32Type>(batch, 2)?;
+        for i in 0..batch.num_rows() {
+            line_items.push(LineItem {
+                order_key: order_key.value(i),
+                receiptdate: to_date(receiptdate.value(i)),
+                commit_date: to_date(commit_date.value(i)),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl FromTbl for LineItem {
+    const TABLE: &'static str = "lineitem";
+    const COLUMNS: &'static [&'static str] = &["l_orderkey", "l_receiptdate", "l_commitdate"];
 
-        line_items.collect()
+    fn from_tbl(fields: &Fields<'_>) -> Result<Self, TblError> {
+        Ok(LineItem {
+            order_key: fields.parse(0)?,
+            receiptdate: fields.parse(1)?,
+            commit_date: fields.parse(2)?,
+        })
     }
 }
 
@@ -49,51 +131,132 @@ pub struct Order {
 }
 
 impl Order {
-    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
-        // Create iterator for Orders
-        let query = match limit {
-            Some(limit) => format!(
-                "SELECT o_orderkey, o_orderdate, o_orderpriority FROM orders LIMIT {};",
-                limit
-            ),
-            None => "SELECT o_orderkey, o_orderdate, o_orderpriority FROM orders;".to_string(),
-        };
-        let mut stmt = conn
-            .prepare(&query)
-            .expect("Error preparing query for LineItem");
-        let orders = stmt
-            .query_map([], |row| {
-                Ok(Self {
-                    order_key: row.get(0)?,
-                    order_date: to_date(row.get(1)?),
-                    order_priority: row.get(2)?,
-                })
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Result<Vec<Self>, LoadError> {
+        Ok(Order::load_with(conn, limit, LoadMode::Strict)?.rows)
+    }
+
+    pub fn load_with(conn: &Connection, limit: Option<u32>, mode: LoadMode) -> Result<Loaded<Self>, LoadError> {
+        Order::load_table_with(conn, "orders", limit, mode)
+    }
+
+    /// Same as `load`, from another table with the columns of orders, e.g. a refresh staging table.
+    pub fn load_table(conn: &Connection, table: &str) -> Result<Vec<Self>, LoadError> {
+        Ok(Order::load_table_with(conn, table, None, LoadMode::Strict)?.rows)
+    }
+
+    fn load_table_with(conn: &Connection, table: &str, limit: Option<u32>, mode: LoadMode) -> Result<Loaded<Self>, LoadError> {
+        try_load_rows(conn, table, &["o_orderkey", "o_orderdate", "o_orderpriority"], limit, mode, |row| {
+            Ok(Order {
+                order_key: row.get(0)?,
+                order_date: to_date(row.get(1)?),
+                order_priority: row.get(2)?,
             })
-            .expect("Error querying LineItem");
+        })
+    }
 
-        let orders = orders.filter_map(|x| x.ok());
+    /// Same as `load`, but converts whole Arrow record batches instead of one row at a time.
+    pub fn load_arrow(conn: &Connection, limit: Option<u32>) -> Result<Vec<Self>, LoadError> {
+        load_batches(conn, Self::TABLE, Self::COLUMNS, limit, Self::from_batch)
+    }
+}
 
-        orders.collect()
+impl FromBatch for Order {
+    fn from_batch(batch: &RecordBatch, orders: &mut Vec<Self>) -> duckdb::Result<()> {
+        let order_key = primitive_column::<Int32Type>(batch, 0)?;
+        let order_date = primitive_column::<Date32Type>(batch, 1)?;
+        let order_priority = string_column(batch, 2)?;
+        for i in 0..batch.num_rows() {
+            orders.push(Order {
+                order_key: order_key.value(i),
+                order_date: to_date(order_date.value(i)),
+                order_priority: order_priority.value(i).to_string(),
+            });
+        }
+        Ok(())
     }
 }
 
-pub fn load(conn: &Connection) -> (Vec<LineItem>, Vec<Order>) {
-    let line_items = LineItem::load(&conn, None);
-    let orders = Order::load(&conn, None);
+impl FromTbl for Order {
+    const TABLE: &'static str = "orders";
+    const COLUMNS: &'static [&'static str] = &["o_orderkey", "o_orderdate", "o_orderpriority"];
 
-    (line_items, orders)
+    fn from_tbl(fields: &Fields<'_>) -> Result<Self, TblError> {
+        Ok(Order {
+            order_key: fields.parse(0)?,
+            order_date: fields.parse(1)?,
+            order_priority: fields.str(2).to_string(),
+        })
+    }
 }
 
-pub fn query(line_items: Vec<LineItem>, orders: Vec<Order>) {
+/// Result row of Query 4, ordered by o_orderpriority.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Q4Row {
+    pub o_orderpriority: String,
+    pub order_count: i64,
+}
+
+impl From<(String, i64)> for Q4Row {
+    fn from((o_orderpriority, order_count): (String, i64)) -> Q4Row {
+        Q4Row {
+            o_orderpriority,
+            order_count,
+        }
+    }
+}
+
+impl ApproxEq for Q4Row {
+    fn approx_eq(&self, other: &Self, _tolerance: &Tolerance) -> bool {
+        self == other
+    }
+}
+
+impl FromAnswer for Q4Row {
+    fn from_answer(fields: &[&str]) -> Self {
+        Q4Row {
+            o_orderpriority: parse_field(fields, 0),
+            order_count: parse_field(fields, 1),
+        }
+    }
+}
+
+pub fn load(conn: &Connection) -> Result<(Vec<LineItem>, Vec<Order>), LoadError> {
+    let line_items = LineItem::load(&conn, None)?;
+    let orders = Order::load(&conn, None)?;
+
+    Ok((line_items, orders))
+}
+
+pub fn load_arrow(conn: &Connection) -> Result<(Vec<LineItem>, Vec<Order>), LoadError> {
+    Ok((LineItem::load_arrow(conn, None)?, Order::load_arrow(conn, None)?))
+}
+
+/// The line items and orders the staged refresh pair inserts and deletes, see
+/// `refresh::RefreshStream`.
+pub fn load_refresh(conn: &Connection) -> Result<(Delta<LineItem>, Delta<Order>), LoadError> {
+    let line_items = Delta {
+        inserted: LineItem::load_table(conn, refresh::RF1_LINEITEM)?,
+        deleted: LineItem::load_table(conn, refresh::RF2_LINEITEM)?,
+    };
+    let orders = Delta {
+        inserted: Order::load_table(conn, refresh::RF1_ORDERS)?,
+        deleted: Order::load_table(conn, refresh::RF2_ORDERS)?,
+    };
+    Ok((line_items, orders))
+}
+
+/// Reads `lineitem.tbl` and `orders.tbl` in `dir` instead of scanning DuckDB.
+pub fn load_tbl(dir: &Path) -> Result<(Vec<LineItem>, Vec<Order>), TblError> {
+    Ok((tbl::read(dir)?, tbl::read(dir)?))
+}
+
+pub fn query(line_items: Vec<LineItem>, orders: Vec<Order>, params: &QueryParams) -> Vec<Q4Row> {
 
     // 1. Scan orders
     let orders_filtered = orders
         .into_iter()
-        // 1.2 Filter orders on o_orderdate >= '1993-07-01' and < '1993-10-01'
-        .filter(|order| {
-            order.order_date >= NaiveDate::from_ymd_opt(1993, 7, 1).unwrap()
-                && order.order_date < NaiveDate::from_ymd_opt(1993, 10, 1).unwrap()
-        });
+        // 1.2 Filter orders on o_orderdate >= DATE and < DATE + 3 months, e.g. ['1993-07-01', '1993-10-01')
+        .filter(|order| params.filter(&order.order_date));
 
     // 2. Scan from lineitem.
     let line_items_filtered = line_items
@@ -101,32 +264,202 @@ pub fn query(line_items: Vec<LineItem>, orders: Vec<Order>) {
         // 2.2 Filter lineitem on l_commitdate < l_receiptdate
         .filter(|line_item| line_item.commit_date < line_item.receiptdate);
 
-    // 3. Join the two. o_orderkey = l_orderkey, payload: o_orderpriority
-    // Build side: Orders?
-    // Probe side: LineItem?
-    // HashJoin?
-    let join_build = line_items_filtered.fold(HashSet::new(), |mut acc, e| {
-        acc.insert(e.order_key);
-        acc
-    });
-    let joined =
-        orders_filtered.filter_map(|o| join_build.get(&o.order_key).map(|_| o.order_priority));
+    // 3. Semi-join the two. o_orderkey = l_orderkey, payload: o_orderpriority
+    // Build side: LineItem, see `query_with_plan` for the orders as the build side.
+    // Probe side: Orders
+    let joined = semi_join(orders_filtered, line_items_filtered.map(|l| l.order_key), |o| o.order_key)
+        .map(|o| o.order_priority);
 
     // 4. Aggregate.
     // Hash aggregation in DuckDB
     // 4.1 Group by: "o_orderpriority"
     // 4.2 Count
     let agg = joined.fold(HashMap::new(), |mut acc, e| {
-        let count = acc.entry(e).or_insert(0);
+        let count = acc.entry(e).or_insert(0i64);
         *count += 1;
         acc
     });
 
-    // 5. Print: "o_orderpriority", "order_count"
-    agg.into_iter().for_each(|x| println!("{:?}", x));
+    // 5. Sort by o_orderpriority and collect: "o_orderpriority", "order_count"
+    let mut result: Vec<Q4Row> = agg.into_iter().map(Q4Row::from).collect();
+    result.sort_by(|a, b| a.o_orderpriority.cmp(&b.o_orderpriority));
+    result
+}
+
+/// Estimate the filtered orders (left) and line items (right) and plan the semi-join, see
+/// `JoinPlan::choose_semi_join`.
+pub fn plan(conn: &Connection, params: &QueryParams, nodes: usize) -> JoinPlan {
+    let orders = Estimate::filtered(
+        conn,
+        "orders",
+        "o_orderkey",
+        "o_orderdate >= CAST(? AS date) AND o_orderdate < CAST(? AS date)",
+        params![params.date.to_string(), params.date_end().to_string()],
+    );
+    let line_items = Estimate::filtered(conn, "lineitem", "l_orderkey", "l_commitdate < l_receiptdate", params![]);
+    JoinPlan::choose_semi_join(orders, line_items, nodes)
+}
+
+/**
+ * `query` with the build side of the semi-join chosen by `plan`. With the line items as the build
+ * side, the orders probe their distinct keys, see `query`. With the orders as the build side, the
+ * line items probe the filtered orders, and an order leaves the hash table on its first match, so
+ * it is counted once.
+ */
+pub fn query_with_plan(line_items: Vec<LineItem>, orders: Vec<Order>, params: &QueryParams, plan: &JoinPlan) -> Vec<Q4Row> {
+    if plan.build == Side::Right {
+        return query(line_items, orders, params);
+    }
+
+    // 1. Build: filter orders on o_orderdate, by o_orderkey with o_orderpriority.
+    let mut join_build: HashMap<i32, String> = orders
+        .into_iter()
+        .filter(|order| params.filter(&order.order_date))
+        .map(|o| (o.order_key, o.order_priority))
+        .collect();
+
+    // 2. Probe: filter lineitem on l_commitdate < l_receiptdate, the first match takes the order.
+    let joined = line_items
+        .into_iter()
+        .filter(|line_item| line_item.commit_date < line_item.receiptdate)
+        .filter_map(|l| join_build.remove(&l.order_key));
+
+    // 3. Count per o_orderpriority.
+    let agg = joined.fold(HashMap::new(), |mut acc, e| {
+        *acc.entry(e).or_insert(0i64) += 1;
+        acc
+    });
+
+    // 4. Sort by o_orderpriority and collect: "o_orderpriority", "order_count"
+    let mut result: Vec<Q4Row> = agg.into_iter().map(Q4Row::from).collect();
+    result.sort_by(|a, b| a.o_orderpriority.cmp(&b.o_orderpriority));
+    result
+}
+
+/**
+ * Bloom filter pushdown, see `super::bloom`. The orders of one quarter are few compared to the line
+ * items, so a Bloom filter of their keys drops most line items before they reach the hash set of
+ * the semi-join.
+ */
+pub fn query_bloom(line_items: Vec<LineItem>, orders: Vec<Order>, params: &QueryParams) -> Vec<Q4Row> {
+
+    // 1. Scan orders, filter on o_orderdate.
+    let orders_filtered: Vec<Order> = orders
+        .into_iter()
+        .filter(|order| params.filter(&order.order_date))
+        .collect();
+
+    // 2. Bloom filter of the qualifying o_orderkey.
+    let order_keys: Vec<i32> = orders_filtered.iter().map(|o| o.order_key).collect();
+    let bloom = BloomFilter::from_keys(&order_keys, DEFAULT_FALSE_POSITIVE_RATE);
+
+    // 3. Scan lineitem, filter on l_commitdate < l_receiptdate and probe the Bloom filter.
+    let line_items_filtered = line_items
+        .into_iter()
+        .filter(|line_item| line_item.commit_date < line_item.receiptdate)
+        .map(|l| l.order_key)
+        .filter(|order_key| bloom.contains(order_key));
+
+    // 4. Semi-join the two. o_orderkey = l_orderkey, payload: o_orderpriority
+    let joined = semi_join(orders_filtered, line_items_filtered, |o| o.order_key)
+        .map(|o| o.order_priority);
+
+    // 5. Count per o_orderpriority.
+    let agg = joined.fold(HashMap::new(), |mut acc, e| {
+        *acc.entry(e).or_insert(0i64) += 1;
+        acc
+    });
+
+    // 6. Sort by o_orderpriority and collect: "o_orderpriority", "order_count"
+    let mut result: Vec<Q4Row> = agg.into_iter().map(Q4Row::from).collect();
+    result.sort_by(|a, b| a.o_orderpriority.cmp(&b.o_orderpriority));
+    result
+}
+
+/**
+ * Sort-merge semi-join instead of the hash set, see `super::merge_join`. Both inputs come out of
+ * `dbgen` in order key order, so the sorts are cheap.
+ */
+pub fn query_merge_join(line_items: Vec<LineItem>, orders: Vec<Order>, params: &QueryParams) -> Vec<Q4Row> {
+    // 1. Scan orders, filter on o_orderdate and sort by o_orderkey.
+    let mut orders_sorted: Vec<(i32, String)> = orders
+        .into_iter()
+        .filter(|order| params.filter(&order.order_date))
+        .map(|o| (o.order_key, o.order_priority))
+        .collect();
+    orders_sorted.sort_unstable_by_key(|x| x.0);
+
+    // 2. Scan lineitem, filter on l_commitdate < l_receiptdate and sort by l_orderkey.
+    let mut line_items_sorted: Vec<i32> = line_items
+        .into_iter()
+        .filter(|line_item| line_item.commit_date < line_item.receiptdate)
+        .map(|l| l.order_key)
+        .collect();
+    line_items_sorted.sort_unstable();
+
+    // 3. Merge semi-join. o_orderkey = l_orderkey, payload: o_orderpriority
+    let joined = merge_semi_join(orders_sorted, line_items_sorted);
+
+    // 4. Count per o_orderpriority.
+    let agg = joined.into_iter().fold(HashMap::new(), |mut acc, (_, o_orderpriority)| {
+        *acc.entry(o_orderpriority).or_insert(0i64) += 1;
+        acc
+    });
+
+    // 5. Sort by o_orderpriority and collect: "o_orderpriority", "order_count"
+    let mut result: Vec<Q4Row> = agg.into_iter().map(Q4Row::from).collect();
+    result.sort_by(|a, b| a.o_orderpriority.cmp(&b.o_orderpriority));
+    result
+}
+
+/**
+ * Morsel-driven: the threads first build partial hash sets of the qualifying l_orderkey from
+ * morsels of lineitem, which are unioned into the build side. The threads then probe morsels of
+ * orders and count per o_orderpriority, the partial counts are merged at the end.
+ */
+pub fn query_parallel(line_items: Vec<LineItem>, orders: Vec<Order>, params: &QueryParams, parallelism: &Parallelism) -> Vec<Q4Row> {
+    // 1. Build: filter lineitem on l_commitdate < l_receiptdate into one partial set per thread.
+    let partials = morsels(&line_items, parallelism, HashSet::new, |keys: &mut HashSet<i32>, morsel| {
+        keys.extend(morsel.iter().filter(|l| l.commit_date < l.receiptdate).map(|l| l.order_key));
+    });
+
+    // 2. Union the partial sets into the largest one.
+    let mut partials = partials.into_iter();
+    let mut join_build = partials.next().unwrap_or_default();
+    for keys in partials {
+        if keys.len() > join_build.len() {
+            let smaller = std::mem::replace(&mut join_build, keys);
+            join_build.extend(smaller);
+        } else {
+            join_build.extend(keys);
+        }
+    }
+
+    // 3. Probe: filter orders on o_orderdate and count the matches per o_orderpriority.
+    let partials = morsels(&orders, parallelism, HashMap::new, |counts: &mut HashMap<&str, i64>, morsel| {
+        for order in morsel {
+            if params.filter(&order.order_date) && join_build.contains(&order.order_key) {
+                *counts.entry(order.order_priority.as_str()).or_insert(0) += 1;
+            }
+        }
+    });
+
+    // 4. Merge the partial counts.
+    let mut agg: HashMap<&str, i64> = HashMap::new();
+    for (priority, count) in partials.into_iter().flatten() {
+        *agg.entry(priority).or_insert(0) += count;
+    }
+
+    // 5. Sort by o_orderpriority and collect: "o_orderpriority", "order_count"
+    let mut result: Vec<Q4Row> = agg
+        .into_iter()
+        .map(|(priority, count)| Q4Row::from((priority.to_string(), count)))
+        .collect();
+    result.sort_by(|a, b| a.o_orderpriority.cmp(&b.o_orderpriority));
+    result
 }
 
-pub fn query_duckdb(conn: &Connection, limit: Option<u32>) {
+pub fn query_duckdb(conn: &Connection, limit: Option<u32>, params: &QueryParams) -> Vec<Q4Row> {
     let orders_table = match limit {
         Some(limit) => format!("(SELECT * FROM orders LIMIT {})", limit),
         None => "orders".to_string(),
@@ -145,8 +478,8 @@ pub fn query_duckdb(conn: &Connection, limit: Option<u32>) {
     FROM
     {}
     WHERE
-    o_orderdate >= CAST('1993-07-01' AS date)
-    AND o_orderdate < CAST('1993-10-01' AS date)
+    o_orderdate >= CAST(? AS date)
+    AND o_orderdate < CAST(? AS date)
     AND EXISTS (
     SELECT
     *
@@ -164,34 +497,123 @@ pub fn query_duckdb(conn: &Connection, limit: Option<u32>) {
         ))
         .expect("Error preparing query for DuckDB");
 
-    let mut rows = stmt.query([]).expect("Error executing Query 4");
+    let mut rows = stmt
+        .query(params![params.date.to_string(), params.date_end().to_string()])
+        .expect("Error executing Query 4");
+    let mut result = vec![];
     while let Some(row) = rows.next().unwrap() {
-        let order_priority: String = row.get(0).unwrap();
-        let order_count: i64 = row.get(1).unwrap();
-        println!("{}, {}", order_priority, order_count);
+        result.push(Q4Row {
+            o_orderpriority: row.get(0).unwrap(),
+            order_count: row.get(1).unwrap(),
+        });
     }
+    result
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::tpch::initialize::initialize_database;
+    use crate::tpch::answers::{answer_tolerance, expected};
+    use crate::tpch::verify::{assert_match, compare};
 
     #[test]
     fn test_query_4() {
         let limit = None;
 
-        let conn = initialize_database(1);
-        let line_items = LineItem::load(&conn, limit);
-        let orders = Order::load(&conn, limit);
+        let conn = initialize_database(0.01);
+        let line_items = LineItem::load(&conn, limit).unwrap();
+        let orders = Order::load(&conn, limit).unwrap();
 
         // Call the query function
-        query(line_items, orders);
+        let result = query(line_items, orders, &QueryParams::default());
+
+        let expected = query_duckdb(&conn, limit, &QueryParams::default());
+        assert_match(&[compare("Q4", "duckdb", "baseline", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_4_merge_join() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+        let (line_items, orders) = load(&conn).unwrap();
+
+        let result = query_merge_join(line_items, orders, &params);
+
+        let expected = query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q4", "duckdb", "baseline-merge-join", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_4_bloom() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+        let (line_items, orders) = load(&conn).unwrap();
+
+        let result = query_bloom(line_items, orders, &params);
+
+        let expected = query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q4", "duckdb", "baseline-bloom", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_4_plan() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+
+        // The orders of one quarter are fewer than the late line items.
+        let plan = plan(&conn, &params, 1);
+        assert_eq!(plan.build, Side::Left);
+        assert!(plan.left.cardinality() < plan.right.cardinality());
+
+        let expected = query_duckdb(&conn, None, &params);
+        for build in [Side::Left, Side::Right] {
+            let (line_items, orders) = load(&conn).unwrap();
+            let result = query_with_plan(line_items, orders, &params, &JoinPlan { build, ..plan.clone() });
+            assert_match(&[compare("Q4", "duckdb", "baseline-plan", &expected, &result, &Tolerance::default())]);
+        }
+    }
+
+    #[test]
+    fn test_query_4_params() {
+        assert_eq!(QueryParams::default().date_end(), NaiveDate::from_ymd_opt(1993, 10, 1).unwrap());
+
+        let conn = initialize_database(0.01);
+        let params = QueryParams::new(NaiveDate::from_ymd_opt(1997, 10, 1).unwrap());
+        let (line_items, orders) = load(&conn).unwrap();
+        let result = query(line_items, orders, &params);
+
+        let expected = query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q4", "duckdb", "baseline", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_4_parallel() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+        let expected = query_duckdb(&conn, None, &params);
+
+        for threads in [1, 2, 4] {
+            let parallelism = Parallelism::new(threads).with_morsel_size(1000);
+            let (line_items, orders) = load_arrow(&conn).unwrap();
+            let result = query_parallel(line_items, orders, &params, &parallelism);
+            assert_match(&[compare("Q4", "duckdb", "baseline-parallel", &expected, &result, &Tolerance::default())]);
+        }
+    }
+
+    #[test]
+    fn test_query_4_answer() {
+        let conn = initialize_database(0.01);
+        let (line_items, orders) = load(&conn).unwrap();
+        let result = query(line_items, orders, &QueryParams::default());
+
+        let expected = expected(&conn, 4, 0.01);
+        assert_match(&[compare("Q4", "tpch_answers", "baseline", &expected, &result, &answer_tolerance())]);
     }
 
     #[test]
     fn test_query_4_duckdb() {
-        let conn = initialize_database(1);
+        let conn = initialize_database(0.01);
 
         let res = conn
             .query_row(
@@ -211,7 +633,9 @@ mod tests {
                 },
             )
             .unwrap();
-        println!("Orders filtered: {:?}", res);
+        let params = QueryParams::default();
+        let (line_items, orders) = load(&conn).unwrap();
+        assert_eq!(res as usize, orders.iter().filter(|o| params.filter(&o.order_date)).count());
 
         let res = conn
             .query_row(
@@ -230,18 +654,74 @@ mod tests {
                 },
             )
             .unwrap();
-        println!("LineItem filtered: {:?}", res);
+        assert_eq!(res as usize, line_items.iter().filter(|l| l.commit_date < l.receiptdate).count());
 
-        query_duckdb(&conn, None);
+        let result = query_duckdb(&conn, None, &params);
+        assert_eq!(result.len(), 5);
     }
 
     #[test]
     fn test_load() {
-        let conn = initialize_database(1);
-        let line_items = LineItem::load(&conn, Some(1));
-        let orders = Order::load(&conn, Some(1));
+        let conn = initialize_database(0.01);
+        let line_items = LineItem::load(&conn, Some(1)).unwrap();
+        let orders = Order::load(&conn, Some(1)).unwrap();
 
         assert_eq!(line_items.len(), 1);
         assert_eq!(orders.len(), 1);
     }
+
+    #[test]
+    fn test_load_mode() {
+        // o_orderkey is a BIGINT, the second key does not fit Order::order_key.
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            r#"
+            CREATE TABLE orders (o_orderkey BIGINT, o_orderdate DATE, o_orderpriority VARCHAR);
+            INSERT INTO orders VALUES
+                (1, DATE '1993-07-01', '1-URGENT'),
+                (1099511627776, DATE '1993-07-02', '2-HIGH'),
+                (3, DATE '1993-07-03', '3-MEDIUM');
+            "#,
+        )
+        .unwrap();
+
+        match Order::load(&conn, None) {
+            Err(LoadError::Conversion { table, column, row, .. }) => {
+                assert_eq!(table, "orders");
+                assert_eq!(column.as_deref(), Some("o_orderkey"));
+                assert_eq!(row, 1);
+            }
+            other => panic!("Expected a conversion error, got {:?}", other.map(|orders| orders.len())),
+        }
+
+        let loaded = Order::load_with(&conn, None, LoadMode::Lenient).unwrap();
+        assert_eq!(loaded.rows.iter().map(|o| o.order_key).collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(loaded.dropped, 1);
+    }
+
+    #[test]
+    fn test_load_tbl() {
+        let conn = initialize_database(0.01);
+        let dir = std::env::temp_dir().join(format!("hydro_benchmarks_tbl_q4_{}", std::process::id()));
+        tbl::export(&conn, "lineitem", &dir);
+        tbl::export(&conn, "orders", &dir);
+        let params = QueryParams::default();
+        let (line_items, orders) = load_tbl(&dir).unwrap();
+        let result = query(line_items, orders, &params);
+
+        let expected = query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q4", "duckdb", "baseline-tbl", &expected, &result, &Tolerance::default())]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_arrow() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+        let (line_items, orders) = load_arrow(&conn).unwrap();
+        let result = query(line_items, orders, &params);
+
+        let expected = query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q4", "duckdb", "baseline-arrow", &expected, &result, &Tolerance::default())]);
+    }
 }
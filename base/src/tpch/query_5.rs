@@ -0,0 +1,326 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use duckdb::Connection;
+
+use super::answers::{parse_field, FromAnswer};
+use super::util::{load_rows, to_date};
+use super::verify::{ApproxEq, Tolerance};
+
+#[derive(Debug, Clone)]
+pub struct Customer {
+    pub c_custkey: i64,
+    pub c_nationkey: i32,
+}
+
+impl Customer {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "customer", &["c_custkey", "c_nationkey"], limit, |row| {
+            Ok(Customer {
+                c_custkey: row.get(0)?,
+                c_nationkey: row.get(1)?,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Order {
+    pub o_orderkey: i64,
+    pub o_custkey: i64,
+    pub o_orderdate: NaiveDate,
+}
+
+impl Order {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "orders", &["o_orderkey", "o_custkey", "o_orderdate"], limit, |row| {
+            Ok(Order {
+                o_orderkey: row.get(0)?,
+                o_custkey: row.get(1)?,
+                o_orderdate: to_date(row.get(2)?),
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LineItem {
+    pub l_orderkey: i64,
+    pub l_suppkey: i64,
+    pub l_extendedprice: f64,
+    pub l_discount: f64,
+}
+
+impl LineItem {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "lineitem", &["l_orderkey", "l_suppkey", "l_extendedprice", "l_discount"], limit, |row| {
+            Ok(LineItem {
+                l_orderkey: row.get(0)?,
+                l_suppkey: row.get(1)?,
+                l_extendedprice: row.get(2)?,
+                l_discount: row.get(3)?,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Supplier {
+    pub s_suppkey: i64,
+    pub s_nationkey: i32,
+}
+
+impl Supplier {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "supplier", &["s_suppkey", "s_nationkey"], limit, |row| {
+            Ok(Supplier {
+                s_suppkey: row.get(0)?,
+                s_nationkey: row.get(1)?,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Nation {
+    pub n_nationkey: i32,
+    pub n_name: String,
+    pub n_regionkey: i32,
+}
+
+impl Nation {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "nation", &["n_nationkey", "n_name", "n_regionkey"], limit, |row| {
+            Ok(Nation {
+                n_nationkey: row.get(0)?,
+                n_name: row.get(1)?,
+                n_regionkey: row.get(2)?,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Region {
+    pub r_regionkey: i32,
+    pub r_name: String,
+}
+
+impl Region {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "region", &["r_regionkey", "r_name"], limit, |row| {
+            Ok(Region {
+                r_regionkey: row.get(0)?,
+                r_name: row.get(1)?,
+            })
+        })
+    }
+}
+
+/// Result row of Query 5, ordered by revenue descending.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Q5Row {
+    pub n_name: String,
+    pub revenue: f64,
+}
+
+impl ApproxEq for Q5Row {
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+        self.n_name == other.n_name && tolerance.eq_f64(self.revenue, other.revenue)
+    }
+}
+
+impl FromAnswer for Q5Row {
+    fn from_answer(fields: &[&str]) -> Self {
+        Q5Row {
+            n_name: parse_field(fields, 0),
+            revenue: parse_field(fields, 1),
+        }
+    }
+}
+
+pub type Tables = (
+    Vec<Customer>,
+    Vec<Order>,
+    Vec<LineItem>,
+    Vec<Supplier>,
+    Vec<Nation>,
+    Vec<Region>,
+);
+
+pub fn load(conn: &Connection) -> Tables {
+    (
+        Customer::load(conn, None),
+        Order::load(conn, None),
+        LineItem::load(conn, None),
+        Supplier::load(conn, None),
+        Nation::load(conn, None),
+        Region::load(conn, None),
+    )
+}
+
+pub fn query(
+    customer: Vec<Customer>,
+    orders: Vec<Order>,
+    line_items: Vec<LineItem>,
+    supplier: Vec<Supplier>,
+    nation: Vec<Nation>,
+    region: Vec<Region>,
+) -> Vec<Q5Row> {
+    // Join order: the small dimension tables are used as build sides, lineitem is the final probe
+    // side. region -> nation -> customer -> orders -> lineitem -> supplier
+
+    // 1. Scan region and filter on r_name = 'ASIA'.
+    let region_build: Vec<i32> = region
+        .into_iter()
+        .filter(|r| r.r_name == "ASIA")
+        .map(|r| r.r_regionkey)
+        .collect();
+
+    // 2. Scan nation and probe region.
+    // Keys: n_nationkey
+    // Payload: n_name
+    let nation_build: HashMap<i32, String> = nation
+        .into_iter()
+        .filter(|n| region_build.contains(&n.n_regionkey))
+        .map(|n| (n.n_nationkey, n.n_name))
+        .collect();
+
+    // 3. Scan customer and probe nation.
+    // Keys: c_custkey
+    // Payload: c_nationkey
+    let customer_build: HashMap<i64, i32> = customer
+        .into_iter()
+        .filter(|c| nation_build.contains_key(&c.c_nationkey))
+        .map(|c| (c.c_custkey, c.c_nationkey))
+        .collect();
+
+    // 4. Scan orders, filter on o_orderdate in [1994-01-01, 1995-01-01) and probe customer.
+    // Keys: o_orderkey
+    // Payload: c_nationkey
+    let orders_build: HashMap<i64, i32> = orders
+        .into_iter()
+        .filter(|o| {
+            o.o_orderdate >= NaiveDate::from_ymd_opt(1994, 1, 1).unwrap()
+                && o.o_orderdate < NaiveDate::from_ymd_opt(1995, 1, 1).unwrap()
+        })
+        .filter_map(|o| customer_build.get(&o.o_custkey).map(|c_nationkey| (o.o_orderkey, *c_nationkey)))
+        .collect();
+
+    // 5. Scan supplier.
+    // Keys: s_suppkey
+    // Payload: s_nationkey
+    let supplier_build: HashMap<i64, i32> = supplier
+        .into_iter()
+        .map(|s| (s.s_suppkey, s.s_nationkey))
+        .collect();
+
+    // 6. Scan lineitem, probe orders and supplier, and keep c_nationkey = s_nationkey.
+    let joined = line_items.into_iter().filter_map(|l| {
+        let c_nationkey = orders_build.get(&l.l_orderkey)?;
+        let s_nationkey = supplier_build.get(&l.l_suppkey)?;
+        if c_nationkey == s_nationkey {
+            Some((*c_nationkey, l.l_extendedprice * (1.0 - l.l_discount)))
+        } else {
+            None
+        }
+    });
+
+    // 7. Group by n_name and sum the revenue.
+    let agg = joined.fold(HashMap::new(), |mut acc, (n_nationkey, revenue)| {
+        *acc.entry(n_nationkey).or_insert(0.0) += revenue;
+        acc
+    });
+
+    // 8. Sort by revenue desc.
+    let mut result: Vec<Q5Row> = agg
+        .into_iter()
+        .map(|(n_nationkey, revenue)| Q5Row {
+            n_name: nation_build[&n_nationkey].clone(),
+            revenue,
+        })
+        .collect();
+    result.sort_by(|a, b| b.revenue.total_cmp(&a.revenue));
+    result
+}
+
+pub fn query_duckdb(conn: &Connection, limit: Option<u32>) -> Vec<Q5Row> {
+    let table = |name: &str| match limit {
+        Some(limit) => format!("(SELECT * FROM {} LIMIT {}) AS {}", name, limit, name),
+        None => name.to_string(),
+    };
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"
+        SELECT
+            n_name,
+            sum(l_extendedprice * (1 - l_discount)) AS revenue
+        FROM
+            {},
+            {},
+            {},
+            {},
+            {},
+            {}
+        WHERE
+            c_custkey = o_custkey
+            AND l_orderkey = o_orderkey
+            AND l_suppkey = s_suppkey
+            AND c_nationkey = s_nationkey
+            AND s_nationkey = n_nationkey
+            AND n_regionkey = r_regionkey
+            AND r_name = 'ASIA'
+            AND o_orderdate >= CAST('1994-01-01' AS date)
+            AND o_orderdate < CAST('1995-01-01' AS date)
+        GROUP BY
+            n_name
+        ORDER BY
+            revenue DESC;
+    "#,
+            table("customer"),
+            table("orders"),
+            table("lineitem"),
+            table("supplier"),
+            table("nation"),
+            table("region")
+        ))
+        .expect("Error preparing query for DuckDB");
+
+    let mut rows = stmt.query([]).expect("Error executing Query 5");
+    let mut result = vec![];
+    while let Some(row) = rows.next().unwrap() {
+        result.push(Q5Row {
+            n_name: row.get(0).unwrap(),
+            revenue: row.get(1).unwrap(),
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tpch::answers::{answer_tolerance, expected};
+    use crate::tpch::initialize::initialize_database;
+    use crate::tpch::verify::{assert_match, compare};
+
+    #[test]
+    fn test_query_5() {
+        let conn = initialize_database(0.01);
+        let (customer, orders, line_items, supplier, nation, region) = load(&conn);
+        let result = query(customer, orders, line_items, supplier, nation, region);
+
+        let expected = query_duckdb(&conn, None);
+        assert_match(&[compare("Q5", "duckdb", "baseline", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_5_answer() {
+        let conn = initialize_database(0.01);
+        let (customer, orders, line_items, supplier, nation, region) = load(&conn);
+        let result = query(customer, orders, line_items, supplier, nation, region);
+
+        let expected = expected(&conn, 5, 0.01);
+        assert_match(&[compare("Q5", "tpch_answers", "baseline", &expected, &result, &answer_tolerance())]);
+    }
+}
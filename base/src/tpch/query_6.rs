@@ -0,0 +1,221 @@
+use chrono::NaiveDate;
+use duckdb::Connection;
+
+use super::answers::{parse_field, FromAnswer};
+use super::util::{load_rows, to_date};
+use super::verify::{ApproxEq, Tolerance};
+
+#[derive(Debug, Clone)]
+pub struct LineItem {
+    pub l_quantity: f64,
+    pub l_extendedprice: f64,
+    pub l_discount: f64,
+    pub l_shipdate: NaiveDate,
+}
+
+impl LineItem {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Vec<Self> {
+        load_rows(conn, "lineitem", &["l_quantity", "l_extendedprice", "l_discount", "l_shipdate"], limit, |row| {
+            Ok(LineItem {
+                l_quantity: row.get(0)?,
+                l_extendedprice: row.get(1)?,
+                l_discount: row.get(2)?,
+                l_shipdate: to_date(row.get(3)?),
+            })
+        })
+    }
+
+    /**
+     * l_shipdate >= '1994-01-01' AND l_shipdate < '1995-01-01'
+     * AND l_discount BETWEEN 0.05 AND 0.07 AND l_quantity < 24
+     */
+    pub fn filter(&self) -> bool {
+        self.l_shipdate >= NaiveDate::from_ymd_opt(1994, 1, 1).unwrap()
+            && self.l_shipdate < NaiveDate::from_ymd_opt(1995, 1, 1).unwrap()
+            && self.l_discount >= 0.05
+            && self.l_discount <= 0.07
+            && self.l_quantity < 24.0
+    }
+}
+
+/// Result row of Query 6. `revenue` is NULL if no line item qualifies.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Q6Row {
+    pub revenue: Option<f64>,
+}
+
+impl Q6Row {
+    /// Build the result from the sum and the number of qualifying line items.
+    pub fn new(revenue: f64, count: u64) -> Q6Row {
+        Q6Row {
+            revenue: if count > 0 { Some(revenue) } else { None },
+        }
+    }
+}
+
+impl ApproxEq for Q6Row {
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+        match (self.revenue, other.revenue) {
+            (Some(a), Some(b)) => tolerance.eq_f64(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl FromAnswer for Q6Row {
+    fn from_answer(fields: &[&str]) -> Self {
+        Q6Row {
+            revenue: Some(parse_field(fields, 0)),
+        }
+    }
+}
+
+pub fn load(conn: &Connection) -> Vec<LineItem> {
+    LineItem::load(conn, None)
+}
+
+/**
+ * Row-wise: every line item is filtered and aggregated on its own.
+ */
+pub fn query(line_items: Vec<LineItem>) -> Vec<Q6Row> {
+    // 1. Scan lineitem and filter.
+    // 2. Aggregate: sum(l_extendedprice * l_discount)
+    let revenue = line_items
+        .into_iter()
+        .filter(|l| l.filter())
+        .map(|l| l.l_extendedprice * l.l_discount)
+        .reduce(|acc, x| acc + x);
+
+    vec![Q6Row { revenue }]
+}
+
+/**
+ * Evaluate the predicates of Query 6 on a chunk of at most `CHUNK_SIZE` line items, one predicate
+ * at a time over the whole chunk. Returns the selection mask of the chunk.
+ */
+pub fn filter_chunk<const CHUNK_SIZE: usize>(chunk: &[LineItem]) -> [bool; CHUNK_SIZE] {
+    let date_from = NaiveDate::from_ymd_opt(1994, 1, 1).unwrap();
+    let date_to = NaiveDate::from_ymd_opt(1995, 1, 1).unwrap();
+
+    let mut selection = [false; CHUNK_SIZE];
+    for (s, l) in selection.iter_mut().zip(chunk) {
+        *s = l.l_shipdate >= date_from;
+    }
+    for (s, l) in selection.iter_mut().zip(chunk) {
+        *s &= l.l_shipdate < date_to;
+    }
+    for (s, l) in selection.iter_mut().zip(chunk) {
+        *s &= l.l_discount >= 0.05;
+    }
+    for (s, l) in selection.iter_mut().zip(chunk) {
+        *s &= l.l_discount <= 0.07;
+    }
+    for (s, l) in selection.iter_mut().zip(chunk) {
+        *s &= l.l_quantity < 24.0;
+    }
+    selection
+}
+
+/**
+ * Sum of l_extendedprice * l_discount and the number of qualifying line items of a chunk.
+ * The aggregate is branch-free: unselected line items contribute 0.
+ */
+pub fn aggregate_chunk<const CHUNK_SIZE: usize>(chunk: &[LineItem]) -> (f64, u64) {
+    let selection = filter_chunk::<CHUNK_SIZE>(chunk);
+    selection
+        .iter()
+        .zip(chunk)
+        .fold((0.0, 0), |(revenue, count), (s, l)| {
+            let s = *s as u64;
+            (revenue + s as f64 * l.l_extendedprice * l.l_discount, count + s)
+        })
+}
+
+/**
+ * Chunked: the line items are processed in chunks of `CHUNK_SIZE`, see `aggregate_chunk`.
+ */
+pub fn query_chunks<const CHUNK_SIZE: usize>(line_items: Vec<LineItem>) -> Vec<Q6Row> {
+    let (revenue, count) = line_items
+        .chunks(CHUNK_SIZE)
+        .map(aggregate_chunk::<CHUNK_SIZE>)
+        .fold((0.0, 0), |(revenue, count), (r, c)| (revenue + r, count + c));
+
+    vec![Q6Row::new(revenue, count)]
+}
+
+pub fn query_duckdb(conn: &Connection, limit: Option<u32>) -> Vec<Q6Row> {
+    let table = |name: &str| match limit {
+        Some(limit) => format!("(SELECT * FROM {} LIMIT {}) AS {}", name, limit, name),
+        None => name.to_string(),
+    };
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"
+        SELECT
+            sum(l_extendedprice * l_discount) AS revenue
+        FROM
+            {}
+        WHERE
+            l_shipdate >= CAST('1994-01-01' AS date)
+            AND l_shipdate < CAST('1995-01-01' AS date)
+            AND l_discount BETWEEN 0.05 AND 0.07
+            AND l_quantity < 24;
+    "#,
+            table("lineitem")
+        ))
+        .expect("Error preparing query for DuckDB");
+
+    let mut rows = stmt.query([]).expect("Error executing Query 6");
+    let mut result = vec![];
+    while let Some(row) = rows.next().unwrap() {
+        result.push(Q6Row {
+            revenue: row.get(0).unwrap(),
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tpch::answers::{answer_tolerance, expected};
+    use crate::tpch::initialize::initialize_database;
+    use crate::tpch::verify::{assert_match, compare};
+
+    #[test]
+    fn test_filter_chunk() {
+        let line_item = |l_quantity, l_discount| LineItem {
+            l_quantity,
+            l_extendedprice: 100.0,
+            l_discount,
+            l_shipdate: NaiveDate::from_ymd_opt(1994, 6, 1).unwrap(),
+        };
+        let chunk = [line_item(1.0, 0.06), line_item(30.0, 0.06), line_item(1.0, 0.1)];
+
+        // The chunk is shorter than CHUNK_SIZE, the tail stays unselected.
+        assert_eq!(filter_chunk::<4>(&chunk), [true, false, false, false]);
+        assert_eq!(aggregate_chunk::<4>(&chunk), (6.0, 1));
+    }
+
+    #[test]
+    fn test_query_6() {
+        let conn = initialize_database(0.01);
+        let expected = query_duckdb(&conn, None);
+
+        let result = query(load(&conn));
+        assert_match(&[compare("Q6", "duckdb", "baseline", &expected, &result, &Tolerance::default())]);
+
+        let result = query_chunks::<1024>(load(&conn));
+        assert_match(&[compare("Q6", "duckdb", "baseline-chunks", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_6_answer() {
+        let conn = initialize_database(0.01);
+        let result = query(load(&conn));
+
+        let expected = expected(&conn, 6, 0.01);
+        assert_match(&[compare("Q6", "tpch_answers", "baseline", &expected, &result, &answer_tolerance())]);
+    }
+}
@@ -0,0 +1,179 @@
+//! The TPC-H refresh functions RF1 and RF2.
+//!
+//! RF1 inserts new orders together with their line items, RF2 deletes old orders together with
+//! their line items. Both touch `SF * 1500` orders per pair. The new orders of RF1 are copies of
+//! sampled existing orders with fresh order keys above the largest one, so they keep the value
+//! distributions of `dbgen`. RF2 deletes the orders with the smallest keys.
+//!
+//! `RefreshStream::stage` writes the rows of the next pair into the staging tables `rf1_orders`,
+//! `rf1_lineitem`, `rf2_orders` and `rf2_lineitem` without touching `orders` and `lineitem`, so the
+//! queries can load the rows a pair inserts and deletes with their own projections. `apply` then
+//! runs the pair against the database. The tables have to be writable, see
+//! `initialize::initialize_in_memory`.
+
+use duckdb::Connection;
+
+use super::scale_factor::ScaleFactor;
+
+pub const RF1_ORDERS: &str = "rf1_orders";
+pub const RF1_LINEITEM: &str = "rf1_lineitem";
+pub const RF2_ORDERS: &str = "rf2_orders";
+pub const RF2_LINEITEM: &str = "rf2_lineitem";
+
+/// The rows one refresh pair inserts and deletes, in the projection of a query.
+#[derive(Debug, Clone)]
+pub struct Delta<T> {
+    pub inserted: Vec<T>,
+    pub deleted: Vec<T>,
+}
+
+impl<T> Delta<T> {
+    /// Only inserts, e.g. the initial load of an incrementally maintained query.
+    pub fn insert(rows: Vec<T>) -> Self {
+        Delta { inserted: rows, deleted: vec![] }
+    }
+
+    pub fn len(&self) -> usize {
+        self.inserted.len() + self.deleted.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inserted.is_empty() && self.deleted.is_empty()
+    }
+}
+
+/// Generates the refresh pairs of one scale factor.
+#[derive(Debug, Clone)]
+pub struct RefreshStream {
+    size: usize,
+    pairs: u64,
+}
+
+impl RefreshStream {
+    /// `SF * 1500` orders per refresh function, at least one.
+    pub fn new(scale_factor: ScaleFactor) -> Self {
+        RefreshStream::with_size(((scale_factor.value() * 1500.0).round() as usize).max(1))
+    }
+
+    pub fn with_size(size: usize) -> Self {
+        RefreshStream { size, pairs: 0 }
+    }
+
+    /// The number of orders each refresh function inserts or deletes.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// The number of pairs staged so far.
+    pub fn pairs(&self) -> u64 {
+        self.pairs
+    }
+
+    /// Stage the next pair into the `rf1_*` and `rf2_*` tables. The sample of RF1 depends on the
+    /// number of the pair, so consecutive pairs copy different orders.
+    pub fn stage(&mut self, conn: &Connection) {
+        conn.execute_batch(&format!(
+            r#"
+            CREATE OR REPLACE TEMP TABLE rf1_keys AS
+                SELECT
+                    o_orderkey AS old_key,
+                    (SELECT max(o_orderkey) FROM orders) + row_number() OVER (ORDER BY o_orderkey) AS new_key
+                FROM (SELECT o_orderkey FROM orders ORDER BY hash(o_orderkey, {pair}) LIMIT {size});
+            CREATE OR REPLACE TEMP TABLE {rf1_orders} AS
+                SELECT o.* REPLACE (k.new_key AS o_orderkey)
+                FROM orders o JOIN rf1_keys k ON o.o_orderkey = k.old_key;
+            CREATE OR REPLACE TEMP TABLE {rf1_lineitem} AS
+                SELECT l.* REPLACE (k.new_key AS l_orderkey)
+                FROM lineitem l JOIN rf1_keys k ON l.l_orderkey = k.old_key;
+            CREATE OR REPLACE TEMP TABLE {rf2_orders} AS
+                SELECT * FROM orders ORDER BY o_orderkey LIMIT {size};
+            CREATE OR REPLACE TEMP TABLE {rf2_lineitem} AS
+                SELECT * FROM lineitem WHERE l_orderkey IN (SELECT o_orderkey FROM {rf2_orders});
+            "#,
+            pair = self.pairs,
+            size = self.size,
+            rf1_orders = RF1_ORDERS,
+            rf1_lineitem = RF1_LINEITEM,
+            rf2_orders = RF2_ORDERS,
+            rf2_lineitem = RF2_LINEITEM,
+        ))
+        .expect("Error staging refresh functions");
+        self.pairs += 1;
+    }
+}
+
+/// Run the staged pair: RF1 inserts the `rf1_*` rows, RF2 deletes the `rf2_*` orders and their
+/// line items.
+pub fn apply(conn: &Connection) {
+    conn.execute_batch(&format!(
+        r#"
+        BEGIN TRANSACTION;
+        INSERT INTO orders SELECT * FROM {rf1_orders};
+        INSERT INTO lineitem SELECT * FROM {rf1_lineitem};
+        DELETE FROM lineitem WHERE l_orderkey IN (SELECT o_orderkey FROM {rf2_orders});
+        DELETE FROM orders WHERE o_orderkey IN (SELECT o_orderkey FROM {rf2_orders});
+        COMMIT;
+        "#,
+        rf1_orders = RF1_ORDERS,
+        rf1_lineitem = RF1_LINEITEM,
+        rf2_orders = RF2_ORDERS,
+    ))
+    .expect("Error applying refresh functions");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tpch::initialize::initialize_in_memory;
+
+    fn count(conn: &Connection, table: &str) -> i64 {
+        conn.query_row(&format!("SELECT count(*) FROM {};", table), [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_refresh_stream() {
+        assert_eq!(RefreshStream::new(ScaleFactor::from(1)).size(), 1500);
+        assert_eq!(RefreshStream::new(ScaleFactor::from(0.01)).size(), 15);
+        assert_eq!(RefreshStream::new(ScaleFactor::from(0)).size(), 1);
+
+        let conn = initialize_in_memory(0.01);
+        let orders = count(&conn, "orders");
+        let line_items = count(&conn, "lineitem");
+
+        let mut stream = RefreshStream::new(ScaleFactor::from(0.01));
+        stream.stage(&conn);
+        assert_eq!(stream.pairs(), 1);
+        assert_eq!(count(&conn, RF1_ORDERS), 15);
+        assert_eq!(count(&conn, RF2_ORDERS), 15);
+        let inserted = count(&conn, RF1_LINEITEM);
+        let deleted = count(&conn, RF2_LINEITEM);
+        // Staging leaves the tables alone.
+        assert_eq!(count(&conn, "orders"), orders);
+
+        apply(&conn);
+        assert_eq!(count(&conn, "orders"), orders);
+        assert_eq!(count(&conn, "lineitem"), line_items + inserted - deleted);
+
+        // The new orders have fresh keys and their line items came along.
+        let orphans: i64 = conn
+            .query_row(
+                "SELECT count(*) FROM lineitem WHERE l_orderkey NOT IN (SELECT o_orderkey FROM orders);",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(orphans, 0);
+
+        // RF2 of a pair never deletes the orders its RF1 inserts.
+        stream.stage(&conn);
+        let overlap: i64 = conn
+            .query_row(
+                "SELECT count(*) FROM rf2_orders WHERE o_orderkey IN (SELECT o_orderkey FROM rf1_orders);",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(overlap, 0);
+    }
+}
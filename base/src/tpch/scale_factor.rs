@@ -0,0 +1,77 @@
+//! TPC-H scale factors.
+//!
+//! `dbgen` and `tpch_answers()` take fractional scale factors such as 0.01 and 0.1, which are
+//! small enough for tests but keep every table complete, unlike a LIMIT per table. Schema and file
+//! names use `ScaleFactor::name`, where the decimal point is replaced so the name is a valid
+//! identifier.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct ScaleFactor(f64);
+
+impl ScaleFactor {
+    pub fn new(value: f64) -> Self {
+        assert!(value.is_finite() && value >= 0.0, "Invalid scale factor {}", value);
+        ScaleFactor(value)
+    }
+
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+
+    /// The scale factor with `_` for the decimal point, e.g. `0_01`.
+    pub fn name(&self) -> String {
+        self.to_string().replace('.', "_")
+    }
+
+    /// The schema the tables are generated into, e.g. `SF_0_01`.
+    pub fn schema(&self) -> String {
+        format!("SF_{}", self.name())
+    }
+}
+
+impl fmt::Display for ScaleFactor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<f64> for ScaleFactor {
+    fn from(value: f64) -> Self {
+        ScaleFactor::new(value)
+    }
+}
+
+impl From<u32> for ScaleFactor {
+    fn from(value: u32) -> Self {
+        ScaleFactor::new(value as f64)
+    }
+}
+
+/// Integer literals default to `i32`, so `initialize_database(1)` needs this conversion.
+impl From<i32> for ScaleFactor {
+    fn from(value: i32) -> Self {
+        ScaleFactor::new(value as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scale_factor() {
+        assert_eq!(ScaleFactor::from(1).schema(), "SF_1");
+        assert_eq!(ScaleFactor::from(10u32).name(), "10");
+        assert_eq!(ScaleFactor::from(0.01).schema(), "SF_0_01");
+        assert_eq!(ScaleFactor::from(0.1).to_string(), "0.1");
+        assert_eq!(ScaleFactor::from(0.1).value(), 0.1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_scale_factor_negative() {
+        let _ = ScaleFactor::from(-1);
+    }
+}
@@ -0,0 +1,198 @@
+//! Pipelined scans from DuckDB.
+//!
+//! The loaders return a fully materialized `Vec`, so a query only starts once its whole input is
+//! in memory. A `Loader` instead runs the scan of a table on its own thread, over its own
+//! connection to the same database, and hands the converted Arrow record batches to the consumer,
+//! e.g. through a bounded channel. The consumer processes a batch while the next ones are loaded,
+//! and at most the capacity of the channel waits in memory. A `Scan` is a loader with a
+//! `std::sync::mpsc` channel, whose rows are read as an iterator. A row struct declares how it
+//! converts a record batch by implementing `FromBatch`, the table and projection are those of
+//! `FromTbl`.
+
+use std::panic;
+use std::sync::mpsc::{self, Receiver};
+use std::thread::{self, JoinHandle};
+use std::vec;
+
+use duckdb::arrow::record_batch::RecordBatch;
+use duckdb::Connection;
+
+use super::load::LoadError;
+use super::tbl::FromTbl;
+
+/// Batches in flight between the loader and the consumer.
+pub const DEFAULT_CAPACITY: usize = 16;
+
+/// The minimum rows of a batch handed to the consumer. DuckDB produces record batches of 2048 rows,
+/// which are coalesced, so a consumer that does work per batch, e.g. a Hydroflow tick, does it less
+/// often.
+pub const DEFAULT_BATCH_ROWS: usize = 65_536;
+
+pub trait FromBatch: FromTbl + Send + 'static {
+    /// Append the rows of `batch`, which holds `FromTbl::COLUMNS` in this order, up to the first row
+    /// that cannot be converted.
+    fn from_batch(batch: &RecordBatch, rows: &mut Vec<Self>) -> duckdb::Result<()>;
+}
+
+/// The thread of a scan. It is joined when dropped, so the consumer must drop its end of the
+/// channel first, which stops a loader that waits for the consumer.
+pub struct Loader {
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Loader {
+    /**
+     * Scan the first `limit` rows of `T::TABLE` and hand them to `send` in batches of at least
+     * `batch_rows` rows, in scan order. The scan stops early once `send` returns false, e.g. because
+     * the consumer dropped its end of the channel.
+     */
+    pub fn spawn<T, F>(conn: &Connection, limit: Option<u32>, batch_rows: usize, send: F) -> Self
+    where
+        T: FromBatch,
+        F: FnMut(Vec<T>) -> bool + Send + 'static,
+    {
+        let conn = conn
+            .try_clone()
+            .unwrap_or_else(|e| panic!("Error connecting to scan {}: {}", T::TABLE, e));
+        let handle = thread::spawn(move || load(&conn, limit, batch_rows, send));
+        Loader { handle: Some(handle) }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        match &self.handle {
+            Some(handle) => handle.is_finished(),
+            None => true,
+        }
+    }
+
+    /// Wait for the scan to end. Panics if the loader panicked, instead of ending early.
+    pub fn join(mut self) {
+        if let Some(Err(error)) = self.handle.take().map(JoinHandle::join) {
+            panic::resume_unwind(error);
+        }
+    }
+}
+
+impl Drop for Loader {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            // A panic of the loader is only reported by `join`.
+            let _ = handle.join();
+        }
+    }
+}
+
+fn load<T: FromBatch, F: FnMut(Vec<T>) -> bool>(conn: &Connection, limit: Option<u32>, batch_rows: usize, mut send: F) {
+    let query = match limit {
+        Some(limit) => format!("SELECT {} FROM {} LIMIT {};", T::COLUMNS.join(", "), T::TABLE, limit),
+        None => format!("SELECT {} FROM {};", T::COLUMNS.join(", "), T::TABLE),
+    };
+    let mut stmt = conn
+        .prepare(&query)
+        .unwrap_or_else(|e| panic!("Error preparing query for {}: {}", T::TABLE, e));
+    let batches = stmt
+        .query_arrow([])
+        .unwrap_or_else(|e| panic!("Error querying {}: {}", T::TABLE, e));
+
+    let mut rows = vec![];
+    // The rows handed to the consumer so far.
+    let mut sent = 0;
+    for batch in batches {
+        if let Err(error) = T::from_batch(&batch, &mut rows) {
+            panic!("{}", LoadError::conversion(T::TABLE, T::COLUMNS, sent + rows.len(), error));
+        }
+        if rows.len() >= batch_rows {
+            sent += rows.len();
+            // The consumer is gone.
+            if !send(std::mem::take(&mut rows)) {
+                return;
+            }
+        }
+    }
+    if !rows.is_empty() {
+        send(rows);
+    }
+}
+
+/// The rows of a table in scan order, see the module documentation.
+pub struct Scan<T> {
+    // Dropped before the loader, which stops it.
+    batches: Receiver<Vec<T>>,
+    rows: vec::IntoIter<T>,
+    loader: Option<Loader>,
+}
+
+impl<T: FromBatch> Scan<T> {
+    /// Scan the whole table with `DEFAULT_CAPACITY`.
+    pub fn start(conn: &Connection) -> Self {
+        Scan::with_capacity(conn, None, DEFAULT_CAPACITY)
+    }
+
+    /// Scan the first `limit` rows, with at most `capacity` batches in flight.
+    pub fn with_capacity(conn: &Connection, limit: Option<u32>, capacity: usize) -> Self {
+        let (batch_send, batch_recv) = mpsc::sync_channel(capacity);
+        let loader = Loader::spawn(conn, limit, DEFAULT_BATCH_ROWS, move |batch| batch_send.send(batch).is_ok());
+        Scan { batches: batch_recv, rows: vec![].into_iter(), loader: Some(loader) }
+    }
+}
+
+/// Blocks while the loader is behind. Panics if the loader panicked, instead of ending early.
+impl<T> Iterator for Scan<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        loop {
+            if let Some(row) = self.rows.next() {
+                return Some(row);
+            }
+            match self.batches.recv() {
+                Ok(batch) => self.rows = batch.into_iter(),
+                Err(_) => {
+                    if let Some(loader) = self.loader.take() {
+                        loader.join();
+                    }
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+    use crate::tpch::initialize::initialize_in_memory;
+    use crate::tpch::query_4::Order;
+
+    #[test]
+    fn test_scan() {
+        let conn = initialize_in_memory(0.01);
+        let expected = Order::load_arrow(&conn, None).unwrap();
+
+        // A capacity of one makes the loader wait for the consumer on every batch.
+        let scanned: Vec<Order> = Scan::with_capacity(&conn, None, 1).collect();
+        assert_eq!(scanned.len(), expected.len());
+        assert!(scanned.iter().zip(&expected).all(|(a, b)| a.order_key == b.order_key && a.order_priority == b.order_priority));
+
+        assert_eq!(Scan::<Order>::with_capacity(&conn, Some(10), 1).count(), 10);
+
+        // Dropping the receiving end stops a loader that waits for the consumer.
+        let (batch_send, batch_recv) = mpsc::sync_channel(1);
+        let loader = Loader::spawn::<Order, _>(&conn, None, 1, move |batch| batch_send.send(batch).is_ok());
+        assert!(!batch_recv.recv().unwrap().is_empty());
+        drop(batch_recv);
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !loader.is_finished() {
+            assert!(Instant::now() < deadline, "The loader did not stop");
+            thread::sleep(Duration::from_millis(1));
+        }
+        loader.join();
+
+        // Dropping a scan early drops its receiving end and then joins the loader.
+        let mut scan = Scan::<Order>::with_capacity(&conn, None, 1);
+        assert!(scan.next().is_some());
+        drop(scan);
+    }
+}
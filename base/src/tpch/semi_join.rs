@@ -0,0 +1,54 @@
+//! Semi-joins and anti-joins.
+//!
+//! A semi-join keeps the rows of the probe side that have a match on the build side, an anti-join
+//! the rows that have none. Neither emits anything of the build side, so the build side only needs
+//! its distinct keys: duplicates on the build side do not duplicate the output, unlike a hash join.
+//! Both keep the order and the duplicates of the probe side, e.g. the EXISTS of Query 4 and the
+//! NOT EXISTS of Query 21 and 22.
+
+use std::collections::HashSet;
+use std::hash::Hash;
+
+/// The rows of `probe` whose `key` is in `build`.
+pub fn semi_join<P, B, K, F>(probe: P, build: B, key: F) -> impl Iterator<Item = P::Item>
+where
+    P: IntoIterator,
+    B: IntoIterator<Item = K>,
+    K: Eq + Hash,
+    F: Fn(&P::Item) -> K,
+{
+    let build: HashSet<K> = build.into_iter().collect();
+    probe.into_iter().filter(move |x| build.contains(&key(x)))
+}
+
+/// The rows of `probe` whose `key` is not in `build`.
+pub fn anti_join<P, B, K, F>(probe: P, build: B, key: F) -> impl Iterator<Item = P::Item>
+where
+    P: IntoIterator,
+    B: IntoIterator<Item = K>,
+    K: Eq + Hash,
+    F: Fn(&P::Item) -> K,
+{
+    let build: HashSet<K> = build.into_iter().collect();
+    probe.into_iter().filter(move |x| !build.contains(&key(x)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_semi_join() {
+        let orders = vec![(1, "a"), (2, "b"), (3, "c"), (1, "d")];
+        let line_items = vec![1, 1, 1, 3, 4];
+
+        let semi: Vec<_> = semi_join(orders.clone(), line_items.clone(), |o| o.0).collect();
+        assert_eq!(semi, vec![(1, "a"), (3, "c"), (1, "d")]);
+
+        let anti: Vec<_> = anti_join(orders.clone(), line_items, |o| o.0).collect();
+        assert_eq!(anti, vec![(2, "b")]);
+
+        assert_eq!(semi_join(orders.clone(), vec![], |o| o.0).count(), 0);
+        assert_eq!(anti_join(orders.clone(), vec![], |o| o.0).count(), orders.len());
+    }
+}
@@ -0,0 +1,205 @@
+//! One scan of lineitem for several queries.
+//!
+//! Query 1, 4 and 19 each load their own projection of lineitem, so running them one after another
+//! scans lineitem three times. `LineItem` is the union of their projections: a multi-query plan
+//! scans lineitem once and converts every row into the row of each query, see
+//! `hydroflow_base::tpch::shared_scan`.
+
+use chrono::NaiveDate;
+use duckdb::arrow::datatypes::{Date32Type, Float64Type, Int32Type, Int64Type};
+use duckdb::arrow::record_batch::RecordBatch;
+use duckdb::Connection;
+
+use super::load::{LoadError, LoadMode, Loaded};
+use super::query_1;
+use super::query_19::{self, Part};
+use super::query_4::{self, Order};
+use super::scan::FromBatch;
+use super::tbl::{Fields, FromTbl, TblError};
+use super::util::{char_value, get_char, load_batches, primitive_column, string_column, to_date, try_load_rows};
+
+#[derive(Debug, Clone)]
+pub struct LineItem {
+    pub l_orderkey: i32,
+    pub l_partkey: i64,
+    pub l_quantity: f64,
+    pub l_extendedprice: f64,
+    pub l_discount: f64,
+    pub l_tax: f64,
+    pub l_returnflag: char,
+    pub l_linestatus: char,
+    pub l_shipdate: NaiveDate,
+    pub l_commitdate: NaiveDate,
+    pub l_receiptdate: NaiveDate,
+    pub l_shipinstruct: String,
+    pub l_shipmode: String,
+}
+
+impl LineItem {
+    pub fn load(conn: &Connection, limit: Option<u32>) -> Result<Vec<Self>, LoadError> {
+        Ok(LineItem::load_with(conn, limit, LoadMode::Strict)?.rows)
+    }
+
+    pub fn load_with(conn: &Connection, limit: Option<u32>, mode: LoadMode) -> Result<Loaded<Self>, LoadError> {
+        try_load_rows(conn, Self::TABLE, Self::COLUMNS, limit, mode, |row| {
+            Ok(LineItem {
+                l_orderkey: row.get(0)?,
+                l_partkey: row.get(1)?,
+                l_quantity: row.get(2)?,
+                l_extendedprice: row.get(3)?,
+                l_discount: row.get(4)?,
+                l_tax: row.get(5)?,
+                l_returnflag: get_char(row, 6)?,
+                l_linestatus: get_char(row, 7)?,
+                l_shipdate: to_date(row.get(8)?),
+                l_commitdate: to_date(row.get(9)?),
+                l_receiptdate: to_date(row.get(10)?),
+                l_shipinstruct: row.get(11)?,
+                l_shipmode: row.get(12)?,
+            })
+        })
+    }
+
+    /// Same as `load`, but converts whole Arrow record batches instead of one row at a time.
+    pub fn load_arrow(conn: &Connection, limit: Option<u32>) -> Result<Vec<Self>, LoadError> {
+        load_batches(conn, Self::TABLE, Self::COLUMNS, limit, Self::from_batch)
+    }
+}
+
+/// Lineitem once, and the other tables of Query 4 and 19.
+pub type Tables = (Vec<LineItem>, Vec<Order>, Vec<Part>);
+
+pub fn load(conn: &Connection) -> Result<Tables, LoadError> {
+    Ok((LineItem::load(conn, None)?, Order::load(conn, None)?, Part::load(conn, None)?))
+}
+
+/// Same as `load`, but converts whole Arrow record batches instead of one row at a time.
+pub fn load_arrow(conn: &Connection) -> Result<Tables, LoadError> {
+    Ok((LineItem::load_arrow(conn, None)?, Order::load_arrow(conn, None)?, Part::load_arrow(conn, None)?))
+}
+
+impl FromTbl for LineItem {
+    const TABLE: &'static str = "lineitem";
+    const COLUMNS: &'static [&'static str] = &[
+        "l_orderkey", "l_partkey", "l_quantity", "l_extendedprice", "l_discount", "l_tax", "l_returnflag", "l_linestatus",
+        "l_shipdate", "l_commitdate", "l_receiptdate", "l_shipinstruct", "l_shipmode",
+    ];
+
+    fn from_tbl(fields: &Fields<'_>) -> Result<Self, TblError> {
+        Ok(LineItem {
+            l_orderkey: fields.parse(0)?,
+            l_partkey: fields.parse(1)?,
+            l_quantity: fields.parse(2)?,
+            l_extendedprice: fields.parse(3)?,
+            l_discount: fields.parse(4)?,
+            l_tax: fields.parse(5)?,
+            l_returnflag: fields.char(6)?,
+            l_linestatus: fields.char(7)?,
+            l_shipdate: fields.parse(8)?,
+            l_commitdate: fields.parse(9)?,
+            l_receiptdate: fields.parse(10)?,
+            l_shipinstruct: fields.str(11).to_string(),
+            l_shipmode: fields.str(12).to_string(),
+        })
+    }
+}
+
+impl FromBatch for LineItem {
+    fn from_batch(batch: &RecordBatch, line_items: &mut Vec<Self>) -> duckdb::Result<()> {
+        let l_orderkey = primitive_column::<Int32Type>(batch, 0)?;
+        let l_partkey = primitive_column::<Int64Type>(batch, 1)?;
+        let l_quantity = primitive_column::<Float64Type>(batch, 2)?;
+        let l_extendedprice = primitive_column::<Float64Type>(batch, 3)?;
+        let l_discount = primitive_column::<Float64Type>(batch, 4)?;
+        let l_tax = primitive_column::<Float64Type>(batch, 5)?;
+        let l_returnflag = string_column(batch, 6)?;
+        let l_linestatus = string_column(batch, 7)?;
+        let l_shipdate = primitive_column::<Date32Type>(batch, 8)?;
+        let l_commitdate = primitive_column::<Date32Type>(batch, 9)?;
+        let l_receiptdate = primitive_column::<Date32Type>(batch, 10)?;
+        let l_shipinstruct = string_column(batch, 11)?;
+        let l_shipmode = string_column(batch, 12)?;
+        for i in 0..batch.num_rows() {
+            line_items.push(LineItem {
+                l_orderkey: l_orderkey.value(i),
+                l_partkey: l_partkey.value(i),
+                l_quantity: l_quantity.value(i),
+                l_extendedprice: l_extendedprice.value(i),
+                l_discount: l_discount.value(i),
+                l_tax: l_tax.value(i),
+                l_returnflag: char_value(l_returnflag.value(i), 6)?,
+                l_linestatus: char_value(l_linestatus.value(i), 7)?,
+                l_shipdate: to_date(l_shipdate.value(i)),
+                l_commitdate: to_date(l_commitdate.value(i)),
+                l_receiptdate: to_date(l_receiptdate.value(i)),
+                l_shipinstruct: l_shipinstruct.value(i).to_string(),
+                l_shipmode: l_shipmode.value(i).to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl From<&LineItem> for query_1::LineItem {
+    fn from(l: &LineItem) -> Self {
+        query_1::LineItem {
+            l_returnflag: l.l_returnflag,
+            l_linestatus: l.l_linestatus,
+            l_quantity: l.l_quantity,
+            l_extendedprice: l.l_extendedprice,
+            l_discount: l.l_discount,
+            l_tax: l.l_tax,
+            l_shipdate: l.l_shipdate,
+        }
+    }
+}
+
+impl From<&LineItem> for query_4::LineItem {
+    fn from(l: &LineItem) -> Self {
+        query_4::LineItem {
+            order_key: l.l_orderkey,
+            receiptdate: l.l_receiptdate,
+            commit_date: l.l_commitdate,
+        }
+    }
+}
+
+impl From<&LineItem> for query_19::LineItem {
+    fn from(l: &LineItem) -> Self {
+        query_19::LineItem {
+            l_partkey: l.l_partkey,
+            l_shipmode: l.l_shipmode.clone(),
+            l_quantity: l.l_quantity,
+            l_shipinstruct: l.l_shipinstruct.clone(),
+            l_discount: l.l_discount,
+            l_extendedprice: l.l_extendedprice,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tpch::initialize::initialize_in_memory;
+
+    #[test]
+    fn test_shared_scan() {
+        let conn = initialize_in_memory(0.01);
+        let shared = LineItem::load_arrow(&conn, None).unwrap();
+        let q1 = query_1::LineItem::load_arrow(&conn, None).unwrap();
+        let q4 = query_4::LineItem::load_arrow(&conn, None).unwrap();
+        let q19 = query_19::LineItem::load_arrow(&conn, None).unwrap();
+        assert_eq!(shared.len(), q1.len());
+        assert_eq!(LineItem::load(&conn, None).unwrap().len(), shared.len());
+
+        // The projections of the shared scan are those of the queries, in scan order.
+        for (((l, l1), l4), l19) in shared.iter().zip(&q1).zip(&q4).zip(&q19) {
+            let p1 = query_1::LineItem::from(l);
+            assert!(p1.l_returnflag == l1.l_returnflag && p1.l_quantity == l1.l_quantity && p1.l_shipdate == l1.l_shipdate);
+            let p4 = query_4::LineItem::from(l);
+            assert!(p4.order_key == l4.order_key && p4.commit_date == l4.commit_date && p4.receiptdate == l4.receiptdate);
+            let p19 = query_19::LineItem::from(l);
+            assert!(p19.l_partkey == l19.l_partkey && p19.l_shipmode == l19.l_shipmode && p19.l_extendedprice == l19.l_extendedprice);
+        }
+    }
+}
@@ -0,0 +1,342 @@
+//! Reader of the `.tbl` files of the official `dbgen`.
+//!
+//! A `.tbl` file has one row per line, every field is terminated by `|`. `TblReader` streams the
+//! rows of a file with a single line buffer and only hands out the projected columns, so a table
+//! can be read into the query structs without DuckDB. A row struct declares its table and
+//! projection by implementing `FromTbl`.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use duckdb::Connection;
+
+/// The columns of `lineitem.tbl` in file order.
+pub const LINEITEM: &[&str] = &[
+    "l_orderkey", "l_partkey", "l_suppkey", "l_linenumber", "l_quantity", "l_extendedprice", "l_discount", "l_tax",
+    "l_returnflag", "l_linestatus", "l_shipdate", "l_commitdate", "l_receiptdate", "l_shipinstruct", "l_shipmode",
+    "l_comment",
+];
+
+/// The columns of `orders.tbl` in file order.
+pub const ORDERS: &[&str] = &[
+    "o_orderkey", "o_custkey", "o_orderstatus", "o_totalprice", "o_orderdate", "o_orderpriority", "o_clerk",
+    "o_shippriority", "o_comment",
+];
+
+/// The columns of `part.tbl` in file order.
+pub const PART: &[&str] = &[
+    "p_partkey", "p_name", "p_mfgr", "p_brand", "p_type", "p_size", "p_container", "p_retailprice", "p_comment",
+];
+
+/// The columns of `table` in file order, for the tables the reader supports.
+pub fn schema(table: &str) -> Option<&'static [&'static str]> {
+    match table {
+        "lineitem" => Some(LINEITEM),
+        "orders" => Some(ORDERS),
+        "part" => Some(PART),
+        _ => None,
+    }
+}
+
+/// The file of `table` in `dir`, e.g. `<dir>/lineitem.tbl`.
+pub fn path(dir: &Path, table: &str) -> PathBuf {
+    dir.join(format!("{}.tbl", table))
+}
+
+pub trait FromTbl: Sized {
+    const TABLE: &'static str;
+    /// The projection, `from_tbl` gets the values of these columns in this order.
+    const COLUMNS: &'static [&'static str];
+
+    fn from_tbl(fields: &Fields<'_>) -> Result<Self, TblError>;
+}
+
+#[derive(Debug)]
+pub enum TblError {
+    Io(io::Error),
+    UnknownTable(String),
+    UnknownColumn { table: String, column: String },
+    /// Line `line` (1-based) ends before `column`.
+    MissingField { table: String, column: String, line: usize },
+    /// The value of `column` in line `line` (1-based) does not parse.
+    Parse {
+        table: String,
+        column: String,
+        line: usize,
+        value: String,
+        error: String,
+    },
+}
+
+impl fmt::Display for TblError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TblError::Io(error) => write!(f, "Error reading .tbl file: {}", error),
+            TblError::UnknownTable(table) => write!(f, "Unknown table {}", table),
+            TblError::UnknownColumn { table, column } => write!(f, "Unknown column {}.{}", table, column),
+            TblError::MissingField { table, column, line } => {
+                write!(f, "Missing {}.{} in line {}", table, column, line)
+            }
+            TblError::Parse { table, column, line, value, error } => {
+                write!(f, "Error parsing {}.{} in line {} from {:?}: {}", table, column, line, value, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TblError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TblError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for TblError {
+    fn from(error: io::Error) -> Self {
+        TblError::Io(error)
+    }
+}
+
+/// The projected values of one line.
+pub struct Fields<'a> {
+    table: &'a str,
+    columns: &'a [String],
+    line: &'a str,
+    ranges: &'a [Range<usize>],
+    line_nr: usize,
+}
+
+impl<'a> Fields<'a> {
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The raw value of the `index`-th projected column.
+    pub fn str(&self, index: usize) -> &'a str {
+        &self.line[self.ranges[index].clone()]
+    }
+
+    pub fn parse<T: FromStr>(&self, index: usize) -> Result<T, TblError>
+    where
+        T::Err: fmt::Display,
+    {
+        let value = self.str(index);
+        value.parse().map_err(|e: T::Err| TblError::Parse {
+            table: self.table.to_string(),
+            column: self.columns[index].clone(),
+            line: self.line_nr,
+            value: value.to_string(),
+            error: e.to_string(),
+        })
+    }
+
+    /// The first character of a CHAR(1) column.
+    pub fn char(&self, index: usize) -> Result<char, TblError> {
+        self.parse(index)
+    }
+}
+
+pub struct TblReader<R> {
+    reader: R,
+    table: String,
+    columns: Vec<String>,
+    /// For every column of the file, the position in the projection.
+    slots: Vec<Option<usize>>,
+    line: String,
+    ranges: Vec<Range<usize>>,
+    line_nr: usize,
+}
+
+impl<R: BufRead> TblReader<R> {
+    /// Reads the `columns` of `table`, in the given order.
+    pub fn new(reader: R, table: &str, columns: &[&str]) -> Result<Self, TblError> {
+        let schema = schema(table).ok_or_else(|| TblError::UnknownTable(table.to_string()))?;
+        let mut slots = vec![None; schema.len()];
+        for (slot, column) in columns.iter().enumerate() {
+            let index = schema.iter().position(|c| c == column).ok_or_else(|| TblError::UnknownColumn {
+                table: table.to_string(),
+                column: column.to_string(),
+            })?;
+            slots[index] = Some(slot);
+        }
+        Ok(TblReader {
+            reader,
+            table: table.to_string(),
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            slots,
+            line: String::new(),
+            ranges: vec![0..0; columns.len()],
+            line_nr: 0,
+        })
+    }
+
+    /// The next line, `None` at the end of the file. Empty lines are skipped.
+    pub fn next_fields(&mut self) -> Option<Result<Fields<'_>, TblError>> {
+        loop {
+            self.line.clear();
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) => return None,
+                Ok(_) => {}
+                Err(e) => return Some(Err(e.into())),
+            }
+            self.line_nr += 1;
+            let len = self.line.trim_end_matches(['\n', '\r']).len();
+            self.line.truncate(len);
+            // The trailing `|` of `dbgen` terminates the last field.
+            if self.line.ends_with('|') {
+                self.line.pop();
+            }
+            if !self.line.is_empty() {
+                break;
+            }
+        }
+
+        // Locate the projected fields.
+        let mut start = 0;
+        let mut visited = 0;
+        for slot in &self.slots {
+            if start > self.line.len() {
+                break;
+            }
+            let end = self.line[start..].find('|').map_or(self.line.len(), |i| start + i);
+            if let Some(slot) = slot {
+                self.ranges[*slot] = start..end;
+            }
+            start = end + 1;
+            visited += 1;
+        }
+        if let Some(missing) = self.slots[visited..].iter().flatten().next() {
+            return Some(Err(TblError::MissingField {
+                table: self.table.clone(),
+                column: self.columns[*missing].clone(),
+                line: self.line_nr,
+            }));
+        }
+
+        Some(Ok(Fields {
+            table: &self.table,
+            columns: &self.columns,
+            line: &self.line,
+            ranges: &self.ranges,
+            line_nr: self.line_nr,
+        }))
+    }
+
+    /// The rows as `T`, projected to `T::COLUMNS`.
+    pub fn rows<T: FromTbl>(reader: R) -> Result<Rows<R, T>, TblError> {
+        Ok(Rows {
+            reader: TblReader::new(reader, T::TABLE, T::COLUMNS)?,
+            row: PhantomData,
+        })
+    }
+}
+
+/// Streams the rows of a `.tbl` file as `T`.
+pub struct Rows<R, T> {
+    reader: TblReader<R>,
+    row: PhantomData<fn() -> T>,
+}
+
+impl<R: BufRead, T: FromTbl> Iterator for Rows<R, T> {
+    type Item = Result<T, TblError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.reader.next_fields().map(|fields| fields.and_then(|fields| T::from_tbl(&fields)))
+    }
+}
+
+/// Streams the rows of `<dir>/<T::TABLE>.tbl`.
+pub fn open<T: FromTbl>(dir: &Path) -> Result<Rows<BufReader<File>, T>, TblError> {
+    let file = File::open(path(dir, T::TABLE))?;
+    TblReader::rows(BufReader::new(file))
+}
+
+/// Reads all rows of `<dir>/<T::TABLE>.tbl`.
+pub fn read<T: FromTbl>(dir: &Path) -> Result<Vec<T>, TblError> {
+    open(dir)?.collect()
+}
+
+/// Writes `table` of `conn` to `<dir>/<table>.tbl`, e.g. to get `.tbl` files of a scale factor
+/// without building `dbgen`. The lines lack the trailing `|` of `dbgen`, which the reader accepts.
+pub fn export(conn: &Connection, table: &str, dir: &Path) -> PathBuf {
+    std::fs::create_dir_all(dir).unwrap_or_else(|e| panic!("Error creating {}: {}", dir.display(), e));
+    let path = path(dir, table);
+    let schema = schema(table).unwrap_or_else(|| panic!("Unknown table {}", table));
+    conn.execute_batch(&format!(
+        "COPY (SELECT {} FROM {}) TO '{}' (FORMAT CSV, DELIMITER '|', HEADER false);",
+        schema.join(", "),
+        table,
+        path.display()
+    ))
+    .unwrap_or_else(|e| panic!("Error exporting {} to {}: {}", table, path.display(), e));
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tpch::query_1;
+
+    use chrono::NaiveDate;
+
+    const LINEITEM_TBL: &str = "\
+1|155190|7706|1|17|21168.23|0.04|0.02|N|O|1996-03-13|1996-02-12|1996-03-22|DELIVER IN PERSON|TRUCK|egular courts above the|
+1|67310|7311|2|36|45983.16|0.09|0.06|N|O|1996-04-12|1996-02-28|1996-04-20|TAKE BACK RETURN|MAIL|ly final dependencies: slyly bold |
+
+3|4297|1798|1|45|54058.05|0.06|0.00|R|F|1994-02-02|1994-01-04|1994-02-23|NONE|AIR|ongside of the furiously brave acco|
+";
+
+    #[test]
+    fn test_tbl_reader() {
+        let mut reader = TblReader::new(LINEITEM_TBL.as_bytes(), "lineitem", &["l_shipmode", "l_orderkey", "l_comment"]).unwrap();
+        let mut rows = vec![];
+        while let Some(fields) = reader.next_fields() {
+            let fields = fields.unwrap();
+            assert_eq!(fields.len(), 3);
+            rows.push((fields.str(0).to_string(), fields.parse::<i32>(1).unwrap(), fields.str(2).to_string()));
+        }
+        assert_eq!(rows, vec![
+            ("TRUCK".to_string(), 1, "egular courts above the".to_string()),
+            ("MAIL".to_string(), 1, "ly final dependencies: slyly bold ".to_string()),
+            ("AIR".to_string(), 3, "ongside of the furiously brave acco".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_tbl_rows() {
+        let line_items: Vec<query_1::LineItem> = TblReader::rows(LINEITEM_TBL.as_bytes()).unwrap().collect::<Result<_, _>>().unwrap();
+        assert_eq!(line_items.len(), 3);
+        assert_eq!(line_items[2].l_returnflag, 'R');
+        assert_eq!(line_items[2].l_quantity, 45.0);
+        assert_eq!(line_items[2].l_tax, 0.0);
+        assert_eq!(line_items[2].l_shipdate, NaiveDate::from_ymd_opt(1994, 2, 2).unwrap());
+    }
+
+    #[test]
+    fn test_tbl_errors() {
+        assert!(matches!(TblReader::new("".as_bytes(), "nation", &[]), Err(TblError::UnknownTable(_))));
+        assert!(matches!(
+            TblReader::new("".as_bytes(), "lineitem", &["o_orderkey"]),
+            Err(TblError::UnknownColumn { .. })
+        ));
+
+        let tbl = "1|155190|7706|1|17|21168.23|0.04|0.02|N|O|1996-13-13|1996-02-12|1996-03-22|DELIVER IN PERSON|TRUCK|x|\n";
+        let error = TblReader::rows::<query_1::LineItem>(tbl.as_bytes()).unwrap().next().unwrap().err().unwrap();
+        assert!(matches!(&error, TblError::Parse { column, line: 1, .. } if column == "l_shipdate"), "{}", error);
+
+        let tbl = "1|155190|7706|1|17|21168.23|0.04|0.02|N|O|\n";
+        let error = TblReader::rows::<query_1::LineItem>(tbl.as_bytes()).unwrap().next().unwrap().err().unwrap();
+        assert!(matches!(&error, TblError::MissingField { column, line: 1, .. } if column == "l_shipdate"), "{}", error);
+    }
+}
@@ -0,0 +1,131 @@
+//! ORDER BY ... LIMIT k.
+//!
+//! `TopK` keeps the first `k` rows of a stream according to a query's `order_by`. With
+//! `Strategy::Heap` it holds at most `k` rows in a bounded heap whose root is the worst row kept
+//! so far, so every further row is either dropped after one comparison or replaces the root.
+//! `Strategy::Sort` collects all rows and sorts them once at the end, which is what the queries
+//! did before and is kept as the reference for benchmarks.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+pub type OrderBy<T> = fn(&T, &T) -> Ordering;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Bounded heap of at most `k` rows.
+    Heap,
+    /// Collect everything, sort and truncate.
+    Sort,
+}
+
+/// Heap entry ordered by the query's ORDER BY, the max-heap keeps the worst row at the root.
+struct Entry<T> {
+    row: T,
+    order_by: OrderBy<T>,
+}
+
+impl<T> PartialEq for Entry<T> {
+    fn eq(&self, other: &Self) -> bool {
+        (self.order_by)(&self.row, &other.row) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for Entry<T> {}
+
+impl<T> PartialOrd for Entry<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Entry<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.order_by)(&self.row, &other.row)
+    }
+}
+
+enum Rows<T> {
+    Heap(BinaryHeap<Entry<T>>),
+    Sort(Vec<T>),
+}
+
+pub struct TopK<T> {
+    k: usize,
+    order_by: OrderBy<T>,
+    rows: Rows<T>,
+}
+
+impl<T> TopK<T> {
+    pub fn new(strategy: Strategy, k: usize, order_by: OrderBy<T>) -> Self {
+        let rows = match strategy {
+            Strategy::Heap => Rows::Heap(BinaryHeap::with_capacity(k)),
+            Strategy::Sort => Rows::Sort(Vec::new()),
+        };
+        TopK { k, order_by, rows }
+    }
+
+    pub fn push(&mut self, row: T) {
+        let order_by = self.order_by;
+        match &mut self.rows {
+            Rows::Heap(heap) => {
+                if heap.len() < self.k {
+                    heap.push(Entry { row, order_by });
+                } else if let Some(mut worst) = heap.peek_mut() {
+                    // Replacing the root sifts it down once `worst` is dropped.
+                    if order_by(&row, &worst.row) == Ordering::Less {
+                        worst.row = row;
+                    }
+                }
+            }
+            Rows::Sort(rows) => rows.push(row),
+        }
+    }
+
+    /// The first `k` rows in ORDER BY order.
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        match self.rows {
+            Rows::Heap(heap) => heap.into_sorted_vec().into_iter().map(|e| e.row).collect(),
+            Rows::Sort(mut rows) => {
+                rows.sort_by(self.order_by);
+                rows.truncate(self.k);
+                rows
+            }
+        }
+    }
+}
+
+impl<T> Extend<T> for TopK<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for row in iter {
+            self.push(row);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn desc(a: &i64, b: &i64) -> Ordering {
+        b.cmp(a)
+    }
+
+    #[test]
+    fn test_top_k() {
+        let rows = [5, 1, 9, 3, 7, 9, 2, 8];
+        for strategy in [Strategy::Heap, Strategy::Sort] {
+            let mut top_k = TopK::new(strategy, 3, desc);
+            top_k.extend(rows);
+            assert_eq!(top_k.into_sorted_vec(), vec![9, 9, 8], "{:?}", strategy);
+
+            let mut top_k = TopK::new(strategy, 10, desc);
+            top_k.extend(rows);
+            assert_eq!(top_k.into_sorted_vec(), vec![9, 9, 8, 7, 5, 3, 2, 1], "{:?}", strategy);
+
+            let mut top_k = TopK::new(strategy, 0, desc);
+            top_k.extend(rows);
+            assert!(top_k.into_sorted_vec().is_empty(), "{:?}", strategy);
+        }
+    }
+}
@@ -1,4 +1,15 @@
 use chrono::{DateTime, NaiveDate};
+use duckdb::arrow::array::{ArrowPrimitiveType, AsArray, PrimitiveArray, StringArray};
+use duckdb::arrow::compute::{cast_with_options, CastOptions};
+use duckdb::arrow::datatypes::{DataType, Decimal128Type};
+use duckdb::arrow::error::ArrowError;
+use duckdb::arrow::util::display::FormatOptions;
+use duckdb::arrow::record_batch::RecordBatch;
+use duckdb::types::Type;
+use duckdb::{Connection, Row};
+
+use super::decimal::Decimal;
+use super::load::{LoadError, LoadMode, Loaded};
 
 // Conversion taken from DuckDB's main branch: https://github.com/duckdb/duckdb-rs/blob/a1aa55aff22b75e149e9cf7cface6464b3dc0ccc/src/types/chrono.rs#L71C39-L71C111
 pub fn to_date(value: i32) -> NaiveDate {
@@ -8,6 +19,143 @@ pub fn to_date(value: i32) -> NaiveDate {
         .date()
 }
 
+/// The character of the CHAR(1) column `i` of `row`. An empty value fails like any other conversion.
+pub fn get_char(row: &Row<'_>, i: usize) -> duckdb::Result<char> {
+    let value: String = row.get(i)?;
+    char_value(&value, i)
+}
+
+/// The character of a value of the CHAR(1) column `i`, see `get_char`.
+pub fn char_value(value: &str, i: usize) -> duckdb::Result<char> {
+    value
+        .chars()
+        .next()
+        .ok_or_else(|| duckdb::Error::FromSqlConversionFailure(i, Type::Text, "empty CHAR(1) value".into()))
+}
+
+/**
+ * Scan the given columns of a table, optionally only the first `limit` rows, and convert every
+ * row with `f`. Panics if a row cannot be converted, see `try_load_rows`.
+ */
+pub fn load_rows<T, F>(conn: &Connection, table: &str, columns: &[&str], limit: Option<u32>, f: F) -> Vec<T>
+where
+    F: FnMut(&Row<'_>) -> duckdb::Result<T>,
+{
+    try_load_rows(conn, table, columns, limit, LoadMode::Strict, f)
+        .unwrap_or_else(|e| panic!("{}", e))
+        .rows
+}
+
+/**
+ * Scan the given columns of a table, optionally only the first `limit` rows, and convert every
+ * row with `f`. A row that cannot be converted fails the load in `LoadMode::Strict` and is dropped
+ * in `LoadMode::Lenient`.
+ */
+pub fn try_load_rows<T, F>(
+    conn: &Connection,
+    table: &str,
+    columns: &[&str],
+    limit: Option<u32>,
+    mode: LoadMode,
+    f: F,
+) -> Result<Loaded<T>, LoadError>
+where
+    F: FnMut(&Row<'_>) -> duckdb::Result<T>,
+{
+    let query = match limit {
+        Some(limit) => format!("SELECT {} FROM {} LIMIT {};", columns.join(", "), table, limit),
+        None => format!("SELECT {} FROM {};", columns.join(", "), table),
+    };
+    let query_error = |error| LoadError::Query { table: table.to_string(), error };
+    let mut stmt = conn.prepare(&query).map_err(query_error)?;
+    let rows = stmt.query_map([], f).map_err(query_error)?;
+
+    let mut loaded = Loaded { rows: vec![], dropped: 0 };
+    for (row, result) in rows.enumerate() {
+        match (result, mode) {
+            (Ok(x), _) => loaded.rows.push(x),
+            (Err(error), LoadMode::Strict) => return Err(LoadError::conversion(table, columns, row, error)),
+            (Err(_), LoadMode::Lenient) => loaded.dropped += 1,
+        }
+    }
+    Ok(loaded)
+}
+
+/**
+ * Scan the given columns of a table through DuckDB's Arrow interface, optionally only the first
+ * `limit` rows. `f` converts a whole record batch at a time and appends the rows to the result,
+ * up to the row that fails to convert, which fails the load like `LoadMode::Strict`.
+ */
+pub fn load_batches<T, F>(conn: &Connection, table: &str, columns: &[&str], limit: Option<u32>, mut f: F) -> Result<Vec<T>, LoadError>
+where
+    F: FnMut(&RecordBatch, &mut Vec<T>) -> duckdb::Result<()>,
+{
+    let query = match limit {
+        Some(limit) => format!("SELECT {} FROM {} LIMIT {};", columns.join(", "), table, limit),
+        None => format!("SELECT {} FROM {};", columns.join(", "), table),
+    };
+    let query_error = |error| LoadError::Query { table: table.to_string(), error };
+    let mut stmt = conn.prepare(&query).map_err(query_error)?;
+    let batches = stmt.query_arrow([]).map_err(query_error)?;
+
+    let mut result = vec![];
+    for batch in batches {
+        f(&batch, &mut result).map_err(|error| LoadError::conversion(table, columns, result.len(), error))?;
+    }
+    Ok(result)
+}
+
+/// Casts fail on values that do not fit the target type instead of turning them into nulls.
+const STRICT_CAST: CastOptions = CastOptions {
+    safe: false,
+    format_options: FormatOptions::new(),
+};
+
+/**
+ * Column `i` of a record batch as a primitive array of `T`. DuckDB hands out DECIMAL columns as
+ * Decimal128 and keys as Int64, the column is cast to the type the row struct uses.
+ */
+pub fn primitive_column<T: ArrowPrimitiveType>(batch: &RecordBatch, i: usize) -> duckdb::Result<PrimitiveArray<T>> {
+    let column = cast_with_options(batch.column(i), &T::DATA_TYPE, &STRICT_CAST).map_err(|e| cast_error(i, e))?;
+    Ok(column.as_primitive::<T>().clone())
+}
+
+/**
+ * Column `i` of a record batch as Decimal128 values in units of `Decimal::SCALE`, i.e. the values of
+ * `Decimal::from_units`. The rescaling of DuckDB's DECIMAL(15,2) is exact.
+ */
+pub fn decimal_column(batch: &RecordBatch, i: usize) -> duckdb::Result<PrimitiveArray<Decimal128Type>> {
+    let data_type = DataType::Decimal128(38, Decimal::SCALE as i8);
+    let column = cast_with_options(batch.column(i), &data_type, &STRICT_CAST).map_err(|e| cast_error(i, e))?;
+    Ok(column.as_primitive::<Decimal128Type>().clone())
+}
+
+/// Column `i` of a record batch as a string array.
+pub fn string_column(batch: &RecordBatch, i: usize) -> duckdb::Result<StringArray> {
+    let column = cast_with_options(batch.column(i), &DataType::Utf8, &STRICT_CAST).map_err(|e| cast_error(i, e))?;
+    Ok(column.as_string::<i32>().clone())
+}
+
+/// A failed cast of column `i`, as the conversion error of a row loader.
+fn cast_error(i: usize, error: ArrowError) -> duckdb::Error {
+    duckdb::Error::FromSqlConversionFailure(i, Type::Any, Box::new(error))
+}
+
+/**
+ * SQL `value LIKE '%a%b%'`: true if all `parts` occur in `value` in the given order without
+ * overlapping.
+ */
+pub fn contains_in_order(value: &str, parts: &[&str]) -> bool {
+    let mut rest = value;
+    for part in parts {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -26,4 +174,12 @@ mod tests {
         let date = to_date(raw);
         assert_eq!(date, NaiveDate::from_ymd_opt(1992, 3, 22).unwrap());
     }
+
+    #[test]
+    fn test_contains_in_order() {
+        assert!(contains_in_order("carefully special foxes requests", &["special", "requests"]));
+        assert!(!contains_in_order("requests are special", &["special", "requests"]));
+        assert!(!contains_in_order("specialrequest", &["special", "requests"]));
+        assert!(contains_in_order("anything", &[]));
+    }
 }
\ No newline at end of file
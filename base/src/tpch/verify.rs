@@ -0,0 +1,359 @@
+//! Cross-implementation verification of the TPC-H queries.
+//!
+//! Every implementation returns typed rows in the order of the query's ORDER BY clause, so the
+//! results can be compared row by row. Floating point columns are compared with a tolerance
+//! because the implementations sum in different orders (and DuckDB sums DECIMALs exactly).
+
+use std::fmt::{self, Debug, Display};
+
+use duckdb::Connection;
+
+use super::{
+    query_1, query_10, query_12, query_13, query_14, query_16, query_17, query_18, query_19, query_2, query_20,
+    query_21, query_22, query_3, query_4, query_5, query_6,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Tolerance {
+    pub absolute: f64,
+    pub relative: f64,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Tolerance {
+            absolute: 1e-6,
+            relative: 1e-9,
+        }
+    }
+}
+
+impl Tolerance {
+    /// Two values are equal if they are within either the absolute or the relative tolerance.
+    pub fn eq_f64(&self, a: f64, b: f64) -> bool {
+        let diff = (a - b).abs();
+        diff <= self.absolute || diff <= self.relative * a.abs().max(b.abs())
+    }
+}
+
+pub trait ApproxEq {
+    fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool;
+}
+
+#[derive(Debug, Clone)]
+pub enum RowDiff<T> {
+    Mismatch { row: usize, expected: T, actual: T },
+    Missing { row: usize, expected: T },
+    Unexpected { row: usize, actual: T },
+}
+
+impl<T: Debug> Display for RowDiff<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RowDiff::Mismatch { row, expected, actual } => {
+                write!(f, "row {}: expected {:?}, got {:?}", row, expected, actual)
+            }
+            RowDiff::Missing { row, expected } => {
+                write!(f, "row {}: missing {:?}", row, expected)
+            }
+            RowDiff::Unexpected { row, actual } => {
+                write!(f, "row {}: unexpected {:?}", row, actual)
+            }
+        }
+    }
+}
+
+/// Positional row-level diff of two ordered results.
+pub fn diff_rows<T: ApproxEq + Clone>(expected: &[T], actual: &[T], tolerance: &Tolerance) -> Vec<RowDiff<T>> {
+    let mut diffs = vec![];
+    for row in 0..expected.len().max(actual.len()) {
+        match (expected.get(row), actual.get(row)) {
+            (Some(e), Some(a)) if !e.approx_eq(a, tolerance) => diffs.push(RowDiff::Mismatch {
+                row,
+                expected: e.clone(),
+                actual: a.clone(),
+            }),
+            (Some(e), None) => diffs.push(RowDiff::Missing {
+                row,
+                expected: e.clone(),
+            }),
+            (None, Some(a)) => diffs.push(RowDiff::Unexpected {
+                row,
+                actual: a.clone(),
+            }),
+            _ => {}
+        }
+    }
+    diffs
+}
+
+/// Outcome of comparing one implementation against a reference implementation.
+#[derive(Debug)]
+pub struct Report<T> {
+    pub query: &'static str,
+    pub reference: &'static str,
+    pub implementation: &'static str,
+    pub diffs: Vec<RowDiff<T>>,
+}
+
+impl<T> Report<T> {
+    pub fn is_match(&self) -> bool {
+        self.diffs.is_empty()
+    }
+}
+
+impl<T: Debug> Display for Report<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_match() {
+            return write!(f, "{}: {} matches {}", self.query, self.implementation, self.reference);
+        }
+        writeln!(
+            f,
+            "{}: {} differs from {} in {} rows",
+            self.query,
+            self.implementation,
+            self.reference,
+            self.diffs.len()
+        )?;
+        for diff in &self.diffs {
+            writeln!(f, "  {}", diff)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn compare<T: ApproxEq + Clone>(
+    query: &'static str,
+    reference: &'static str,
+    implementation: &'static str,
+    expected: &[T],
+    actual: &[T],
+    tolerance: &Tolerance,
+) -> Report<T> {
+    Report {
+        query,
+        reference,
+        implementation,
+        diffs: diff_rows(expected, actual, tolerance),
+    }
+}
+
+/// Panics with the row-level diffs if any implementation disagrees with its reference.
+pub fn assert_match<T: Debug>(reports: &[Report<T>]) {
+    for report in reports {
+        assert!(report.is_match(), "{}", report);
+    }
+}
+
+pub fn verify_query_1(conn: &Connection, params: &query_1::QueryParams) -> Vec<Report<query_1::Q1Row>> {
+    let expected = query_1::query_duckdb(conn, None, params);
+    let baseline = query_1::query(query_1::load(conn).expect("Error loading Q1"), params);
+
+    vec![compare("Q1", "duckdb", "baseline", &expected, &baseline, &Tolerance::default())]
+}
+
+pub fn verify_query_2(conn: &Connection) -> Vec<Report<query_2::Q2Row>> {
+    let expected = query_2::query_duckdb(conn, None);
+    let (part, supplier, partsupp, nation, region) = query_2::load(conn);
+    let baseline = query_2::query(part, supplier, partsupp, nation, region);
+
+    vec![compare("Q2", "duckdb", "baseline", &expected, &baseline, &Tolerance::default())]
+}
+
+pub fn verify_query_3(conn: &Connection) -> Vec<Report<query_3::Q3Row>> {
+    let expected = query_3::query_duckdb(conn, None);
+    let (customer, orders, line_items) = query_3::load(conn);
+    let baseline = query_3::query(customer, orders, line_items);
+
+    vec![compare("Q3", "duckdb", "baseline", &expected, &baseline, &Tolerance::default())]
+}
+
+pub fn verify_query_4(conn: &Connection, params: &query_4::QueryParams) -> Vec<Report<query_4::Q4Row>> {
+    let expected = query_4::query_duckdb(conn, None, params);
+    let (line_items, orders) = query_4::load(conn).expect("Error loading Q4");
+    let baseline = query_4::query(line_items, orders, params);
+
+    vec![compare("Q4", "duckdb", "baseline", &expected, &baseline, &Tolerance::default())]
+}
+
+pub fn verify_query_5(conn: &Connection) -> Vec<Report<query_5::Q5Row>> {
+    let expected = query_5::query_duckdb(conn, None);
+    let (customer, orders, line_items, supplier, nation, region) = query_5::load(conn);
+    let baseline = query_5::query(customer, orders, line_items, supplier, nation, region);
+
+    vec![compare("Q5", "duckdb", "baseline", &expected, &baseline, &Tolerance::default())]
+}
+
+pub fn verify_query_6(conn: &Connection) -> Vec<Report<query_6::Q6Row>> {
+    let expected = query_6::query_duckdb(conn, None);
+    let baseline = query_6::query(query_6::load(conn));
+    let baseline_chunks = query_6::query_chunks::<1024>(query_6::load(conn));
+
+    let tolerance = Tolerance::default();
+    vec![
+        compare("Q6", "duckdb", "baseline", &expected, &baseline, &tolerance),
+        compare("Q6", "duckdb", "baseline-chunks", &expected, &baseline_chunks, &tolerance),
+    ]
+}
+
+pub fn verify_query_10(conn: &Connection) -> Vec<Report<query_10::Q10Row>> {
+    let expected = query_10::query_duckdb(conn, None);
+    let (customer, orders, line_items, nation) = query_10::load(conn);
+    let baseline = query_10::query(customer, orders, line_items, nation);
+
+    vec![compare("Q10", "duckdb", "baseline", &expected, &baseline, &Tolerance::default())]
+}
+
+pub fn verify_query_12(conn: &Connection) -> Vec<Report<query_12::Q12Row>> {
+    let expected = query_12::query_duckdb(conn, None);
+    let (orders, line_items) = query_12::load(conn);
+    let baseline = query_12::query(orders, line_items);
+    let (orders, line_items) = query_12::load(conn);
+    let baseline_chunks = query_12::query_chunks::<1024>(orders, line_items);
+
+    let tolerance = Tolerance::default();
+    vec![
+        compare("Q12", "duckdb", "baseline", &expected, &baseline, &tolerance),
+        compare("Q12", "duckdb", "baseline-chunks", &expected, &baseline_chunks, &tolerance),
+    ]
+}
+
+pub fn verify_query_13(conn: &Connection) -> Vec<Report<query_13::Q13Row>> {
+    let expected = query_13::query_duckdb(conn, None);
+    let (customer, orders) = query_13::load(conn);
+    let baseline = query_13::query(customer, orders);
+
+    vec![compare("Q13", "duckdb", "baseline", &expected, &baseline, &Tolerance::default())]
+}
+
+pub fn verify_query_14(conn: &Connection) -> Vec<Report<query_14::Q14Row>> {
+    let expected = query_14::query_duckdb(conn, None);
+    let (line_items, part) = query_14::load(conn);
+    let baseline = query_14::query(line_items, part);
+    let (line_items, part) = query_14::load(conn);
+    let baseline_chunks = query_14::query_chunks::<1024>(line_items, part);
+
+    let tolerance = Tolerance::default();
+    vec![
+        compare("Q14", "duckdb", "baseline", &expected, &baseline, &tolerance),
+        compare("Q14", "duckdb", "baseline-chunks", &expected, &baseline_chunks, &tolerance),
+    ]
+}
+
+pub fn verify_query_16(conn: &Connection) -> Vec<Report<query_16::Q16Row>> {
+    let expected = query_16::query_duckdb(conn, None);
+    let (partsupp, part, supplier) = query_16::load(conn);
+    let baseline = query_16::query(partsupp, part, supplier);
+
+    vec![compare("Q16", "duckdb", "baseline", &expected, &baseline, &Tolerance::default())]
+}
+
+pub fn verify_query_17(conn: &Connection) -> Vec<Report<query_17::Q17Row>> {
+    let expected = query_17::query_duckdb(conn, None);
+    let (line_items, part) = query_17::load(conn);
+    let baseline = query_17::query(line_items, part);
+
+    vec![compare("Q17", "duckdb", "baseline", &expected, &baseline, &Tolerance::default())]
+}
+
+pub fn verify_query_18(conn: &Connection) -> Vec<Report<query_18::Q18Row>> {
+    let expected = query_18::query_duckdb(conn, None);
+    let (customer, orders, line_items) = query_18::load(conn);
+    let baseline = query_18::query(customer, orders, line_items);
+
+    vec![compare("Q18", "duckdb", "baseline", &expected, &baseline, &Tolerance::default())]
+}
+
+pub fn verify_query_19(conn: &Connection, params: &query_19::QueryParams) -> Vec<Report<query_19::Q19Row>> {
+    let expected = query_19::query_duckdb(conn, None, params);
+    let (line_items, part) = query_19::load(conn).expect("Error loading Q19");
+    let baseline = query_19::query(line_items, part, params);
+
+    vec![compare("Q19", "duckdb", "baseline", &expected, &baseline, &Tolerance::default())]
+}
+
+pub fn verify_query_20(conn: &Connection) -> Vec<Report<query_20::Q20Row>> {
+    let expected = query_20::query_duckdb(conn, None);
+    let (supplier, nation, partsupp, part, line_items) = query_20::load(conn);
+    let baseline = query_20::query(supplier, nation, partsupp, part, line_items);
+
+    vec![compare("Q20", "duckdb", "baseline", &expected, &baseline, &Tolerance::default())]
+}
+
+pub fn verify_query_21(conn: &Connection) -> Vec<Report<query_21::Q21Row>> {
+    let expected = query_21::query_duckdb(conn, None);
+    let (supplier, line_items, orders, nation) = query_21::load(conn);
+    let baseline = query_21::query(supplier, line_items, orders, nation);
+
+    vec![compare("Q21", "duckdb", "baseline", &expected, &baseline, &Tolerance::default())]
+}
+
+pub fn verify_query_22(conn: &Connection) -> Vec<Report<query_22::Q22Row>> {
+    let expected = query_22::query_duckdb(conn, None);
+    let (customer, orders) = query_22::load(conn);
+    let baseline = query_22::query(customer, orders);
+
+    vec![compare("Q22", "duckdb", "baseline", &expected, &baseline, &Tolerance::default())]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tpch::initialize::initialize_database;
+    use crate::tpch::qgen::QGen;
+
+    #[derive(Debug, Clone)]
+    struct Row(f64);
+
+    impl ApproxEq for Row {
+        fn approx_eq(&self, other: &Self, tolerance: &Tolerance) -> bool {
+            tolerance.eq_f64(self.0, other.0)
+        }
+    }
+
+    #[test]
+    fn test_diff_rows() {
+        let tolerance = Tolerance::default();
+        let expected = vec![Row(1.0), Row(2.0), Row(3.0)];
+
+        assert!(diff_rows(&expected, &[Row(1.0), Row(2.0 + 1e-12), Row(3.0)], &tolerance).is_empty());
+
+        let diffs = diff_rows(&expected, &[Row(1.0), Row(2.5)], &tolerance);
+        assert_eq!(diffs.len(), 2);
+        assert!(matches!(diffs[0], RowDiff::Mismatch { row: 1, .. }));
+        assert!(matches!(diffs[1], RowDiff::Missing { row: 2, .. }));
+    }
+
+    #[test]
+    fn test_verify() {
+        let conn = initialize_database(0.01);
+        assert_match(&verify_query_1(&conn, &query_1::QueryParams::default()));
+        assert_match(&verify_query_2(&conn));
+        assert_match(&verify_query_3(&conn));
+        assert_match(&verify_query_4(&conn, &query_4::QueryParams::default()));
+        assert_match(&verify_query_5(&conn));
+        assert_match(&verify_query_6(&conn));
+        assert_match(&verify_query_10(&conn));
+        assert_match(&verify_query_12(&conn));
+        assert_match(&verify_query_13(&conn));
+        assert_match(&verify_query_14(&conn));
+        assert_match(&verify_query_16(&conn));
+        assert_match(&verify_query_17(&conn));
+        assert_match(&verify_query_18(&conn));
+        assert_match(&verify_query_19(&conn, &query_19::QueryParams::default()));
+        assert_match(&verify_query_20(&conn));
+        assert_match(&verify_query_21(&conn));
+        assert_match(&verify_query_22(&conn));
+    }
+
+    #[test]
+    fn test_verify_params() {
+        let conn = initialize_database(0.01);
+        for seed in 0..3 {
+            let mut qgen = QGen::new(seed);
+            assert_match(&verify_query_1(&conn, &qgen.query_1()));
+            assert_match(&verify_query_4(&conn, &qgen.query_4()));
+            assert_match(&verify_query_19(&conn, &qgen.query_19()));
+        }
+    }
+}
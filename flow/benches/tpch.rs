@@ -0,0 +1,148 @@
+use base::tpch::decimal::Decimal;
+use base::tpch::initialize::initialize_database;
+use base::tpch::query_1::{LineItem as Q1LineItem, Q1Row, QueryParams as Q1Params};
+use base::tpch::query_4::{LineItem as Q4LineItem, Order, Q4Row, QueryParams as Q4Params};
+use base::tpch::query_19::{LineItem as Q19LineItem, Part, Q19Row, QueryParams as Q19Params};
+use base::tpch::verify::{assert_match, compare, Tolerance};
+use criterion::{criterion_group, criterion_main, Criterion};
+use hydroflow_base::tpch::query_1::query as query_1_hf;
+use hydroflow_base::tpch::query_4::{query as query_4_hf, query_set_join as query_4_hf_set_join};
+use hydroflow_base::tpch::query_19::query_decimal as query_19_hf_decimal;
+
+/// Runs the Hydroflow+ graph of Query 1 in-process and collects its rows like the `hydroflow_syntax!` graph.
+fn query_1_hfplus(line_items: Vec<Q1LineItem>, params: &Q1Params) -> Vec<Q1Row> {
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Q1Row>();
+    let mut flow = flow::tpch::query_1::query_1_local!(line_items, params.clone(), &output_send);
+    flow.run_available();
+
+    let mut rows = hydroflow::util::collect_ready::<Vec<_>, _>(output_recv);
+    rows.sort_by_key(|row| (row.l_returnflag, row.l_linestatus));
+    rows
+}
+
+/// Runs the Hydroflow+ graph of Query 4 in-process and collects its rows like the `hydroflow_syntax!` graph.
+fn query_4_hfplus(line_items: Vec<Q4LineItem>, orders: Vec<Order>, params: &Q4Params) -> Vec<Q4Row> {
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Q4Row>();
+    let mut flow = flow::tpch::query_4::query_4_local!(line_items, orders, *params, &output_send);
+    flow.run_available();
+
+    let mut rows = hydroflow::util::collect_ready::<Vec<_>, _>(output_recv);
+    rows.sort_by(|a, b| a.o_orderpriority.cmp(&b.o_orderpriority));
+    rows
+}
+
+/// Runs the Hydroflow+ graph of Query 19 in-process, the revenue is NULL if no line item qualifies.
+fn query_19_hfplus(line_items: Vec<Q19LineItem<Decimal>>, part: Vec<Part>, params: &Q19Params) -> Vec<Q19Row<Decimal>> {
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Decimal>();
+    let mut flow = flow::tpch::query_19::query_19_local!(line_items, part, params, &output_send);
+    flow.run_available();
+
+    let revenue = hydroflow::util::collect_ready::<Vec<_>, _>(output_recv).pop();
+    vec![Q19Row { revenue }]
+}
+
+/**
+ * Query 1 with the Hydroflow+ graph against the hand-written `hydroflow_syntax!` graph.
+ * Both run the same operators, any difference is the code Hydroflow+ generates.
+ */
+fn tpch_sf1_query_1(c: &mut Criterion) {
+    let scale_factor = 1;
+    let conn = initialize_database(scale_factor);
+
+    // Only benchmark once both implementations agree with DuckDB
+    let params = Q1Params::default();
+    let expected = base::tpch::query_1::query_duckdb(&conn, None, &params);
+    let hfplus = query_1_hfplus(base::tpch::query_1::load(&conn).unwrap(), &params);
+    assert_match(&[compare("Q1", "duckdb", "hydroflow-plus", &expected, &hfplus, &Tolerance::default())]);
+
+    c.bench_function("query_1_hf", |b| {
+        b.iter_batched(
+            || base::tpch::query_1::load(&conn).unwrap(),
+            |line_items| query_1_hf(line_items, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_1_hfplus", |b| {
+        b.iter_batched(
+            || base::tpch::query_1::load(&conn).unwrap(),
+            |line_items| query_1_hfplus(line_items, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+}
+
+/**
+ * Query 4 with the semi-join of the Hydroflow+ graph and the `hydroflow_syntax!` graph against the
+ * former `join()` with a unit payload, see `hydroflow_base::tpch::semi_join`.
+ */
+fn tpch_sf1_query_4(c: &mut Criterion) {
+    let scale_factor = 1;
+    let conn = initialize_database(scale_factor);
+
+    // Only benchmark once all implementations agree with DuckDB
+    let params = Q4Params::default();
+    let expected = base::tpch::query_4::query_duckdb(&conn, None, &params);
+    let (line_items, orders) = base::tpch::query_4::load(&conn).unwrap();
+    let hfplus = query_4_hfplus(line_items, orders, &params);
+    assert_match(&[compare("Q4", "duckdb", "hydroflow-plus", &expected, &hfplus, &Tolerance::default())]);
+
+    c.bench_function("query_4_hf", |b| {
+        b.iter_batched(
+            || base::tpch::query_4::load(&conn).unwrap(),
+            |(line_items, orders)| query_4_hf(line_items, orders, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_4_hf_set_join", |b| {
+        b.iter_batched(
+            || base::tpch::query_4::load(&conn).unwrap(),
+            |(line_items, orders)| query_4_hf_set_join(line_items, orders, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_4_hfplus", |b| {
+        b.iter_batched(
+            || base::tpch::query_4::load(&conn).unwrap(),
+            |(line_items, orders)| query_4_hfplus(line_items, orders, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+}
+
+/**
+ * Query 19 with the Hydroflow+ graph against the hand-written `hydroflow_syntax!` graph.
+ * The join of Hydroflow+ needs `Eq` payloads, so both use `Decimal` arithmetic.
+ */
+fn tpch_sf1_query_19(c: &mut Criterion) {
+    let scale_factor = 1;
+    let conn = initialize_database(scale_factor);
+
+    // Only benchmark once both implementations agree with DuckDB
+    let params = Q19Params::default();
+    let expected = base::tpch::query_19::query_duckdb_decimal(&conn, None, &params);
+    let (line_items, part) = base::tpch::query_19::load_decimal(&conn).unwrap();
+    let hfplus = query_19_hfplus(line_items, part, &params);
+    assert_match(&[compare("Q19", "duckdb", "hydroflow-plus", &expected, &hfplus, &Tolerance::default())]);
+
+    c.bench_function("query_19_hf_decimal", |b| {
+        b.iter_batched(
+            || base::tpch::query_19::load_decimal(&conn).unwrap(),
+            |(line_items, part)| query_19_hf_decimal(line_items, part, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_19_hfplus", |b| {
+        b.iter_batched(
+            || base::tpch::query_19::load_decimal(&conn).unwrap(),
+            |(line_items, part)| query_19_hfplus(line_items, part, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+}
+
+criterion_group!(tpch, tpch_sf1_query_1, tpch_sf1_query_4, tpch_sf1_query_19);
+criterion_main!(tpch);
@@ -12,6 +12,7 @@ async fn main() {
     let flow = hydroflow_plus::FlowBuilder::new();
     let orders = stageleft::RuntimeData::new(&"FAKE");
     let lineitem = stageleft::RuntimeData::new(&"FAKE");
+    let params = stageleft::RuntimeData::new(&"FAKE");
     let second_process = query_4(
         &flow,
         &DeployProcessSpec::new(|| {
@@ -23,6 +24,7 @@ async fn main() {
         }),
         lineitem,
         orders,
+        params,
     );
 
     println!("Deploying");
@@ -1,5 +1,8 @@
 use std::cell::RefCell;
 
+use base::tpch::initialize::initialize_database;
+use base::tpch::plan::{Distribution, Side};
+use base::tpch::query_4::{plan, QueryParams};
 use flow::tpch::query_4_distributed::query_4_distributed;
 use hydro_deploy::{Deployment, HydroflowCrate};
 use hydroflow::futures::StreamExt;
@@ -8,21 +11,39 @@ use hydroflow_plus_cli_integration::{DeployClusterSpec, DeployCrateWrapper, Depl
 #[tokio::main]
 async fn main() {
     let intermediate_aggregation = true;
+    let bloom_filter = true;
     let cluster_size = 2;
     let profile = "dev";
 
+    // Choose the build side and distribution from the statistics of the database
+    let conn = initialize_database(1);
+    let params = QueryParams::default();
+    let plan = plan(&conn, &params, cluster_size);
+    println!("Plan: {}", plan);
+
+    // The binary filters on the same date and compiles the same graph from these flags
+    let args: Vec<String> = [params.date.to_string()]
+        .into_iter()
+        .chain(
+            [intermediate_aggregation, bloom_filter, plan.build == Side::Left, plan.distribution == Distribution::Broadcast]
+                .map(|flag| flag.to_string()),
+        )
+        .collect();
+
     let deployment = RefCell::new(Deployment::new());
     let localhost = deployment.borrow_mut().Localhost();
 
     let flow = hydroflow_plus::FlowBuilder::new();
     let orders = stageleft::RuntimeData::new(&"FAKE");
     let lineitem = stageleft::RuntimeData::new(&"FAKE");
+    let fake_params = stageleft::RuntimeData::new(&"FAKE");
     let second_process = query_4_distributed(
         &flow,
         &DeployProcessSpec::new(|| {
             deployment.borrow_mut().add_service(
                 HydroflowCrate::new(".", localhost.clone())
                     .bin("query_4_distributed")
+                    .args(args.clone())
                     //.perf("~/query_4_perf.dat".into()).profile("profile"),
                     .profile(profile),
             )
@@ -33,6 +54,7 @@ async fn main() {
                     deployment.borrow_mut().add_service(
                         HydroflowCrate::new(".", localhost.clone())
                             .bin("query_4_distributed")
+                            .args(args.clone())
                             //.perf("~/query_4_perf.dat".into()).profile("profile"),
                             .profile(profile),
                     )
@@ -41,7 +63,11 @@ async fn main() {
         }),
         lineitem,
         orders,
+        fake_params,
         intermediate_aggregation,
+        bloom_filter,
+        plan.build,
+        plan.distribution,
     );
 
     let mut deployment = deployment.into_inner();
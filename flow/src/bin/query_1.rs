@@ -0,0 +1,16 @@
+use base::tpch::initialize::initialize_database;
+use base::tpch::query_1::QueryParams;
+
+#[tokio::main]
+async fn main() {
+    hydroflow_plus::util::cli::launch!(|ports| {
+        // Load query data
+        let scale_factor = 1;
+        let conn = initialize_database(scale_factor);
+        let lineitem = base::tpch::query_1::load(&conn).unwrap();
+        let params = QueryParams::default();
+
+        flow::tpch::query_1::query_1_runtime!(ports, lineitem, params)
+    })
+    .await;
+}
@@ -0,0 +1,17 @@
+use base::tpch::initialize::initialize_database;
+use base::tpch::query_19::QueryParams;
+
+#[tokio::main]
+async fn main() {
+    hydroflow_plus::util::cli::launch!(|ports| {
+        // Load query data
+        let scale_factor = 1;
+        let conn = initialize_database(scale_factor);
+        let (lineitem, part) = base::tpch::query_19::load_decimal(&conn).unwrap();
+        // The graph borrows the parameters for as long as it runs.
+        let params: &'static QueryParams = Box::leak(Box::new(QueryParams::default()));
+
+        flow::tpch::query_19::query_19_runtime!(ports, lineitem, part, params)
+    })
+    .await;
+}
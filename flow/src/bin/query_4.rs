@@ -1,4 +1,5 @@
 use base::tpch::initialize::initialize_database;
+use base::tpch::query_4::QueryParams;
 
 #[tokio::main]
 async fn main() {
@@ -9,10 +10,11 @@ async fn main() {
         // Load query data
         let scale_factor = 1;
         let conn = initialize_database(scale_factor);
-        let (lineitem, orders) = base::tpch::query_4::load(&conn);
+        let (lineitem, orders) = base::tpch::query_4::load(&conn).unwrap();
         //let orders = Order::load(&conn, Some(1000));
+        let params = QueryParams::default();
 
-        flow::tpch::query_4::query_4_runtime!(ports, lineitem, orders)
+        flow::tpch::query_4::query_4_runtime!(ports, lineitem, orders, params)
     })
     .await;
 }
@@ -1,16 +1,49 @@
 use base::tpch::initialize::initialize_database;
+use base::tpch::query_4::QueryParams;
+use chrono::NaiveDate;
+
+/**
+ * Expands `query_4_distributed_runtime` with literals for the values of the flags, which the entry
+ * needs to compile the graph, so there is one graph for each combination.
+ */
+macro_rules! with_flags {
+    ($ports:ident, $lineitem:ident, $orders:ident, $params:ident, [$($literal:tt),*], []) => {
+        flow::tpch::query_4_distributed::query_4_distributed_runtime!($ports, $lineitem, $orders, $params, $($literal),*)
+    };
+    ($ports:ident, $lineitem:ident, $orders:ident, $params:ident, [$($literal:tt),*], [$flag:ident $(, $rest:ident)*]) => {
+        if $flag {
+            with_flags!($ports, $lineitem, $orders, $params, [$($literal,)* true], [$($rest),*])
+        } else {
+            with_flags!($ports, $lineitem, $orders, $params, [$($literal,)* false], [$($rest),*])
+        }
+    };
+}
 
 #[tokio::main]
 async fn main() {
+    // The date of the query and the flags of the deployment, which plans the join for them, see
+    // examples/query_4_distributed.rs
+    let mut args = std::env::args().skip(1);
+    let date: NaiveDate = args
+        .next()
+        .map(|arg| arg.parse().unwrap_or_else(|e| panic!("Expected a date, got {}: {}", arg, e)))
+        .expect("Expected the date of the query");
+    let params = QueryParams::new(date);
+    let flags: Vec<bool> = args
+        .map(|arg| arg.parse().unwrap_or_else(|e| panic!("Expected true or false, got {}: {}", arg, e)))
+        .collect();
+    let [intermediate_aggregation, bloom_filter, build_orders, broadcast] = flags[..] else {
+        panic!("Expected the flags intermediate_aggregation, bloom_filter, build_orders and broadcast, got {:?}", flags);
+    };
 
     hydroflow_plus::util::cli::launch!(|ports| {
         
         // Load query data
         let scale_factor = 1;
         let conn = initialize_database(scale_factor);
-        let (lineitem, orders) = base::tpch::query_4::load(&conn);
+        let (lineitem, orders) = base::tpch::query_4::load(&conn).unwrap();
 
-        flow::tpch::query_4_distributed::query_4_distributed_runtime!(ports, lineitem, orders, false)
+        with_flags!(ports, lineitem, orders, params, [], [intermediate_aggregation, bloom_filter, build_orders, broadcast])
     })
     .await;
 }
@@ -1,2 +1,5 @@
+pub mod query_1;
+pub mod query_19;
 pub mod query_4;
-pub mod query_4_distributed;
\ No newline at end of file
+pub mod query_4_distributed;
+pub mod semi_join;
\ No newline at end of file
@@ -0,0 +1,130 @@
+use base::tpch::query_1::{LineItem, LineItemAgg1, LineItemAgg2, Q1Row, QueryParams};
+use hydroflow_plus::*;
+use hydroflow_plus::stream::Windowed;
+use hydroflow_plus::util::cli::HydroCLI;
+use hydroflow_plus_cli_integration::HydroflowPlusMeta;
+use stageleft::*;
+use tokio::sync::mpsc::UnboundedSender;
+
+/**
+ * The result rows of Query 1, unsorted.
+ */
+fn query_1_rows<'a, D: LocalDeploy<'a>>(
+    flow: &FlowBuilder<'a, D>,
+    process: &D::Process,
+    lineitem: RuntimeData<Vec<LineItem>>,
+    params: RuntimeData<QueryParams>,
+) -> Stream<'a, Q1Row, Windowed, D::Process> {
+    // 1. Scan from lineitem: "l_returnflag", "l_linestatus", "l_quantity", "l_extendedprice", "l_discount", "l_tax", "l_shipdate"
+    let line_items = flow.source_iter(process, lineitem);
+
+    // 2. Filter l_shipdate <= date '1998-12-01' - interval DELTA day, i.e., l_shipdate <= '1998-09-02' for DELTA = 90.
+    let line_items_filtered = line_items.filter(q!(move |x: &LineItem| x.l_shipdate <= params.shipdate()));
+
+    // 3. + 4. Evaluate the expressions & group by l_returnflag, l_linestatus.
+    let agg = line_items_filtered
+        .map(q!(|x: LineItem| ((x.l_returnflag, x.l_linestatus), x)))
+        .fold_keyed(q!(|| LineItemAgg1::default()), q!(|acc: &mut LineItemAgg1, x: LineItem| acc.add(&x)));
+
+    // Finalize aggregation with average
+    agg.map(q!(|(key, value): ((char, char), LineItemAgg1)| Q1Row::from((key, LineItemAgg2::from(value)))))
+}
+
+pub fn query_1<'a, D: LocalDeploy<'a>>(
+    flow: &FlowBuilder<'a, D>,
+    process_spec: &impl ProcessSpec<'a, D>,
+    lineitem: RuntimeData<Vec<LineItem>>,
+    params: RuntimeData<QueryParams>,
+) -> D::Process {
+    let process = flow.process(process_spec);
+
+    // 5. Sort by l_returnflag, l_linestatus and print.
+    query_1_rows(flow, &process, lineitem, params)
+        .fold(q!(|| vec![]), q!(|acc: &mut Vec<Q1Row>, x: Q1Row| acc.push(x)))
+        .for_each(q!(|x: Vec<Q1Row>| {
+            let mut x = x;
+            x.sort_by_key(|row| (row.l_returnflag, row.l_linestatus));
+            for row in x {
+                println!("{:?}", row);
+            }
+        }));
+
+    process
+}
+
+/**
+ * Sends the result rows to `output` instead of printing them, unsorted.
+ */
+pub fn query_1_collect<'a, D: LocalDeploy<'a>>(
+    flow: &FlowBuilder<'a, D>,
+    process_spec: &impl ProcessSpec<'a, D>,
+    lineitem: RuntimeData<Vec<LineItem>>,
+    params: RuntimeData<QueryParams>,
+    output: RuntimeData<&'a UnboundedSender<Q1Row>>,
+) -> D::Process {
+    let process = flow.process(process_spec);
+    query_1_rows(flow, &process, lineitem, params).for_each(q!(move |x: Q1Row| output.send(x).unwrap()));
+    process
+}
+
+#[stageleft::entry]
+pub fn query_1_runtime<'a>(
+    flow: FlowBuilder<'a, SingleProcessGraph>,
+    _cli: RuntimeData<&'a HydroCLI<HydroflowPlusMeta>>,
+    lineitem: RuntimeData<Vec<LineItem>>,
+    params: RuntimeData<QueryParams>,
+) -> impl Quoted<'a, Hydroflow<'a>> {
+    query_1(&flow, &(), lineitem, params);
+    flow.extract().optimize_default()
+}
+
+/// In-process, without a deployment, e.g. to benchmark against the `hydroflow_syntax!` graph.
+#[stageleft::entry]
+pub fn query_1_local<'a>(
+    flow: FlowBuilder<'a, SingleProcessGraph>,
+    lineitem: RuntimeData<Vec<LineItem>>,
+    params: RuntimeData<QueryParams>,
+    output: RuntimeData<&'a UnboundedSender<Q1Row>>,
+) -> impl Quoted<'a, Hydroflow<'a>> {
+    query_1_collect(&flow, &(), lineitem, params, output);
+    flow.extract().optimize_default()
+}
+
+#[stageleft::runtime]
+#[cfg(test)]
+mod tests {
+    use hydro_deploy::{Deployment, HydroflowCrate};
+    use hydroflow_plus::futures::StreamExt;
+    use hydroflow_plus_cli_integration::{DeployCrateWrapper, DeployProcessSpec};
+
+    #[tokio::test]
+    async fn test_query_1() {
+        let mut deployment = Deployment::new();
+        let localhost = deployment.Localhost();
+
+        let flow = hydroflow_plus::FlowBuilder::new();
+        let lineitem = stageleft::RuntimeData::new(&"FAKE");
+        let params = stageleft::RuntimeData::new(&"FAKE");
+        let second_process = super::query_1(
+            &flow,
+            &DeployProcessSpec::new(|| {
+                deployment.add_service(
+                    HydroflowCrate::new(".", localhost.clone())
+                        .bin("query_1")
+                        .profile("dev"),
+                )
+            }),
+            lineitem,
+            params,
+        );
+
+        deployment.deploy().await.unwrap();
+        let second_process_stdout = second_process.stdout().await;
+        deployment.start().await.unwrap();
+
+        // One row per (l_returnflag, l_linestatus) group.
+        let res = second_process_stdout.take(4).collect::<Vec<_>>().await;
+        assert_eq!(res.len(), 4);
+        assert!(res[0].contains("l_returnflag: 'A'"), "{:?}", res);
+    }
+}
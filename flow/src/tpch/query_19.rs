@@ -0,0 +1,163 @@
+use base::tpch::decimal::{Decimal, Numeric};
+use base::tpch::query_19::{LineItem, Part, QueryParams};
+use hydroflow_plus::*;
+use hydroflow_plus::stream::Windowed;
+use hydroflow_plus::util::cli::HydroCLI;
+use hydroflow_plus_cli_integration::HydroflowPlusMeta;
+use stageleft::*;
+use tokio::sync::mpsc::UnboundedSender;
+
+/**
+ * The revenue of Query 19, nothing if no line item qualifies. The join of Hydroflow+ has set
+ * semantics and needs `Eq` payloads, so the money columns are `Decimal` and every line item carries
+ * its position to keep equal line items apart.
+ */
+fn query_19_revenue<'a, D: LocalDeploy<'a>>(
+    flow: &FlowBuilder<'a, D>,
+    process: &D::Process,
+    lineitem: RuntimeData<Vec<LineItem<Decimal>>>,
+    part: RuntimeData<Vec<Part>>,
+    params: RuntimeData<&'a QueryParams>,
+) -> Stream<'a, Decimal, Windowed, D::Process> {
+    // 1. Scan part.
+    let part_filtered = flow.source_iter(process, part)
+        // 2. Pushed down filter on part.
+        .filter(q!(move |part: &Part| {
+            Part::filter_1(params, &part.p_brand, &part.p_size, &part.p_container)
+            || Part::filter_2(params, &part.p_brand, &part.p_size, &part.p_container)
+            || Part::filter_3(params, &part.p_brand, &part.p_size, &part.p_container)
+        }));
+
+    // 3. Scan lineitem.
+    let lineitem_filtered = flow.source_iter(process, lineitem)
+        // 4. Pushed down lineitem filter.
+        // l_shipinstruct = "DELIVER IN PERSON"
+        // l_shipmode = "AIR" or "AIR REG"
+        .filter(q!(|l: &LineItem<Decimal>| l.l_shipinstruct == "DELIVER IN PERSON" && (l.l_shipmode == "AIR" || l.l_shipmode == "AIR REG")))
+        .filter(q!(move |l: &LineItem<Decimal>| {
+            LineItem::filter_1(params, &l.l_quantity) || LineItem::filter_2(params, &l.l_quantity) || LineItem::filter_3(params, &l.l_quantity)
+        }));
+
+    // 5. Join the two
+    // Keys left (p_partkey)
+    // Payload left (p_brand, p_container, p_size)
+    let part_join = part_filtered.map(q!(|p: Part| (p.p_partkey, (p.p_brand, p.p_container, p.p_size))));
+    // Keys right (l_partkey)
+    // Payload right (position, l_quantity, l_discount, l_extendedprice)
+    let lineitem_join = lineitem_filtered
+        .enumerate()
+        .map(q!(|(i, l): (usize, LineItem<Decimal>)| (l.l_partkey, (i, l.l_quantity, l.l_discount, l.l_extendedprice))));
+    let joined = part_join.join(lineitem_join);
+
+    // 6. Filter again, we need to make sure the right tuples survived.
+    let join_filtered = joined.filter(q!(move |(_key, ((p_brand, p_container, p_size), (_i, l_quantity, _l_discount, _l_extendedprice))): &(i64, ((String, String, i32), (usize, Decimal, Decimal, Decimal)))| {
+        (Part::filter_1(params, p_brand, p_size, p_container) && LineItem::filter_1(params, l_quantity))
+        || (Part::filter_2(params, p_brand, p_size, p_container) && LineItem::filter_2(params, l_quantity))
+        || (Part::filter_3(params, p_brand, p_size, p_container) && LineItem::filter_3(params, l_quantity))
+    }));
+
+    // 7. Aggregate the result.
+    // 7.1 Compute (l_extendedprice * (1 - l_discount))
+    // 7.2. Aggregate sum
+    join_filtered
+        .map(q!(|(_key, (_part, (_i, _l_quantity, l_discount, l_extendedprice))): (i64, ((String, String, i32), (usize, Decimal, Decimal, Decimal)))| {
+            l_extendedprice * (Decimal::one() - l_discount)
+        }))
+        .reduce(q!(|acc: &mut Decimal, x: Decimal| *acc += x))
+}
+
+pub fn query_19<'a, D: LocalDeploy<'a>>(
+    flow: &FlowBuilder<'a, D>,
+    process_spec: &impl ProcessSpec<'a, D>,
+    lineitem: RuntimeData<Vec<LineItem<Decimal>>>,
+    part: RuntimeData<Vec<Part>>,
+    params: RuntimeData<&'a QueryParams>,
+) -> D::Process {
+    let process = flow.process(process_spec);
+
+    // 8. Print: "revenue"
+    query_19_revenue(flow, &process, lineitem, part, params).for_each(q!(|x: Decimal| println!("{}", x)));
+
+    process
+}
+
+/**
+ * Sends the revenue to `output` instead of printing it.
+ */
+pub fn query_19_collect<'a, D: LocalDeploy<'a>>(
+    flow: &FlowBuilder<'a, D>,
+    process_spec: &impl ProcessSpec<'a, D>,
+    lineitem: RuntimeData<Vec<LineItem<Decimal>>>,
+    part: RuntimeData<Vec<Part>>,
+    params: RuntimeData<&'a QueryParams>,
+    output: RuntimeData<&'a UnboundedSender<Decimal>>,
+) -> D::Process {
+    let process = flow.process(process_spec);
+    query_19_revenue(flow, &process, lineitem, part, params).for_each(q!(move |x: Decimal| output.send(x).unwrap()));
+    process
+}
+
+#[stageleft::entry]
+pub fn query_19_runtime<'a>(
+    flow: FlowBuilder<'a, SingleProcessGraph>,
+    _cli: RuntimeData<&'a HydroCLI<HydroflowPlusMeta>>,
+    lineitem: RuntimeData<Vec<LineItem<Decimal>>>,
+    part: RuntimeData<Vec<Part>>,
+    params: RuntimeData<&'a QueryParams>,
+) -> impl Quoted<'a, Hydroflow<'a>> {
+    query_19(&flow, &(), lineitem, part, params);
+    flow.extract().optimize_default()
+}
+
+/// In-process, without a deployment, e.g. to benchmark against the `hydroflow_syntax!` graph.
+#[stageleft::entry]
+pub fn query_19_local<'a>(
+    flow: FlowBuilder<'a, SingleProcessGraph>,
+    lineitem: RuntimeData<Vec<LineItem<Decimal>>>,
+    part: RuntimeData<Vec<Part>>,
+    params: RuntimeData<&'a QueryParams>,
+    output: RuntimeData<&'a UnboundedSender<Decimal>>,
+) -> impl Quoted<'a, Hydroflow<'a>> {
+    query_19_collect(&flow, &(), lineitem, part, params, output);
+    flow.extract().optimize_default()
+}
+
+#[stageleft::runtime]
+#[cfg(test)]
+mod tests {
+    use hydro_deploy::{Deployment, HydroflowCrate};
+    use hydroflow_plus::futures::StreamExt;
+    use hydroflow_plus_cli_integration::{DeployCrateWrapper, DeployProcessSpec};
+
+    #[tokio::test]
+    async fn test_query_19() {
+        let mut deployment = Deployment::new();
+        let localhost = deployment.Localhost();
+
+        let flow = hydroflow_plus::FlowBuilder::new();
+        let lineitem = stageleft::RuntimeData::new(&"FAKE");
+        let part = stageleft::RuntimeData::new(&"FAKE");
+        let params = stageleft::RuntimeData::new(&"FAKE");
+        let second_process = super::query_19(
+            &flow,
+            &DeployProcessSpec::new(|| {
+                deployment.add_service(
+                    HydroflowCrate::new(".", localhost.clone())
+                        .bin("query_19")
+                        .profile("dev"),
+                )
+            }),
+            lineitem,
+            part,
+            params,
+        );
+
+        deployment.deploy().await.unwrap();
+        let second_process_stdout = second_process.stdout().await;
+        deployment.start().await.unwrap();
+
+        // The revenue of the validation parameters at SF 1.
+        let res = second_process_stdout.take(1).collect::<Vec<_>>().await;
+        assert_eq!(res, vec!["3083843.057800"]);
+    }
+}
@@ -1,64 +1,102 @@
-use base::tpch::query_4::{LineItem, Order};
-use chrono::NaiveDate;
+use base::tpch::query_4::{LineItem, Order, Q4Row, QueryParams};
 use hydroflow_plus::*;
+use hydroflow_plus::stream::Windowed;
 use hydroflow_plus::util::cli::HydroCLI;
 use hydroflow_plus_cli_integration::HydroflowPlusMeta;
 use stageleft::*;
+use tokio::sync::mpsc::UnboundedSender;
 
-pub fn query_4<'a, D: LocalDeploy<'a>>(
+use super::semi_join::SemiJoin;
+
+/**
+ * The order count per o_orderpriority, unsorted.
+ */
+fn query_4_counts<'a, D: LocalDeploy<'a>>(
     flow: &FlowBuilder<'a, D>,
-    process_spec: &impl ProcessSpec<'a, D>,
+    process: &D::Process,
     lineitem: RuntimeData<Vec<LineItem>>,
-    orders: RuntimeData<Vec<Order>>
-) -> D::Process {
-    let process = flow.process(process_spec);
-
+    orders: RuntimeData<Vec<Order>>,
+    params: RuntimeData<QueryParams>,
+) -> Stream<'a, (String, i64), Windowed, D::Process> {
     // 1. Scan orders
-    let orders_filtered = flow.source_iter(&process, orders)
+    let orders_filtered = flow.source_iter(process, orders)
     //let orders_filtered = process.source_iter(q!(vec![Order{order_key: 0, order_date: NaiveDate::from_ymd_opt(1993, 7, 1).unwrap(), order_priority: "prior".to_string()}]))
-    // 1.2 Filter orders on o_orderdate >= '1993-07-01' and < '1993-10-01'
-        .filter(q!(|order: &Order| {
-        order.order_date >= NaiveDate::from_ymd_opt(1993, 7, 1).unwrap()
-            && order.order_date < NaiveDate::from_ymd_opt(1993, 10, 1).unwrap()
-    }));
+    // 1.2 Filter orders on o_orderdate >= DATE and < DATE + 3 months, e.g. ['1993-07-01', '1993-10-01')
+        .filter(q!(move |order: &Order| params.filter(&order.order_date)));
 
     // 2. Scan from lineitem.
     //let line_items_filtered = process.source_iter(q!(vec![LineItem{order_key: 0, receiptdate: NaiveDate::from_ymd_opt(1993, 7, 1).unwrap(), commit_date: NaiveDate::from_ymd_opt(1993, 10, 1).unwrap()}]))
-    let line_items_filtered = flow.source_iter(&process, lineitem)
+    let line_items_filtered = flow.source_iter(process, lineitem)
         // 2.2 Filter lineitem on l_commitdate < l_receiptdate
         .filter(q!(|line_item: &LineItem| line_item.commit_date < line_item.receiptdate));
 
-    // 3. Join the two. o_orderkey = l_orderkey, payload: o_orderpriority
-    // TODO: How to define the build side?
-    // TODO: Multiset join in HF+?
-    // XXX: Semijoin?
-    // Build side: Orders?
-    // Probe side: LineItem?
-    let line_items_join = line_items_filtered.map(q!(|l|(l.order_key, None::<u8>)));
-    let joined = orders_filtered.map(q!(|e|(e.order_key, e.order_priority))).join(line_items_join);
-    // XXX: Join only outputs unique values? Where did we take these semantics?
+    // 3. Semi-join the two. o_orderkey = l_orderkey, payload: o_orderpriority
+    // Build side: LineItem
+    // Probe side: Orders
+    let line_items_keys = line_items_filtered.map(q!(|l: LineItem| l.order_key));
+    let joined = orders_filtered.map(q!(|e: Order| (e.order_key, e.order_priority))).semi_join(line_items_keys);
 
     // 4. Aggregate.
     // Hash aggregation in DuckDB
     // 4.1 Group by: "o_orderpriority"
     // 4.2 Count
-    // XXX: Why is it legal to return a value when it is not used afterwards?
-    let agg = joined.map(q!(|x|(x.1.0, 1))).reduce_keyed(q!(|acc, x| *acc = *acc + x));
+    joined.map(q!(|x: (i32, String)| (x.1, 1i64))).reduce_keyed(q!(|acc: &mut i64, x: i64| *acc = *acc + x))
+}
+
+pub fn query_4<'a, D: LocalDeploy<'a>>(
+    flow: &FlowBuilder<'a, D>,
+    process_spec: &impl ProcessSpec<'a, D>,
+    lineitem: RuntimeData<Vec<LineItem>>,
+    orders: RuntimeData<Vec<Order>>,
+    params: RuntimeData<QueryParams>,
+) -> D::Process {
+    let process = flow.process(process_spec);
 
     // 5. Print: "o_orderpriority", "order_count"
-    agg.for_each(q!(|x| println!("{:?}", x)));
+    query_4_counts(flow, &process, lineitem, orders, params).for_each(q!(|x| println!("{:?}", x)));
 
     process
 }
 
+/**
+ * Sends the result rows to `output` instead of printing them, unsorted.
+ */
+pub fn query_4_collect<'a, D: LocalDeploy<'a>>(
+    flow: &FlowBuilder<'a, D>,
+    process_spec: &impl ProcessSpec<'a, D>,
+    lineitem: RuntimeData<Vec<LineItem>>,
+    orders: RuntimeData<Vec<Order>>,
+    params: RuntimeData<QueryParams>,
+    output: RuntimeData<&'a UnboundedSender<Q4Row>>,
+) -> D::Process {
+    let process = flow.process(process_spec);
+    query_4_counts(flow, &process, lineitem, orders, params)
+        .for_each(q!(move |x: (String, i64)| output.send(Q4Row::from(x)).unwrap()));
+    process
+}
+
 #[stageleft::entry]
 pub fn query_4_runtime<'a>(
     flow: FlowBuilder<'a, SingleProcessGraph>,
     _cli: RuntimeData<&'a HydroCLI<HydroflowPlusMeta>>,
     lineitem: RuntimeData<Vec<LineItem>>,
     orders: RuntimeData<Vec<Order>>,
+    params: RuntimeData<QueryParams>,
+) -> impl Quoted<'a, Hydroflow<'a>> {
+    query_4(&flow, &(), lineitem, orders, params);
+    flow.extract().optimize_default()
+}
+
+/// In-process, without a deployment, e.g. to benchmark against the `hydroflow_syntax!` graph.
+#[stageleft::entry]
+pub fn query_4_local<'a>(
+    flow: FlowBuilder<'a, SingleProcessGraph>,
+    lineitem: RuntimeData<Vec<LineItem>>,
+    orders: RuntimeData<Vec<Order>>,
+    params: RuntimeData<QueryParams>,
+    output: RuntimeData<&'a UnboundedSender<Q4Row>>,
 ) -> impl Quoted<'a, Hydroflow<'a>> {
-    query_4(&flow, &(), lineitem, orders);
+    query_4_collect(&flow, &(), lineitem, orders, params, output);
     flow.extract().optimize_default()
 }
 
@@ -77,6 +115,7 @@ mod tests {
         let flow = hydroflow_plus::FlowBuilder::new();
         let orders = stageleft::RuntimeData::new(&"FAKE");
         let lineitem = stageleft::RuntimeData::new(&"FAKE");
+        let params = stageleft::RuntimeData::new(&"FAKE");
         let second_process = super::query_4(
             &flow,
             &DeployProcessSpec::new(|| {
@@ -87,7 +126,8 @@ mod tests {
                 )
             }),
             lineitem,
-            orders
+            orders,
+            params,
         );
 
         println!("Deploying");
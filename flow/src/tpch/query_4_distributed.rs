@@ -1,57 +1,88 @@
 use std::hash::{Hash, Hasher};
+use std::rc::Rc;
 
 use __staged::stream::Windowed;
 use serde::{de::DeserializeOwned, Serialize};
-use base::tpch::query_4::{LineItem, Order};
-use chrono::NaiveDate;
+use base::tpch::bloom::{BloomFilter, DEFAULT_FALSE_POSITIVE_RATE};
+use base::tpch::plan::{partition, Distribution, Side};
+use base::tpch::query_4::{LineItem, Order, QueryParams};
 use hydroflow_plus::*;
 use hydroflow_plus::util::cli::HydroCLI;
 use hydroflow_plus_cli_integration::{CLIRuntime, HydroflowPlusMeta};
 use stageleft::*;
 
 /* Inputs on single machine 
+ * The build side is either broadcast to every node, with the probe side spread round robin, or hash
+ * partitioned like the probe side. The rows are routed like `base::tpch::plan::Distribution` prices them.
+ * A broadcast may match the probe rows of one key on several nodes, so a semi-join only broadcasts
+ * the side it does not count, see `JoinPlan::choose_semi_join`.
  */
-fn distributed_join<'a, Key: Hash + Eq + Serialize + DeserializeOwned, T: Serialize + DeserializeOwned, U: Serialize + DeserializeOwned, D: Deploy<'a, ClusterId = u32>>(build: Stream<'a, (Key, T), Windowed, D::Process>, probe: Stream<'a, (Key, U), Windowed, D::Process>, cluster: &D::Cluster) -> Stream<'a, (Key, (T, U)), Windowed, D::Cluster> {
+fn distributed_join<'a, Key: Hash + Eq + Serialize + DeserializeOwned, T: Serialize + DeserializeOwned, U: Serialize + DeserializeOwned, D: Deploy<'a, ClusterId = u32>>(build: Stream<'a, (Key, T), Windowed, D::Process>, probe: Stream<'a, (Key, U), Windowed, D::Process>, cluster: &D::Cluster, distribution: Distribution) -> Stream<'a, (Key, (T, U)), Windowed, D::Cluster> {
 
     // Note: Let compiler move the persist after the broadcast when user puts it before the broadcast
-    let build = build.broadcast_bincode(cluster).all_ticks();
+    let build = match distribution {
+        Distribution::Broadcast => build.broadcast_bincode(cluster).all_ticks(),
+        Distribution::Shuffle => {
+            let all_ids_vec = cluster.ids();
+
+            build.map(q!(|(key, value)| (partition(&key, all_ids_vec.len()), (key, value))))
+                .send_bincode(cluster).all_ticks()
+        }
+    };
 
     let all_ids_vec = cluster.ids();
 
-    let probe = probe.map(q!(|(key, value)| {
-        let mut hasher = std::collections::hash_map::DefaultHasher::new();
-        key.hash(&mut hasher);
-        let id = (hasher.finish() % all_ids_vec.len() as u64) as u32;
-
-        (id, (key, value))
-
-    })).send_bincode(cluster);
+    let probe = match distribution {
+        Distribution::Broadcast => probe.enumerate()
+            .map(q!(|(i, (key, value))| (Distribution::Broadcast.probe_node(i, &key, all_ids_vec.len()), (key, value)))),
+        Distribution::Shuffle => probe.enumerate()
+            .map(q!(|(i, (key, value))| (Distribution::Shuffle.probe_node(i, &key, all_ids_vec.len()), (key, value)))),
+    }.send_bincode(cluster);
 
     let joined = build.join(probe.tick_batch());
 
     joined
 }
 
+/* Sideways information passing, see `base::tpch::bloom`: drops the probe tuples whose key is not in
+ * a Bloom filter of the build keys. Both sides are still on the process, so the dropped tuples are
+ * never sent to the cluster.
+ */
+fn bloom_filter_pushdown<'a, Key: Hash + Clone + 'a, T: 'a, U: Clone + 'a, N: Location<'a>>(build: Stream<'a, (Key, T), Windowed, N>, probe: Stream<'a, (Key, U), Windowed, N>) -> Stream<'a, (Key, U), Windowed, N> {
+
+    let bloom = build
+        .fold(q!(|| vec![]), q!(|keys, (key, _)| keys.push(key)))
+        .map(q!(|keys| Rc::new(BloomFilter::from_keys(&keys, DEFAULT_FALSE_POSITIVE_RATE))));
+
+    probe.cross_product(bloom)
+        .filter_map(q!(|((key, value), bloom)| if bloom.contains(&key) { Some((key, value)) } else { None }))
+}
+
 pub fn query_4_distributed<'a, D: Deploy<'a, ClusterId = u32>>(
     flow: &FlowBuilder<'a, D>,
     process_spec: &impl ProcessSpec<'a, D>,
     cluster_spec: &impl ClusterSpec<'a, D>,
     lineitem: RuntimeData<Vec<LineItem>>,
     orders: RuntimeData<Vec<Order>>,
+    params: RuntimeData<QueryParams>,
     intermediate_aggregation: bool,
+    bloom_filter: bool,
+    build: Side,
+    distribution: Distribution,
 ) -> D::Process {
     let process = flow.process(process_spec);
     let cluster = flow.cluster(cluster_spec);
 
-    println!("Query 4 distributed with intermediate aggregation: {}", intermediate_aggregation);
+    println!("Query 4 distributed with intermediate aggregation: {}, Bloom filter: {}, build side: {:?}, {:?}", intermediate_aggregation, bloom_filter, build, distribution);
+    assert!(
+        build == Side::Right || distribution == Distribution::Shuffle,
+        "Broadcasting the orders counts an order on every node with one of its line items, see `query_4::plan`"
+    );
 
     // 1. Scan orders
     let orders_filtered = flow.source_iter(&process, orders)
-    // 1.2 Filter orders on o_orderdate >= '1993-07-01' and < '1993-10-01'
-        .filter(q!(|order: &Order| {
-        order.order_date >= NaiveDate::from_ymd_opt(1993, 7, 1).unwrap()
-            && order.order_date < NaiveDate::from_ymd_opt(1993, 10, 1).unwrap()
-    }));
+    // 1.2 Filter orders on o_orderdate >= DATE and < DATE + 3 months, e.g. ['1993-07-01', '1993-10-01')
+        .filter(q!(move |order: &Order| params.filter(&order.order_date)));
 
     // 2. Scan from lineitem.
     let line_items_filtered = flow.source_iter(&process, lineitem)
@@ -59,17 +90,26 @@ pub fn query_4_distributed<'a, D: Deploy<'a, ClusterId = u32>>(
         .filter(q!(|line_item: &LineItem| line_item.commit_date < line_item.receiptdate));
 
     // 3. Join the two. o_orderkey = l_orderkey, payload: o_orderpriority
-    // TODO: How to define the build side?
     // TODO: Multiset join in HF+?
     // XXX: Semijoin?
-    // Build side: Orders?
-    // Probe side: LineItem?
+    // Build side (left: Orders, right: LineItem) and distribution from `base::tpch::query_4::plan`
     let line_items_join = line_items_filtered.map(q!(|l|(l.order_key, None::<u8>)));
     let orders_join = orders_filtered.map(q!(|e|(e.order_key, e.order_priority)));
 
+    // Only the line items that pass the Bloom filter of the order keys go to the cluster
+    let line_items_join = if bloom_filter {
+        bloom_filter_pushdown(orders_join.clone(), line_items_join)
+    } else {
+        line_items_join
+    };
+
     // Distributed join
     //let joined = orders_join.join(line_items_join);
-    let joined = distributed_join::<_, _, _, D>(orders_join, line_items_join, &cluster);
+    let joined = match build {
+        Side::Left => distributed_join::<_, _, _, D>(orders_join, line_items_join, &cluster, distribution),
+        Side::Right => distributed_join::<_, _, _, D>(line_items_join, orders_join, &cluster, distribution)
+            .map(q!(|(key, (line_item, order))| (key, (order, line_item)))),
+    };
 
     // 4. Aggregate.
     // Hash aggregation in DuckDB
@@ -107,6 +147,7 @@ pub fn query_4_distributed_partitioned<'a, D: Deploy<'a, ClusterId = u32>>(
     cluster_spec: &impl ClusterSpec<'a, D>,
     lineitem: RuntimeData<Vec<LineItem>>,
     orders: RuntimeData<Vec<Order>>,
+    params: RuntimeData<QueryParams>,
     intermediate_aggregation: bool,
 ) -> D::Process {
     let process = flow.process(process_spec);
@@ -118,11 +159,8 @@ pub fn query_4_distributed_partitioned<'a, D: Deploy<'a, ClusterId = u32>>(
 
     // 1. Scan orders
     let orders_filtered = flow.source_iter(&cluster, orders)
-    // 1.2 Filter orders on o_orderdate >= '1993-07-01' and < '1993-10-01'
-        .filter(q!(|order: &Order| {
-        order.order_date >= NaiveDate::from_ymd_opt(1993, 7, 1).unwrap()
-            && order.order_date < NaiveDate::from_ymd_opt(1993, 10, 1).unwrap()
-    }));
+    // 1.2 Filter orders on o_orderdate >= DATE and < DATE + 3 months, e.g. ['1993-07-01', '1993-10-01')
+        .filter(q!(move |order: &Order| params.filter(&order.order_date)));
 
     // 2. Scan from lineitem.
     let line_items_filtered = flow.source_iter(&cluster, lineitem)
@@ -142,8 +180,8 @@ pub fn query_4_distributed_partitioned<'a, D: Deploy<'a, ClusterId = u32>>(
 
     // 3. Join the two. o_orderkey = l_orderkey, payload: o_orderpriority
     // XXX: Semijoin?
-    // Build side: Orders?
-    // Probe side: LineItem?
+    // Build side: Orders, every node scans all of them like a broadcast
+    // Probe side: LineItem, partitioned
     let line_items_join = line_items_filtered.map(q!(|l|(l.order_key, None::<u8>)));
     let orders_join = orders_filtered.map(q!(|e|(e.order_key, e.order_priority)));
 
@@ -187,9 +225,16 @@ pub fn query_4_distributed_runtime<'a>(
     cli: RuntimeData<&'a HydroCLI<HydroflowPlusMeta>>,
     lineitem: RuntimeData<Vec<LineItem>>,
     orders: RuntimeData<Vec<Order>>,
+    params: RuntimeData<QueryParams>,
     intermediate_aggregation: bool,
+    bloom_filter: bool,
+    build_orders: bool,
+    broadcast: bool,
 ) -> impl Quoted<'a, Hydroflow<'a>> {
-    query_4_distributed(&flow, &cli, &cli, lineitem, orders, intermediate_aggregation);
+    // The plan is fixed when the graph is compiled, so it is passed as flags
+    let build = if build_orders { Side::Left } else { Side::Right };
+    let distribution = if broadcast { Distribution::Broadcast } else { Distribution::Shuffle };
+    query_4_distributed(&flow, &cli, &cli, lineitem, orders, params, intermediate_aggregation, bloom_filter, build, distribution);
     flow.extract()
         .optimize_default()
         .with_dynamic_id(q!(cli.meta.subgraph_id))
@@ -201,9 +246,10 @@ pub fn query_4_distributed_partitioned_runtime<'a>(
     cli: RuntimeData<&'a HydroCLI<HydroflowPlusMeta>>,
     lineitem: RuntimeData<Vec<LineItem>>,
     orders: RuntimeData<Vec<Order>>,
+    params: RuntimeData<QueryParams>,
     intermediate_aggregation: bool,
 ) -> impl Quoted<'a, Hydroflow<'a>> {
-    query_4_distributed_partitioned(&flow, &cli, &cli, lineitem, orders, intermediate_aggregation);
+    query_4_distributed_partitioned(&flow, &cli, &cli, lineitem, orders, params, intermediate_aggregation);
     flow.extract()
         .optimize_default()
         .with_dynamic_id(q!(cli.meta.subgraph_id))
@@ -0,0 +1,81 @@
+use std::hash::Hash;
+
+use hydroflow_plus::*;
+use hydroflow_plus::stream::Windowed;
+use stageleft::*;
+use tokio::sync::mpsc::UnboundedSender;
+
+/**
+ * Semi-joins and anti-joins of keyed streams, see `base::tpch::semi_join`. Only the keys of the
+ * build side matter, so it is a stream of keys. `join` and `anti_join` have set semantics, so the
+ * tuples of `self` are tagged with their position first, like the line items of Query 19, and
+ * equal tuples of `self` are all kept.
+ */
+pub trait SemiJoin<'a, K, V, N> {
+    /// The tuples of `self` whose key is in `keys`.
+    fn semi_join(self, keys: Stream<'a, K, Windowed, N>) -> Stream<'a, (K, V), Windowed, N>;
+
+    /// The tuples of `self` whose key is not in `keys`.
+    fn anti_semi_join(self, keys: Stream<'a, K, Windowed, N>) -> Stream<'a, (K, V), Windowed, N>;
+}
+
+impl<'a, K: Eq + Hash, V, N: Location<'a>> SemiJoin<'a, K, V, N> for Stream<'a, (K, V), Windowed, N> {
+    fn semi_join(self, keys: Stream<'a, K, Windowed, N>) -> Stream<'a, (K, V), Windowed, N> {
+        self.enumerate()
+            .map(q!(|(i, (k, v))| (k, (i, v))))
+            .join(keys.map(q!(|k| (k, ()))))
+            .map(q!(|(k, ((_i, v), ()))| (k, v)))
+    }
+
+    fn anti_semi_join(self, keys: Stream<'a, K, Windowed, N>) -> Stream<'a, (K, V), Windowed, N> {
+        self.enumerate()
+            .map(q!(|(i, (k, v))| (k, (i, v))))
+            .anti_join(keys)
+            .map(q!(|(k, (_i, v))| (k, v)))
+    }
+}
+
+/// Both joins of `probe` with `keys` in-process, to test them.
+#[stageleft::entry]
+pub fn semi_join_local<'a>(
+    flow: FlowBuilder<'a, SingleProcessGraph>,
+    probe: RuntimeData<Vec<(i32, String)>>,
+    keys: RuntimeData<Vec<i32>>,
+    semi_output: RuntimeData<&'a UnboundedSender<(i32, String)>>,
+    anti_output: RuntimeData<&'a UnboundedSender<(i32, String)>>,
+) -> impl Quoted<'a, Hydroflow<'a>> {
+    let process = flow.process(&());
+    let probe = flow.source_iter(&process, probe);
+    let keys = flow.source_iter(&process, keys);
+    probe.clone()
+        .semi_join(keys.clone())
+        .for_each(q!(move |x: (i32, String)| semi_output.send(x).unwrap()));
+    probe
+        .anti_semi_join(keys)
+        .for_each(q!(move |x: (i32, String)| anti_output.send(x).unwrap()));
+    flow.extract().optimize_default()
+}
+
+#[stageleft::runtime]
+#[cfg(test)]
+mod tests {
+    use hydroflow_plus::util::collect_ready;
+
+    #[test]
+    fn test_semi_join() {
+        let orders = vec![(1, "a"), (2, "b"), (3, "c"), (1, "a"), (2, "b")];
+        let orders: Vec<(i32, String)> = orders.into_iter().map(|(k, v)| (k, v.to_string())).collect();
+        let line_items = vec![1, 1, 1, 3, 4];
+
+        let (semi_send, mut semi_recv) = hydroflow_plus::util::unbounded_channel();
+        let (anti_send, mut anti_recv) = hydroflow_plus::util::unbounded_channel();
+        let mut flow = super::semi_join_local!(orders, line_items, &semi_send, &anti_send);
+        flow.run_available();
+
+        // Duplicate keys do not duplicate the output, duplicate tuples of the probe side are kept.
+        let mut semi = collect_ready::<Vec<_>, _>(&mut semi_recv);
+        semi.sort();
+        assert_eq!(semi, vec![(1, "a".to_string()), (1, "a".to_string()), (3, "c".to_string())]);
+        assert_eq!(collect_ready::<Vec<_>, _>(&mut anti_recv), vec![(2, "b".to_string()), (2, "b".to_string())]);
+    }
+}
@@ -1,96 +1,958 @@
 use base::tpch::{
-    initialize::initialize_database, query_1::load as load_q1, query_19::load as load_q19,
-    query_4::load as load_q4,
+    initialize::initialize_database, query_1::load as load_q1, query_1::QueryParams as Q1Params, query_10::load as load_q10,
+    query_12::load as load_q12, query_13::load as load_q13, query_14::load as load_q14,
+    query_16::load as load_q16, query_17::load as load_q17, query_18::load as load_q18,
+    query_19::load as load_q19, query_19::QueryParams as Q19Params, query_2::load as load_q2, query_20::load as load_q20, query_21::load as load_q21,
+    query_22::load as load_q22, query_3::load as load_q3, query_4::load as load_q4, query_4::QueryParams as Q4Params,
+    query_5::load as load_q5, query_6::load as load_q6,
 };
 //use base::tpch::query_1::query as query_1_base;
+use base::tpch::query_1::load_columns as load_q1_columns;
+use base::tpch::query_1::load_decimal as load_q1_decimal;
+use base::tpch::query_1::query_columns as query_1_base_columns;
+use base::tpch::query_1::query as query_1_base_generic;
+use base::tpch::query_19::load_columns as load_q19_columns;
+use base::tpch::query_19::load_decimal as load_q19_decimal;
+use base::tpch::query_19::query_columns as query_19_base_columns;
+use base::tpch::query_19::query as query_19_base_generic;
 use base::tpch::query_1::query_duckdb as query_1_duckdb;
 use base::tpch::query_19::query_duckdb as query_19_duckdb;
+use base::tpch::query_3::query_duckdb as query_3_duckdb;
+use base::tpch::query_3::query_with as query_3_base_with;
+use base::tpch::query_5::query_duckdb as query_5_duckdb;
+use base::tpch::query_10::query_duckdb as query_10_duckdb;
+use base::tpch::query_10::query_with as query_10_base_with;
+use base::tpch::query_2::query_duckdb as query_2_duckdb;
+use base::tpch::query_13::query_duckdb as query_13_duckdb;
+use base::tpch::query_16::query_duckdb as query_16_duckdb;
+use base::tpch::query_17::query_duckdb as query_17_duckdb;
+use base::tpch::query_18::query_duckdb as query_18_duckdb;
+use base::tpch::query_18::query_with as query_18_base_with;
+use base::tpch::top_k::Strategy;
+use base::tpch::parallel::Parallelism;
+use base::tpch::query_1::query_parallel as query_1_base_parallel;
+use base::tpch::query_4::query_parallel as query_4_base_parallel;
+use base::tpch::query_19::query_parallel as query_19_base_parallel;
+use base::tpch::query_4::query_merge_join as query_4_base_merge_join;
+use base::tpch::query_19::query_merge_join as query_19_base_merge_join;
+use base::tpch::query_4::query_bloom as query_4_base_bloom;
+use base::tpch::query_19::query_bloom as query_19_base_bloom;
+use base::tpch::query_4::{plan as query_4_plan, query_with_plan as query_4_base_planned};
+use base::tpch::query_19::{plan as query_19_plan, query_with_plan as query_19_base_planned};
+use base::tpch::qgen::QGen;
+use base::tpch::query_21::query_duckdb as query_21_duckdb;
+use base::tpch::query_20::query_duckdb as query_20_duckdb;
+use base::tpch::query_22::query_duckdb as query_22_duckdb;
+use base::tpch::query_6::query_duckdb as query_6_duckdb;
+use base::tpch::query_6::query_chunks as query_6_base_chunks;
+use base::tpch::query_12::query_duckdb as query_12_duckdb;
+use base::tpch::query_12::query_chunks as query_12_base_chunks;
+use base::tpch::query_14::query_duckdb as query_14_duckdb;
+use base::tpch::query_14::query_chunks as query_14_base_chunks;
 use hydroflow_base::tpch::query_1::query as query_1_hf;
 use hydroflow_base::tpch::query_1::query_base as query_1_base;
+use hydroflow_base::tpch::query_1::query_columns as query_1_hf_columns;
+use hydroflow_base::tpch::query_1::query_partitioned as query_1_hf_partitioned;
+use hydroflow_base::tpch::query_1::query_decimal as query_1_hf_decimal;
 use hydroflow_base::tpch::query_19::query as query_19_hf;
 use hydroflow_base::tpch::query_19::query_base as query_19_base;
+use hydroflow_base::tpch::query_19::query_columns as query_19_hf_columns;
+use hydroflow_base::tpch::query_19::query_partitioned as query_19_hf_partitioned;
+use hydroflow_base::tpch::query_19::query_decimal as query_19_hf_decimal;
+use hydroflow_base::tpch::query_19::query_merge_join as query_19_hf_merge_join;
+use hydroflow_base::tpch::query_19::query_bloom as query_19_hf_bloom;
+use hydroflow_base::tpch::query_3::query as query_3_hf;
+use hydroflow_base::tpch::query_3::query_base as query_3_base;
+use hydroflow_base::tpch::query_3::query_with as query_3_hf_with;
+use hydroflow_base::tpch::query_5::query as query_5_hf;
+use hydroflow_base::tpch::query_5::query_base as query_5_base;
+use hydroflow_base::tpch::query_10::query as query_10_hf;
+use hydroflow_base::tpch::query_10::query_base as query_10_base;
+use hydroflow_base::tpch::query_10::query_with as query_10_hf_with;
+use hydroflow_base::tpch::query_2::query as query_2_hf;
+use hydroflow_base::tpch::query_2::query_base as query_2_base;
+use hydroflow_base::tpch::query_13::query as query_13_hf;
+use hydroflow_base::tpch::query_13::query_base as query_13_base;
+use hydroflow_base::tpch::query_16::query as query_16_hf;
+use hydroflow_base::tpch::query_16::query_base as query_16_base;
+use hydroflow_base::tpch::query_17::query as query_17_hf;
+use hydroflow_base::tpch::query_17::query_base as query_17_base;
+use hydroflow_base::tpch::query_18::query as query_18_hf;
+use hydroflow_base::tpch::query_18::query_base as query_18_base;
+use hydroflow_base::tpch::query_18::query_with as query_18_hf_with;
+use hydroflow_base::tpch::query_20::query as query_20_hf;
+use hydroflow_base::tpch::query_20::query_base as query_20_base;
+use hydroflow_base::tpch::query_21::query as query_21_hf;
+use hydroflow_base::tpch::query_21::query_base as query_21_base;
+use hydroflow_base::tpch::query_22::query as query_22_hf;
+use hydroflow_base::tpch::query_22::query_base as query_22_base;
+use hydroflow_base::tpch::query_6::query as query_6_hf;
+use hydroflow_base::tpch::query_6::query_chunks as query_6_hf_chunks;
+use hydroflow_base::tpch::query_6::query_base as query_6_base;
+use hydroflow_base::tpch::query_12::query as query_12_hf;
+use hydroflow_base::tpch::query_12::query_chunks as query_12_hf_chunks;
+use hydroflow_base::tpch::query_12::query_base as query_12_base;
+use hydroflow_base::tpch::query_14::query as query_14_hf;
+use hydroflow_base::tpch::query_14::query_chunks as query_14_hf_chunks;
+use hydroflow_base::tpch::query_14::query_base as query_14_base;
 //use base::tpch::query_4::query as query_4_base;
 use base::tpch::query_4::query_duckdb as query_4_duckdb;
-use criterion::{criterion_group, criterion_main, Criterion};
+use base::tpch::verify::assert_match;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use hydroflow_base::tpch::query_4::query as query_4_hf;
 use hydroflow_base::tpch::query_4::query_base as query_4_base;
+use hydroflow_base::tpch::query_4::query_partitioned as query_4_hf_partitioned;
+use hydroflow_base::tpch::query_4::query_set_join as query_4_hf_set_join;
+use hydroflow_base::tpch::query_4::query_merge_join as query_4_hf_merge_join;
+use hydroflow_base::tpch::query_4::query_bloom as query_4_hf_bloom;
+use hydroflow_base::tpch::verify::{
+    verify_query_1, verify_query_1_decimal, verify_query_10, verify_query_12, verify_query_13, verify_query_14,
+    verify_query_16, verify_query_17, verify_query_18, verify_query_19, verify_query_19_decimal, verify_query_2,
+    verify_query_20, verify_query_21, verify_query_22, verify_query_3, verify_query_4,
+    verify_query_5, verify_query_6,
+};
 
 /**
 * Query 1 is a straight pipeline that is well suited for compiling.
 * We expect HF to be faster than DuckDB.
+* The query_1_params group repeats the benchmark with DELTA drawn by qgen.
+* The _decimal variants use exact `Decimal` arithmetic instead of `f64`.
+* The _columns variants scan `LineItemColumns` in column chunks with a selection vector.
 */
 fn tpch_sf1_query_1(c: &mut Criterion) {
     let scale_factor = 1;
     let conn = initialize_database(scale_factor);
 
+    // Only benchmark once all implementations agree with DuckDB
+    let params = Q1Params::default();
+    assert_match(&verify_query_1(&conn, &params));
+
     c.bench_function("query_1_baseline", |b| {
         b.iter_batched(
-            || load_q1(&conn),
-            |line_items| query_1_base(line_items),
+            || load_q1(&conn).unwrap(),
+            |line_items| query_1_base(line_items, &params),
             criterion::BatchSize::SmallInput,
         )
     });
 
     c.bench_function("query_1_hf", |b| {
         b.iter_batched(
-            || load_q1(&conn),
-            |line_items| query_1_hf(line_items),
+            || load_q1(&conn).unwrap(),
+            |line_items| query_1_hf(line_items, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_1_baseline_columns-256", |b| {
+        b.iter_batched(
+            || load_q1_columns(&conn).unwrap(),
+            |line_items| query_1_base_columns::<256>(line_items, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_1_baseline_columns-2048", |b| {
+        b.iter_batched(
+            || load_q1_columns(&conn).unwrap(),
+            |line_items| query_1_base_columns::<2048>(line_items, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_1_hf_columns-256", |b| {
+        b.iter_batched(
+            || load_q1_columns(&conn).unwrap(),
+            |line_items| query_1_hf_columns::<256>(line_items, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_1_hf_columns-2048", |b| {
+        b.iter_batched(
+            || load_q1_columns(&conn).unwrap(),
+            |line_items| query_1_hf_columns::<2048>(line_items, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    assert_match(&verify_query_1_decimal(&conn, &params));
+    c.bench_function("query_1_baseline_decimal", |b| {
+        b.iter_batched(
+            || load_q1_decimal(&conn).unwrap(),
+            |line_items| query_1_base_generic(line_items, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_1_hf_decimal", |b| {
+        b.iter_batched(
+            || load_q1_decimal(&conn).unwrap(),
+            |line_items| query_1_hf_decimal(line_items, &params),
             criterion::BatchSize::SmallInput,
         )
     });
 
     // Set duckdb for benchmarking to single thread
     let _ = conn.execute("SET threads = 1;", []);
-    c.bench_function("query_1_duckdb", |b| b.iter(|| query_1_duckdb(&conn, None)));
+    c.bench_function("query_1_duckdb", |b| b.iter(|| query_1_duckdb(&conn, None, &params)));
+
+    let mut group = c.benchmark_group("query_1_params");
+    for seed in 0..3 {
+        let params = QGen::new(seed).query_1();
+        assert_match(&verify_query_1(&conn, &params));
+
+        group.bench_with_input(BenchmarkId::new("baseline", &params), &params, |b, params| {
+            b.iter_batched(
+                || load_q1(&conn).unwrap(),
+                |line_items| query_1_base(line_items, params),
+                criterion::BatchSize::SmallInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("hf", &params), &params, |b, params| {
+            b.iter_batched(
+                || load_q1(&conn).unwrap(),
+                |line_items| query_1_hf(line_items, params),
+                criterion::BatchSize::SmallInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("duckdb", &params), &params, |b, params| {
+            b.iter(|| query_1_duckdb(&conn, None, params))
+        });
+    }
+    group.finish();
+}
+
+/**
+ * Query 2 decorrelates the min(ps_supplycost) subquery into a group by on ps_partkey.
+ */
+fn tpch_sf1_query_2(c: &mut Criterion) {
+    let scale_factor = 1;
+    let conn = initialize_database(scale_factor);
+
+    // Only benchmark once all implementations agree with DuckDB
+    assert_match(&verify_query_2(&conn));
+
+    c.bench_function("query_2_baseline", |b| {
+        b.iter_batched(
+            || load_q2(&conn),
+            |(part, supplier, partsupp, nation, region)| query_2_base(part, supplier, partsupp, nation, region),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_2_hf", |b| {
+        b.iter_batched(
+            || load_q2(&conn),
+            |(part, supplier, partsupp, nation, region)| query_2_hf(part, supplier, partsupp, nation, region),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    // Set duckdb for benchmarking to single thread
+    let _ = conn.execute("SET threads = 1;", []);
+    c.bench_function("query_2_duckdb", |b| b.iter(|| query_2_duckdb(&conn, None)));
+}
+
+/**
+ * Query 3 joins three tables and keeps the top 10 orders by revenue.
+ * The sort variants collect all groups and sort them instead of keeping a bounded heap.
+ */
+fn tpch_sf1_query_3(c: &mut Criterion) {
+    let scale_factor = 1;
+    let conn = initialize_database(scale_factor);
+
+    // Only benchmark once all implementations agree with DuckDB
+    assert_match(&verify_query_3(&conn));
+
+    c.bench_function("query_3_baseline", |b| {
+        b.iter_batched(
+            || load_q3(&conn),
+            |(customer, orders, line_items)| query_3_base(customer, orders, line_items),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_3_baseline_sort", |b| {
+        b.iter_batched(
+            || load_q3(&conn),
+            |(customer, orders, line_items)| query_3_base_with(Strategy::Sort, customer, orders, line_items),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_3_hf", |b| {
+        b.iter_batched(
+            || load_q3(&conn),
+            |(customer, orders, line_items)| query_3_hf(customer, orders, line_items),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_3_hf_sort", |b| {
+        b.iter_batched(
+            || load_q3(&conn),
+            |(customer, orders, line_items)| query_3_hf_with(Strategy::Sort, customer, orders, line_items),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    // Set duckdb for benchmarking to single thread
+    let _ = conn.execute("SET threads = 1;", []);
+    c.bench_function("query_3_duckdb", |b| b.iter(|| query_3_duckdb(&conn, None)));
 }
 
 /**
  * Query 4 is medium complex. All implementations should be on par.
+ * The query_4_params group repeats the benchmark with DATE drawn by qgen.
+ * query_4_hf_set_join is the former semi-join of the HF graph, a `join()` with a unit payload.
+ * The _merge_join variants sort both sides by o_orderkey and merge them instead of hashing.
+ * The _bloom variants drop line items with a Bloom filter of the qualifying orders before the join.
+ * query_4_baseline_planned builds on the side chosen from DuckDB's statistics, the plan is printed.
  */
 fn tpch_sf1_query_4(c: &mut Criterion) {
     let scale_factor = 1;
     let conn = initialize_database(scale_factor);
 
+    // Only benchmark once all implementations agree with DuckDB
+    let params = Q4Params::default();
+    assert_match(&verify_query_4(&conn, &params));
+
+    let plan = query_4_plan(&conn, &params, 1);
+    println!("Query 4 plan: {}", plan);
+
     c.bench_function("query_4_baseline", |b| {
         b.iter_batched(
-            || load_q4(&conn),
-            |(line_items, orders)| query_4_base(line_items, orders),
+            || load_q4(&conn).unwrap(),
+            |(line_items, orders)| query_4_base(line_items, orders, &params),
             criterion::BatchSize::SmallInput,
         )
     });
 
     c.bench_function("query_4_hf", |b| {
         b.iter_batched(
-            || load_q4(&conn),
-            |(line_items, orders)| query_4_hf(line_items, orders),
+            || load_q4(&conn).unwrap(),
+            |(line_items, orders)| query_4_hf(line_items, orders, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_4_hf_set_join", |b| {
+        b.iter_batched(
+            || load_q4(&conn).unwrap(),
+            |(line_items, orders)| query_4_hf_set_join(line_items, orders, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_4_baseline_merge_join", |b| {
+        b.iter_batched(
+            || load_q4(&conn).unwrap(),
+            |(line_items, orders)| query_4_base_merge_join(line_items, orders, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_4_hf_merge_join", |b| {
+        b.iter_batched(
+            || load_q4(&conn).unwrap(),
+            |(line_items, orders)| query_4_hf_merge_join(line_items, orders, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_4_baseline_bloom", |b| {
+        b.iter_batched(
+            || load_q4(&conn).unwrap(),
+            |(line_items, orders)| query_4_base_bloom(line_items, orders, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_4_hf_bloom", |b| {
+        b.iter_batched(
+            || load_q4(&conn).unwrap(),
+            |(line_items, orders)| query_4_hf_bloom(line_items, orders, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_4_baseline_planned", |b| {
+        b.iter_batched(
+            || load_q4(&conn).unwrap(),
+            |(line_items, orders)| query_4_base_planned(line_items, orders, &params, &plan),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    // Set duckdb for benchmarking to single thread
+    let _ = conn.execute("SET threads = 1;", []);
+    c.bench_function("query_4_duckdb", |b| b.iter(|| query_4_duckdb(&conn, None, &params)));
+
+    let mut group = c.benchmark_group("query_4_params");
+    for seed in 0..3 {
+        let params = QGen::new(seed).query_4();
+        assert_match(&verify_query_4(&conn, &params));
+
+        group.bench_with_input(BenchmarkId::new("baseline", &params), &params, |b, params| {
+            b.iter_batched(
+                || load_q4(&conn).unwrap(),
+                |(line_items, orders)| query_4_base(line_items, orders, params),
+                criterion::BatchSize::SmallInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("hf", &params), &params, |b, params| {
+            b.iter_batched(
+                || load_q4(&conn).unwrap(),
+                |(line_items, orders)| query_4_hf(line_items, orders, params),
+                criterion::BatchSize::SmallInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("duckdb", &params), &params, |b, params| {
+            b.iter(|| query_4_duckdb(&conn, None, params))
+        });
+    }
+    group.finish();
+}
+
+/**
+ * Query 5 is a six-way join. Join order matters: the small dimension tables are the build sides.
+ */
+fn tpch_sf1_query_5(c: &mut Criterion) {
+    let scale_factor = 1;
+    let conn = initialize_database(scale_factor);
+
+    // Only benchmark once all implementations agree with DuckDB
+    assert_match(&verify_query_5(&conn));
+
+    c.bench_function("query_5_baseline", |b| {
+        b.iter_batched(
+            || load_q5(&conn),
+            |(customer, orders, line_items, supplier, nation, region)| {
+                query_5_base(customer, orders, line_items, supplier, nation, region)
+            },
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_5_hf", |b| {
+        b.iter_batched(
+            || load_q5(&conn),
+            |(customer, orders, line_items, supplier, nation, region)| {
+                query_5_hf(customer, orders, line_items, supplier, nation, region)
+            },
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    // Set duckdb for benchmarking to single thread
+    let _ = conn.execute("SET threads = 1;", []);
+    c.bench_function("query_5_duckdb", |b| b.iter(|| query_5_duckdb(&conn, None)));
+}
+
+/**
+ * Query 6 is a scan with a selective filter and a global sum. The chunked variants evaluate the
+ * predicates a chunk at a time, like vectorized_sum.
+ */
+fn tpch_sf1_query_6(c: &mut Criterion) {
+    let scale_factor = 1;
+    let conn = initialize_database(scale_factor);
+
+    // Only benchmark once all implementations agree with DuckDB
+    assert_match(&verify_query_6(&conn));
+
+    c.bench_function("query_6_baseline", |b| {
+        b.iter_batched(
+            || load_q6(&conn),
+            |line_items| query_6_base(line_items),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_6_baseline_chunks-256", |b| {
+        b.iter_batched(
+            || load_q6(&conn),
+            |line_items| query_6_base_chunks::<256>(line_items),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_6_baseline_chunks-2048", |b| {
+        b.iter_batched(
+            || load_q6(&conn),
+            |line_items| query_6_base_chunks::<2048>(line_items),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_6_hf", |b| {
+        b.iter_batched(
+            || load_q6(&conn),
+            |line_items| query_6_hf(line_items),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_6_hf_chunks-256", |b| {
+        b.iter_batched(
+            || load_q6(&conn),
+            |line_items| query_6_hf_chunks::<256>(line_items),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_6_hf_chunks-2048", |b| {
+        b.iter_batched(
+            || load_q6(&conn),
+            |line_items| query_6_hf_chunks::<2048>(line_items),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    // Set duckdb for benchmarking to single thread
+    let _ = conn.execute("SET threads = 1;", []);
+    c.bench_function("query_6_duckdb", |b| b.iter(|| query_6_duckdb(&conn, None)));
+}
+
+/**
+ * Query 10 aggregates before joining the wide customer rows and keeps the top 20.
+ * The sort variants collect all groups and sort them instead of keeping a bounded heap.
+ */
+fn tpch_sf1_query_10(c: &mut Criterion) {
+    let scale_factor = 1;
+    let conn = initialize_database(scale_factor);
+
+    // Only benchmark once all implementations agree with DuckDB
+    assert_match(&verify_query_10(&conn));
+
+    c.bench_function("query_10_baseline", |b| {
+        b.iter_batched(
+            || load_q10(&conn),
+            |(customer, orders, line_items, nation)| {
+                query_10_base(customer, orders, line_items, nation)
+            },
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_10_baseline_sort", |b| {
+        b.iter_batched(
+            || load_q10(&conn),
+            |(customer, orders, line_items, nation)| query_10_base_with(Strategy::Sort, customer, orders, line_items, nation),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_10_hf", |b| {
+        b.iter_batched(
+            || load_q10(&conn),
+            |(customer, orders, line_items, nation)| query_10_hf(customer, orders, line_items, nation),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_10_hf_sort", |b| {
+        b.iter_batched(
+            || load_q10(&conn),
+            |(customer, orders, line_items, nation)| query_10_hf_with(Strategy::Sort, customer, orders, line_items, nation),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    // Set duckdb for benchmarking to single thread
+    let _ = conn.execute("SET threads = 1;", []);
+    c.bench_function("query_10_duckdb", |b| b.iter(|| query_10_duckdb(&conn, None)));
+}
+
+/**
+ * Query 12 filters lineitem on date and ship mode predicates before the join with orders.
+ * The chunked variants filter lineitem a chunk at a time, the join stays per tuple.
+ */
+fn tpch_sf1_query_12(c: &mut Criterion) {
+    let scale_factor = 1;
+    let conn = initialize_database(scale_factor);
+
+    // Only benchmark once all implementations agree with DuckDB
+    assert_match(&verify_query_12(&conn));
+
+    c.bench_function("query_12_baseline", |b| {
+        b.iter_batched(
+            || load_q12(&conn),
+            |(orders, line_items)| query_12_base(orders, line_items),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_12_baseline_chunks-256", |b| {
+        b.iter_batched(
+            || load_q12(&conn),
+            |(orders, line_items)| query_12_base_chunks::<256>(orders, line_items),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_12_baseline_chunks-2048", |b| {
+        b.iter_batched(
+            || load_q12(&conn),
+            |(orders, line_items)| query_12_base_chunks::<2048>(orders, line_items),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_12_hf", |b| {
+        b.iter_batched(
+            || load_q12(&conn),
+            |(orders, line_items)| query_12_hf(orders, line_items),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_12_hf_chunks-256", |b| {
+        b.iter_batched(
+            || load_q12(&conn),
+            |(orders, line_items)| query_12_hf_chunks::<256>(orders, line_items),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_12_hf_chunks-2048", |b| {
+        b.iter_batched(
+            || load_q12(&conn),
+            |(orders, line_items)| query_12_hf_chunks::<2048>(orders, line_items),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    // Set duckdb for benchmarking to single thread
+    let _ = conn.execute("SET threads = 1;", []);
+    c.bench_function("query_12_duckdb", |b| b.iter(|| query_12_duckdb(&conn, None)));
+}
+
+/**
+ * Query 13 is a left outer join. Hydroflow builds it from a join, an anti join and a union.
+ */
+fn tpch_sf1_query_13(c: &mut Criterion) {
+    let scale_factor = 1;
+    let conn = initialize_database(scale_factor);
+
+    // Only benchmark once all implementations agree with DuckDB
+    assert_match(&verify_query_13(&conn));
+
+    c.bench_function("query_13_baseline", |b| {
+        b.iter_batched(
+            || load_q13(&conn),
+            |(customer, orders)| query_13_base(customer, orders),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_13_hf", |b| {
+        b.iter_batched(
+            || load_q13(&conn),
+            |(customer, orders)| query_13_hf(customer, orders),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    // Set duckdb for benchmarking to single thread
+    let _ = conn.execute("SET threads = 1;", []);
+    c.bench_function("query_13_duckdb", |b| b.iter(|| query_13_duckdb(&conn, None)));
+}
+
+/**
+ * Query 14 aggregates a promotion revenue ratio after the join with part.
+ * The chunked variants filter lineitem a chunk at a time, the join stays per tuple.
+ */
+fn tpch_sf1_query_14(c: &mut Criterion) {
+    let scale_factor = 1;
+    let conn = initialize_database(scale_factor);
+
+    // Only benchmark once all implementations agree with DuckDB
+    assert_match(&verify_query_14(&conn));
+
+    c.bench_function("query_14_baseline", |b| {
+        b.iter_batched(
+            || load_q14(&conn),
+            |(line_items, part)| query_14_base(line_items, part),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_14_baseline_chunks-256", |b| {
+        b.iter_batched(
+            || load_q14(&conn),
+            |(line_items, part)| query_14_base_chunks::<256>(line_items, part),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_14_baseline_chunks-2048", |b| {
+        b.iter_batched(
+            || load_q14(&conn),
+            |(line_items, part)| query_14_base_chunks::<2048>(line_items, part),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_14_hf", |b| {
+        b.iter_batched(
+            || load_q14(&conn),
+            |(line_items, part)| query_14_hf(line_items, part),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_14_hf_chunks-256", |b| {
+        b.iter_batched(
+            || load_q14(&conn),
+            |(line_items, part)| query_14_hf_chunks::<256>(line_items, part),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_14_hf_chunks-2048", |b| {
+        b.iter_batched(
+            || load_q14(&conn),
+            |(line_items, part)| query_14_hf_chunks::<2048>(line_items, part),
             criterion::BatchSize::SmallInput,
         )
     });
 
     // Set duckdb for benchmarking to single thread
     let _ = conn.execute("SET threads = 1;", []);
-    c.bench_function("query_4_duckdb", |b| b.iter(|| query_4_duckdb(&conn, None)));
+    c.bench_function("query_14_duckdb", |b| b.iter(|| query_14_duckdb(&conn, None)));
+}
+
+/**
+ * Query 16 runs NOT IN as an anti join and counts distinct suppliers per group.
+ */
+fn tpch_sf1_query_16(c: &mut Criterion) {
+    let scale_factor = 1;
+    let conn = initialize_database(scale_factor);
+
+    // Only benchmark once all implementations agree with DuckDB
+    assert_match(&verify_query_16(&conn));
+
+    c.bench_function("query_16_baseline", |b| {
+        b.iter_batched(
+            || load_q16(&conn),
+            |(partsupp, part, supplier)| query_16_base(partsupp, part, supplier),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_16_hf", |b| {
+        b.iter_batched(
+            || load_q16(&conn),
+            |(partsupp, part, supplier)| query_16_hf(partsupp, part, supplier),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    // Set duckdb for benchmarking to single thread
+    let _ = conn.execute("SET threads = 1;", []);
+    c.bench_function("query_16_duckdb", |b| b.iter(|| query_16_duckdb(&conn, None)));
+}
+
+/**
+ * Query 17 decorrelates the avg(l_quantity) subquery into a group by on l_partkey.
+ */
+fn tpch_sf1_query_17(c: &mut Criterion) {
+    let scale_factor = 1;
+    let conn = initialize_database(scale_factor);
+
+    // Only benchmark once all implementations agree with DuckDB
+    assert_match(&verify_query_17(&conn));
+
+    c.bench_function("query_17_baseline", |b| {
+        b.iter_batched(
+            || load_q17(&conn),
+            |(line_items, part)| query_17_base(line_items, part),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_17_hf", |b| {
+        b.iter_batched(
+            || load_q17(&conn),
+            |(line_items, part)| query_17_hf(line_items, part),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    // Set duckdb for benchmarking to single thread
+    let _ = conn.execute("SET threads = 1;", []);
+    c.bench_function("query_17_duckdb", |b| b.iter(|| query_17_duckdb(&conn, None)));
+}
+
+/**
+ * Query 18 decorrelates the IN subquery into a group by on l_orderkey and keeps the top 100 orders.
+ * The sort variants collect all groups and sort them instead of keeping a bounded heap.
+ */
+fn tpch_sf1_query_18(c: &mut Criterion) {
+    let scale_factor = 1;
+    let conn = initialize_database(scale_factor);
+
+    // Only benchmark once all implementations agree with DuckDB
+    assert_match(&verify_query_18(&conn));
+
+    c.bench_function("query_18_baseline", |b| {
+        b.iter_batched(
+            || load_q18(&conn),
+            |(customer, orders, line_items)| query_18_base(customer, orders, line_items),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_18_baseline_sort", |b| {
+        b.iter_batched(
+            || load_q18(&conn),
+            |(customer, orders, line_items)| query_18_base_with(Strategy::Sort, customer, orders, line_items),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_18_hf", |b| {
+        b.iter_batched(
+            || load_q18(&conn),
+            |(customer, orders, line_items)| query_18_hf(customer, orders, line_items),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_18_hf_sort", |b| {
+        b.iter_batched(
+            || load_q18(&conn),
+            |(customer, orders, line_items)| query_18_hf_with(Strategy::Sort, customer, orders, line_items),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    // Set duckdb for benchmarking to single thread
+    let _ = conn.execute("SET threads = 1;", []);
+    c.bench_function("query_18_duckdb", |b| b.iter(|| query_18_duckdb(&conn, None)));
 }
 
 /**
  * Query 19 is well suited for vectorization and should be faster with DuckDB.
+ * The query_19_params group repeats the benchmark with QUANTITY and BRAND drawn by qgen.
+ * The _decimal variants use exact `Decimal` arithmetic instead of `f64`.
+ * The _columns variants probe `LineItemColumns` in column chunks with a selection vector.
+ * The _merge_join variants sort both sides by the part key, lineitem is not generated in that order.
+ * The _bloom variants drop line items with a Bloom filter of the qualifying parts before the join.
+ * query_19_baseline_planned builds on the side chosen from DuckDB's statistics, the plan is printed.
  */
 fn tpch_sf1_query_19(c: &mut Criterion) {
     let scale_factor = 1;
     let conn = initialize_database(scale_factor);
 
+    // Only benchmark once all implementations agree with DuckDB
+    let params = Q19Params::default();
+    assert_match(&verify_query_19(&conn, &params));
+
+    let plan = query_19_plan(&conn, &params, 1);
+    println!("Query 19 plan: {}", plan);
+
     c.bench_function("query_19_baseline", |b| {
         b.iter_batched(
-            || load_q19(&conn),
-            |(line_items, part)| query_19_base(line_items, part),
+            || load_q19(&conn).unwrap(),
+            |(line_items, part)| query_19_base(line_items, part, &params),
             criterion::BatchSize::SmallInput,
         )
     });
 
     c.bench_function("query_19_hf", |b| {
         b.iter_batched(
-            || load_q19(&conn),
-            |(line_items, part)| query_19_hf(line_items, part),
+            || load_q19(&conn).unwrap(),
+            |(line_items, part)| query_19_hf(line_items, part, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_19_baseline_merge_join", |b| {
+        b.iter_batched(
+            || load_q19(&conn).unwrap(),
+            |(line_items, part)| query_19_base_merge_join(line_items, part, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_19_hf_merge_join", |b| {
+        b.iter_batched(
+            || load_q19(&conn).unwrap(),
+            |(line_items, part)| query_19_hf_merge_join(line_items, part, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_19_baseline_bloom", |b| {
+        b.iter_batched(
+            || load_q19(&conn).unwrap(),
+            |(line_items, part)| query_19_base_bloom(line_items, part, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_19_hf_bloom", |b| {
+        b.iter_batched(
+            || load_q19(&conn).unwrap(),
+            |(line_items, part)| query_19_hf_bloom(line_items, part, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_19_baseline_planned", |b| {
+        b.iter_batched(
+            || load_q19(&conn).unwrap(),
+            |(line_items, part)| query_19_base_planned(line_items, part, &params, &plan),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_19_baseline_columns-256", |b| {
+        b.iter_batched(
+            || load_q19_columns(&conn).unwrap(),
+            |(line_items, part)| query_19_base_columns::<256>(line_items, part, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_19_baseline_columns-2048", |b| {
+        b.iter_batched(
+            || load_q19_columns(&conn).unwrap(),
+            |(line_items, part)| query_19_base_columns::<2048>(line_items, part, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_19_hf_columns-256", |b| {
+        b.iter_batched(
+            || load_q19_columns(&conn).unwrap(),
+            |(line_items, part)| query_19_hf_columns::<256>(line_items, part, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_19_hf_columns-2048", |b| {
+        b.iter_batched(
+            || load_q19_columns(&conn).unwrap(),
+            |(line_items, part)| query_19_hf_columns::<2048>(line_items, part, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    assert_match(&verify_query_19_decimal(&conn, &params));
+    c.bench_function("query_19_baseline_decimal", |b| {
+        b.iter_batched(
+            || load_q19_decimal(&conn).unwrap(),
+            |(line_items, part)| query_19_base_generic(line_items, part, &params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_19_hf_decimal", |b| {
+        b.iter_batched(
+            || load_q19_decimal(&conn).unwrap(),
+            |(line_items, part)| query_19_hf_decimal(line_items, part, &params),
             criterion::BatchSize::SmallInput,
         )
     });
@@ -98,14 +960,236 @@ fn tpch_sf1_query_19(c: &mut Criterion) {
     // Set duckdb for benchmarking to single thread
     let _ = conn.execute("SET threads = 1;", []);
     c.bench_function("query_19_duckdb", |b| {
-        b.iter(|| query_19_duckdb(&conn, None))
+        b.iter(|| query_19_duckdb(&conn, None, &params))
+    });
+
+    let mut group = c.benchmark_group("query_19_params");
+    for seed in 0..3 {
+        let params = QGen::new(seed).query_19();
+        assert_match(&verify_query_19(&conn, &params));
+
+        group.bench_with_input(BenchmarkId::new("baseline", &params), &params, |b, params| {
+            b.iter_batched(
+                || load_q19(&conn).unwrap(),
+                |(line_items, part)| query_19_base(line_items, part, params),
+                criterion::BatchSize::SmallInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("hf", &params), &params, |b, params| {
+            b.iter_batched(
+                || load_q19(&conn).unwrap(),
+                |(line_items, part)| query_19_hf(line_items, part, params),
+                criterion::BatchSize::SmallInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("duckdb", &params), &params, |b, params| {
+            b.iter(|| query_19_duckdb(&conn, None, params))
+        });
+    }
+    group.finish();
+}
+
+/**
+ * Query 20 turns the nested IN subqueries into semi joins and the correlated sum into a group by.
+ */
+fn tpch_sf1_query_20(c: &mut Criterion) {
+    let scale_factor = 1;
+    let conn = initialize_database(scale_factor);
+
+    // Only benchmark once all implementations agree with DuckDB
+    assert_match(&verify_query_20(&conn));
+
+    c.bench_function("query_20_baseline", |b| {
+        b.iter_batched(
+            || load_q20(&conn),
+            |(supplier, nation, partsupp, part, line_items)| query_20_base(supplier, nation, partsupp, part, line_items),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_20_hf", |b| {
+        b.iter_batched(
+            || load_q20(&conn),
+            |(supplier, nation, partsupp, part, line_items)| query_20_hf(supplier, nation, partsupp, part, line_items),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    // Set duckdb for benchmarking to single thread
+    let _ = conn.execute("SET threads = 1;", []);
+    c.bench_function("query_20_duckdb", |b| b.iter(|| query_20_duckdb(&conn, None)));
+}
+
+/**
+ * Query 21 decorrelates EXISTS and NOT EXISTS on lineitem into a group by l_orderkey.
+ */
+fn tpch_sf1_query_21(c: &mut Criterion) {
+    let scale_factor = 1;
+    let conn = initialize_database(scale_factor);
+
+    // Only benchmark once all implementations agree with DuckDB
+    assert_match(&verify_query_21(&conn));
+
+    c.bench_function("query_21_baseline", |b| {
+        b.iter_batched(
+            || load_q21(&conn),
+            |(supplier, line_items, orders, nation)| query_21_base(supplier, line_items, orders, nation),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_21_hf", |b| {
+        b.iter_batched(
+            || load_q21(&conn),
+            |(supplier, line_items, orders, nation)| query_21_hf(supplier, line_items, orders, nation),
+            criterion::BatchSize::SmallInput,
+        )
     });
+
+    // Set duckdb for benchmarking to single thread
+    let _ = conn.execute("SET threads = 1;", []);
+    c.bench_function("query_21_duckdb", |b| b.iter(|| query_21_duckdb(&conn, None)));
+}
+
+/**
+ * Query 22 computes the uncorrelated average once and runs NOT EXISTS as an anti join.
+ */
+fn tpch_sf1_query_22(c: &mut Criterion) {
+    let scale_factor = 1;
+    let conn = initialize_database(scale_factor);
+
+    // Only benchmark once all implementations agree with DuckDB
+    assert_match(&verify_query_22(&conn));
+
+    c.bench_function("query_22_baseline", |b| {
+        b.iter_batched(
+            || load_q22(&conn),
+            |(customer, orders)| query_22_base(customer, orders),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("query_22_hf", |b| {
+        b.iter_batched(
+            || load_q22(&conn),
+            |(customer, orders)| query_22_hf(customer, orders),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    // Set duckdb for benchmarking to single thread
+    let _ = conn.execute("SET threads = 1;", []);
+    c.bench_function("query_22_duckdb", |b| b.iter(|| query_22_duckdb(&conn, None)));
+}
+
+/**
+ * The morsel-driven baselines and the partitioned Hydroflow graphs against DuckDB at the same
+ * number of threads. The other benchmarks run DuckDB on a single thread to match the
+ * single-threaded baselines.
+ */
+fn tpch_sf1_threads(c: &mut Criterion) {
+    let scale_factor = 1;
+    let conn = initialize_database(scale_factor);
+    let thread_counts = [1, 2, 4, 8];
+
+    let params = Q1Params::default();
+    assert_match(&verify_query_1(&conn, &params));
+    let mut group = c.benchmark_group("query_1_threads");
+    for threads in thread_counts {
+        let parallelism = Parallelism::new(threads);
+        group.bench_with_input(BenchmarkId::new("baseline", threads), &parallelism, |b, parallelism| {
+            b.iter_batched(
+                || load_q1(&conn).unwrap(),
+                |line_items| query_1_base_parallel(line_items, &params, parallelism),
+                criterion::BatchSize::SmallInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("hf", threads), &threads, |b, &threads| {
+            b.iter_batched(
+                || load_q1(&conn).unwrap(),
+                |line_items| query_1_hf_partitioned(line_items, &params, threads),
+                criterion::BatchSize::SmallInput,
+            )
+        });
+        let _ = conn.execute(&format!("SET threads = {};", threads), []);
+        group.bench_with_input(BenchmarkId::new("duckdb", threads), &threads, |b, _| {
+            b.iter(|| query_1_duckdb(&conn, None, &params))
+        });
+    }
+    group.finish();
+
+    let params = Q4Params::default();
+    assert_match(&verify_query_4(&conn, &params));
+    let mut group = c.benchmark_group("query_4_threads");
+    for threads in thread_counts {
+        let parallelism = Parallelism::new(threads);
+        group.bench_with_input(BenchmarkId::new("baseline", threads), &parallelism, |b, parallelism| {
+            b.iter_batched(
+                || load_q4(&conn).unwrap(),
+                |(line_items, orders)| query_4_base_parallel(line_items, orders, &params, parallelism),
+                criterion::BatchSize::SmallInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("hf", threads), &threads, |b, &threads| {
+            b.iter_batched(
+                || load_q4(&conn).unwrap(),
+                |(line_items, orders)| query_4_hf_partitioned(line_items, orders, &params, threads),
+                criterion::BatchSize::SmallInput,
+            )
+        });
+        let _ = conn.execute(&format!("SET threads = {};", threads), []);
+        group.bench_with_input(BenchmarkId::new("duckdb", threads), &threads, |b, _| {
+            b.iter(|| query_4_duckdb(&conn, None, &params))
+        });
+    }
+    group.finish();
+
+    let params = Q19Params::default();
+    assert_match(&verify_query_19(&conn, &params));
+    let mut group = c.benchmark_group("query_19_threads");
+    for threads in thread_counts {
+        let parallelism = Parallelism::new(threads);
+        group.bench_with_input(BenchmarkId::new("baseline", threads), &parallelism, |b, parallelism| {
+            b.iter_batched(
+                || load_q19(&conn).unwrap(),
+                |(line_items, part)| query_19_base_parallel(line_items, part, &params, parallelism),
+                criterion::BatchSize::SmallInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("hf", threads), &threads, |b, &threads| {
+            b.iter_batched(
+                || load_q19(&conn).unwrap(),
+                |(line_items, part)| query_19_hf_partitioned(line_items, part, &params, threads),
+                criterion::BatchSize::SmallInput,
+            )
+        });
+        let _ = conn.execute(&format!("SET threads = {};", threads), []);
+        group.bench_with_input(BenchmarkId::new("duckdb", threads), &threads, |b, _| {
+            b.iter(|| query_19_duckdb(&conn, None, &params))
+        });
+    }
+    group.finish();
 }
 
 criterion_group!(
     benches,
     tpch_sf1_query_1,
+    tpch_sf1_query_2,
+    tpch_sf1_query_3,
     tpch_sf1_query_4,
+    tpch_sf1_query_5,
+    tpch_sf1_query_6,
+    tpch_sf1_query_10,
+    tpch_sf1_query_12,
+    tpch_sf1_query_13,
+    tpch_sf1_query_14,
+    tpch_sf1_query_16,
+    tpch_sf1_query_17,
+    tpch_sf1_query_18,
     tpch_sf1_query_19,
+    tpch_sf1_query_20,
+    tpch_sf1_query_21,
+    tpch_sf1_query_22,
+    tpch_sf1_threads,
 );
 criterion_main!(benches);
@@ -0,0 +1,122 @@
+use base::tpch::initialize::initialize_database;
+use base::tpch::verify::{assert_match, compare, Tolerance};
+use base::tpch::{query_1, query_19, query_4, shared_scan, tbl};
+use criterion::{criterion_group, criterion_main, Criterion};
+use hydroflow_base::tpch::query_1::{query as query_1_hf, query_scan as query_1_hf_scan};
+use hydroflow_base::tpch::query_19::{query as query_19_hf, query_scan as query_19_hf_scan};
+use hydroflow_base::tpch::query_4::{query as query_4_hf, query_scan as query_4_hf_scan};
+use hydroflow_base::tpch::shared_scan::query as shared_scan_hf;
+
+/**
+ * Loading the query inputs row by row with `query_map` against whole Arrow record batches with
+ * `query_arrow`, and parsing the same tables from `.tbl` files without DuckDB. All produce the same
+ * row structs.
+ */
+fn tpch_sf1_load(c: &mut Criterion) {
+    let scale_factor = 1;
+    let conn = initialize_database(scale_factor);
+    let dir = std::env::temp_dir().join("hydro_benchmarks_tpch_tbl_sf_1");
+    for table in ["lineitem", "orders", "part"] {
+        if !tbl::path(&dir, table).exists() {
+            tbl::export(&conn, table, &dir);
+        }
+    }
+
+    c.bench_function("load_query_1_rows", |b| b.iter(|| query_1::load(&conn).unwrap()));
+    c.bench_function("load_query_1_arrow", |b| b.iter(|| query_1::load_arrow(&conn).unwrap()));
+    c.bench_function("load_query_1_tbl", |b| b.iter(|| query_1::load_tbl(&dir).unwrap()));
+
+    c.bench_function("load_query_4_rows", |b| b.iter(|| query_4::load(&conn).unwrap()));
+    c.bench_function("load_query_4_arrow", |b| b.iter(|| query_4::load_arrow(&conn).unwrap()));
+    c.bench_function("load_query_4_tbl", |b| b.iter(|| query_4::load_tbl(&dir).unwrap()));
+
+    c.bench_function("load_query_19_rows", |b| b.iter(|| query_19::load(&conn).unwrap()));
+    c.bench_function("load_query_19_arrow", |b| b.iter(|| query_19::load_arrow(&conn).unwrap()));
+    c.bench_function("load_query_19_tbl", |b| b.iter(|| query_19::load_tbl(&dir).unwrap()));
+}
+
+/**
+ * End-to-end time of the Hydroflow queries including the load. The materialized variants load the
+ * whole input with `query_arrow` before the graph starts, the scan variants stream the same record
+ * batches into the graph while it runs, see `hydroflow_base::tpch::scan`.
+ */
+fn tpch_sf1_end_to_end(c: &mut Criterion) {
+    let scale_factor = 1;
+    let conn = initialize_database(scale_factor);
+
+    let params = query_1::QueryParams::default();
+    let expected = query_1::query_duckdb(&conn, None, &params);
+    assert_match(&[compare("Q1", "duckdb", "hydroflow-scan", &expected, &query_1_hf_scan(&conn, &params), &Tolerance::default())]);
+    let mut group = c.benchmark_group("query_1_end_to_end");
+    group.bench_function("materialized", |b| b.iter(|| query_1_hf(query_1::load_arrow(&conn).unwrap(), &params)));
+    group.bench_function("scan", |b| b.iter(|| query_1_hf_scan(&conn, &params)));
+    group.finish();
+
+    let params = query_4::QueryParams::default();
+    let expected = query_4::query_duckdb(&conn, None, &params);
+    assert_match(&[compare("Q4", "duckdb", "hydroflow-scan", &expected, &query_4_hf_scan(&conn, &params), &Tolerance::default())]);
+    let mut group = c.benchmark_group("query_4_end_to_end");
+    group.bench_function("materialized", |b| {
+        b.iter(|| {
+            let (line_items, orders) = query_4::load_arrow(&conn).unwrap();
+            query_4_hf(line_items, orders, &params)
+        })
+    });
+    group.bench_function("scan", |b| b.iter(|| query_4_hf_scan(&conn, &params)));
+    group.finish();
+
+    let params = query_19::QueryParams::default();
+    let expected = query_19::query_duckdb(&conn, None, &params);
+    assert_match(&[compare("Q19", "duckdb", "hydroflow-scan", &expected, &query_19_hf_scan(&conn, &params), &Tolerance::default())]);
+    let mut group = c.benchmark_group("query_19_end_to_end");
+    group.bench_function("materialized", |b| {
+        b.iter(|| {
+            let (line_items, part) = query_19::load_arrow(&conn).unwrap();
+            query_19_hf(line_items, part, &params)
+        })
+    });
+    group.bench_function("scan", |b| b.iter(|| query_19_hf_scan(&conn, &params)));
+    group.finish();
+}
+
+/**
+ * Query 1, 4 and 19 together, including the load. The sequential variant runs the three graphs one
+ * after another and scans lineitem once per query, the shared variant scans lineitem once and fans
+ * it out to all three queries in one graph, see `hydroflow_base::tpch::shared_scan`.
+ */
+fn tpch_sf1_shared_scan(c: &mut Criterion) {
+    let scale_factor = 1;
+    let conn = initialize_database(scale_factor);
+    let params_1 = query_1::QueryParams::default();
+    let params_4 = query_4::QueryParams::default();
+    let params_19 = query_19::QueryParams::default();
+
+    let (line_items, orders, part) = shared_scan::load_arrow(&conn).unwrap();
+    let result = shared_scan_hf(line_items, orders, part, &params_1, &params_4, &params_19);
+    let tolerance = Tolerance::default();
+    assert_match(&[compare("Q1", "duckdb", "hydroflow-shared-scan", &query_1::query_duckdb(&conn, None, &params_1), &result.q1, &tolerance)]);
+    assert_match(&[compare("Q4", "duckdb", "hydroflow-shared-scan", &query_4::query_duckdb(&conn, None, &params_4), &result.q4, &tolerance)]);
+    assert_match(&[compare("Q19", "duckdb", "hydroflow-shared-scan", &query_19::query_duckdb(&conn, None, &params_19), &result.q19, &tolerance)]);
+
+    let mut group = c.benchmark_group("shared_scan");
+    group.bench_function("sequential", |b| {
+        b.iter(|| {
+            let q1 = query_1_hf(query_1::load_arrow(&conn).unwrap(), &params_1);
+            let (line_items, orders) = query_4::load_arrow(&conn).unwrap();
+            let q4 = query_4_hf(line_items, orders, &params_4);
+            let (line_items, part) = query_19::load_arrow(&conn).unwrap();
+            let q19 = query_19_hf(line_items, part, &params_19);
+            (q1, q4, q19)
+        })
+    });
+    group.bench_function("shared", |b| {
+        b.iter(|| {
+            let (line_items, orders, part) = shared_scan::load_arrow(&conn).unwrap();
+            shared_scan_hf(line_items, orders, part, &params_1, &params_4, &params_19)
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, tpch_sf1_load, tpch_sf1_end_to_end, tpch_sf1_shared_scan);
+criterion_main!(benches);
@@ -0,0 +1,88 @@
+use base::tpch::initialize::initialize_in_memory;
+use base::tpch::refresh::{self, Delta, RefreshStream};
+use base::tpch::scale_factor::ScaleFactor;
+use base::tpch::verify::{assert_match, compare, Tolerance};
+use base::tpch::{query_1, query_4};
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use hydroflow_base::tpch::query_1::{query as query_1_hf, query_incremental as query_1_incremental};
+use hydroflow_base::tpch::query_4::{query as query_4_hf, query_incremental as query_4_incremental};
+
+/**
+ * The answer after one refresh pair (RF1 + RF2): the incremental graphs only process the rows the
+ * pair inserts and deletes on top of the state of the initial load, recomputing runs the query on
+ * the whole refreshed tables. Loading is not measured for either.
+ */
+fn tpch_sf1_refresh(c: &mut Criterion) {
+    let scale_factor = ScaleFactor::from(1);
+    let conn = initialize_in_memory(scale_factor);
+    let q1_params = query_1::QueryParams::default();
+    let q4_params = query_4::QueryParams::default();
+
+    // The tables before and the rows of one refresh pair.
+    let q1_base = query_1::load(&conn).unwrap();
+    let q4_base = query_4::load(&conn).unwrap();
+    RefreshStream::new(scale_factor).stage(&conn);
+    let q1_delta = query_1::load_refresh(&conn).unwrap();
+    let q4_delta = query_4::load_refresh(&conn).unwrap();
+    refresh::apply(&conn);
+
+    // Only benchmark once the incremental answers agree with DuckDB on the refreshed tables.
+    let mut query = query_1_incremental(&q1_params);
+    query.refresh(Delta::insert(q1_base.clone()));
+    let result = query.refresh(q1_delta.clone());
+    let expected = query_1::query_duckdb(&conn, None, &q1_params);
+    assert_match(&[compare("Q1", "duckdb", "hydroflow-incremental", &expected, &result, &Tolerance::default())]);
+
+    let mut query = query_4_incremental(&q4_params);
+    query.refresh((Delta::insert(q4_base.0.clone()), Delta::insert(q4_base.1.clone())));
+    let result = query.refresh(q4_delta.clone());
+    let expected = query_4::query_duckdb(&conn, None, &q4_params);
+    assert_match(&[compare("Q4", "duckdb", "hydroflow-incremental", &expected, &result, &Tolerance::default())]);
+
+    let mut group = c.benchmark_group("query_1_refresh");
+    group.throughput(Throughput::Elements(q1_delta.len() as u64));
+    group.bench_function("incremental", |b| {
+        b.iter_batched(
+            || {
+                let mut query = query_1_incremental(&q1_params);
+                query.refresh(Delta::insert(q1_base.clone()));
+                (query, q1_delta.clone())
+            },
+            |(mut query, delta)| query.refresh(delta),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("recompute", |b| {
+        b.iter_batched(
+            || query_1::load(&conn).unwrap(),
+            |line_items| query_1_hf(line_items, &q1_params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("query_4_refresh");
+    group.throughput(Throughput::Elements((q4_delta.0.len() + q4_delta.1.len()) as u64));
+    group.bench_function("incremental", |b| {
+        b.iter_batched(
+            || {
+                let mut query = query_4_incremental(&q4_params);
+                query.refresh((Delta::insert(q4_base.0.clone()), Delta::insert(q4_base.1.clone())));
+                (query, q4_delta.clone())
+            },
+            |(mut query, delta)| query.refresh(delta),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("recompute", |b| {
+        b.iter_batched(
+            || query_4::load(&conn).unwrap(),
+            |(line_items, orders)| query_4_hf(line_items, orders, &q4_params),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, tpch_sf1_refresh);
+criterion_main!(benches);
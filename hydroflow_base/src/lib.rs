@@ -2,6 +2,6 @@ pub mod tpcc;
 //pub mod kmeans_hf;
 //pub mod matrix_vector_multiply;
 //pub mod vectorized_sum;
-//pub mod tpch;
+pub mod tpch;
 //pub mod nexmark;
 //pub mod cron;
\ No newline at end of file
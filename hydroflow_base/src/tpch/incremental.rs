@@ -0,0 +1,35 @@
+//! Incrementally maintained queries.
+//!
+//! An `Incremental` owns a query graph that keeps its state across ticks, e.g. in a `'static`
+//! aggregate or a `persist_mut_keyed()`. Every refresh batch sent in runs as one tick, at the end of
+//! which the graph emits the updated answer. The initial load is an insert-only batch, see
+//! `base::tpch::refresh::Delta::insert`.
+
+use hydroflow::scheduled::graph::Hydroflow;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+
+pub struct Incremental<I, O> {
+    flow: Hydroflow<'static>,
+    refresh_send: UnboundedSender<I>,
+    output_recv: UnboundedReceiver<O>,
+}
+
+impl<I, O> Incremental<I, O> {
+    /// `flow` reads the batches sent to `refresh_send` and sends the answer rows of every tick to
+    /// the sender of `output_recv`.
+    pub fn new(flow: Hydroflow<'static>, refresh_send: UnboundedSender<I>, output_recv: UnboundedReceiver<O>) -> Self {
+        Incremental { flow, refresh_send, output_recv }
+    }
+
+    /// Apply one batch and return the answer after it.
+    pub fn refresh(&mut self, batch: I) -> Vec<O> {
+        self.refresh_send.send(batch).unwrap_or_else(|_| panic!("Query graph dropped its input"));
+        self.flow.run_available();
+
+        let mut answer = vec![];
+        while let Ok(row) = self.output_recv.try_recv() {
+            answer.push(row);
+        }
+        answer
+    }
+}
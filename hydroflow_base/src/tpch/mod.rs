@@ -1,3 +1,23 @@
 pub mod query_1;
+pub mod query_2;
+pub mod query_3;
 pub mod query_4;
-pub mod query_19;
\ No newline at end of file
+pub mod query_5;
+pub mod query_6;
+pub mod query_10;
+pub mod query_12;
+pub mod query_13;
+pub mod query_14;
+pub mod query_16;
+pub mod query_17;
+pub mod query_18;
+pub mod query_19;
+pub mod query_20;
+pub mod query_21;
+pub mod query_22;
+pub mod partitioned;
+pub mod incremental;
+pub mod scan;
+pub mod semi_join;
+pub mod shared_scan;
+pub mod verify;
\ No newline at end of file
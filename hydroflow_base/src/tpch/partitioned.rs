@@ -0,0 +1,102 @@
+//! Multi-threaded execution of the query graphs.
+//!
+//! The input is split into one partition per thread, by range where any split works, e.g. for the
+//! aggregation of Query 1, or by hash of the join key so that matching tuples of both inputs end up
+//! in the same partition. Every thread builds and runs its own instance of the query graph on its
+//! partition and sends the partial results, e.g. partial aggregates, into a shared channel. A merge
+//! graph on the calling thread reads the channel and combines the partial results.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::thread;
+
+use tokio::sync::mpsc::UnboundedSender;
+
+/// The partition of `key` among `partitions`, the same for equal keys of both join inputs.
+pub fn partition_of<K: Hash>(key: &K, partitions: usize) -> usize {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    (hasher.finish() % partitions as u64) as usize
+}
+
+/// Split `items` into `partitions` contiguous ranges of nearly equal length.
+pub fn range_partition<T>(items: Vec<T>, partitions: usize) -> Vec<Vec<T>> {
+    assert!(partitions > 0, "Need at least one partition");
+    let len = items.len();
+    let mut items = items.into_iter();
+    (0..partitions)
+        .map(|i| {
+            let size = len / partitions + usize::from(i < len % partitions);
+            items.by_ref().take(size).collect()
+        })
+        .collect()
+}
+
+/// Split `items` into `partitions` by hash of `key`, see `partition_of`.
+pub fn hash_partition<T, K: Hash>(items: Vec<T>, partitions: usize, key: impl Fn(&T) -> K) -> Vec<Vec<T>> {
+    assert!(partitions > 0, "Need at least one partition");
+    let mut result: Vec<Vec<T>> = (0..partitions).map(|_| vec![]).collect();
+    for item in items {
+        result[partition_of(&key(&item), partitions)].push(item);
+    }
+    result
+}
+
+/**
+ * Run `graph` on every partition on its own scoped thread. `graph` builds and runs the query graph
+ * of one partition and sends its partial results to `partial_send`. Returns once all graphs have
+ * run, so the receiving end holds every partial result.
+ */
+pub fn run_partitioned<I, P, G>(partitions: Vec<I>, partial_send: &UnboundedSender<P>, graph: G)
+where
+    I: Send,
+    P: Send,
+    G: Fn(I, UnboundedSender<P>) + Sync,
+{
+    thread::scope(|s| {
+        let handles: Vec<_> = partitions
+            .into_iter()
+            .map(|partition| {
+                let partial_send = partial_send.clone();
+                let graph = &graph;
+                s.spawn(move || graph(partition, partial_send))
+            })
+            .collect();
+        for handle in handles {
+            handle.join().expect("Partition graph panicked");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range_partition() {
+        let partitions = range_partition((0..10).collect(), 3);
+        assert_eq!(partitions, vec![vec![0, 1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]]);
+        assert_eq!(range_partition(Vec::<i32>::new(), 2), vec![vec![], vec![]]);
+    }
+
+    #[test]
+    fn test_hash_partition() {
+        let partitions = hash_partition((0..1000).collect(), 4, |x: &i64| x % 100);
+        assert_eq!(partitions.len(), 4);
+        assert_eq!(partitions.iter().map(Vec::len).sum::<usize>(), 1000);
+        for (i, partition) in partitions.iter().enumerate() {
+            assert!(partition.iter().all(|x| partition_of(&(x % 100), 4) == i));
+        }
+    }
+
+    #[test]
+    fn test_run_partitioned() {
+        let (partial_send, partial_recv) = hydroflow::util::unbounded_channel::<i64>();
+        run_partitioned(range_partition((1..=100).collect(), 4), &partial_send, |partition: Vec<i64>, send| {
+            send.send(partition.iter().sum()).unwrap();
+        });
+        let partials = hydroflow::util::collect_ready::<Vec<i64>, _>(partial_recv);
+        assert_eq!(partials.len(), 4);
+        assert_eq!(partials.iter().sum::<i64>(), 5050);
+    }
+}
@@ -1,49 +1,69 @@
-use chrono::NaiveDate;
+use std::collections::HashMap;
+use std::path::Path;
+
+use duckdb::Connection;
+use hydroflow::futures::stream::{self, Stream};
 use hydroflow::hydroflow_syntax;
 
-use base::tpch::query_1::{LineItem, LineItem2, LineItemAgg1, LineItemAgg2, query as query_base_original};
+use base::tpch::decimal::{Decimal, Numeric};
+use base::tpch::tbl::{self, TblError};
+use base::tpch::query_1::{aggregate_chunk, select_chunk, LineItem, LineItem2, LineItemAgg1, LineItemAgg2, LineItemChunk, LineItemColumns, Q1Row, QueryParams, query as query_base_original};
+use base::tpch::refresh::Delta;
+
+use super::incremental::Incremental;
+use super::partitioned::{range_partition, run_partitioned};
+use super::scan::{run_to_end, scan, Ends};
+
+pub fn query(line_items: Vec<LineItem>, params: &QueryParams) -> Vec<Q1Row> {
+    graph(line_items, params)
+}
+
+/// The graph of `query` and `query_decimal`, in the arithmetic of `N`.
+fn graph<N: Numeric>(line_items: Vec<LineItem<N>>, params: &QueryParams) -> Vec<Q1Row<N>> {
+    let shipdate = params.shipdate();
 
-pub fn query(line_items: Vec<LineItem>) {
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Q1Row<N>>();
 
     let mut flow = hydroflow_syntax! {
         // 1. Scan from lineitem: "l_returnflag", "l_linestatus", "l_quantity", "l_extendedprice", "l_discount", "l_tax", "l_shipdate"
         line_items = source_iter(line_items);
 
-        // 2. Filter l_shipdate <= date '1998-12-01' - interval '90' day i.e., l_shipdate <= '1998-09-02'.
+        // 2. Filter l_shipdate <= date '1998-12-01' - interval DELTA day, i.e., l_shipdate <= '1998-09-02' for DELTA = 90.
         // (2.1 Evaluate expression.)
         // 2.2. Filter on the expression. Need everything apart from l_shipdate.
-        line_items_filtered = line_items -> filter(|x| x.l_shipdate <= NaiveDate::from_ymd_opt(1998, 9, 2).unwrap());
+        line_items_filtered = line_items -> filter(|x| x.l_shipdate <= shipdate);
 
         // 3. Evaluate expressions for the aggregations.
         // XXX: Skipping projection of l_tax, could drop that column.
         line_items_proj = line_items_filtered -> map(|x| {
             // Project the fields.
-            let x: LineItem2 = x.into();
+            let x: LineItem2<N> = x.into();
             // l_extendedprice * (1 - l_discount) AS disc_price,
-            let disc_price = x.l_extendedprice * (1.0 - x.l_discount);
+            let disc_price = x.l_extendedprice * (N::one() - x.l_discount);
             // l_extendedprice * (1 - l_discount) * (1 + l_tax) AS charge,
-            let charge = disc_price * (1.0 + x.l_tax);
+            let charge = disc_price * (N::one() + x.l_tax);
             return (x, disc_price, charge)
         });
 
         // 4. Group by l_returnflag, l_linestatus & compute aggregates.
         agg = line_items_proj -> map(|(x, disc_price, charge)| {
                 // Group by l_returnflag, l_linestatus
-                ((x.l_returnflag, x.l_linestatus), (x.l_quantity, x.l_extendedprice, disc_price, charge))
+                ((x.l_returnflag, x.l_linestatus), (x.l_quantity, x.l_extendedprice, x.l_discount, disc_price, charge))
             })
-            -> fold_keyed(Default::default, |acc: &mut LineItemAgg1, x| {
+            -> fold_keyed(Default::default, |acc: &mut LineItemAgg1<N>, x| {
                 // Hash aggregate without average
-                let (l_quantity, l_extendedprice, disc_price, charge) = x;
+                let (l_quantity, l_extendedprice, l_discount, disc_price, charge) = x;
                 acc.sum_qty += l_quantity;
                 acc.sum_base_price += l_extendedprice;
                 acc.sum_disc_price += disc_price;
                 acc.sum_charge += charge;
+                acc.sum_discount += l_discount;
                 acc.count_order += 1;
             })
             -> map(|(key, value)| {
                 // Finalize aggregation with average
                 let (l_returnflag, l_linestatus) = key;
-                let value: LineItemAgg2 = value.into();
+                let value: LineItemAgg2<N> = value.into();
                 ((l_returnflag, l_linestatus), value)
             });
 
@@ -51,48 +71,350 @@ pub fn query(line_items: Vec<LineItem>) {
         // XXX: InkFuse is skipping this step.
         ordered = agg -> sort_by_key(|x| &x.0);
 
-        // Attach the sink for printing.
-        ordered -> for_each(|((l_returnflag, l_linestatus), x): ((char, char), LineItemAgg2)| {
-            println!("{}, {}, {}, {}, {}, {}, {}, {}, {}, {}",
-                    l_returnflag,
-                    l_linestatus,
-                    x.sum_qty,
-                    x.sum_base_price,
-                    x.sum_disc_price,
-                    x.sum_charge,
-                    x.avg_qty,
-                    x.avg_price,
-                    x.avg_disc,
-                    x.count_order,
-            );
-        });
+        // Attach the sink for collecting the result rows.
+        ordered -> map(|x: ((char, char), LineItemAgg2<N>)| Q1Row::from(x))
+            -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    hydroflow::util::collect_ready::<Vec<Q1Row<N>>, _>(output_recv)
+}
+
+/**
+ * Every tuple of the dataflow is a column chunk of `CHUNK_SIZE` line items with its selection
+ * vector, which is aggregated as a whole, see `base::tpch::query_1::aggregate_chunk`.
+ */
+pub fn query_columns<const CHUNK_SIZE: usize>(line_items: LineItemColumns, params: &QueryParams) -> Vec<Q1Row> {
+    let shipdate = params.shipdate();
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Q1Row>();
+
+    let mut flow = hydroflow_syntax! {
+        // 1. Scan lineitem in column chunks.
+        chunks = source_iter(line_items.chunks(CHUNK_SIZE));
+
+        // 2. Filter l_shipdate <= date '1998-12-01' - interval DELTA day into a selection vector.
+        chunks_selected = chunks -> map(|chunk: LineItemChunk| (chunk, select_chunk(&chunk, shipdate)));
+
+        // 3. + 4. Evaluate the expressions on the selected rows & group by l_returnflag, l_linestatus.
+        agg = chunks_selected
+            -> fold(HashMap::new, |groups: &mut HashMap<(char, char), LineItemAgg1>, (chunk, selection): (LineItemChunk, Vec<usize>)| {
+                aggregate_chunk(&chunk, &selection, groups);
+            })
+            -> flat_map(|groups| groups.into_iter())
+            -> map(|(key, value)| {
+                // Finalize aggregation with average
+                let value: LineItemAgg2 = value.into();
+                (key, value)
+            });
+
+        // 5. Sort by l_returnflag, l_linestatus.
+        ordered = agg -> sort_by_key(|x| &x.0);
+
+        // Attach the sink for collecting the result rows.
+        ordered -> map(|x: ((char, char), LineItemAgg2)| Q1Row::from(x))
+            -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    hydroflow::util::collect_ready::<Vec<Q1Row>, _>(output_recv)
+}
+
+/**
+ * Runs one instance of the query graph per thread on a range partition of the line items, see
+ * `super::partitioned`. The instances stop at the partial hash aggregates, the merge graph
+ * combines them and finalizes the averages.
+ */
+pub fn query_partitioned(line_items: Vec<LineItem>, params: &QueryParams, threads: usize) -> Vec<Q1Row> {
+    let shipdate = params.shipdate();
+
+    let (partial_send, partial_recv) = hydroflow::util::unbounded_channel::<((char, char), LineItemAgg1)>();
+
+    run_partitioned(range_partition(line_items, threads), &partial_send, |line_items, partial_send| {
+        let mut flow = hydroflow_syntax! {
+            // 1. Scan the partition of lineitem.
+            line_items = source_iter(line_items);
+
+            // 2. Filter l_shipdate <= date '1998-12-01' - interval DELTA day.
+            line_items_filtered = line_items -> filter(|x: &LineItem| x.l_shipdate <= shipdate);
+
+            // 3. + 4. Evaluate the expressions & group by l_returnflag, l_linestatus into partial aggregates.
+            line_items_filtered
+                -> map(|x: LineItem| ((x.l_returnflag, x.l_linestatus), x))
+                -> fold_keyed(Default::default, |acc: &mut LineItemAgg1, x: LineItem| acc.add(&x))
+                -> for_each(|x| partial_send.send(x).unwrap());
+        };
+        flow.run_available();
+    });
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Q1Row>();
+
+    let mut flow = hydroflow_syntax! {
+        // 5. Merge the partial aggregates of all partitions & finalize the averages.
+        agg = source_stream(partial_recv)
+            -> fold_keyed(Default::default, |acc: &mut LineItemAgg1, x: LineItemAgg1| acc.merge(x))
+            -> map(|(key, value)| {
+                let value: LineItemAgg2 = value.into();
+                (key, value)
+            });
+
+        // 6. Sort by l_returnflag, l_linestatus.
+        ordered = agg -> sort_by_key(|x| &x.0);
+
+        // Attach the sink for collecting the result rows.
+        ordered -> map(|x: ((char, char), LineItemAgg2)| Q1Row::from(x))
+            -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    hydroflow::util::collect_ready::<Vec<Q1Row>, _>(output_recv)
+}
+
+/**
+ * Maintains the answer under refreshes, see `super::incremental`. The hash aggregate is `'static`
+ * and keeps the partial sums of every group across ticks. Inserted line items are added to their
+ * group and deleted ones are taken back out, so a tick only touches the rows of its batch.
+ */
+pub fn query_incremental(params: &QueryParams) -> Incremental<Delta<LineItem>, Q1Row> {
+    let shipdate = params.shipdate();
+
+    let (refresh_send, refresh_recv) = hydroflow::util::unbounded_channel::<Delta<LineItem>>();
+    let (output_send, output_recv) = tokio::sync::mpsc::unbounded_channel::<Q1Row>();
+
+    let flow = hydroflow_syntax! {
+        // 1. Scan the inserted and deleted line items of a batch, tagged with whether they are inserted.
+        line_items = source_stream(refresh_recv)
+            -> flat_map(|delta: Delta<LineItem>| {
+                delta.inserted.into_iter().map(|x| (x, true)).chain(delta.deleted.into_iter().map(|x| (x, false)))
+            });
+
+        // 2. Filter l_shipdate <= date '1998-12-01' - interval DELTA day.
+        line_items_filtered = line_items -> filter(|(x, _inserted): &(LineItem, bool)| x.l_shipdate <= shipdate);
+
+        // 3. + 4. Group by l_returnflag, l_linestatus & maintain the aggregates across ticks.
+        agg = line_items_filtered
+            -> map(|(x, inserted): (LineItem, bool)| ((x.l_returnflag, x.l_linestatus), (x, inserted)))
+            -> fold_keyed::<'static>(Default::default, |acc: &mut LineItemAgg1, (x, inserted): (LineItem, bool)| {
+                if inserted {
+                    acc.add(&x);
+                } else {
+                    acc.remove(&x);
+                }
+            })
+            // A group whose line items were all deleted is gone.
+            -> filter(|(_key, value): &((char, char), LineItemAgg1)| value.count_order > 0)
+            -> map(|(key, value): ((char, char), LineItemAgg1)| {
+                // Finalize aggregation with average
+                let value: LineItemAgg2 = value.into();
+                (key, value)
+            });
+
+        // 5. Sort by l_returnflag, l_linestatus.
+        ordered = agg -> sort_by_key(|x| &x.0);
+
+        // Attach the sink for the answer of the tick.
+        ordered -> map(|x: ((char, char), LineItemAgg2)| Q1Row::from(x))
+            -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    Incremental::new(flow, refresh_send, output_recv)
+}
+
+/// Same as `query`, with exact `Decimal` arithmetic on the money columns.
+pub fn query_decimal(line_items: Vec<LineItem<Decimal>>, params: &QueryParams) -> Vec<Q1Row<Decimal>> {
+    graph(line_items, params)
+}
+
+/**
+ * The graph of `query` over a stream of line items, which it pulls with `source_stream`. The graph
+ * may run over several ticks while the stream waits for line items, see `super::scan::run_to_end`.
+ * Every tick stops at the partial aggregates of its line items, the merge graph combines the
+ * partial aggregates of all ticks and finalizes the averages, like in `query_partitioned`.
+ */
+pub fn query_stream<S: Stream<Item = LineItem> + Unpin>(line_items: S, params: &QueryParams) -> Vec<Q1Row> {
+    let shipdate = params.shipdate();
+
+    let ends = Ends::default();
+    let line_items = ends.track(line_items);
+    let (partial_send, partial_recv) = hydroflow::util::unbounded_channel::<((char, char), LineItemAgg1)>();
+
+    let mut flow = hydroflow_syntax! {
+        // 1. Scan lineitem from the stream.
+        line_items = source_stream(line_items);
+
+        // 2. Filter l_shipdate <= date '1998-12-01' - interval DELTA day.
+        line_items_filtered = line_items -> filter(|x: &LineItem| x.l_shipdate <= shipdate);
+
+        // 3. + 4. Evaluate the expressions & group the line items of the tick by l_returnflag, l_linestatus into partial aggregates.
+        line_items_filtered
+            -> map(|x: LineItem| ((x.l_returnflag, x.l_linestatus), x))
+            -> fold_keyed(Default::default, |acc: &mut LineItemAgg1, x: LineItem| acc.add(&x))
+            -> for_each(|x| partial_send.send(x).unwrap());
+    };
+
+    let partials = run_to_end(&mut flow, &ends, 1, partial_recv);
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Q1Row>();
+
+    let mut flow = hydroflow_syntax! {
+        // 5. Merge the partial aggregates of all ticks & finalize the averages.
+        agg = source_iter(partials)
+            -> fold_keyed(Default::default, |acc: &mut LineItemAgg1, x: LineItemAgg1| acc.merge(x))
+            -> map(|(key, value)| {
+                let value: LineItemAgg2 = value.into();
+                (key, value)
+            });
+
+        // 6. Sort by l_returnflag, l_linestatus.
+        ordered = agg -> sort_by_key(|x| &x.0);
+
+        // Attach the sink for collecting the result rows.
+        ordered -> map(|x: ((char, char), LineItemAgg2)| Q1Row::from(x))
+            -> for_each(|x| output_send.send(x).unwrap());
     };
 
     flow.run_available();
+    hydroflow::util::collect_ready::<Vec<Q1Row>, _>(output_recv)
 }
 
-pub fn query_base(line_items: Vec<LineItem>) {
+/// Streams `lineitem.tbl` in `dir` into the graph, the file is parsed while the graph runs. The
+/// first row that does not parse ends the stream and is returned instead of the result.
+pub fn query_tbl(dir: &Path, params: &QueryParams) -> Result<Vec<Q1Row>, TblError> {
+    let mut error = None;
+    let line_items = tbl::open::<LineItem>(dir)?.map_while(|line_item| line_item.map_err(|e| error = Some(e)).ok());
+    let result = query_stream(stream::iter(line_items), params);
+    error.map_or(Ok(result), Err)
+}
+
+/// Streams lineitem from DuckDB into the graph, the table is loaded while the graph runs, see
+/// `super::scan`.
+pub fn query_scan(conn: &Connection, params: &QueryParams) -> Vec<Q1Row> {
+    let (line_items, loader) = scan::<LineItem>(conn);
+    let result = query_stream(line_items, params);
+    loader.join();
+    result
+}
+
+pub fn query_base(line_items: Vec<LineItem>, params: &QueryParams) -> Vec<Q1Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Vec<Q1Row>>();
 
     let mut flow = hydroflow_syntax! {
         source_iter([line_items]) -> for_each(|line_items|{
-            query_base_original(line_items);
+            output_send.send(query_base_original(line_items, params)).unwrap();
         });
     };
 
     flow.run_available();
+    let res = hydroflow::util::collect_ready::<Vec<Vec<Q1Row>>, _>(output_recv);
+
+    assert!(res.len() == 1);
+    res.into_iter().next().unwrap()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use base::tpch::initialize::initialize_database;
+    use base::tpch::initialize::{initialize_database, initialize_in_memory};
+    use base::tpch::query_1::{load_columns, load_refresh, query_duckdb, query_duckdb_decimal};
+    use base::tpch::refresh::{self, RefreshStream};
+    use base::tpch::scale_factor::ScaleFactor;
+    use base::tpch::verify::{assert_match, compare, Tolerance};
 
     #[test]
     fn test_query() {
-        let conn = initialize_database(1);
+        let conn = initialize_database(0.01);
+
+        let line_items = LineItem::load(&conn, None).unwrap();
+
+        let result = super::query(line_items, &QueryParams::default());
+
+        let expected = query_duckdb(&conn, None, &QueryParams::default());
+        assert_match(&[compare("Q1", "duckdb", "hydroflow", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_columns() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+
+        let result = super::query_columns::<1024>(load_columns(&conn).unwrap(), &params);
+
+        let expected = query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q1", "duckdb", "hydroflow-columns", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_partitioned() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+        let expected = query_duckdb(&conn, None, &params);
+
+        for threads in [1, 2, 4] {
+            let result = super::query_partitioned(LineItem::load(&conn, None).unwrap(), &params, threads);
+            assert_match(&[compare("Q1", "duckdb", "hydroflow-partitioned", &expected, &result, &Tolerance::default())]);
+        }
+    }
+
+    #[test]
+    fn test_query_incremental() {
+        let conn = initialize_in_memory(0.01);
+        let params = QueryParams::default();
+        let mut query = super::query_incremental(&params);
+
+        let result = query.refresh(Delta::insert(LineItem::load(&conn, None).unwrap()));
+        let expected = query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q1", "duckdb", "hydroflow-incremental", &expected, &result, &Tolerance::default())]);
+
+        let mut stream = RefreshStream::new(ScaleFactor::from(0.01));
+        for _ in 0..3 {
+            stream.stage(&conn);
+            let delta = load_refresh(&conn).unwrap();
+            refresh::apply(&conn);
+
+            let result = query.refresh(delta);
+            let expected = query_duckdb(&conn, None, &params);
+            assert_match(&[compare("Q1", "duckdb", "hydroflow-incremental", &expected, &result, &Tolerance::default())]);
+        }
+    }
+
+    #[test]
+    fn test_query_decimal() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+
+        let result = super::query_decimal(LineItem::load_decimal(&conn, None).unwrap(), &params);
+
+        let expected = query_duckdb_decimal(&conn, None, &params);
+        assert_match(&[compare("Q1", "duckdb", "hydroflow-decimal", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_tbl() {
+        let conn = initialize_database(0.01);
+        let dir = std::env::temp_dir().join(format!("hydro_benchmarks_tbl_hf_q1_{}", std::process::id()));
+        tbl::export(&conn, "lineitem", &dir);
+
+        let result = super::query_tbl(&dir, &QueryParams::default()).unwrap();
+
+        let expected = query_duckdb(&conn, None, &QueryParams::default());
+        assert_match(&[compare("Q1", "duckdb", "hydroflow-tbl", &expected, &result, &Tolerance::default())]);
+
+        // A malformed row fails the query instead of panicking.
+        let mut file = std::fs::OpenOptions::new().append(true).open(tbl::path(&dir, "lineitem")).unwrap();
+        std::io::Write::write_all(&mut file, b"1|1|1|1|many|10.0|0.1|0.0|N|O|1996-03-13|1996-02-12|1996-03-22|NONE|AIR|comment\n").unwrap();
+        assert!(matches!(super::query_tbl(&dir, &QueryParams::default()), Err(TblError::Parse { .. })));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_query_scan() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
 
-        let line_items = LineItem::load(&conn, Some(1000));
+        let result = super::query_scan(&conn, &params);
 
-        super::query(line_items);
+        let expected = query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q1", "duckdb", "hydroflow-scan", &expected, &result, &Tolerance::default())]);
     }
 }
\ No newline at end of file
@@ -0,0 +1,103 @@
+use chrono::NaiveDate;
+use hydroflow::hydroflow_syntax;
+
+use base::tpch::query_10::{Customer, LineItem, Nation, Order, Q10Row, query as query_base_original};
+use base::tpch::top_k::{Strategy, TopK};
+
+pub fn query(customer: Vec<Customer>, orders: Vec<Order>, line_items: Vec<LineItem>, nation: Vec<Nation>) -> Vec<Q10Row> {
+    query_with(Strategy::Heap, customer, orders, line_items, nation)
+}
+
+/// Query 10 with the given top-k strategy for ORDER BY ... LIMIT 20.
+pub fn query_with(
+    strategy: Strategy,
+    customer: Vec<Customer>,
+    orders: Vec<Order>,
+    line_items: Vec<LineItem>,
+    nation: Vec<Nation>,
+) -> Vec<Q10Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Q10Row>();
+
+    let mut flow = hydroflow_syntax! {
+        // 1. Scan orders, filter on o_orderdate in [1993-10-01, 1994-01-01).
+        orders_filtered = source_iter(orders)
+            -> filter(|o| {
+                o.o_orderdate >= NaiveDate::from_ymd_opt(1993, 10, 1).unwrap()
+                    && o.o_orderdate < NaiveDate::from_ymd_opt(1994, 1, 1).unwrap()
+            });
+
+        // 2. Scan lineitem, filter on l_returnflag = 'R'.
+        line_items_filtered = source_iter(line_items) -> filter(|l| l.l_returnflag == 'R');
+
+        // 3. Join orders and lineitem on o_orderkey = l_orderkey.
+        orders_filtered -> map(|o| (o.o_orderkey, o.o_custkey)) -> [0]orders_line_items;
+        line_items_filtered -> map(|l| (l.l_orderkey, l.l_extendedprice * (1.0 - l.l_discount))) -> [1]orders_line_items;
+        orders_line_items = join_multiset() -> map(|(_o_orderkey, (o_custkey, revenue))| (o_custkey, revenue));
+
+        // 4. Group by c_custkey and sum the revenue. The remaining GROUP BY columns are functionally
+        // dependent on c_custkey and are joined in afterwards.
+        agg = orders_line_items -> fold_keyed(|| 0.0, |acc: &mut f64, revenue: f64| *acc += revenue);
+
+        // 5. Join customer on c_custkey.
+        agg -> [0]customer_agg;
+        source_iter(customer) -> map(|c| (c.c_custkey, c)) -> [1]customer_agg;
+        customer_agg = join_multiset() -> map(|(_c_custkey, (revenue, c))| (c.c_nationkey, (c, revenue)));
+
+        // 6. Join nation on c_nationkey = n_nationkey.
+        source_iter(nation) -> map(|n| (n.n_nationkey, n.n_name)) -> [0]joined;
+        customer_agg -> [1]joined;
+        joined = join_multiset() -> map(|(_n_nationkey, (n_name, (c, revenue)))| Q10Row::new(c, revenue, n_name));
+
+        // 7. Keep the first 20 rows by revenue desc.
+        ordered = joined
+            -> fold(|| TopK::new(strategy, 20, Q10Row::order_by), |acc: &mut TopK<Q10Row>, x: Q10Row| acc.push(x))
+            -> flat_map(TopK::into_sorted_vec);
+
+        // 8. Collect
+        ordered -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    hydroflow::util::collect_ready::<Vec<Q10Row>, _>(output_recv)
+}
+
+pub fn query_base(customer: Vec<Customer>, orders: Vec<Order>, line_items: Vec<LineItem>, nation: Vec<Nation>) -> Vec<Q10Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Vec<Q10Row>>();
+
+    let mut flow = hydroflow_syntax! {
+        source_iter([(customer, orders, line_items, nation)]) -> for_each(|(customer, orders, line_items, nation)|{
+            output_send.send(query_base_original(customer, orders, line_items, nation)).unwrap();
+        });
+    };
+
+    flow.run_available();
+    let res = hydroflow::util::collect_ready::<Vec<Vec<Q10Row>>, _>(output_recv);
+
+    assert!(res.len() == 1);
+    res.into_iter().next().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use base::tpch::initialize::initialize_database;
+    use base::tpch::query_10::{load, query_duckdb};
+    use base::tpch::top_k::Strategy;
+    use base::tpch::verify::{assert_match, compare, Tolerance};
+
+    #[test]
+    fn test_query() {
+        let conn = initialize_database(0.01);
+
+        let expected = query_duckdb(&conn, None);
+
+        let (customer, orders, line_items, nation) = load(&conn);
+        let result = super::query(customer, orders, line_items, nation);
+        assert_match(&[compare("Q10", "duckdb", "hydroflow", &expected, &result, &Tolerance::default())]);
+
+        let (customer, orders, line_items, nation) = load(&conn);
+        let result = super::query_with(Strategy::Sort, customer, orders, line_items, nation);
+        assert_match(&[compare("Q10", "duckdb", "hydroflow-sort", &expected, &result, &Tolerance::default())]);
+    }
+}
@@ -0,0 +1,123 @@
+use hydroflow::hydroflow_syntax;
+
+use base::tpch::query_12::{LineItem, Order, Q12Row, filter_chunk, query as query_base_original};
+
+pub fn query(orders: Vec<Order>, line_items: Vec<LineItem>) -> Vec<Q12Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Q12Row>();
+
+    let mut flow = hydroflow_syntax! {
+        // 1. Scan lineitem and filter on l_shipmode, l_commitdate, l_shipdate and l_receiptdate.
+        lineitem_filtered = source_iter(line_items) -> filter(|l| l.filter()) -> map(|l| (l.l_orderkey, l.l_shipmode));
+
+        // 2. Join orders and lineitem on o_orderkey = l_orderkey.
+        // Payload left: o_orderpriority IN ('1-URGENT', '2-HIGH')
+        source_iter(orders) -> map(|o| (o.o_orderkey, o.is_high_priority())) -> [0]joined;
+        lineitem_filtered -> [1]joined;
+        joined = join_multiset() -> map(|(_o_orderkey, (high, l_shipmode))| (l_shipmode, high));
+
+        // 3. Group by l_shipmode and sum the CASE expressions.
+        // 4. Sort by l_shipmode.
+        agg = joined
+            -> fold_keyed(|| (0i64, 0i64), |acc: &mut (i64, i64), high: bool| {
+                if high {
+                    acc.0 += 1;
+                } else {
+                    acc.1 += 1;
+                }
+            })
+            -> map(Q12Row::from)
+            -> sort_by_key(|x: &Q12Row| &x.l_shipmode);
+
+        // 5. Collect: "l_shipmode", "high_line_count", "low_line_count"
+        agg -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    hydroflow::util::collect_ready::<Vec<Q12Row>, _>(output_recv)
+}
+
+/**
+ * Every tuple of the lineitem scan is a chunk of `CHUNK_SIZE` line items, which is filtered as a
+ * whole, see `base::tpch::query_12::filter_chunk`. The join and the aggregate stay per tuple.
+ */
+pub fn query_chunks<const CHUNK_SIZE: usize>(orders: Vec<Order>, line_items: Vec<LineItem>) -> Vec<Q12Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Q12Row>();
+
+    let mut flow = hydroflow_syntax! {
+        // 1. Scan lineitem in chunks and filter every chunk.
+        lineitem_filtered = source_iter(line_items.chunks(CHUNK_SIZE))
+            -> flat_map(|chunk: &[LineItem]| {
+                let selection = filter_chunk::<CHUNK_SIZE>(chunk);
+                selection
+                    .into_iter()
+                    .zip(chunk)
+                    .filter(|(s, _)| *s)
+                    .map(|(_, l)| (l.l_orderkey, l.l_shipmode.clone()))
+                    .collect::<Vec<_>>()
+            });
+
+        // 2. Join orders and lineitem on o_orderkey = l_orderkey.
+        source_iter(orders) -> map(|o| (o.o_orderkey, o.is_high_priority())) -> [0]joined;
+        lineitem_filtered -> [1]joined;
+        joined = join_multiset() -> map(|(_o_orderkey, (high, l_shipmode))| (l_shipmode, high));
+
+        // 3. Group by l_shipmode and sum the CASE expressions.
+        // 4. Sort by l_shipmode.
+        agg = joined
+            -> fold_keyed(|| (0i64, 0i64), |acc: &mut (i64, i64), high: bool| {
+                if high {
+                    acc.0 += 1;
+                } else {
+                    acc.1 += 1;
+                }
+            })
+            -> map(Q12Row::from)
+            -> sort_by_key(|x: &Q12Row| &x.l_shipmode);
+
+        // 5. Collect: "l_shipmode", "high_line_count", "low_line_count"
+        agg -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    hydroflow::util::collect_ready::<Vec<Q12Row>, _>(output_recv)
+}
+
+pub fn query_base(orders: Vec<Order>, line_items: Vec<LineItem>) -> Vec<Q12Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Vec<Q12Row>>();
+
+    let mut flow = hydroflow_syntax! {
+        source_iter([(orders, line_items)]) -> for_each(|(orders, line_items)|{
+            output_send.send(query_base_original(orders, line_items)).unwrap();
+        });
+    };
+
+    flow.run_available();
+    let res = hydroflow::util::collect_ready::<Vec<Vec<Q12Row>>, _>(output_recv);
+
+    assert!(res.len() == 1);
+    res.into_iter().next().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use base::tpch::initialize::initialize_database;
+    use base::tpch::query_12::{load, query_duckdb};
+    use base::tpch::verify::{assert_match, compare, Tolerance};
+
+    #[test]
+    fn test_query() {
+        let conn = initialize_database(0.01);
+        let expected = query_duckdb(&conn, None);
+
+        let (orders, line_items) = load(&conn);
+        let result = super::query(orders, line_items);
+        assert_match(&[compare("Q12", "duckdb", "hydroflow", &expected, &result, &Tolerance::default())]);
+
+        let (orders, line_items) = load(&conn);
+        let result = super::query_chunks::<1024>(orders, line_items);
+        assert_match(&[compare("Q12", "duckdb", "hydroflow-chunks", &expected, &result, &Tolerance::default())]);
+    }
+}
@@ -0,0 +1,99 @@
+use hydroflow::hydroflow_syntax;
+
+use base::tpch::query_13::{Customer, Order, Q13Row, query as query_base_original};
+
+pub fn query(customer: Vec<Customer>, orders: Vec<Order>) -> Vec<Q13Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Q13Row>();
+
+    let mut flow = hydroflow_syntax! {
+        // 1. Scan customer.
+        customers = source_iter(customer) -> map(|c| (c.c_custkey, ())) -> tee();
+
+        // 2. Scan orders and filter on o_comment NOT LIKE '%special%requests%'.
+        orders_filtered = source_iter(orders)
+            -> filter(|o| !o.is_special_request())
+            -> map(|o| (o.o_custkey, o.o_orderkey))
+            -> tee();
+
+        // 3. Left outer join customer and orders on c_custkey = o_custkey.
+        // Hydroflow has no outer join, so it is the union of
+        // 3.1 the inner join: customers with orders, payload Some(o_orderkey)
+        customers -> [0]matched;
+        orders_filtered -> [1]matched;
+        matched = join_multiset() -> map(|(c_custkey, ((), o_orderkey))| (c_custkey, Some(o_orderkey)));
+
+        // 3.2 the anti join: customers without orders, payload NULL
+        customers -> [pos]unmatched;
+        orders_filtered -> map(|(o_custkey, _o_orderkey)| o_custkey) -> [neg]unmatched;
+        unmatched = anti_join() -> map(|(c_custkey, ())| (c_custkey, None::<i64>));
+
+        outer_joined = union();
+        matched -> outer_joined;
+        unmatched -> outer_joined;
+
+        // 4. Group by c_custkey and count(o_orderkey), which skips NULLs.
+        c_orders = outer_joined
+            -> fold_keyed(|| 0i64, |acc: &mut i64, o_orderkey: Option<i64>| {
+                if o_orderkey.is_some() {
+                    *acc += 1;
+                }
+            });
+
+        // 5. Group by c_count and count(*).
+        agg = c_orders
+            -> map(|(_c_custkey, c_count)| (c_count, 1i64))
+            -> reduce_keyed(|acc: &mut i64, x: i64| *acc += x)
+            -> map(|(c_count, custdist)| Q13Row { c_count, custdist });
+
+        // 6. Sort by custdist desc, c_count desc.
+        ordered = agg
+            -> fold(Vec::new, |acc: &mut Vec<Q13Row>, x: Q13Row| acc.push(x))
+            -> flat_map(|mut x: Vec<Q13Row>| {
+                x.sort_by(Q13Row::order_by);
+                x
+            });
+
+        // 7. Collect: "c_count", "custdist"
+        ordered -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    hydroflow::util::collect_ready::<Vec<Q13Row>, _>(output_recv)
+}
+
+pub fn query_base(customer: Vec<Customer>, orders: Vec<Order>) -> Vec<Q13Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Vec<Q13Row>>();
+
+    let mut flow = hydroflow_syntax! {
+        source_iter([(customer, orders)]) -> for_each(|(customer, orders)|{
+            output_send.send(query_base_original(customer, orders)).unwrap();
+        });
+    };
+
+    flow.run_available();
+    let res = hydroflow::util::collect_ready::<Vec<Vec<Q13Row>>, _>(output_recv);
+
+    assert!(res.len() == 1);
+    res.into_iter().next().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use base::tpch::initialize::initialize_database;
+    use base::tpch::query_13::{load, query_duckdb};
+    use base::tpch::verify::{assert_match, compare, Tolerance};
+
+    #[test]
+    fn test_query() {
+        let conn = initialize_database(0.01);
+
+        let (customer, orders) = load(&conn);
+
+        let result = super::query(customer, orders);
+
+        let expected = query_duckdb(&conn, None);
+        assert_match(&[compare("Q13", "duckdb", "hydroflow", &expected, &result, &Tolerance::default())]);
+    }
+}
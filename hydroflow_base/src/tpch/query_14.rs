@@ -0,0 +1,123 @@
+use hydroflow::hydroflow_syntax;
+
+use base::tpch::query_14::{LineItem, Part, Q14Row, filter_chunk, query as query_base_original};
+
+pub fn query(line_items: Vec<LineItem>, part: Vec<Part>) -> Vec<Q14Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<(f64, f64, u64)>();
+
+    let mut flow = hydroflow_syntax! {
+        // 1. Scan lineitem and filter on l_shipdate.
+        lineitem_filtered = source_iter(line_items)
+            -> filter(|l| l.filter())
+            -> map(|l| (l.l_partkey, l.l_extendedprice * (1.0 - l.l_discount)));
+
+        // 2. Join part and lineitem on p_partkey = l_partkey.
+        // Payload left: p_type LIKE 'PROMO%'
+        source_iter(part) -> map(|p| (p.p_partkey, p.is_promo())) -> [0]joined;
+        lineitem_filtered -> [1]joined;
+        joined = join_multiset() -> map(|(_p_partkey, (promo, revenue))| (promo, revenue));
+
+        // 3. Aggregate: sum(CASE WHEN promo THEN revenue ELSE 0 END), sum(revenue), count(*)
+        joined
+            -> fold(|| (0.0, 0.0, 0u64), |acc: &mut (f64, f64, u64), (promo, revenue): (bool, f64)| {
+                if promo {
+                    acc.0 += revenue;
+                }
+                acc.1 += revenue;
+                acc.2 += 1;
+            })
+            -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    let res = hydroflow::util::collect_ready::<Vec<(f64, f64, u64)>, _>(output_recv);
+
+    assert!(res.len() == 1);
+    let (promo_revenue, revenue, count) = res[0];
+    vec![Q14Row::new(promo_revenue, revenue, count)]
+}
+
+/**
+ * Every tuple of the lineitem scan is a chunk of `CHUNK_SIZE` line items, which is filtered as a
+ * whole, see `base::tpch::query_14::filter_chunk`. The join and the aggregate stay per tuple.
+ */
+pub fn query_chunks<const CHUNK_SIZE: usize>(line_items: Vec<LineItem>, part: Vec<Part>) -> Vec<Q14Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<(f64, f64, u64)>();
+
+    let mut flow = hydroflow_syntax! {
+        // 1. Scan lineitem in chunks and filter every chunk.
+        lineitem_filtered = source_iter(line_items.chunks(CHUNK_SIZE))
+            -> flat_map(|chunk: &[LineItem]| {
+                let selection = filter_chunk::<CHUNK_SIZE>(chunk);
+                selection
+                    .into_iter()
+                    .zip(chunk)
+                    .filter(|(s, _)| *s)
+                    .map(|(_, l)| (l.l_partkey, l.l_extendedprice * (1.0 - l.l_discount)))
+                    .collect::<Vec<_>>()
+            });
+
+        // 2. Join part and lineitem on p_partkey = l_partkey.
+        source_iter(part) -> map(|p| (p.p_partkey, p.is_promo())) -> [0]joined;
+        lineitem_filtered -> [1]joined;
+        joined = join_multiset() -> map(|(_p_partkey, (promo, revenue))| (promo, revenue));
+
+        // 3. Aggregate: sum(CASE WHEN promo THEN revenue ELSE 0 END), sum(revenue), count(*)
+        joined
+            -> fold(|| (0.0, 0.0, 0u64), |acc: &mut (f64, f64, u64), (promo, revenue): (bool, f64)| {
+                if promo {
+                    acc.0 += revenue;
+                }
+                acc.1 += revenue;
+                acc.2 += 1;
+            })
+            -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    let res = hydroflow::util::collect_ready::<Vec<(f64, f64, u64)>, _>(output_recv);
+
+    assert!(res.len() == 1);
+    let (promo_revenue, revenue, count) = res[0];
+    vec![Q14Row::new(promo_revenue, revenue, count)]
+}
+
+pub fn query_base(line_items: Vec<LineItem>, part: Vec<Part>) -> Vec<Q14Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Vec<Q14Row>>();
+
+    let mut flow = hydroflow_syntax! {
+        source_iter([(line_items, part)]) -> for_each(|(line_items, part)|{
+            output_send.send(query_base_original(line_items, part)).unwrap();
+        });
+    };
+
+    flow.run_available();
+    let res = hydroflow::util::collect_ready::<Vec<Vec<Q14Row>>, _>(output_recv);
+
+    assert!(res.len() == 1);
+    res.into_iter().next().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use base::tpch::initialize::initialize_database;
+    use base::tpch::query_14::{load, query_duckdb};
+    use base::tpch::verify::{assert_match, compare, Tolerance};
+
+    #[test]
+    fn test_query() {
+        let conn = initialize_database(0.01);
+        let expected = query_duckdb(&conn, None);
+
+        let (line_items, part) = load(&conn);
+        let result = super::query(line_items, part);
+        assert_match(&[compare("Q14", "duckdb", "hydroflow", &expected, &result, &Tolerance::default())]);
+
+        let (line_items, part) = load(&conn);
+        let result = super::query_chunks::<1024>(line_items, part);
+        assert_match(&[compare("Q14", "duckdb", "hydroflow-chunks", &expected, &result, &Tolerance::default())]);
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::HashSet;
+
+use hydroflow::hydroflow_syntax;
+
+use base::tpch::query_16::{Part, PartSupp, Q16Row, Supplier, query as query_base_original};
+
+pub fn query(partsupp: Vec<PartSupp>, part: Vec<Part>, supplier: Vec<Supplier>) -> Vec<Q16Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Q16Row>();
+
+    let mut flow = hydroflow_syntax! {
+        // 1. Scan supplier and filter on s_comment LIKE '%Customer%Complaints%'.
+        complaints = source_iter(supplier) -> filter(|s| s.has_complaints()) -> map(|s| s.s_suppkey);
+
+        // 2. Scan partsupp and anti join supplier on ps_suppkey NOT IN (...).
+        source_iter(partsupp) -> map(|ps| (ps.ps_suppkey, ps.ps_partkey)) -> [pos]partsupp_filtered;
+        complaints -> [neg]partsupp_filtered;
+        partsupp_filtered = anti_join() -> map(|(ps_suppkey, ps_partkey)| (ps_partkey, ps_suppkey));
+
+        // 3. Scan part, filter on p_brand, p_type and p_size and join partsupp on p_partkey = ps_partkey.
+        source_iter(part) -> filter(|p| p.filter()) -> map(|p| (p.p_partkey, (p.p_brand, p.p_type, p.p_size))) -> [0]joined;
+        partsupp_filtered -> [1]joined;
+        joined = join_multiset() -> map(|(_p_partkey, (key, ps_suppkey))| (key, ps_suppkey));
+
+        // 4. Group by p_brand, p_type, p_size and count(DISTINCT ps_suppkey).
+        agg = joined
+            -> fold_keyed(HashSet::new, |acc: &mut HashSet<i64>, ps_suppkey: i64| {
+                acc.insert(ps_suppkey);
+            })
+            -> map(|((p_brand, p_type, p_size), suppliers)| Q16Row {
+                p_brand,
+                p_type,
+                p_size,
+                supplier_cnt: suppliers.len() as i64,
+            });
+
+        // 5. Sort by supplier_cnt desc, p_brand, p_type, p_size.
+        ordered = agg
+            -> fold(Vec::new, |acc: &mut Vec<Q16Row>, x: Q16Row| acc.push(x))
+            -> flat_map(|mut x: Vec<Q16Row>| {
+                x.sort_by(Q16Row::order_by);
+                x
+            });
+
+        // 6. Collect: "p_brand", "p_type", "p_size", "supplier_cnt"
+        ordered -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    hydroflow::util::collect_ready::<Vec<Q16Row>, _>(output_recv)
+}
+
+pub fn query_base(partsupp: Vec<PartSupp>, part: Vec<Part>, supplier: Vec<Supplier>) -> Vec<Q16Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Vec<Q16Row>>();
+
+    let mut flow = hydroflow_syntax! {
+        source_iter([(partsupp, part, supplier)]) -> for_each(|(partsupp, part, supplier)|{
+            output_send.send(query_base_original(partsupp, part, supplier)).unwrap();
+        });
+    };
+
+    flow.run_available();
+    let res = hydroflow::util::collect_ready::<Vec<Vec<Q16Row>>, _>(output_recv);
+
+    assert!(res.len() == 1);
+    res.into_iter().next().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use base::tpch::initialize::initialize_database;
+    use base::tpch::query_16::{load, query_duckdb};
+    use base::tpch::verify::{assert_match, compare, Tolerance};
+
+    #[test]
+    fn test_query() {
+        let conn = initialize_database(0.01);
+
+        let (partsupp, part, supplier) = load(&conn);
+
+        let result = super::query(partsupp, part, supplier);
+
+        let expected = query_duckdb(&conn, None);
+        assert_match(&[compare("Q16", "duckdb", "hydroflow", &expected, &result, &Tolerance::default())]);
+    }
+}
@@ -0,0 +1,85 @@
+use hydroflow::hydroflow_syntax;
+
+use base::tpch::query_17::{LineItem, Part, Q17Row, query as query_base_original};
+
+pub fn query(line_items: Vec<LineItem>, part: Vec<Part>) -> Vec<Q17Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<f64>();
+
+    let mut flow = hydroflow_syntax! {
+        // 1. Scan part and filter on p_brand = 'Brand#23' and p_container = 'MED BOX'.
+        part_filtered = source_iter(part)
+            -> filter(|p| p.p_brand == "Brand#23" && p.p_container == "MED BOX");
+
+        // 2. Join part and lineitem on p_partkey = l_partkey.
+        // Payload right: l_quantity, l_extendedprice
+        part_filtered -> map(|p| (p.p_partkey, ())) -> [0]part_line_items;
+        source_iter(line_items) -> map(|l| (l.l_partkey, (l.l_quantity, l.l_extendedprice))) -> [1]part_line_items;
+        part_line_items = join_multiset()
+            -> map(|(l_partkey, ((), (l_quantity, l_extendedprice)))| (l_partkey, (l_quantity, l_extendedprice)))
+            -> tee();
+
+        // 3. Subquery: group by l_partkey and compute 0.2 * avg(l_quantity).
+        // The subquery reads all line items of a part, which is exactly the output of the join.
+        threshold = part_line_items
+            -> map(|(l_partkey, (l_quantity, _l_extendedprice))| (l_partkey, l_quantity))
+            -> fold_keyed(|| (0.0, 0u64), |acc: &mut (f64, u64), l_quantity: f64| {
+                acc.0 += l_quantity;
+                acc.1 += 1;
+            })
+            -> map(|(l_partkey, (sum, count))| (l_partkey, 0.2 * sum / count as f64));
+
+        // 4. Join the line items with the threshold of their part on l_partkey.
+        threshold -> [0]joined;
+        part_line_items -> [1]joined;
+        joined = join_multiset()
+            -> filter(|(_l_partkey, (threshold, (l_quantity, _l_extendedprice)))| l_quantity < threshold)
+            -> map(|(_l_partkey, (_threshold, (_l_quantity, l_extendedprice)))| l_extendedprice);
+
+        // 5. Aggregate: sum(l_extendedprice)
+        joined -> reduce(|acc, x| *acc += x)
+            -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    let res = hydroflow::util::collect_ready::<Vec<f64>, _>(output_recv);
+
+    // reduce() emits nothing on empty input, which matches SQL's NULL sum.
+    vec![Q17Row { avg_yearly: res.first().map(|sum| sum / 7.0) }]
+}
+
+pub fn query_base(line_items: Vec<LineItem>, part: Vec<Part>) -> Vec<Q17Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Vec<Q17Row>>();
+
+    let mut flow = hydroflow_syntax! {
+        source_iter([(line_items, part)]) -> for_each(|(line_items, part)|{
+            output_send.send(query_base_original(line_items, part)).unwrap();
+        });
+    };
+
+    flow.run_available();
+    let res = hydroflow::util::collect_ready::<Vec<Vec<Q17Row>>, _>(output_recv);
+
+    assert!(res.len() == 1);
+    res.into_iter().next().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use base::tpch::initialize::initialize_database;
+    use base::tpch::query_17::{load, query_duckdb};
+    use base::tpch::verify::{assert_match, compare, Tolerance};
+
+    #[test]
+    fn test_query() {
+        let conn = initialize_database(0.01);
+
+        let (line_items, part) = load(&conn);
+
+        let result = super::query(line_items, part);
+
+        let expected = query_duckdb(&conn, None);
+        assert_match(&[compare("Q17", "duckdb", "hydroflow", &expected, &result, &Tolerance::default())]);
+    }
+}
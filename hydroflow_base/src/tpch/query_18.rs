@@ -0,0 +1,95 @@
+use hydroflow::hydroflow_syntax;
+
+use base::tpch::query_18::{Customer, LineItem, Order, Q18Row, QUANTITY, query as query_base_original};
+use base::tpch::top_k::{Strategy, TopK};
+
+pub fn query(customer: Vec<Customer>, orders: Vec<Order>, line_items: Vec<LineItem>) -> Vec<Q18Row> {
+    query_with(Strategy::Heap, customer, orders, line_items)
+}
+
+/// Query 18 with the given top-k strategy for ORDER BY ... LIMIT 100.
+pub fn query_with(strategy: Strategy, customer: Vec<Customer>, orders: Vec<Order>, line_items: Vec<LineItem>) -> Vec<Q18Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Q18Row>();
+
+    let mut flow = hydroflow_syntax! {
+        // 1. Subquery: scan lineitem, group by l_orderkey and sum(l_quantity).
+        // 2. HAVING sum(l_quantity) > 300. The outer sum(l_quantity) is the same sum.
+        large_orders = source_iter(line_items)
+            -> map(|l| (l.l_orderkey, l.l_quantity))
+            -> reduce_keyed(|acc: &mut f64, l_quantity: f64| *acc += l_quantity)
+            -> filter(|(_l_orderkey, sum_quantity)| *sum_quantity > QUANTITY);
+
+        // 3. Join the large orders with orders on l_orderkey = o_orderkey.
+        // Payload right: o_custkey, o_orderdate, o_totalprice
+        large_orders -> [0]orders_joined;
+        source_iter(orders) -> map(|o| (o.o_orderkey, (o.o_custkey, o.o_orderdate, o.o_totalprice))) -> [1]orders_joined;
+        orders_joined = join_multiset()
+            -> map(|(o_orderkey, (sum_quantity, (o_custkey, o_orderdate, o_totalprice)))| {
+                (o_custkey, (o_orderkey, o_orderdate, o_totalprice, sum_quantity))
+            });
+
+        // 4. Join the result with customer on o_custkey = c_custkey.
+        orders_joined -> [0]joined;
+        source_iter(customer) -> map(|c| (c.c_custkey, c.c_name)) -> [1]joined;
+        joined = join_multiset()
+            -> map(|(c_custkey, ((o_orderkey, o_orderdate, o_totalprice, sum_quantity), c_name))| Q18Row {
+                c_name,
+                c_custkey,
+                o_orderkey,
+                o_orderdate,
+                o_totalprice,
+                sum_quantity,
+            });
+
+        // 5. Keep the first 100 rows by o_totalprice desc, o_orderdate.
+        ordered = joined
+            -> fold(|| TopK::new(strategy, 100, Q18Row::order_by), |acc: &mut TopK<Q18Row>, x: Q18Row| acc.push(x))
+            -> flat_map(TopK::into_sorted_vec);
+
+        // 6. Collect: "c_name", "c_custkey", "o_orderkey", "o_orderdate", "o_totalprice", "sum(l_quantity)"
+        ordered -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    hydroflow::util::collect_ready::<Vec<Q18Row>, _>(output_recv)
+}
+
+pub fn query_base(customer: Vec<Customer>, orders: Vec<Order>, line_items: Vec<LineItem>) -> Vec<Q18Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Vec<Q18Row>>();
+
+    let mut flow = hydroflow_syntax! {
+        source_iter([(customer, orders, line_items)]) -> for_each(|(customer, orders, line_items)|{
+            output_send.send(query_base_original(customer, orders, line_items)).unwrap();
+        });
+    };
+
+    flow.run_available();
+    let res = hydroflow::util::collect_ready::<Vec<Vec<Q18Row>>, _>(output_recv);
+
+    assert!(res.len() == 1);
+    res.into_iter().next().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use base::tpch::initialize::initialize_database;
+    use base::tpch::query_18::{load, query_duckdb};
+    use base::tpch::top_k::Strategy;
+    use base::tpch::verify::{assert_match, compare, Tolerance};
+
+    #[test]
+    fn test_query() {
+        let conn = initialize_database(0.01);
+        let expected = query_duckdb(&conn, None);
+
+        let (customer, orders, line_items) = load(&conn);
+        let result = super::query(customer, orders, line_items);
+        assert_match(&[compare("Q18", "duckdb", "hydroflow", &expected, &result, &Tolerance::default())]);
+
+        let (customer, orders, line_items) = load(&conn);
+        let result = super::query_with(Strategy::Sort, customer, orders, line_items);
+        assert_match(&[compare("Q18", "duckdb", "hydroflow-sort", &expected, &result, &Tolerance::default())]);
+    }
+}
@@ -1,17 +1,36 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use duckdb::Connection;
+use hydroflow::futures::stream::Stream;
 use hydroflow::hydroflow_syntax;
 
-use base::tpch::query_19::{Part, LineItem, query as query_base_original};
+use base::tpch::bloom::{BloomFilter, DEFAULT_FALSE_POSITIVE_RATE};
+use base::tpch::decimal::{Decimal, Numeric};
+use base::tpch::merge_join::merge_join;
+use base::tpch::query_19::{revenue_chunk, select_chunk, Part, LineItem, LineItemChunk, LineItemColumns, Q19Row, QueryParams, query as query_base_original};
+
+use super::partitioned::{hash_partition, run_partitioned};
+use super::scan::{run_to_end, scan, Ends};
+
+/// The answer of a graph that sums the revenue with `reduce()`, whose output is `sums`. `reduce()`
+/// emits nothing on empty input, which matches SQL's NULL sum.
+pub fn answer<N: Copy>(sums: Vec<N>) -> Vec<Q19Row<N>> {
+    vec![Q19Row { revenue: sums.first().copied() }]
+}
+
+pub fn query(line_items: Vec<LineItem>, part: Vec<Part>, params: &QueryParams) -> Vec<Q19Row> {
 
-pub fn query(line_items: Vec<LineItem>, part: Vec<Part>) {
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<f64>();
 
     let mut flow = hydroflow_syntax! {
         // 1. Scan part.
         part_filtered = source_iter(part)
         // 2. Pushed down filter on part.
             -> filter(|part| {
-                Part::filter_1(&part.p_brand, &part.p_size, &part.p_container)
-                || Part::filter_2(&part.p_brand, &part.p_size, &part.p_container)
-                || Part::filter_3(&part.p_brand, &part.p_size, &part.p_container)
+                Part::filter_1(params, &part.p_brand, &part.p_size, &part.p_container)
+                || Part::filter_2(params, &part.p_brand, &part.p_size, &part.p_container)
+                || Part::filter_3(params, &part.p_brand, &part.p_size, &part.p_container)
             });
 
         // 3. Scan lineitem.
@@ -20,11 +39,11 @@ pub fn query(line_items: Vec<LineItem>, part: Vec<Part>) {
             // l_shipinstruct = "DELIVER IN PERSON"
             // l_shipmode = "AIR" or "AIR REG"
             -> filter(|lineitem| lineitem.l_shipinstruct == "DELIVER IN PERSON" && (lineitem.l_shipmode == "AIR" || lineitem.l_shipmode == "AIR REG"))
-            -> filter(|lineitem| LineItem::filter_1(&lineitem.l_quantity) || LineItem::filter_2(&lineitem.l_quantity) || LineItem::filter_3(&lineitem.l_quantity));
+            -> filter(|lineitem| LineItem::filter_1(params, &lineitem.l_quantity) || LineItem::filter_2(params, &lineitem.l_quantity) || LineItem::filter_3(params, &lineitem.l_quantity));
 
         // 5. Join the two
-        // Build: Part?
-        // Probe: LineItem?
+        // No build side: join_multiset() hashes both inputs, see `base::tpch::query_19::query_with_plan`
+        // for a one-sided hash join on the side chosen by the plan.
 
         // Keys left (p_partkey)
         // Payload left (p_brand, p_container, p_size)
@@ -46,9 +65,9 @@ pub fn query(line_items: Vec<LineItem>, part: Vec<Part>) {
 
         // 6. Filter again, we need to make sure the right tuples survived.
         join_filtered = joined -> filter(|(p_brand, p_container, p_size, l_quantity, _l_discount, _l_extendedprice)| {
-            (Part::filter_1(&p_brand, &p_size, &p_container) && LineItem::filter_1(&l_quantity))
-            || (Part::filter_2(&p_brand, &p_size, &p_container) && LineItem::filter_2(&l_quantity))
-            || (Part::filter_3(&p_brand, &p_size, &p_container) && LineItem::filter_3(&l_quantity))
+            (Part::filter_1(params, &p_brand, &p_size, &p_container) && LineItem::filter_1(params, l_quantity))
+            || (Part::filter_2(params, &p_brand, &p_size, &p_container) && LineItem::filter_2(params, l_quantity))
+            || (Part::filter_3(params, &p_brand, &p_size, &p_container) && LineItem::filter_3(params, l_quantity))
         });
 
         // 7. Aggregate the result.
@@ -58,40 +77,499 @@ pub fn query(line_items: Vec<LineItem>, part: Vec<Part>) {
             l_extendedprice * (1.0 - l_discount)
         }) -> reduce(|a, b| *a += b);
 
+        // 8. Collect: "revenue"
+        agg -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    answer(hydroflow::util::collect_ready::<Vec<f64>, _>(output_recv))
+}
+
+/**
+ * Bloom filter pushdown, see `base::tpch::bloom`. The keys of the filtered parts are folded into a
+ * Bloom filter, which is passed to the lineitem pipeline with a `cross_join_multiset()` and drops
+ * almost all line items before the `join_multiset()`.
+ */
+pub fn query_bloom(line_items: Vec<LineItem>, part: Vec<Part>, params: &QueryParams) -> Vec<Q19Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<f64>();
+
+    let mut flow = hydroflow_syntax! {
+        // 1. Scan part, 2. pushed down filter on part.
+        part_filtered = source_iter(part)
+            -> filter(|part: &Part| {
+                Part::filter_1(params, &part.p_brand, &part.p_size, &part.p_container)
+                || Part::filter_2(params, &part.p_brand, &part.p_size, &part.p_container)
+                || Part::filter_3(params, &part.p_brand, &part.p_size, &part.p_container)
+            })
+            -> map(|p: Part| (p.p_partkey, (p.p_brand, p.p_container, p.p_size)))
+            -> tee();
+
+        // 3. Bloom filter of the qualifying p_partkey, shared by all line items.
+        part_filtered
+            -> map(|(partkey, _)| partkey)
+            -> fold(Vec::new, |keys: &mut Vec<i64>, key| keys.push(key))
+            -> map(|keys: Vec<i64>| Rc::new(BloomFilter::from_keys(&keys, DEFAULT_FALSE_POSITIVE_RATE)))
+            -> [0]pushdown;
+
+        // 4. Scan lineitem, pushed down filters and probe the Bloom filter. The probe side is
+        // projected first, so the cross join copies the join tuple rather than the line item.
+        source_iter(line_items)
+            -> filter(|lineitem: &LineItem| lineitem.l_shipinstruct == "DELIVER IN PERSON" && (lineitem.l_shipmode == "AIR" || lineitem.l_shipmode == "AIR REG"))
+            -> filter(|lineitem: &LineItem| LineItem::filter_1(params, &lineitem.l_quantity) || LineItem::filter_2(params, &lineitem.l_quantity) || LineItem::filter_3(params, &lineitem.l_quantity))
+            -> map(|l: LineItem| (l.l_partkey, (l.l_quantity, l.l_discount, l.l_extendedprice)))
+            -> [1]pushdown;
+        pushdown = cross_join_multiset()
+            -> filter_map(|(bloom, l): (Rc<BloomFilter>, (i64, (f64, f64, f64)))| bloom.contains(&l.0).then_some(l));
+
+        // 5. Join on p_partkey = l_partkey.
+        part_filtered -> [0]joined;
+        pushdown -> [1]joined;
+        joined = join_multiset();
+
+        // 6. Filter again and 7. sum the revenue.
+        joined
+            -> filter(|(_key, ((p_brand, p_container, p_size), (l_quantity, _l_discount, _l_extendedprice))): &(i64, ((String, String, i32), (f64, f64, f64)))| {
+                (Part::filter_1(params, p_brand, p_size, p_container) && LineItem::filter_1(params, l_quantity))
+                || (Part::filter_2(params, p_brand, p_size, p_container) && LineItem::filter_2(params, l_quantity))
+                || (Part::filter_3(params, p_brand, p_size, p_container) && LineItem::filter_3(params, l_quantity))
+            })
+            -> map(|(_key, (_part, (_l_quantity, l_discount, l_extendedprice)))| l_extendedprice * (1.0 - l_discount))
+            -> reduce(|a, b| *a += b)
+            // 8. Collect: "revenue"
+            -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    answer(hydroflow::util::collect_ready::<Vec<f64>, _>(output_recv))
+}
+
+/**
+ * Sort-merge join instead of the hash join, see `base::tpch::merge_join`. Both inputs are sorted
+ * with `sort_by_key`, collected into one vector each and merged once both are complete.
+ */
+pub fn query_merge_join(line_items: Vec<LineItem>, part: Vec<Part>, params: &QueryParams) -> Vec<Q19Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<f64>();
+
+    let mut flow = hydroflow_syntax! {
+        // 1. Scan part, 2. pushed down filter and sort by p_partkey.
+        part_sorted = source_iter(part)
+            -> filter(|part: &Part| {
+                Part::filter_1(params, &part.p_brand, &part.p_size, &part.p_container)
+                || Part::filter_2(params, &part.p_brand, &part.p_size, &part.p_container)
+                || Part::filter_3(params, &part.p_brand, &part.p_size, &part.p_container)
+            })
+            -> map(|p: Part| (p.p_partkey, (p.p_brand, p.p_container, p.p_size)))
+            -> sort_by_key(|x| &x.0)
+            -> fold(Vec::new, |acc: &mut Vec<(i64, (String, String, i32))>, x| acc.push(x));
+
+        // 3. Scan lineitem, 4. pushed down filter and sort by l_partkey.
+        lineitem_sorted = source_iter(line_items)
+            -> filter(|lineitem: &LineItem| lineitem.l_shipinstruct == "DELIVER IN PERSON" && (lineitem.l_shipmode == "AIR" || lineitem.l_shipmode == "AIR REG"))
+            -> filter(|lineitem: &LineItem| LineItem::filter_1(params, &lineitem.l_quantity) || LineItem::filter_2(params, &lineitem.l_quantity) || LineItem::filter_3(params, &lineitem.l_quantity))
+            -> map(|l: LineItem| (l.l_partkey, (l.l_quantity, l.l_discount, l.l_extendedprice)))
+            -> sort_by_key(|x| &x.0)
+            -> fold(Vec::new, |acc: &mut Vec<(i64, (f64, f64, f64))>, x| acc.push(x));
+
+        // 5. Merge join on p_partkey = l_partkey.
+        part_sorted -> [0]joined;
+        lineitem_sorted -> [1]joined;
+        joined = zip() -> flat_map(|(part, line_items)| merge_join(part, line_items));
+
+        // 6. Filter again and 7. sum the revenue.
+        joined
+            -> filter(|(_key, ((p_brand, p_container, p_size), (l_quantity, _l_discount, _l_extendedprice))): &(i64, ((String, String, i32), (f64, f64, f64)))| {
+                (Part::filter_1(params, p_brand, p_size, p_container) && LineItem::filter_1(params, l_quantity))
+                || (Part::filter_2(params, p_brand, p_size, p_container) && LineItem::filter_2(params, l_quantity))
+                || (Part::filter_3(params, p_brand, p_size, p_container) && LineItem::filter_3(params, l_quantity))
+            })
+            -> map(|(_key, (_part, (_l_quantity, l_discount, l_extendedprice)))| l_extendedprice * (1.0 - l_discount))
+            -> reduce(|a, b| *a += b)
+            // 8. Collect: "revenue"
+            -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    answer(hydroflow::util::collect_ready::<Vec<f64>, _>(output_recv))
+}
+
+/**
+ * The graph of `query` over streams of both inputs, which it pulls with `source_stream`. The graph
+ * may run over several ticks while the streams wait for rows, see `super::scan::run_to_end`. The
+ * filtered rows of both inputs are kept across ticks, and the join is split in two halves, so that
+ * every pair matches in exactly one tick: the new line items of a tick probe the parts of all ticks
+ * so far, the new parts of a tick probe the line items of the earlier ticks. Every tick stops at
+ * its partial revenue, the merge graph adds it up.
+ */
+pub fn query_stream<L, P>(line_items: L, part: P, params: &QueryParams) -> Vec<Q19Row>
+where
+    L: Stream<Item = LineItem> + Unpin,
+    P: Stream<Item = Part> + Unpin,
+{
+    let ends = Ends::default();
+    let (line_items, part) = (ends.track(line_items), ends.track(part));
+    let (partial_send, partial_recv) = hydroflow::util::unbounded_channel::<f64>();
+
+    let mut flow = hydroflow_syntax! {
+        // 1. Scan part.
+        part_filtered = source_stream(part)
+        // 2. Pushed down filter on part.
+            -> filter(|part: &Part| {
+                Part::filter_1(params, &part.p_brand, &part.p_size, &part.p_container)
+                || Part::filter_2(params, &part.p_brand, &part.p_size, &part.p_container)
+                || Part::filter_3(params, &part.p_brand, &part.p_size, &part.p_container)
+            })
+            -> map(|p: Part| (p.p_partkey, (p.p_brand, p.p_container, p.p_size)))
+            -> tee();
+
+        // 3. Scan lineitem.
+        lineitem_filtered = source_stream(line_items)
+            // 4. Pushed down lineitem filter.
+            -> filter(|lineitem: &LineItem| lineitem.l_shipinstruct == "DELIVER IN PERSON" && (lineitem.l_shipmode == "AIR" || lineitem.l_shipmode == "AIR REG"))
+            -> filter(|lineitem: &LineItem| LineItem::filter_1(params, &lineitem.l_quantity) || LineItem::filter_2(params, &lineitem.l_quantity) || LineItem::filter_3(params, &lineitem.l_quantity))
+            -> map(|l: LineItem| (l.l_partkey, (l.l_quantity, l.l_discount, l.l_extendedprice)))
+            -> tee();
+
+        // 5. Join the two on p_partkey = l_partkey.
+        // 5.1 The new line items probe the parts of all ticks so far.
+        part_filtered -> [0]new_line_items;
+        lineitem_filtered -> [1]new_line_items;
+        new_line_items = join_multiset::<'static, 'tick>();
+        // 5.2 The new parts probe the line items of the earlier ticks.
+        part_filtered -> [0]new_parts;
+        lineitem_filtered -> defer_tick() -> [1]new_parts;
+        new_parts = join_multiset::<'tick, 'static>();
+        joined = union();
+        new_line_items -> joined;
+        new_parts -> joined;
 
-        // 8. Print
-        agg -> for_each(|x| {
-            println!("{:?}", x);
+        // 6. Filter again and 7. sum the partial revenue of the tick.
+        joined
+            -> filter(|(_key, ((p_brand, p_container, p_size), (l_quantity, _l_discount, _l_extendedprice))): &(i64, ((String, String, i32), (f64, f64, f64)))| {
+                (Part::filter_1(params, p_brand, p_size, p_container) && LineItem::filter_1(params, l_quantity))
+                || (Part::filter_2(params, p_brand, p_size, p_container) && LineItem::filter_2(params, l_quantity))
+                || (Part::filter_3(params, p_brand, p_size, p_container) && LineItem::filter_3(params, l_quantity))
+            })
+            -> map(|(_key, (_part, (_l_quantity, l_discount, l_extendedprice)))| l_extendedprice * (1.0 - l_discount))
+            -> reduce(|a, b| *a += b)
+            -> for_each(|x| partial_send.send(x).unwrap());
+    };
+
+    let partials = run_to_end(&mut flow, &ends, 2, partial_recv);
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<f64>();
+
+    let mut flow = hydroflow_syntax! {
+        // 8. Add up the partial revenue of the ticks that had qualifying rows.
+        source_iter(partials) -> reduce(|a: &mut f64, b: f64| *a += b)
+            -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    answer(hydroflow::util::collect_ready::<Vec<f64>, _>(output_recv))
+}
+
+/// Streams lineitem and part from DuckDB into the graph, both tables are loaded while the graph
+/// runs, see `super::query_1::query_scan`.
+pub fn query_scan(conn: &Connection, params: &QueryParams) -> Vec<Q19Row> {
+    let (line_items, line_items_loader) = scan::<LineItem>(conn);
+    let (part, part_loader) = scan::<Part>(conn);
+    let result = query_stream(line_items, part, params);
+    line_items_loader.join();
+    part_loader.join();
+    result
+}
+
+/**
+ * The build side is collected into one hash table, every tuple of the probe side is a column chunk
+ * of `CHUNK_SIZE` line items with its selection vector, see `base::tpch::query_19::revenue_chunk`.
+ */
+pub fn query_columns<const CHUNK_SIZE: usize>(line_items: LineItemColumns, part: Vec<Part>, params: &QueryParams) -> Vec<Q19Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<f64>();
+
+    let mut flow = hydroflow_syntax! {
+        // 1. Scan part.
+        part_filtered = source_iter(part)
+        // 2. Pushed down filter on part.
+            -> filter(|part| {
+                Part::filter_1(params, &part.p_brand, &part.p_size, &part.p_container)
+                || Part::filter_2(params, &part.p_brand, &part.p_size, &part.p_container)
+                || Part::filter_3(params, &part.p_brand, &part.p_size, &part.p_container)
+            });
+
+        // 3. Scan lineitem in column chunks.
+        chunks = source_iter(line_items.chunks(CHUNK_SIZE))
+            // 4. Pushed down lineitem filters into a selection vector.
+            -> map(|chunk: LineItemChunk| (chunk, select_chunk(&chunk, params)));
+
+        // 5. Join the two
+        // Build: Part, one hash table shared by all chunks.
+        part_filtered
+            -> map(|p| (p.p_partkey, (p.p_brand, p.p_container, p.p_size)))
+            -> fold(HashMap::new, |map: &mut HashMap<i64, (String, String, i32)>, (key, value)| {
+                map.insert(key, value);
+            })
+            -> map(Rc::new)
+            -> [0]probe;
+        // Probe: the LineItem chunks.
+        chunks -> [1]probe;
+
+        // 6. Filter again and 7. aggregate the revenue of every chunk.
+        probe = cross_join_multiset()
+            -> filter_map(|(parts, (chunk, selection)): (Rc<HashMap<i64, (String, String, i32)>>, (LineItemChunk, Vec<usize>))| {
+                revenue_chunk(&chunk, &selection, &parts, params)
+            })
+            -> reduce(|a, b| *a += b);
+
+        // 8. Collect: "revenue"
+        probe -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    answer(hydroflow::util::collect_ready::<Vec<f64>, _>(output_recv))
+}
+
+/**
+ * Runs one instance of the query graph per thread, see `super::partitioned`. Both inputs are hash
+ * partitioned on the part key, so the join is local to every partition. The instances stop at the
+ * partial revenue, the merge graph adds it up.
+ */
+pub fn query_partitioned(line_items: Vec<LineItem>, part: Vec<Part>, params: &QueryParams, threads: usize) -> Vec<Q19Row> {
+
+    let partitions = hash_partition(line_items, threads, |l| l.l_partkey)
+        .into_iter()
+        .zip(hash_partition(part, threads, |p| p.p_partkey))
+        .collect();
+
+    let (partial_send, partial_recv) = hydroflow::util::unbounded_channel::<f64>();
+
+    run_partitioned(partitions, &partial_send, |(line_items, part), partial_send| {
+        let mut flow = hydroflow_syntax! {
+            // 1. Scan the partition of part.
+            part_filtered = source_iter(part)
+            // 2. Pushed down filter on part.
+                -> filter(|part: &Part| {
+                    Part::filter_1(params, &part.p_brand, &part.p_size, &part.p_container)
+                    || Part::filter_2(params, &part.p_brand, &part.p_size, &part.p_container)
+                    || Part::filter_3(params, &part.p_brand, &part.p_size, &part.p_container)
+                });
+
+            // 3. Scan the partition of lineitem.
+            lineitem_filtered = source_iter(line_items)
+                // 4. Pushed down lineitem filter.
+                -> filter(|lineitem: &LineItem| lineitem.l_shipinstruct == "DELIVER IN PERSON" && (lineitem.l_shipmode == "AIR" || lineitem.l_shipmode == "AIR REG"))
+                -> filter(|lineitem: &LineItem| LineItem::filter_1(params, &lineitem.l_quantity) || LineItem::filter_2(params, &lineitem.l_quantity) || LineItem::filter_3(params, &lineitem.l_quantity));
+
+            // 5. Join the two on p_partkey = l_partkey.
+            part_filtered -> map(|p: Part| (p.p_partkey, (p.p_brand, p.p_container, p.p_size))) -> [0]joined;
+            lineitem_filtered -> map(|l: LineItem| (l.l_partkey, (l.l_quantity, l.l_discount, l.l_extendedprice))) -> [1]joined;
+            joined = join_multiset();
+
+            // 6. Filter again and 7. sum the partial revenue.
+            joined
+                -> filter(|(_key, ((p_brand, p_container, p_size), (l_quantity, _l_discount, _l_extendedprice))): &(i64, ((String, String, i32), (f64, f64, f64)))| {
+                    (Part::filter_1(params, p_brand, p_size, p_container) && LineItem::filter_1(params, l_quantity))
+                    || (Part::filter_2(params, p_brand, p_size, p_container) && LineItem::filter_2(params, l_quantity))
+                    || (Part::filter_3(params, p_brand, p_size, p_container) && LineItem::filter_3(params, l_quantity))
+                })
+                -> map(|(_key, (_part, (_l_quantity, l_discount, l_extendedprice)))| l_extendedprice * (1.0 - l_discount))
+                -> reduce(|a, b| *a += b)
+                -> for_each(|x| partial_send.send(x).unwrap());
+        };
+        flow.run_available();
+    });
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<f64>();
+
+    let mut flow = hydroflow_syntax! {
+        // 8. Add up the partial revenue of the partitions that had qualifying rows.
+        source_stream(partial_recv) -> reduce(|a: &mut f64, b: f64| *a += b)
+            -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    answer(hydroflow::util::collect_ready::<Vec<f64>, _>(output_recv))
+}
+
+/// Same as `query`, with exact `Decimal` arithmetic on the money columns.
+pub fn query_decimal(line_items: Vec<LineItem<Decimal>>, part: Vec<Part>, params: &QueryParams) -> Vec<Q19Row<Decimal>> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Decimal>();
+
+    let mut flow = hydroflow_syntax! {
+        // 1. Scan part.
+        part_filtered = source_iter(part)
+        // 2. Pushed down filter on part.
+            -> filter(|part| {
+                Part::filter_1(params, &part.p_brand, &part.p_size, &part.p_container)
+                || Part::filter_2(params, &part.p_brand, &part.p_size, &part.p_container)
+                || Part::filter_3(params, &part.p_brand, &part.p_size, &part.p_container)
+            });
+
+        // 3. Scan lineitem.
+        lineitem_filtered = source_iter(line_items)
+            // 4. Pushed down lineitem filter.
+            // l_shipinstruct = "DELIVER IN PERSON"
+            // l_shipmode = "AIR" or "AIR REG"
+            -> filter(|lineitem| lineitem.l_shipinstruct == "DELIVER IN PERSON" && (lineitem.l_shipmode == "AIR" || lineitem.l_shipmode == "AIR REG"))
+            -> filter(|lineitem| LineItem::filter_1(params, &lineitem.l_quantity) || LineItem::filter_2(params, &lineitem.l_quantity) || LineItem::filter_3(params, &lineitem.l_quantity));
+
+        // 5. Join the two
+        // No build side: join_multiset() hashes both inputs, see `base::tpch::query_19::query_with_plan`
+        // for a one-sided hash join on the side chosen by the plan.
+
+        // Keys left (p_partkey)
+        // Payload left (p_brand, p_container, p_size)
+        part_filtered -> map(|p| (p.p_partkey, (p.p_brand, p.p_container, p.p_size))) -> [0]joined;
+            /* .fold(HashMap::new(), |mut map, (key, value)| {
+                map.insert(key, value);
+                map
+            }); */
+
+        // Keys right (l_partkey)
+        // Payload right (l_quantity, l_discount, l_extendedprice)
+        lineitem_filtered -> map(|l| (l.l_partkey, (l.l_quantity, l.l_discount, l.l_extendedprice))) -> [1]joined;
+
+        joined = join_multiset() -> map(|(_key, (part_payload, lineitem_payload))| {
+            let (p_brand, p_container, p_size) = part_payload;
+            let (l_quantity, l_discount, l_extendedprice) = lineitem_payload;
+            (p_brand, p_container, p_size, l_quantity, l_discount, l_extendedprice)
+        });
+
+        // 6. Filter again, we need to make sure the right tuples survived.
+        join_filtered = joined -> filter(|(p_brand, p_container, p_size, l_quantity, _l_discount, _l_extendedprice)| {
+            (Part::filter_1(params, &p_brand, &p_size, &p_container) && LineItem::filter_1(params, l_quantity))
+            || (Part::filter_2(params, &p_brand, &p_size, &p_container) && LineItem::filter_2(params, l_quantity))
+            || (Part::filter_3(params, &p_brand, &p_size, &p_container) && LineItem::filter_3(params, l_quantity))
         });
+
+        // 7. Aggregate the result.
+        // 7.1 Compute (l_extendedprice * (1 - l_discount))
+        // 7.2. Aggregate sum
+        agg = join_filtered -> map(|(_p_brand, _p_container, _p_size, _l_quantity, l_discount, l_extendedprice)| {
+            l_extendedprice * (Decimal::one() - l_discount)
+        }) -> reduce(|a, b| *a += b);
+
+        // 8. Collect: "revenue"
+        agg -> for_each(|x| output_send.send(x).unwrap());
     };
 
     flow.run_available();
+    answer(hydroflow::util::collect_ready::<Vec<Decimal>, _>(output_recv))
 }
 
-pub fn query_base(line_items: Vec<LineItem>, part: Vec<Part>) {
+pub fn query_base(line_items: Vec<LineItem>, part: Vec<Part>, params: &QueryParams) -> Vec<Q19Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Vec<Q19Row>>();
 
     let mut flow = hydroflow_syntax! {
         source_iter([(line_items, part)]) -> for_each(|(line_items, part)|{
-            query_base_original(line_items, part);
+            output_send.send(query_base_original(line_items, part, params)).unwrap();
         });
     };
 
     flow.run_available();
+    let res = hydroflow::util::collect_ready::<Vec<Vec<Q19Row>>, _>(output_recv);
+
+    assert!(res.len() == 1);
+    res.into_iter().next().unwrap()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use base::tpch::initialize::initialize_database;
+    use base::tpch::query_19::{load_columns, load_decimal, query_duckdb, query_duckdb_decimal};
+    use base::tpch::verify::{assert_match, compare, Tolerance};
 
     #[test]
     fn test_query() {
         let limit = None;
-        let conn = initialize_database(1);
+        let conn = initialize_database(0.01);
+
+        let line_items = LineItem::load(&conn, limit).unwrap();
+        let part = Part::load(&conn, limit).unwrap();
 
-        let line_items = LineItem::load(&conn, limit);
-        let part = Part::load(&conn, limit);
+        let result = super::query(line_items, part, &QueryParams::default());
 
-        super::query(line_items, part);
+        let expected = query_duckdb(&conn, limit, &QueryParams::default());
+        assert_match(&[compare("Q19", "duckdb", "hydroflow", &expected, &result, &Tolerance::default())]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_query_columns() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+        let (line_items, part) = load_columns(&conn).unwrap();
+
+        let result = super::query_columns::<1024>(line_items, part, &params);
+
+        let expected = query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q19", "duckdb", "hydroflow-columns", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_partitioned() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+        let expected = query_duckdb(&conn, None, &params);
+
+        for threads in [1, 2, 4] {
+            let line_items = LineItem::load(&conn, None).unwrap();
+            let part = Part::load(&conn, None).unwrap();
+            let result = super::query_partitioned(line_items, part, &params, threads);
+            assert_match(&[compare("Q19", "duckdb", "hydroflow-partitioned", &expected, &result, &Tolerance::default())]);
+        }
+    }
+
+    #[test]
+    fn test_query_decimal() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+        let (line_items, part) = load_decimal(&conn).unwrap();
+
+        let result = super::query_decimal(line_items, part, &params);
+
+        let expected = query_duckdb_decimal(&conn, None, &params);
+        assert_match(&[compare("Q19", "duckdb", "hydroflow-decimal", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_bloom() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+        let line_items = LineItem::load(&conn, None).unwrap();
+        let part = Part::load(&conn, None).unwrap();
+
+        let result = super::query_bloom(line_items, part, &params);
+
+        let expected = query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q19", "duckdb", "hydroflow-bloom", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_merge_join() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+        let line_items = LineItem::load(&conn, None).unwrap();
+        let part = Part::load(&conn, None).unwrap();
+
+        let result = super::query_merge_join(line_items, part, &params);
+
+        let expected = query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q19", "duckdb", "hydroflow-merge-join", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_scan() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+
+        let result = super::query_scan(&conn, &params);
+
+        let expected = query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q19", "duckdb", "hydroflow-scan", &expected, &result, &Tolerance::default())]);
+    }
+}
@@ -0,0 +1,119 @@
+use hydroflow::hydroflow_syntax;
+
+use base::tpch::query_2::{Nation, Part, PartSupp, Q2Row, Region, Supplier, query as query_base_original};
+
+pub fn query(
+    part: Vec<Part>,
+    supplier: Vec<Supplier>,
+    partsupp: Vec<PartSupp>,
+    nation: Vec<Nation>,
+    region: Vec<Region>,
+) -> Vec<Q2Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Q2Row>();
+
+    let mut flow = hydroflow_syntax! {
+        // 1. Scan region and filter on r_name = 'EUROPE'.
+        region_filtered = source_iter(region) -> filter(|r| r.r_name == "EUROPE");
+
+        // 2. Join region and nation on r_regionkey = n_regionkey.
+        region_filtered -> map(|r| (r.r_regionkey, ())) -> [0]region_nation;
+        source_iter(nation) -> map(|n| (n.n_regionkey, (n.n_nationkey, n.n_name))) -> [1]region_nation;
+        region_nation = join_multiset()
+            -> map(|(_r_regionkey, ((), (n_nationkey, n_name)))| (n_nationkey, n_name));
+
+        // 3. Join nation and supplier on n_nationkey = s_nationkey.
+        region_nation -> [0]nation_supplier;
+        source_iter(supplier) -> map(|s| (s.s_nationkey, s)) -> [1]nation_supplier;
+        nation_supplier = join_multiset()
+            -> map(|(_n_nationkey, (n_name, s))| (s.s_suppkey, (s, n_name)));
+
+        // 4. Join supplier and partsupp on s_suppkey = ps_suppkey.
+        nation_supplier -> [0]supplier_partsupp;
+        source_iter(partsupp) -> map(|ps| (ps.ps_suppkey, (ps.ps_partkey, ps.ps_supplycost))) -> [1]supplier_partsupp;
+        supplier_partsupp = join_multiset()
+            -> map(|(_s_suppkey, ((s, n_name), (ps_partkey, ps_supplycost)))| (ps_partkey, (ps_supplycost, s, n_name)));
+
+        // 5. Scan part, filter on p_size = 15 and p_type LIKE '%BRASS' and join on p_partkey = ps_partkey.
+        // The outer query and the subquery share this join, restricting the subquery to the parts
+        // of the outer query is safe as the subquery is only evaluated for those.
+        source_iter(part)
+            -> filter(|p| p.p_size == 15 && p.p_type.ends_with("BRASS"))
+            -> map(|p| (p.p_partkey, p.p_mfgr))
+            -> [0]part_partsupp;
+        supplier_partsupp -> [1]part_partsupp;
+        part_partsupp = join_multiset()
+            -> map(|(p_partkey, (p_mfgr, (ps_supplycost, s, n_name)))| (p_partkey, (ps_supplycost, p_mfgr, s, n_name)))
+            -> tee();
+
+        // 6. Subquery: group by ps_partkey and compute min(ps_supplycost).
+        min_cost = part_partsupp
+            -> map(|(p_partkey, (ps_supplycost, _p_mfgr, _s, _n_name))| (p_partkey, ps_supplycost))
+            -> reduce_keyed(|acc: &mut f64, ps_supplycost: f64| *acc = acc.min(ps_supplycost));
+
+        // 7. Join the outer rows with the minimum on p_partkey and keep ps_supplycost = min(ps_supplycost).
+        min_cost -> [0]joined;
+        part_partsupp -> [1]joined;
+        joined = join_multiset()
+            -> filter(|(_p_partkey, (min_cost, (ps_supplycost, _p_mfgr, _s, _n_name)))| ps_supplycost == min_cost)
+            -> map(|(p_partkey, (_min_cost, (_ps_supplycost, p_mfgr, s, n_name)))| Q2Row::new(s, n_name, p_partkey, p_mfgr));
+
+        // 8. Sort by s_acctbal desc, n_name, s_name, p_partkey and keep the first 100 rows.
+        ordered = joined
+            -> fold(Vec::new, |acc: &mut Vec<Q2Row>, x: Q2Row| acc.push(x))
+            -> flat_map(|mut x: Vec<Q2Row>| {
+                x.sort_by(Q2Row::order_by);
+                x.truncate(100);
+                x
+            });
+
+        // 9. Collect
+        ordered -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    hydroflow::util::collect_ready::<Vec<Q2Row>, _>(output_recv)
+}
+
+pub fn query_base(
+    part: Vec<Part>,
+    supplier: Vec<Supplier>,
+    partsupp: Vec<PartSupp>,
+    nation: Vec<Nation>,
+    region: Vec<Region>,
+) -> Vec<Q2Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Vec<Q2Row>>();
+
+    let mut flow = hydroflow_syntax! {
+        source_iter([(part, supplier, partsupp, nation, region)])
+            -> for_each(|(part, supplier, partsupp, nation, region)|{
+                output_send.send(query_base_original(part, supplier, partsupp, nation, region)).unwrap();
+            });
+    };
+
+    flow.run_available();
+    let res = hydroflow::util::collect_ready::<Vec<Vec<Q2Row>>, _>(output_recv);
+
+    assert!(res.len() == 1);
+    res.into_iter().next().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use base::tpch::initialize::initialize_database;
+    use base::tpch::query_2::{load, query_duckdb};
+    use base::tpch::verify::{assert_match, compare, Tolerance};
+
+    #[test]
+    fn test_query() {
+        let conn = initialize_database(0.01);
+
+        let (part, supplier, partsupp, nation, region) = load(&conn);
+
+        let result = super::query(part, supplier, partsupp, nation, region);
+
+        let expected = query_duckdb(&conn, None);
+        assert_match(&[compare("Q2", "duckdb", "hydroflow", &expected, &result, &Tolerance::default())]);
+    }
+}
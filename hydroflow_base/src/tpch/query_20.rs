@@ -0,0 +1,115 @@
+use chrono::NaiveDate;
+use hydroflow::hydroflow_syntax;
+
+use base::tpch::query_20::{LineItem, Nation, Part, PartSupp, Q20Row, Supplier, query as query_base_original};
+
+pub fn query(
+    supplier: Vec<Supplier>,
+    nation: Vec<Nation>,
+    partsupp: Vec<PartSupp>,
+    part: Vec<Part>,
+    line_items: Vec<LineItem>,
+) -> Vec<Q20Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Q20Row>();
+
+    let mut flow = hydroflow_syntax! {
+        // 1. Scan part and filter on p_name LIKE 'forest%'.
+        part_filtered = source_iter(part)
+            -> filter(|p| p.p_name.starts_with("forest"))
+            -> map(|p| (p.p_partkey, ()))
+            -> tee();
+
+        // 2. Scan lineitem, filter on l_shipdate in [1994-01-01, 1995-01-01) and join part on l_partkey = p_partkey.
+        part_filtered -> [0]part_line_items;
+        source_iter(line_items)
+            -> filter(|l| {
+                l.l_shipdate >= NaiveDate::from_ymd_opt(1994, 1, 1).unwrap()
+                    && l.l_shipdate < NaiveDate::from_ymd_opt(1995, 1, 1).unwrap()
+            })
+            -> map(|l| (l.l_partkey, (l.l_suppkey, l.l_quantity)))
+            -> [1]part_line_items;
+        part_line_items = join_multiset()
+            -> map(|(l_partkey, ((), (l_suppkey, l_quantity)))| ((l_partkey, l_suppkey), l_quantity));
+
+        // 3. Subquery: group by l_partkey, l_suppkey and compute 0.5 * sum(l_quantity).
+        threshold = part_line_items
+            -> fold_keyed(|| 0.0, |acc: &mut f64, l_quantity: f64| *acc += 0.5 * l_quantity);
+
+        // 4. Scan partsupp and join part on ps_partkey = p_partkey (ps_partkey IN (...)).
+        part_filtered -> [0]part_partsupp;
+        source_iter(partsupp) -> map(|ps| (ps.ps_partkey, (ps.ps_suppkey, ps.ps_availqty))) -> [1]part_partsupp;
+        part_partsupp = join_multiset()
+            -> map(|(ps_partkey, ((), (ps_suppkey, ps_availqty)))| ((ps_partkey, ps_suppkey), ps_availqty));
+
+        // 5. Join the subquery on ps_partkey, ps_suppkey and filter on ps_availqty > 0.5 * sum(l_quantity).
+        // Partsupp rows without line items find no partner, like the NULL comparison in SQL.
+        threshold -> [0]partsupp_qualified;
+        part_partsupp -> [1]partsupp_qualified;
+        partsupp_qualified = join_multiset()
+            -> filter(|(_key, (threshold, ps_availqty))| *ps_availqty as f64 > *threshold)
+            -> map(|((_ps_partkey, ps_suppkey), _)| (ps_suppkey, ()));
+
+        // 6. Scan nation, filter on n_name = 'CANADA' and join supplier on n_nationkey = s_nationkey.
+        source_iter(nation) -> filter(|n| n.n_name == "CANADA") -> map(|n| (n.n_nationkey, ())) -> [0]nation_supplier;
+        source_iter(supplier) -> map(|s| (s.s_nationkey, (s.s_suppkey, s.s_name, s.s_address))) -> [1]nation_supplier;
+        nation_supplier = join_multiset()
+            -> map(|(_n_nationkey, ((), (s_suppkey, s_name, s_address)))| (s_suppkey, (s_name, s_address)));
+
+        // 7. Semi join supplier with the qualifying ps_suppkey (s_suppkey IN (...)).
+        // Note: Implementing a semijoin using a hash join with unique output.
+        nation_supplier -> [0]joined;
+        partsupp_qualified -> [1]joined;
+        joined = join() -> map(|(_s_suppkey, ((s_name, s_address), ()))| Q20Row { s_name, s_address });
+
+        // 8. Sort by s_name and collect: "s_name", "s_address"
+        joined -> sort_by_key(|x: &Q20Row| &x.s_name)
+            -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    hydroflow::util::collect_ready::<Vec<Q20Row>, _>(output_recv)
+}
+
+pub fn query_base(
+    supplier: Vec<Supplier>,
+    nation: Vec<Nation>,
+    partsupp: Vec<PartSupp>,
+    part: Vec<Part>,
+    line_items: Vec<LineItem>,
+) -> Vec<Q20Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Vec<Q20Row>>();
+
+    let mut flow = hydroflow_syntax! {
+        source_iter([(supplier, nation, partsupp, part, line_items)])
+            -> for_each(|(supplier, nation, partsupp, part, line_items)|{
+                output_send.send(query_base_original(supplier, nation, partsupp, part, line_items)).unwrap();
+            });
+    };
+
+    flow.run_available();
+    let res = hydroflow::util::collect_ready::<Vec<Vec<Q20Row>>, _>(output_recv);
+
+    assert!(res.len() == 1);
+    res.into_iter().next().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use base::tpch::initialize::initialize_database;
+    use base::tpch::query_20::{load, query_duckdb};
+    use base::tpch::verify::{assert_match, compare, Tolerance};
+
+    #[test]
+    fn test_query() {
+        let conn = initialize_database(0.01);
+
+        let (supplier, nation, partsupp, part, line_items) = load(&conn);
+
+        let result = super::query(supplier, nation, partsupp, part, line_items);
+
+        let expected = query_duckdb(&conn, None);
+        assert_match(&[compare("Q20", "duckdb", "hydroflow", &expected, &result, &Tolerance::default())]);
+    }
+}
@@ -0,0 +1,113 @@
+use hydroflow::hydroflow_syntax;
+
+use base::tpch::query_21::{LineItem, Nation, Order, Q21Row, Supplier, Suppliers, query as query_base_original};
+
+pub fn query(supplier: Vec<Supplier>, line_items: Vec<LineItem>, orders: Vec<Order>, nation: Vec<Nation>) -> Vec<Q21Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Q21Row>();
+
+    let mut flow = hydroflow_syntax! {
+        // 1. Scan orders, filter on o_orderstatus = 'F' and join lineitem on o_orderkey = l_orderkey.
+        // The subqueries only look at line items of the same order, so the filter applies to them as well.
+        source_iter(orders) -> filter(|o| o.o_orderstatus == 'F') -> map(|o| (o.o_orderkey, ())) -> [0]order_line_items;
+        source_iter(line_items) -> map(|l| (l.l_orderkey, (l.l_suppkey, l.is_late()))) -> [1]order_line_items;
+        order_line_items = join_multiset()
+            -> map(|(l_orderkey, ((), (l_suppkey, is_late)))| (l_orderkey, (l_suppkey, is_late)))
+            -> tee();
+
+        // 2. EXISTS subquery: group by l_orderkey and keep the orders with more than one supplier.
+        multi_supplier = order_line_items
+            -> map(|(l_orderkey, (l_suppkey, _is_late))| (l_orderkey, l_suppkey))
+            -> fold_keyed(Suppliers::default, |acc: &mut Suppliers, l_suppkey: i64| acc.insert(l_suppkey))
+            -> filter(|(_l_orderkey, suppliers)| suppliers.is_multiple())
+            -> map(|(l_orderkey, _suppliers)| (l_orderkey, ()));
+
+        // 3. NOT EXISTS subquery: group the late line items by l_orderkey and keep the orders with
+        // more than one late supplier.
+        late = order_line_items -> filter(|(_l_orderkey, (_l_suppkey, is_late))| *is_late) -> tee();
+        multi_late_supplier = late
+            -> map(|(l_orderkey, (l_suppkey, _is_late))| (l_orderkey, l_suppkey))
+            -> fold_keyed(Suppliers::default, |acc: &mut Suppliers, l_suppkey: i64| acc.insert(l_suppkey))
+            -> filter(|(_l_orderkey, suppliers)| suppliers.is_multiple())
+            -> map(|(l_orderkey, _suppliers)| l_orderkey);
+
+        // 4. Scan nation, filter on n_name = 'SAUDI ARABIA' and join supplier on n_nationkey = s_nationkey.
+        source_iter(nation) -> filter(|n| n.n_name == "SAUDI ARABIA") -> map(|n| (n.n_nationkey, ())) -> [0]nation_supplier;
+        source_iter(supplier) -> map(|s| (s.s_nationkey, (s.s_suppkey, s.s_name))) -> [1]nation_supplier;
+        nation_supplier = join_multiset()
+            -> map(|(_n_nationkey, ((), (s_suppkey, s_name)))| (s_suppkey, s_name));
+
+        // 5. Join the late line items (l1) with supplier on l_suppkey = s_suppkey.
+        nation_supplier -> [0]candidates;
+        late -> map(|(l_orderkey, (l_suppkey, _is_late))| (l_suppkey, l_orderkey)) -> [1]candidates;
+        candidates = join_multiset() -> map(|(_s_suppkey, (s_name, l_orderkey))| (l_orderkey, s_name));
+
+        // 6. EXISTS: semi join with the orders that have another supplier.
+        multi_supplier -> [0]exists;
+        candidates -> [1]exists;
+        exists = join_multiset() -> map(|(l_orderkey, ((), s_name))| (l_orderkey, s_name));
+
+        // 7. NOT EXISTS: anti join with the orders that have another late supplier.
+        // The multiset version keeps duplicates, count(*) counts every late line item.
+        exists -> [pos]not_exists;
+        multi_late_supplier -> [neg]not_exists;
+        not_exists = anti_join_multiset();
+
+        // 8. Group by s_name and count(*).
+        agg = not_exists
+            -> map(|(_l_orderkey, s_name)| (s_name, 1i64))
+            -> reduce_keyed(|acc: &mut i64, x: i64| *acc += x)
+            -> map(|(s_name, numwait)| Q21Row { s_name, numwait });
+
+        // 9. Sort by numwait desc, s_name and keep the first 100 rows.
+        ordered = agg
+            -> fold(Vec::new, |acc: &mut Vec<Q21Row>, x: Q21Row| acc.push(x))
+            -> flat_map(|mut x: Vec<Q21Row>| {
+                x.sort_by(Q21Row::order_by);
+                x.truncate(100);
+                x
+            });
+
+        // 10. Collect: "s_name", "numwait"
+        ordered -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    hydroflow::util::collect_ready::<Vec<Q21Row>, _>(output_recv)
+}
+
+pub fn query_base(supplier: Vec<Supplier>, line_items: Vec<LineItem>, orders: Vec<Order>, nation: Vec<Nation>) -> Vec<Q21Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Vec<Q21Row>>();
+
+    let mut flow = hydroflow_syntax! {
+        source_iter([(supplier, line_items, orders, nation)]) -> for_each(|(supplier, line_items, orders, nation)|{
+            output_send.send(query_base_original(supplier, line_items, orders, nation)).unwrap();
+        });
+    };
+
+    flow.run_available();
+    let res = hydroflow::util::collect_ready::<Vec<Vec<Q21Row>>, _>(output_recv);
+
+    assert!(res.len() == 1);
+    res.into_iter().next().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use base::tpch::initialize::initialize_database;
+    use base::tpch::query_21::{load, query_duckdb};
+    use base::tpch::verify::{assert_match, compare, Tolerance};
+
+    #[test]
+    fn test_query() {
+        let conn = initialize_database(0.01);
+
+        let (supplier, line_items, orders, nation) = load(&conn);
+
+        let result = super::query(supplier, line_items, orders, nation);
+
+        let expected = query_duckdb(&conn, None);
+        assert_match(&[compare("Q21", "duckdb", "hydroflow", &expected, &result, &Tolerance::default())]);
+    }
+}
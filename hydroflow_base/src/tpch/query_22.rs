@@ -0,0 +1,91 @@
+use hydroflow::hydroflow_syntax;
+
+use base::tpch::query_22::{Customer, Order, Q22Row, COUNTRY_CODES, query as query_base_original};
+
+pub fn query(customer: Vec<Customer>, orders: Vec<Order>) -> Vec<Q22Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Q22Row>();
+
+    let mut flow = hydroflow_syntax! {
+        // 1. Scan customer and filter on the country code.
+        customer_filtered = source_iter(customer)
+            -> filter(|c| COUNTRY_CODES.contains(&c.cntrycode()))
+            -> map(|c| (c.c_custkey, c.cntrycode().to_string(), c.c_acctbal))
+            -> tee();
+
+        // 2. Subquery: avg(c_acctbal) over the customers with c_acctbal > 0.00.
+        // The subquery is uncorrelated, so it is a global aggregate with a single output.
+        avg_acctbal = customer_filtered
+            -> filter(|(_c_custkey, _cntrycode, c_acctbal)| *c_acctbal > 0.0)
+            -> fold(|| (0.0, 0u64), |acc: &mut (f64, u64), (_c_custkey, _cntrycode, c_acctbal): (i64, String, f64)| {
+                acc.0 += c_acctbal;
+                acc.1 += 1;
+            })
+            -> map(|(sum, count)| ((), sum / count as f64));
+
+        // 3. Cross join the customers with the average and filter on c_acctbal > avg(c_acctbal).
+        avg_acctbal -> [0]customer_avg;
+        customer_filtered -> map(|c| ((), c)) -> [1]customer_avg;
+        customer_avg = join_multiset()
+            -> filter(|((), (avg_acctbal, (_c_custkey, _cntrycode, c_acctbal)))| c_acctbal > avg_acctbal)
+            -> map(|((), (_avg_acctbal, (c_custkey, cntrycode, c_acctbal)))| (c_custkey, (cntrycode, c_acctbal)));
+
+        // 4. NOT EXISTS: anti join customer with orders on c_custkey = o_custkey.
+        // The multiset version keeps the f64 payload, customers are unique on c_custkey anyway.
+        customer_avg -> [pos]customer_no_orders;
+        source_iter(orders) -> map(|o| o.o_custkey) -> [neg]customer_no_orders;
+        customer_no_orders = anti_join_multiset()
+            -> map(|(_c_custkey, (cntrycode, c_acctbal))| (cntrycode, c_acctbal));
+
+        // 5. Group by cntrycode, count(*) and sum(c_acctbal).
+        agg = customer_no_orders
+            -> fold_keyed(|| (0i64, 0.0), |acc: &mut (i64, f64), c_acctbal: f64| {
+                acc.0 += 1;
+                acc.1 += c_acctbal;
+            })
+            -> map(|(cntrycode, (numcust, totacctbal))| Q22Row { cntrycode, numcust, totacctbal });
+
+        // 6. Sort by cntrycode and collect: "cntrycode", "numcust", "totacctbal"
+        agg -> sort_by_key(|x: &Q22Row| &x.cntrycode)
+            -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    hydroflow::util::collect_ready::<Vec<Q22Row>, _>(output_recv)
+}
+
+pub fn query_base(customer: Vec<Customer>, orders: Vec<Order>) -> Vec<Q22Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Vec<Q22Row>>();
+
+    let mut flow = hydroflow_syntax! {
+        source_iter([(customer, orders)]) -> for_each(|(customer, orders)|{
+            output_send.send(query_base_original(customer, orders)).unwrap();
+        });
+    };
+
+    flow.run_available();
+    let res = hydroflow::util::collect_ready::<Vec<Vec<Q22Row>>, _>(output_recv);
+
+    assert!(res.len() == 1);
+    res.into_iter().next().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use base::tpch::initialize::initialize_database;
+    use base::tpch::query_22::{load, query_duckdb};
+    use base::tpch::verify::{assert_match, compare, Tolerance};
+
+    #[test]
+    fn test_query() {
+        let conn = initialize_database(0.01);
+
+        let (customer, orders) = load(&conn);
+
+        let result = super::query(customer, orders);
+
+        let expected = query_duckdb(&conn, None);
+        assert_match(&[compare("Q22", "duckdb", "hydroflow", &expected, &result, &Tolerance::default())]);
+    }
+}
@@ -0,0 +1,105 @@
+use chrono::NaiveDate;
+use hydroflow::hydroflow_syntax;
+
+use base::tpch::query_3::{Customer, LineItem, Order, Q3Row, query as query_base_original};
+use base::tpch::top_k::{Strategy, TopK};
+
+pub fn query(customer: Vec<Customer>, orders: Vec<Order>, line_items: Vec<LineItem>) -> Vec<Q3Row> {
+    query_with(Strategy::Heap, customer, orders, line_items)
+}
+
+/// Query 3 with the given top-k strategy for ORDER BY ... LIMIT 10.
+pub fn query_with(strategy: Strategy, customer: Vec<Customer>, orders: Vec<Order>, line_items: Vec<LineItem>) -> Vec<Q3Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Q3Row>();
+
+    let mut flow = hydroflow_syntax! {
+        // 1. Scan customer and filter on c_mktsegment = 'BUILDING'.
+        customer_filtered = source_iter(customer)
+            -> filter(|c| c.c_mktsegment == "BUILDING");
+
+        // 2. Scan orders and filter on o_orderdate < '1995-03-15'.
+        orders_filtered = source_iter(orders)
+            -> filter(|o| o.o_orderdate < NaiveDate::from_ymd_opt(1995, 3, 15).unwrap());
+
+        // 3. Scan lineitem and filter on l_shipdate > '1995-03-15'.
+        line_items_filtered = source_iter(line_items)
+            -> filter(|l| l.l_shipdate > NaiveDate::from_ymd_opt(1995, 3, 15).unwrap());
+
+        // 4. Join customer and orders on c_custkey = o_custkey.
+        // Keys left (c_custkey), no payload
+        // Keys right (o_custkey), payload right (o_orderkey, o_orderdate, o_shippriority)
+        customer_filtered -> map(|c| (c.c_custkey, ())) -> [0]customer_orders;
+        orders_filtered -> map(|o| (o.o_custkey, (o.o_orderkey, o.o_orderdate, o.o_shippriority))) -> [1]customer_orders;
+        customer_orders = join_multiset()
+            -> map(|(_c_custkey, ((), (o_orderkey, o_orderdate, o_shippriority)))| (o_orderkey, (o_orderdate, o_shippriority)));
+
+        // 5. Join the result with lineitem on o_orderkey = l_orderkey.
+        // Payload right: l_extendedprice * (1 - l_discount)
+        customer_orders -> [0]joined;
+        line_items_filtered -> map(|l| (l.l_orderkey, l.l_extendedprice * (1.0 - l.l_discount))) -> [1]joined;
+        joined = join_multiset()
+            -> map(|(l_orderkey, ((o_orderdate, o_shippriority), revenue))| ((l_orderkey, o_orderdate, o_shippriority), revenue));
+
+        // 6. Group by l_orderkey, o_orderdate, o_shippriority and sum the revenue.
+        agg = joined
+            -> fold_keyed(|| 0.0, |acc: &mut f64, revenue: f64| *acc += revenue)
+            -> map(|((l_orderkey, o_orderdate, o_shippriority), revenue)| Q3Row {
+                l_orderkey,
+                revenue,
+                o_orderdate,
+                o_shippriority,
+            });
+
+        // 7. Keep the first 10 rows by revenue desc, o_orderdate.
+        ordered = agg
+            -> fold(|| TopK::new(strategy, 10, Q3Row::order_by), |acc: &mut TopK<Q3Row>, x: Q3Row| acc.push(x))
+            -> flat_map(TopK::into_sorted_vec);
+
+        // 8. Collect: "l_orderkey", "revenue", "o_orderdate", "o_shippriority"
+        ordered -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    hydroflow::util::collect_ready::<Vec<Q3Row>, _>(output_recv)
+}
+
+pub fn query_base(customer: Vec<Customer>, orders: Vec<Order>, line_items: Vec<LineItem>) -> Vec<Q3Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Vec<Q3Row>>();
+
+    let mut flow = hydroflow_syntax! {
+        source_iter([(customer, orders, line_items)]) -> for_each(|(customer, orders, line_items)|{
+            output_send.send(query_base_original(customer, orders, line_items)).unwrap();
+        });
+    };
+
+    flow.run_available();
+    let res = hydroflow::util::collect_ready::<Vec<Vec<Q3Row>>, _>(output_recv);
+
+    assert!(res.len() == 1);
+    res.into_iter().next().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use base::tpch::initialize::initialize_database;
+    use base::tpch::query_3::{load, query_duckdb};
+    use base::tpch::top_k::Strategy;
+    use base::tpch::verify::{assert_match, compare, Tolerance};
+
+    #[test]
+    fn test_query() {
+        let conn = initialize_database(0.01);
+
+        let expected = query_duckdb(&conn, None);
+
+        let (customer, orders, line_items) = load(&conn);
+        let result = super::query(customer, orders, line_items);
+        assert_match(&[compare("Q3", "duckdb", "hydroflow", &expected, &result, &Tolerance::default())]);
+
+        let (customer, orders, line_items) = load(&conn);
+        let result = super::query_with(Strategy::Sort, customer, orders, line_items);
+        assert_match(&[compare("Q3", "duckdb", "hydroflow-sort", &expected, &result, &Tolerance::default())]);
+    }
+}
@@ -1,18 +1,70 @@
-use chrono::NaiveDate;
+use std::rc::Rc;
+
+use duckdb::Connection;
+use hydroflow::futures::stream::Stream;
 use hydroflow::hydroflow_syntax;
+use hydroflow::util::PersistenceKeyed;
+
+use base::tpch::query_4::{LineItem, Order, Q4Row, QueryParams, query as query_base_original};
+use base::tpch::bloom::{BloomFilter, DEFAULT_FALSE_POSITIVE_RATE};
+use base::tpch::merge_join::merge_semi_join;
+use base::tpch::refresh::Delta;
+
+use super::incremental::Incremental;
+use super::partitioned::{hash_partition, run_partitioned};
+use super::scan::{run_to_end, scan, Ends};
+use super::semi_join::{semi_join_key, semi_join_output};
 
-use base::tpch::query_4::{LineItem, Order, query as query_base_original};
+pub fn query(line_items: Vec<LineItem>, orders: Vec<Order>, params: &QueryParams) -> Vec<Q4Row> {
 
-pub fn query(line_items: Vec<LineItem>, orders: Vec<Order>) {
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Q4Row>();
 
     let mut flow = hydroflow_syntax! {
         // 1. Scan orders
         orders_filtered = source_iter(orders)
-        // 1.2 Filter orders on o_orderdate >= '1993-07-01' and < '1993-10-01'
-        -> filter(|order| {
-            order.order_date >= NaiveDate::from_ymd_opt(1993, 7, 1).unwrap()
-                && order.order_date < NaiveDate::from_ymd_opt(1993, 10, 1).unwrap()
-        });
+        // 1.2 Filter orders on o_orderdate >= DATE and < DATE + 3 months, e.g. ['1993-07-01', '1993-10-01')
+        -> filter(|order| params.filter(&order.order_date));
+
+        // 2. Scan from lineitem.
+        line_items_filtered = source_iter(line_items)
+            // 2.2 Filter lineitem on l_commitdate < l_receiptdate
+            -> filter(|line_item| line_item.commit_date < line_item.receiptdate);
+
+        // 3. Semi-join the two. o_orderkey = l_orderkey, payload: o_orderpriority
+        // Build side: LineItem
+        // Probe side: Orders
+        orders_filtered -> map(|e|(e.order_key, e.order_priority)) -> [0]joined;
+        line_items_filtered -> map(|l| l.order_key) -> unique() -> map(semi_join_key) -> [1]joined;
+        joined = join_multiset() -> map(semi_join_output) -> map(|x| x.1);
+
+        // 4. Aggregate.
+        // Hash aggregation in DuckDB
+        // 4.1 Group by: "o_orderpriority"
+        // 4.2 Count
+        // XXX: Why is it legal to return a value when it is not used afterwards?
+        agg = joined -> map(|x: String| (x, 1i64)) -> reduce_keyed(|acc, x| *acc = *acc + x);
+
+        // 5. Sort by o_orderpriority and collect: "o_orderpriority", "order_count"
+        agg -> sort_by_key(|x| &x.0)
+            -> map(|x: (String, i64)| Q4Row::from(x))
+            -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    hydroflow::util::collect_ready::<Vec<Q4Row>, _>(output_recv)
+}
+
+/// The former semi-join of `query`: a `join()` with a unit payload, whose set semantics drop the
+/// duplicate line items. Kept to benchmark against the semi-join, see `super::semi_join`.
+pub fn query_set_join(line_items: Vec<LineItem>, orders: Vec<Order>, params: &QueryParams) -> Vec<Q4Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Q4Row>();
+
+    let mut flow = hydroflow_syntax! {
+        // 1. Scan orders
+        orders_filtered = source_iter(orders)
+        // 1.2 Filter orders on o_orderdate >= DATE and < DATE + 3 months, e.g. ['1993-07-01', '1993-10-01')
+        -> filter(|order| params.filter(&order.order_date));
 
         // 2. Scan from lineitem.
         line_items_filtered = source_iter(line_items)
@@ -21,8 +73,7 @@ pub fn query(line_items: Vec<LineItem>, orders: Vec<Order>) {
 
         // 3. Join the two. o_orderkey = l_orderkey, payload: o_orderpriority
         // XXX: Semijoin?
-        // Build side: Orders?
-        // Probe side: LineItem?
+        // No build side: join() hashes both inputs, see `base::tpch::query_4::query_with_plan`.
         orders_filtered -> map(|e|(e.order_key, e.order_priority)) -> [0]joined;
         line_items_filtered -> map(|l|(l.order_key, None::<u8>)) -> [1]joined;
         // Note: Implementing a semijoin using a hash join with unique output.
@@ -33,52 +84,428 @@ pub fn query(line_items: Vec<LineItem>, orders: Vec<Order>) {
         // 4.1 Group by: "o_orderpriority"
         // 4.2 Count
         // XXX: Why is it legal to return a value when it is not used afterwards?
-        agg = joined -> map(|x: String| (x, 1)) -> reduce_keyed(|acc, x| *acc = *acc + x);
+        agg = joined -> map(|x: String| (x, 1i64)) -> reduce_keyed(|acc, x| *acc = *acc + x);
+
+        // 5. Sort by o_orderpriority and collect: "o_orderpriority", "order_count"
+        agg -> sort_by_key(|x| &x.0)
+            -> map(|x: (String, i64)| Q4Row::from(x))
+            -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    hydroflow::util::collect_ready::<Vec<Q4Row>, _>(output_recv)
+}
 
-        // 5. Print: "o_orderpriority", "order_count"
-        agg -> for_each(|x| println!("{:?}", x));
+/**
+ * Bloom filter pushdown, see `base::tpch::bloom`. The keys of the filtered orders are folded into a
+ * Bloom filter, which is passed to the lineitem pipeline with a `cross_join_multiset()` and drops
+ * most line items before the semi-join.
+ */
+pub fn query_bloom(line_items: Vec<LineItem>, orders: Vec<Order>, params: &QueryParams) -> Vec<Q4Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Q4Row>();
+
+    let mut flow = hydroflow_syntax! {
+        // 1. Scan orders, filter on o_orderdate.
+        orders_filtered = source_iter(orders)
+            -> filter(|order: &Order| params.filter(&order.order_date))
+            -> map(|o: Order| (o.order_key, o.order_priority))
+            -> tee();
+
+        // 2. Bloom filter of the qualifying o_orderkey, shared by all line items.
+        orders_filtered
+            -> map(|(order_key, _)| order_key)
+            -> fold(Vec::new, |keys: &mut Vec<i32>, key| keys.push(key))
+            -> map(|keys: Vec<i32>| Rc::new(BloomFilter::from_keys(&keys, DEFAULT_FALSE_POSITIVE_RATE)))
+            -> [0]pushdown;
+
+        // 3. Scan lineitem, filter on l_commitdate < l_receiptdate and probe the Bloom filter.
+        source_iter(line_items)
+            -> filter(|line_item: &LineItem| line_item.commit_date < line_item.receiptdate)
+            -> map(|l: LineItem| l.order_key)
+            -> [1]pushdown;
+        pushdown = cross_join_multiset()
+            -> filter_map(|(bloom, order_key): (Rc<BloomFilter>, i32)| bloom.contains(&order_key).then_some(order_key));
+
+        // 4. Semi-join the two. o_orderkey = l_orderkey, payload: o_orderpriority
+        orders_filtered -> [0]joined;
+        pushdown -> unique() -> map(semi_join_key) -> [1]joined;
+        joined = join_multiset() -> map(semi_join_output) -> map(|x| x.1);
+
+        // 5. Count per o_orderpriority.
+        agg = joined -> map(|x: String| (x, 1i64)) -> reduce_keyed(|acc, x| *acc = *acc + x);
+
+        // 6. Sort by o_orderpriority and collect: "o_orderpriority", "order_count"
+        agg -> sort_by_key(|x| &x.0)
+            -> map(|x: (String, i64)| Q4Row::from(x))
+            -> for_each(|x| output_send.send(x).unwrap());
     };
 
     flow.run_available();
+    hydroflow::util::collect_ready::<Vec<Q4Row>, _>(output_recv)
 }
 
-pub fn query_base(line_items: Vec<LineItem>, orders: Vec<Order>) {
+/**
+ * Sort-merge semi-join instead of the hash join, see `base::tpch::merge_join`. Both inputs are
+ * sorted with `sort_by_key`, collected into one vector each and merged once both are complete.
+ */
+pub fn query_merge_join(line_items: Vec<LineItem>, orders: Vec<Order>, params: &QueryParams) -> Vec<Q4Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Q4Row>();
+
+    let mut flow = hydroflow_syntax! {
+        // 1. Scan orders, filter on o_orderdate and sort by o_orderkey.
+        orders_sorted = source_iter(orders)
+            -> filter(|order: &Order| params.filter(&order.order_date))
+            -> map(|o: Order| (o.order_key, o.order_priority))
+            -> sort_by_key(|x| &x.0)
+            -> fold(Vec::new, |acc: &mut Vec<(i32, String)>, x| acc.push(x));
+
+        // 2. Scan lineitem, filter on l_commitdate < l_receiptdate and sort by l_orderkey.
+        line_items_sorted = source_iter(line_items)
+            -> filter(|line_item: &LineItem| line_item.commit_date < line_item.receiptdate)
+            -> map(|l: LineItem| l.order_key)
+            -> sort()
+            -> fold(Vec::new, |acc: &mut Vec<i32>, x| acc.push(x));
+
+        // 3. Merge semi-join. o_orderkey = l_orderkey, payload: o_orderpriority
+        orders_sorted -> [0]joined;
+        line_items_sorted -> [1]joined;
+        joined = zip() -> flat_map(|(orders, line_items)| merge_semi_join(orders, line_items)) -> map(|x| x.1);
+
+        // 4. Count per o_orderpriority.
+        agg = joined -> map(|x: String| (x, 1i64)) -> reduce_keyed(|acc, x| *acc = *acc + x);
+
+        // 5. Sort by o_orderpriority and collect: "o_orderpriority", "order_count"
+        agg -> sort_by_key(|x| &x.0)
+            -> map(|x: (String, i64)| Q4Row::from(x))
+            -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    hydroflow::util::collect_ready::<Vec<Q4Row>, _>(output_recv)
+}
+
+/**
+ * The graph of `query` over streams of both inputs, which it pulls with `source_stream`. The graph
+ * may run over several ticks while the streams wait for rows, see `super::scan::run_to_end`. The
+ * filtered orders and the distinct line item keys are kept across ticks, and the semi-join is split
+ * in two halves, so that every order matches in exactly one tick: the new keys of a tick probe the
+ * orders of all ticks so far, the new orders of a tick probe the keys of the earlier ticks. Every
+ * tick stops at the partial counts per o_orderpriority, the merge graph adds them up.
+ */
+pub fn query_stream<L, O>(line_items: L, orders: O, params: &QueryParams) -> Vec<Q4Row>
+where
+    L: Stream<Item = LineItem> + Unpin,
+    O: Stream<Item = Order> + Unpin,
+{
+    let ends = Ends::default();
+    let (line_items, orders) = (ends.track(line_items), ends.track(orders));
+    let (partial_send, partial_recv) = hydroflow::util::unbounded_channel::<(String, i64)>();
+
+    let mut flow = hydroflow_syntax! {
+        // 1. Scan orders
+        orders_filtered = source_stream(orders)
+            // 1.2 Filter orders on o_orderdate >= DATE and < DATE + 3 months, e.g. ['1993-07-01', '1993-10-01')
+            -> filter(|order: &Order| params.filter(&order.order_date))
+            -> map(|e: Order| (e.order_key, e.order_priority))
+            -> tee();
+
+        // 2. Scan from lineitem.
+        line_item_keys = source_stream(line_items)
+            // 2.2 Filter lineitem on l_commitdate < l_receiptdate
+            -> filter(|line_item: &LineItem| line_item.commit_date < line_item.receiptdate)
+            // Every key is emitted in the first tick it occurs in.
+            -> map(|l: LineItem| l.order_key) -> unique::<'static>() -> map(semi_join_key)
+            -> tee();
+
+        // 3. Semi-join the two. o_orderkey = l_orderkey, payload: o_orderpriority
+        // 3.1 The new keys probe the orders of all ticks so far.
+        orders_filtered -> [0]new_keys;
+        line_item_keys -> [1]new_keys;
+        new_keys = join_multiset::<'static, 'tick>();
+        // 3.2 The new orders probe the keys of the earlier ticks.
+        orders_filtered -> [0]new_orders;
+        line_item_keys -> defer_tick() -> [1]new_orders;
+        new_orders = join_multiset::<'tick, 'static>();
+        joined = union() -> map(semi_join_output) -> map(|x| x.1);
+        new_keys -> joined;
+        new_orders -> joined;
+
+        // 4. Partial count per o_orderpriority of the tick.
+        joined -> map(|x: String| (x, 1i64)) -> reduce_keyed(|acc, x| *acc = *acc + x)
+            -> for_each(|x| partial_send.send(x).unwrap());
+    };
+
+    let partials = run_to_end(&mut flow, &ends, 2, partial_recv);
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Q4Row>();
+
+    let mut flow = hydroflow_syntax! {
+        // 5. Add up the partial counts of all ticks.
+        agg = source_iter(partials) -> reduce_keyed(|acc: &mut i64, x: i64| *acc = *acc + x);
+
+        // 6. Sort by o_orderpriority and collect: "o_orderpriority", "order_count"
+        agg -> sort_by_key(|x| &x.0)
+            -> map(|x: (String, i64)| Q4Row::from(x))
+            -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    hydroflow::util::collect_ready::<Vec<Q4Row>, _>(output_recv)
+}
+
+/// Streams lineitem and orders from DuckDB into the graph, both tables are loaded while the graph
+/// runs, see `super::query_1::query_scan`.
+pub fn query_scan(conn: &Connection, params: &QueryParams) -> Vec<Q4Row> {
+    let (line_items, line_items_loader) = scan::<LineItem>(conn);
+    let (orders, orders_loader) = scan::<Order>(conn);
+    let result = query_stream(line_items, orders, params);
+    line_items_loader.join();
+    orders_loader.join();
+    result
+}
+
+/**
+ * Runs one instance of the query graph per thread, see `super::partitioned`. Both inputs are hash
+ * partitioned on the order key, so the join is local to every partition. The instances stop at
+ * the partial counts per o_orderpriority, the merge graph adds them up.
+ */
+pub fn query_partitioned(line_items: Vec<LineItem>, orders: Vec<Order>, params: &QueryParams, threads: usize) -> Vec<Q4Row> {
+
+    let partitions = hash_partition(line_items, threads, |l| l.order_key)
+        .into_iter()
+        .zip(hash_partition(orders, threads, |o| o.order_key))
+        .collect();
+
+    let (partial_send, partial_recv) = hydroflow::util::unbounded_channel::<(String, i64)>();
+
+    run_partitioned(partitions, &partial_send, |(line_items, orders), partial_send| {
+        let mut flow = hydroflow_syntax! {
+            // 1. Scan the partition of orders.
+            orders_filtered = source_iter(orders)
+            // 1.2 Filter orders on o_orderdate >= DATE and < DATE + 3 months, e.g. ['1993-07-01', '1993-10-01')
+            -> filter(|order: &Order| params.filter(&order.order_date));
+
+            // 2. Scan the partition of lineitem.
+            line_items_filtered = source_iter(line_items)
+                // 2.2 Filter lineitem on l_commitdate < l_receiptdate
+                -> filter(|line_item: &LineItem| line_item.commit_date < line_item.receiptdate);
+
+            // 3. Semi-join the two. o_orderkey = l_orderkey, payload: o_orderpriority
+            orders_filtered -> map(|e: Order| (e.order_key, e.order_priority)) -> [0]joined;
+            line_items_filtered -> map(|l: LineItem| l.order_key) -> unique() -> map(semi_join_key) -> [1]joined;
+            joined = join_multiset() -> map(semi_join_output) -> map(|x| x.1);
+
+            // 4. Partial count per o_orderpriority.
+            joined -> map(|x: String| (x, 1i64)) -> reduce_keyed(|acc, x| *acc = *acc + x)
+                -> for_each(|x| partial_send.send(x).unwrap());
+        };
+        flow.run_available();
+    });
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Q4Row>();
+
+    let mut flow = hydroflow_syntax! {
+        // 5. Add up the partial counts of all partitions.
+        agg = source_stream(partial_recv) -> reduce_keyed(|acc: &mut i64, x: i64| *acc = *acc + x);
+
+        // 6. Sort by o_orderpriority and collect: "o_orderpriority", "order_count"
+        agg -> sort_by_key(|x| &x.0)
+            -> map(|x: (String, i64)| Q4Row::from(x))
+            -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    hydroflow::util::collect_ready::<Vec<Q4Row>, _>(output_recv)
+}
+
+/// A row of a refresh batch. RF2 deletes whole orders, the deleted line items do not matter.
+enum Change {
+    LineItem(LineItem),
+    Order(Order),
+    Delete(i32),
+}
+
+/**
+ * Maintains the answer under refreshes, see `super::incremental`. A refresh inserts or deletes an
+ * order together with all its line items, so whether an order qualifies is decided in the tick it
+ * is inserted. The qualifying orders are kept across ticks in `persist_mut_keyed()` by order key,
+ * from where RF2 deletes them, and counted per o_orderpriority every tick.
+ */
+pub fn query_incremental(params: &QueryParams) -> Incremental<(Delta<LineItem>, Delta<Order>), Q4Row> {
+    let params = *params;
+
+    let (refresh_send, refresh_recv) = hydroflow::util::unbounded_channel::<(Delta<LineItem>, Delta<Order>)>();
+    let (output_send, output_recv) = tokio::sync::mpsc::unbounded_channel::<Q4Row>();
+
+    let flow = hydroflow_syntax! {
+        // 1. Split a batch into the inserted line items and orders and the deleted order keys.
+        changes = source_stream(refresh_recv)
+            -> flat_map(|(line_items, orders): (Delta<LineItem>, Delta<Order>)| {
+                line_items.inserted.into_iter().map(Change::LineItem)
+                    .chain(orders.inserted.into_iter().map(Change::Order))
+                    .chain(orders.deleted.into_iter().map(|o| Change::Delete(o.order_key)))
+            })
+            -> demux(|change: Change, var_args!(line_items, orders, deleted)| match change {
+                Change::LineItem(l) => line_items.give(l),
+                Change::Order(o) => orders.give(o),
+                Change::Delete(order_key) => deleted.give(order_key),
+            });
+
+        // 2. Filter the inserted orders on o_orderdate and the inserted lineitem on l_commitdate < l_receiptdate.
+        changes[orders] -> filter(|order: &Order| params.filter(&order.order_date))
+            -> map(|o: Order| (o.order_key, o.order_priority)) -> [0]joined;
+        changes[line_items] -> filter(|line_item: &LineItem| line_item.commit_date < line_item.receiptdate)
+            -> map(|l: LineItem| (l.order_key, None::<u8>)) -> [1]joined;
+
+        // 3. Semi join the orders of the tick with their line items.
+        joined = join() -> map(|(order_key, (o_orderpriority, _))| PersistenceKeyed::Persist(order_key, o_orderpriority));
+
+        // 4. Keep the qualifying orders across ticks, RF2 deletes by order key.
+        qualified = union() -> persist_mut_keyed();
+        joined -> qualified;
+        changes[deleted] -> map(PersistenceKeyed::Delete) -> qualified;
+
+        // 5. Count per o_orderpriority, sort and emit the answer of the tick.
+        qualified -> map(|(_order_key, o_orderpriority): (i32, String)| (o_orderpriority, 1i64))
+            -> reduce_keyed(|acc, x| *acc = *acc + x)
+            -> sort_by_key(|x| &x.0)
+            -> map(|x: (String, i64)| Q4Row::from(x))
+            -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    Incremental::new(flow, refresh_send, output_recv)
+}
+
+pub fn query_base(line_items: Vec<LineItem>, orders: Vec<Order>, params: &QueryParams) -> Vec<Q4Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Vec<Q4Row>>();
 
     let mut flow = hydroflow_syntax! {
         source_iter([(line_items, orders)]) -> for_each(|(line_items, orders)|{
-            query_base_original(line_items, orders);
+            output_send.send(query_base_original(line_items, orders, params)).unwrap();
         });
     };
 
     flow.run_available();
+    let res = hydroflow::util::collect_ready::<Vec<Vec<Q4Row>>, _>(output_recv);
+
+    assert!(res.len() == 1);
+    res.into_iter().next().unwrap()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use base::tpch::initialize::initialize_database;
+    use base::tpch::initialize::{initialize_database, initialize_in_memory};
+    use base::tpch::query_4::{load_refresh, query_duckdb};
+    use base::tpch::refresh::{self, RefreshStream};
+    use base::tpch::scale_factor::ScaleFactor;
+    use base::tpch::verify::{assert_match, compare, Tolerance};
 
     #[test]
     fn test_query() {
-        let conn = initialize_database(1);
+        let conn = initialize_database(0.01);
+
+        let limit = None;
+
+        let line_items = LineItem::load(&conn, limit).unwrap();
+        let orders = Order::load(&conn, limit).unwrap();
+
+        let result = super::query(line_items, orders, &QueryParams::default());
+
+        let expected = query_duckdb(&conn, limit, &QueryParams::default());
+        assert_match(&[compare("Q4", "duckdb", "hydroflow", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_partitioned() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+        let expected = query_duckdb(&conn, None, &params);
+
+        for threads in [1, 2, 4] {
+            let line_items = LineItem::load(&conn, None).unwrap();
+            let orders = Order::load(&conn, None).unwrap();
+            let result = super::query_partitioned(line_items, orders, &params, threads);
+            assert_match(&[compare("Q4", "duckdb", "hydroflow-partitioned", &expected, &result, &Tolerance::default())]);
+        }
+    }
+
+    #[test]
+    fn test_query_bloom() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+        let line_items = LineItem::load(&conn, None).unwrap();
+        let orders = Order::load(&conn, None).unwrap();
 
-        let limit = Some(1000);
+        let result = super::query_bloom(line_items, orders, &params);
 
-        let line_items = LineItem::load(&conn, limit);
-        let orders = Order::load(&conn, limit);
+        let expected = query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q4", "duckdb", "hydroflow-bloom", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_merge_join() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
+        let line_items = LineItem::load(&conn, None).unwrap();
+        let orders = Order::load(&conn, None).unwrap();
+
+        let result = super::query_merge_join(line_items, orders, &params);
+
+        let expected = query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q4", "duckdb", "hydroflow-merge-join", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_scan() {
+        let conn = initialize_database(0.01);
+        let params = QueryParams::default();
 
-        super::query(line_items, orders);
+        let result = super::query_scan(&conn, &params);
+
+        let expected = query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q4", "duckdb", "hydroflow-scan", &expected, &result, &Tolerance::default())]);
+    }
+
+    #[test]
+    fn test_query_incremental() {
+        let conn = initialize_in_memory(0.01);
+        let params = QueryParams::default();
+        let mut query = super::query_incremental(&params);
+
+        let line_items = LineItem::load(&conn, None).unwrap();
+        let orders = Order::load(&conn, None).unwrap();
+        let result = query.refresh((Delta::insert(line_items), Delta::insert(orders)));
+        let expected = query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q4", "duckdb", "hydroflow-incremental", &expected, &result, &Tolerance::default())]);
+
+        let mut stream = RefreshStream::new(ScaleFactor::from(0.01));
+        for _ in 0..3 {
+            stream.stage(&conn);
+            let delta = load_refresh(&conn).unwrap();
+            refresh::apply(&conn);
+
+            let result = query.refresh(delta);
+            let expected = query_duckdb(&conn, None, &params);
+            assert_match(&[compare("Q4", "duckdb", "hydroflow-incremental", &expected, &result, &Tolerance::default())]);
+        }
     }
 
     #[test]
     fn test_query_base() {
-        let conn = initialize_database(1);
+        let conn = initialize_database(0.01);
+
+        let limit = None;
 
-        let limit = Some(1000);
+        let line_items = LineItem::load(&conn, limit).unwrap();
+        let orders = Order::load(&conn, limit).unwrap();
 
-        let line_items = LineItem::load(&conn, limit);
-        let orders = Order::load(&conn, limit);
+        let result = super::query_base(line_items, orders, &QueryParams::default());
 
-        super::query(line_items, orders);
+        let expected = query_duckdb(&conn, limit, &QueryParams::default());
+        assert_match(&[compare("Q4", "duckdb", "baseline", &expected, &result, &Tolerance::default())]);
     }
 }
\ No newline at end of file
@@ -0,0 +1,121 @@
+use chrono::NaiveDate;
+use hydroflow::hydroflow_syntax;
+
+use base::tpch::query_5::{Customer, LineItem, Nation, Order, Q5Row, Region, Supplier, query as query_base_original};
+
+pub fn query(
+    customer: Vec<Customer>,
+    orders: Vec<Order>,
+    line_items: Vec<LineItem>,
+    supplier: Vec<Supplier>,
+    nation: Vec<Nation>,
+    region: Vec<Region>,
+) -> Vec<Q5Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Q5Row>();
+
+    let mut flow = hydroflow_syntax! {
+        // Join order: region -> nation -> customer -> orders -> lineitem -> supplier
+        // The left input of every join is the (smaller) build side.
+
+        // 1. Scan region and filter on r_name = 'ASIA'.
+        region_filtered = source_iter(region) -> filter(|r| r.r_name == "ASIA");
+
+        // 2. Join region and nation on r_regionkey = n_regionkey.
+        region_filtered -> map(|r| (r.r_regionkey, ())) -> [0]region_nation;
+        source_iter(nation) -> map(|n| (n.n_regionkey, (n.n_nationkey, n.n_name))) -> [1]region_nation;
+        region_nation = join_multiset()
+            -> map(|(_r_regionkey, ((), (n_nationkey, n_name)))| (n_nationkey, n_name));
+
+        // 3. Join nation and customer on n_nationkey = c_nationkey.
+        region_nation -> [0]nation_customer;
+        source_iter(customer) -> map(|c| (c.c_nationkey, c.c_custkey)) -> [1]nation_customer;
+        nation_customer = join_multiset()
+            -> map(|(c_nationkey, (n_name, c_custkey))| (c_custkey, (c_nationkey, n_name)));
+
+        // 4. Scan orders, filter on o_orderdate in [1994-01-01, 1995-01-01) and join customer.
+        orders_filtered = source_iter(orders)
+            -> filter(|o| {
+                o.o_orderdate >= NaiveDate::from_ymd_opt(1994, 1, 1).unwrap()
+                    && o.o_orderdate < NaiveDate::from_ymd_opt(1995, 1, 1).unwrap()
+            });
+        nation_customer -> [0]customer_orders;
+        orders_filtered -> map(|o| (o.o_custkey, o.o_orderkey)) -> [1]customer_orders;
+        customer_orders = join_multiset()
+            -> map(|(_c_custkey, ((c_nationkey, n_name), o_orderkey))| (o_orderkey, (c_nationkey, n_name)));
+
+        // 5. Join orders and lineitem on o_orderkey = l_orderkey.
+        customer_orders -> [0]orders_line_items;
+        source_iter(line_items) -> map(|l| (l.l_orderkey, (l.l_suppkey, l.l_extendedprice * (1.0 - l.l_discount)))) -> [1]orders_line_items;
+        orders_line_items = join_multiset()
+            -> map(|(_o_orderkey, ((c_nationkey, n_name), (l_suppkey, revenue)))| ((l_suppkey, c_nationkey), (n_name, revenue)));
+
+        // 6. Join supplier on l_suppkey = s_suppkey and c_nationkey = s_nationkey.
+        source_iter(supplier) -> map(|s| ((s.s_suppkey, s.s_nationkey), ())) -> [0]joined;
+        orders_line_items -> [1]joined;
+        joined = join_multiset() -> map(|(_key, ((), (n_name, revenue)))| (n_name, revenue));
+
+        // 7. Group by n_name and sum the revenue.
+        agg = joined
+            -> fold_keyed(|| 0.0, |acc: &mut f64, revenue: f64| *acc += revenue)
+            -> map(|(n_name, revenue)| Q5Row { n_name, revenue });
+
+        // 8. Sort by revenue desc.
+        ordered = agg
+            -> fold(Vec::new, |acc: &mut Vec<Q5Row>, x: Q5Row| acc.push(x))
+            -> flat_map(|mut x: Vec<Q5Row>| {
+                x.sort_by(|a, b| b.revenue.total_cmp(&a.revenue));
+                x
+            });
+
+        // 9. Collect: "n_name", "revenue"
+        ordered -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    hydroflow::util::collect_ready::<Vec<Q5Row>, _>(output_recv)
+}
+
+pub fn query_base(
+    customer: Vec<Customer>,
+    orders: Vec<Order>,
+    line_items: Vec<LineItem>,
+    supplier: Vec<Supplier>,
+    nation: Vec<Nation>,
+    region: Vec<Region>,
+) -> Vec<Q5Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Vec<Q5Row>>();
+
+    let mut flow = hydroflow_syntax! {
+        source_iter([(customer, orders, line_items, supplier, nation, region)])
+            -> for_each(|(customer, orders, line_items, supplier, nation, region)|{
+                output_send.send(query_base_original(customer, orders, line_items, supplier, nation, region)).unwrap();
+            });
+    };
+
+    flow.run_available();
+    let res = hydroflow::util::collect_ready::<Vec<Vec<Q5Row>>, _>(output_recv);
+
+    assert!(res.len() == 1);
+    res.into_iter().next().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use base::tpch::initialize::initialize_database;
+    use base::tpch::query_5::{load, query_duckdb};
+    use base::tpch::verify::{assert_match, compare, Tolerance};
+
+    #[test]
+    fn test_query() {
+        let conn = initialize_database(0.01);
+
+        let (customer, orders, line_items, supplier, nation, region) = load(&conn);
+
+        let result = super::query(customer, orders, line_items, supplier, nation, region);
+
+        let expected = query_duckdb(&conn, None);
+        assert_match(&[compare("Q5", "duckdb", "hydroflow", &expected, &result, &Tolerance::default())]);
+    }
+}
@@ -0,0 +1,92 @@
+use hydroflow::hydroflow_syntax;
+
+use base::tpch::query_6::{LineItem, Q6Row, aggregate_chunk, query as query_base_original};
+
+pub fn query(line_items: Vec<LineItem>) -> Vec<Q6Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<f64>();
+
+    let mut flow = hydroflow_syntax! {
+        // 1. Scan lineitem and filter on l_shipdate, l_discount and l_quantity.
+        lineitem_filtered = source_iter(line_items) -> filter(|l| l.filter());
+
+        // 2. Aggregate: sum(l_extendedprice * l_discount)
+        lineitem_filtered
+            -> map(|l| l.l_extendedprice * l.l_discount)
+            -> reduce(|acc, x| *acc += x)
+            -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    let res = hydroflow::util::collect_ready::<Vec<f64>, _>(output_recv);
+
+    // reduce() emits nothing on empty input, which matches SQL's NULL sum.
+    vec![Q6Row { revenue: res.first().copied() }]
+}
+
+/**
+ * Every tuple of the dataflow is a chunk of `CHUNK_SIZE` line items, which is filtered and
+ * aggregated as a whole, see `base::tpch::query_6::aggregate_chunk`.
+ */
+pub fn query_chunks<const CHUNK_SIZE: usize>(line_items: Vec<LineItem>) -> Vec<Q6Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<(f64, u64)>();
+
+    let mut flow = hydroflow_syntax! {
+        // 1. Scan lineitem in chunks.
+        chunks = source_iter(line_items.chunks(CHUNK_SIZE));
+
+        // 2. Filter and aggregate every chunk: (sum(l_extendedprice * l_discount), count(*))
+        // 3. Combine the partial aggregates of the chunks.
+        chunks
+            -> map(aggregate_chunk::<CHUNK_SIZE>)
+            -> reduce(|acc: &mut (f64, u64), x: (f64, u64)| {
+                acc.0 += x.0;
+                acc.1 += x.1;
+            })
+            -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    let res = hydroflow::util::collect_ready::<Vec<(f64, u64)>, _>(output_recv);
+
+    // Every chunk emits a partial aggregate, the count tells whether any line item qualified.
+    let (revenue, count) = res.first().copied().unwrap_or((0.0, 0));
+    vec![Q6Row::new(revenue, count)]
+}
+
+pub fn query_base(line_items: Vec<LineItem>) -> Vec<Q6Row> {
+
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<Vec<Q6Row>>();
+
+    let mut flow = hydroflow_syntax! {
+        source_iter([line_items]) -> for_each(|line_items|{
+            output_send.send(query_base_original(line_items)).unwrap();
+        });
+    };
+
+    flow.run_available();
+    let res = hydroflow::util::collect_ready::<Vec<Vec<Q6Row>>, _>(output_recv);
+
+    assert!(res.len() == 1);
+    res.into_iter().next().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use base::tpch::initialize::initialize_database;
+    use base::tpch::query_6::{load, query_duckdb};
+    use base::tpch::verify::{assert_match, compare, Tolerance};
+
+    #[test]
+    fn test_query() {
+        let conn = initialize_database(0.01);
+        let expected = query_duckdb(&conn, None);
+
+        let result = super::query(load(&conn));
+        assert_match(&[compare("Q6", "duckdb", "hydroflow", &expected, &result, &Tolerance::default())]);
+
+        let result = super::query_chunks::<1024>(load(&conn));
+        assert_match(&[compare("Q6", "duckdb", "hydroflow-chunks", &expected, &result, &Tolerance::default())]);
+    }
+}
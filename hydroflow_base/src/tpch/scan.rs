@@ -0,0 +1,123 @@
+//! Pipelined scans from DuckDB into `source_stream`, see `base::tpch::scan`.
+//!
+//! A `Loader` sends the batches of a table into a bounded tokio channel, which the graph reads with
+//! `source_stream` without blocking. The graph runs a tick whenever batches arrived, so a query
+//! graph over scans aggregates the rows of every tick into partial results, which are merged once
+//! the input streams ended, like the partial results of `super::partitioned`. Only the hash tables
+//! of the joins are kept across ticks. `run_to_end` runs such a graph until its input streams end
+//! and returns the partial results of all ticks.
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::task::Poll;
+
+use duckdb::Connection;
+use hydroflow::futures::executor::block_on;
+use hydroflow::futures::stream::{self, Stream, StreamExt};
+use hydroflow::scheduled::graph::Hydroflow;
+use hydroflow::tokio_stream::wrappers::{ReceiverStream, UnboundedReceiverStream};
+
+use base::tpch::scan::{FromBatch, Loader, DEFAULT_BATCH_ROWS};
+
+/// Batches in flight between a loader and the graph, each of at least `DEFAULT_BATCH_ROWS` rows.
+pub const CAPACITY: usize = 4;
+
+/// The rows of `T::TABLE` in scan order, loaded on the thread of the returned `Loader`.
+pub fn scan<T: FromBatch>(conn: &Connection) -> (impl Stream<Item = T> + Unpin, Loader) {
+    let (batch_send, batch_recv) = tokio::sync::mpsc::channel(CAPACITY);
+    let loader = Loader::spawn(conn, None, DEFAULT_BATCH_ROWS, move |batch| batch_send.blocking_send(batch).is_ok());
+    (ReceiverStream::new(batch_recv).flat_map(stream::iter), loader)
+}
+
+/// Counts the input streams of a graph that ended.
+#[derive(Clone, Default)]
+pub struct Ends(Rc<Cell<usize>>);
+
+impl Ends {
+    /// `stream`, which counts as ended once it returned `None`.
+    pub fn track<S: Stream + Unpin>(&self, stream: S) -> impl Stream<Item = S::Item> + Unpin {
+        let mut ends = Some(self.0.clone());
+        stream.chain(stream::poll_fn(move |_| {
+            if let Some(ends) = ends.take() {
+                ends.set(ends.get() + 1);
+            }
+            Poll::Ready(None)
+        }))
+    }
+
+    pub fn count(&self) -> usize {
+        self.0.get()
+    }
+}
+
+/**
+ * Runs `flow` one tick at a time until the `inputs` streams tracked by `ends` ended, and returns
+ * the rows sent to `output_recv` in all ticks. Between ticks, it waits for the streams to wake the
+ * graph.
+ */
+pub fn run_to_end<O>(flow: &mut Hydroflow<'_>, ends: &Ends, inputs: usize, mut output_recv: UnboundedReceiverStream<O>) -> Vec<O> {
+    let mut partials = vec![];
+    loop {
+        while flow.run_tick() {
+            partials.extend(hydroflow::util::collect_ready::<Vec<O>, _>(&mut output_recv));
+        }
+        if ends.count() == inputs {
+            return partials;
+        }
+        block_on(flow.recv_events_async());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread::{self, JoinHandle};
+    use std::time::Duration;
+
+    use base::tpch::initialize::initialize_database;
+    use base::tpch::verify::{assert_match, compare, Tolerance};
+    use base::tpch::{query_19, query_4};
+
+    use super::*;
+    use crate::tpch::query_19::query_stream as query_19_stream;
+    use crate::tpch::query_4::query_stream as query_4_stream;
+
+    /// `rows` in small batches with pauses, so that a graph over the stream spans many ticks.
+    fn batches<T: Send + 'static>(rows: Vec<T>) -> (impl Stream<Item = T> + Unpin, JoinHandle<()>) {
+        let (batch_send, batch_recv) = tokio::sync::mpsc::channel(1);
+        let sender = thread::spawn(move || {
+            let mut rows = rows.into_iter().peekable();
+            while rows.peek().is_some() {
+                let batch: Vec<T> = rows.by_ref().take(1000).collect();
+                batch_send.blocking_send(batch).unwrap();
+                thread::sleep(Duration::from_millis(1));
+            }
+        });
+        (ReceiverStream::new(batch_recv).flat_map(stream::iter), sender)
+    }
+
+    #[test]
+    fn test_run_to_end() {
+        let conn = initialize_database(0.01);
+
+        // Orders and line items of the same order arrive in different ticks, in either order.
+        let params = query_4::QueryParams::default();
+        let (line_items, orders) = query_4::load_arrow(&conn).unwrap();
+        let ((line_items, line_items_sender), (orders, orders_sender)) = (batches(line_items), batches(orders));
+        let result = query_4_stream(line_items, orders, &params);
+        line_items_sender.join().unwrap();
+        orders_sender.join().unwrap();
+
+        let expected = query_4::query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q4", "duckdb", "hydroflow-ticks", &expected, &result, &Tolerance::default())]);
+
+        let params = query_19::QueryParams::default();
+        let (line_items, part) = query_19::load_arrow(&conn).unwrap();
+        let ((line_items, line_items_sender), (part, part_sender)) = (batches(line_items), batches(part));
+        let result = query_19_stream(line_items, part, &params);
+        line_items_sender.join().unwrap();
+        part_sender.join().unwrap();
+
+        let expected = query_19::query_duckdb(&conn, None, &params);
+        assert_match(&[compare("Q19", "duckdb", "hydroflow-ticks", &expected, &result, &Tolerance::default())]);
+    }
+}
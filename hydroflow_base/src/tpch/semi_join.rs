@@ -0,0 +1,92 @@
+//! Semi-joins and anti-joins in `hydroflow_syntax!`, see `base::tpch::semi_join`.
+//!
+//! Hydroflow has no semi-join operator. A `join()` against the keys of the build side with a unit
+//! payload behaves like one only as long as the probe side has no duplicates, since its set
+//! semantics also collapse equal probe tuples. The semi-join is instead composed of three
+//! operators, with the distinct build keys on the right and the multiset join keeping every probe
+//! tuple:
+//!
+//! ```text
+//! probe -> [0]semi;
+//! build_keys -> unique() -> map(semi_join_key) -> [1]semi;
+//! semi = join_multiset() -> map(semi_join_output);
+//! ```
+//!
+//! The anti-join is `anti_join_multiset()` with the probe tuples on `[pos]` and the build keys on
+//! `[neg]`, which drops every probe tuple with a matching key and keeps the duplicates of the rest.
+//! `semi_join` and `anti_join` run the patterns on their own, e.g. to test them.
+
+use std::hash::Hash;
+
+use hydroflow::hydroflow_syntax;
+
+/// A build key as the right input of the semi-join's `join_multiset()`.
+pub fn semi_join_key<K>(key: K) -> (K, ()) {
+    (key, ())
+}
+
+/// Drops the unit payload of the build side from the output of the semi-join's `join_multiset()`.
+pub fn semi_join_output<K, V>((key, (value, ())): (K, (V, ()))) -> (K, V) {
+    (key, value)
+}
+
+/// The tuples of `probe` whose key is in `build`, in no particular order.
+pub fn semi_join<K, V>(probe: Vec<(K, V)>, build: Vec<K>) -> Vec<(K, V)>
+where
+    K: 'static + Clone + Eq + Hash,
+    V: 'static + Clone,
+{
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<(K, V)>();
+
+    let mut flow = hydroflow_syntax! {
+        source_iter(probe) -> [0]semi;
+        source_iter(build) -> unique() -> map(semi_join_key) -> [1]semi;
+        semi = join_multiset() -> map(semi_join_output);
+
+        semi -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    hydroflow::util::collect_ready::<Vec<(K, V)>, _>(output_recv)
+}
+
+/// The tuples of `probe` whose key is not in `build`, in no particular order.
+pub fn anti_join<K, V>(probe: Vec<(K, V)>, build: Vec<K>) -> Vec<(K, V)>
+where
+    K: 'static + Clone + Eq + Hash,
+    V: 'static + Clone,
+{
+    let (output_send, output_recv) = hydroflow::util::unbounded_channel::<(K, V)>();
+
+    let mut flow = hydroflow_syntax! {
+        source_iter(probe) -> [pos]anti;
+        source_iter(build) -> [neg]anti;
+        anti = anti_join_multiset();
+
+        anti -> for_each(|x| output_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+    hydroflow::util::collect_ready::<Vec<(K, V)>, _>(output_recv)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_semi_join() {
+        let orders = vec![(1, "a"), (2, "b"), (3, "c"), (1, "d"), (1, "d")];
+        let line_items = vec![1, 1, 1, 3, 4];
+
+        let mut semi = semi_join(orders.clone(), line_items.clone());
+        semi.sort();
+        assert_eq!(semi, vec![(1, "a"), (1, "d"), (1, "d"), (3, "c")]);
+
+        let anti = anti_join(orders.clone(), line_items);
+        assert_eq!(anti, vec![(2, "b")]);
+
+        assert!(semi_join(orders.clone(), vec![]).is_empty());
+        assert_eq!(anti_join(orders.clone(), vec![]).len(), orders.len());
+    }
+}
@@ -0,0 +1,152 @@
+//! Query 1, 4 and 19 in one graph over one scan of lineitem, see `base::tpch::shared_scan`.
+//!
+//! The scan is fanned out with `tee()` into copies of the subplans of `query_1::query`,
+//! `query_4::query` and `query_19::query`, and each subplan converts the shared rows into the rows
+//! of its query. The copies share the aggregation of the single queries, e.g. `LineItemAgg1::add`.
+//! The rows are shared with `Rc`, so `tee()` copies a pointer and not the strings of every line item.
+
+use std::rc::Rc;
+
+use hydroflow::hydroflow_syntax;
+
+use base::tpch::query_1::{self, LineItemAgg1, LineItemAgg2, Q1Row};
+use base::tpch::query_19::{self, Part, Q19Row};
+use base::tpch::query_4::{self, Order, Q4Row};
+use base::tpch::shared_scan::LineItem;
+
+use super::query_19::answer;
+use super::semi_join::{semi_join_key, semi_join_output};
+
+/// The results of the queries of one graph.
+#[derive(Debug)]
+pub struct Results {
+    pub q1: Vec<Q1Row>,
+    pub q4: Vec<Q4Row>,
+    pub q19: Vec<Q19Row>,
+}
+
+pub fn query(
+    line_items: Vec<LineItem>,
+    orders: Vec<Order>,
+    part: Vec<Part>,
+    params_1: &query_1::QueryParams,
+    params_4: &query_4::QueryParams,
+    params_19: &query_19::QueryParams,
+) -> Results {
+    let shipdate = params_1.shipdate();
+
+    let (q1_send, q1_recv) = hydroflow::util::unbounded_channel::<Q1Row>();
+    let (q4_send, q4_recv) = hydroflow::util::unbounded_channel::<Q4Row>();
+    let (q19_send, q19_recv) = hydroflow::util::unbounded_channel::<f64>();
+
+    let mut flow = hydroflow_syntax! {
+        // Scan lineitem once for all queries.
+        line_items = source_iter(line_items) -> map(Rc::new) -> tee();
+
+        // Query 1
+        // 1. Project lineitem and filter l_shipdate <= date '1998-12-01' - interval DELTA day.
+        line_items
+            -> map(|l: Rc<LineItem>| query_1::LineItem::from(&*l))
+            -> filter(|x| x.l_shipdate <= shipdate)
+            // 2. + 3. Group by l_returnflag, l_linestatus, evaluate the expressions & compute aggregates.
+            -> map(|x: query_1::LineItem| ((x.l_returnflag, x.l_linestatus), x))
+            -> fold_keyed(Default::default, |acc: &mut LineItemAgg1, x: query_1::LineItem| acc.add(&x))
+            -> map(|(key, value)| {
+                let value: LineItemAgg2 = value.into();
+                (key, value)
+            })
+            // 4. Sort by l_returnflag, l_linestatus and collect.
+            -> sort_by_key(|x| &x.0)
+            -> map(|x: ((char, char), LineItemAgg2)| Q1Row::from(x))
+            -> for_each(|x| q1_send.send(x).unwrap());
+
+        // Query 4
+        // 1. Scan orders, filter on o_orderdate.
+        source_iter(orders)
+            -> filter(|order: &Order| params_4.filter(&order.order_date))
+            -> map(|o: Order| (o.order_key, o.order_priority))
+            -> [0]q4_joined;
+
+        // 2. Project lineitem and filter on l_commitdate < l_receiptdate.
+        line_items
+            -> map(|l: Rc<LineItem>| query_4::LineItem::from(&*l))
+            -> filter(|line_item: &query_4::LineItem| line_item.commit_date < line_item.receiptdate)
+            -> map(|l: query_4::LineItem| l.order_key)
+            -> unique()
+            -> map(semi_join_key)
+            -> [1]q4_joined;
+
+        // 3. Semi-join, 4. count per o_orderpriority, 5. sort and collect.
+        q4_joined = join_multiset()
+            -> map(semi_join_output)
+            -> map(|(_, o_orderpriority): (i32, String)| (o_orderpriority, 1i64))
+            -> reduce_keyed(|acc, x| *acc = *acc + x)
+            -> sort_by_key(|x| &x.0)
+            -> map(|x: (String, i64)| Q4Row::from(x))
+            -> for_each(|x| q4_send.send(x).unwrap());
+
+        // Query 19
+        // 1. Scan part, 2. pushed down filter on part.
+        source_iter(part)
+            -> filter(|part: &Part| {
+                Part::filter_1(params_19, &part.p_brand, &part.p_size, &part.p_container)
+                || Part::filter_2(params_19, &part.p_brand, &part.p_size, &part.p_container)
+                || Part::filter_3(params_19, &part.p_brand, &part.p_size, &part.p_container)
+            })
+            -> map(|p: Part| (p.p_partkey, (p.p_brand, p.p_container, p.p_size)))
+            -> [0]q19_joined;
+
+        // 3. Pushed down lineitem filters. The string predicates go first, so only the remaining
+        // line items are projected with their strings.
+        line_items
+            -> filter(|l: &Rc<LineItem>| l.l_shipinstruct == "DELIVER IN PERSON" && (l.l_shipmode == "AIR" || l.l_shipmode == "AIR REG"))
+            -> map(|l: Rc<LineItem>| query_19::LineItem::from(&*l))
+            -> filter(|lineitem: &query_19::LineItem| query_19::LineItem::filter_1(params_19, &lineitem.l_quantity) || query_19::LineItem::filter_2(params_19, &lineitem.l_quantity) || query_19::LineItem::filter_3(params_19, &lineitem.l_quantity))
+            -> map(|l: query_19::LineItem| (l.l_partkey, (l.l_quantity, l.l_discount, l.l_extendedprice)))
+            -> [1]q19_joined;
+
+        // 4. Join on p_partkey = l_partkey, 5. filter again and 6. sum the revenue.
+        q19_joined = join_multiset()
+            -> filter(|(_key, ((p_brand, p_container, p_size), (l_quantity, _l_discount, _l_extendedprice))): &(i64, ((String, String, i32), (f64, f64, f64)))| {
+                (Part::filter_1(params_19, p_brand, p_size, p_container) && query_19::LineItem::filter_1(params_19, l_quantity))
+                || (Part::filter_2(params_19, p_brand, p_size, p_container) && query_19::LineItem::filter_2(params_19, l_quantity))
+                || (Part::filter_3(params_19, p_brand, p_size, p_container) && query_19::LineItem::filter_3(params_19, l_quantity))
+            })
+            -> map(|(_key, (_part, (_l_quantity, l_discount, l_extendedprice)))| l_extendedprice * (1.0 - l_discount))
+            -> reduce(|a, b| *a += b)
+            -> for_each(|x| q19_send.send(x).unwrap());
+    };
+
+    flow.run_available();
+
+    Results {
+        q1: hydroflow::util::collect_ready::<Vec<Q1Row>, _>(q1_recv),
+        q4: hydroflow::util::collect_ready::<Vec<Q4Row>, _>(q4_recv),
+        q19: answer(hydroflow::util::collect_ready::<Vec<f64>, _>(q19_recv)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base::tpch::initialize::initialize_database;
+    use base::tpch::shared_scan::load;
+    use base::tpch::verify::{assert_match, compare, Tolerance};
+
+    use super::*;
+
+    #[test]
+    fn test_query() {
+        let conn = initialize_database(0.01);
+        let params_1 = query_1::QueryParams::default();
+        let params_4 = query_4::QueryParams::default();
+        let params_19 = query_19::QueryParams::default();
+        let (line_items, orders, part) = load(&conn).unwrap();
+
+        let result = query(line_items, orders, part, &params_1, &params_4, &params_19);
+
+        let tolerance = Tolerance::default();
+        assert_match(&[compare("Q1", "duckdb", "hydroflow-shared-scan", &query_1::query_duckdb(&conn, None, &params_1), &result.q1, &tolerance)]);
+        assert_match(&[compare("Q4", "duckdb", "hydroflow-shared-scan", &query_4::query_duckdb(&conn, None, &params_4), &result.q4, &tolerance)]);
+        assert_match(&[compare("Q19", "duckdb", "hydroflow-shared-scan", &query_19::query_duckdb(&conn, None, &params_19), &result.q19, &tolerance)]);
+    }
+}
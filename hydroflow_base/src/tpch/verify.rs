@@ -0,0 +1,646 @@
+use duckdb::Connection;
+
+use base::tpch::answers::{answer_tolerance, expected};
+use base::tpch::decimal::Decimal;
+use base::tpch::parallel::Parallelism;
+use base::tpch::scale_factor::ScaleFactor;
+use base::tpch::verify::{compare, Report, Tolerance};
+use base::tpch::{
+    query_1, query_10, query_12, query_13, query_14, query_16, query_17, query_18, query_19, query_2, query_20,
+    query_21, query_22, query_3, query_4, query_5, query_6,
+};
+
+/**
+ * Run the baseline, the Hydroflow and the DuckDB implementation of a query on the same database
+ * and compare the baseline and Hydroflow results against DuckDB.
+ */
+pub fn verify_query_1(conn: &Connection, params: &query_1::QueryParams) -> Vec<Report<query_1::Q1Row>> {
+    let tolerance = Tolerance::default();
+    let expected = query_1::query_duckdb(conn, None, params);
+    let baseline = query_1::query(query_1::load(conn).expect("Error loading Q1"), params);
+    let baseline_columns = query_1::query_columns::<1024>(query_1::load_columns(conn).expect("Error loading Q1"), params);
+    let baseline_parallel = query_1::query_parallel(query_1::load(conn).expect("Error loading Q1"), params, &Parallelism::default());
+    let hydroflow = super::query_1::query(query_1::load(conn).expect("Error loading Q1"), params);
+    let hydroflow_columns = super::query_1::query_columns::<1024>(query_1::load_columns(conn).expect("Error loading Q1"), params);
+    let hydroflow_partitioned = super::query_1::query_partitioned(query_1::load(conn).expect("Error loading Q1"), params, Parallelism::default().threads);
+    let hydroflow_scan = super::query_1::query_scan(conn, params);
+
+    vec![
+        compare("Q1", "duckdb", "baseline", &expected, &baseline, &tolerance),
+        compare("Q1", "duckdb", "baseline-columns", &expected, &baseline_columns, &tolerance),
+        compare("Q1", "duckdb", "baseline-parallel", &expected, &baseline_parallel, &tolerance),
+        compare("Q1", "duckdb", "hydroflow", &expected, &hydroflow, &tolerance),
+        compare("Q1", "duckdb", "hydroflow-columns", &expected, &hydroflow_columns, &tolerance),
+        compare("Q1", "duckdb", "hydroflow-partitioned", &expected, &hydroflow_partitioned, &tolerance),
+        compare("Q1", "duckdb", "hydroflow-scan", &expected, &hydroflow_scan, &tolerance),
+    ]
+}
+
+/// Same as `verify_query_1` with `Decimal` arithmetic, the sums have to match DuckDB exactly.
+pub fn verify_query_1_decimal(conn: &Connection, params: &query_1::QueryParams) -> Vec<Report<query_1::Q1Row<Decimal>>> {
+    let tolerance = Tolerance::default();
+    let expected = query_1::query_duckdb_decimal(conn, None, params);
+    let baseline = query_1::query(query_1::load_decimal(conn).expect("Error loading Q1"), params);
+    let hydroflow = super::query_1::query_decimal(query_1::load_decimal(conn).expect("Error loading Q1"), params);
+
+    vec![
+        compare("Q1", "duckdb", "baseline-decimal", &expected, &baseline, &tolerance),
+        compare("Q1", "duckdb", "hydroflow-decimal", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn verify_query_2(conn: &Connection) -> Vec<Report<query_2::Q2Row>> {
+    let tolerance = Tolerance::default();
+    let expected = query_2::query_duckdb(conn, None);
+    let (part, supplier, partsupp, nation, region) = query_2::load(conn);
+    let baseline = query_2::query(part, supplier, partsupp, nation, region);
+    let (part, supplier, partsupp, nation, region) = query_2::load(conn);
+    let hydroflow = super::query_2::query(part, supplier, partsupp, nation, region);
+
+    vec![
+        compare("Q2", "duckdb", "baseline", &expected, &baseline, &tolerance),
+        compare("Q2", "duckdb", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn verify_query_3(conn: &Connection) -> Vec<Report<query_3::Q3Row>> {
+    let tolerance = Tolerance::default();
+    let expected = query_3::query_duckdb(conn, None);
+    let (customer, orders, line_items) = query_3::load(conn);
+    let baseline = query_3::query(customer, orders, line_items);
+    let (customer, orders, line_items) = query_3::load(conn);
+    let hydroflow = super::query_3::query(customer, orders, line_items);
+
+    vec![
+        compare("Q3", "duckdb", "baseline", &expected, &baseline, &tolerance),
+        compare("Q3", "duckdb", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn verify_query_4(conn: &Connection, params: &query_4::QueryParams) -> Vec<Report<query_4::Q4Row>> {
+    let tolerance = Tolerance::default();
+    let expected = query_4::query_duckdb(conn, None, params);
+    let (line_items, orders) = query_4::load(conn).expect("Error loading Q4");
+    let baseline = query_4::query(line_items, orders, params);
+    let (line_items, orders) = query_4::load(conn).expect("Error loading Q4");
+    let baseline_parallel = query_4::query_parallel(line_items, orders, params, &Parallelism::default());
+    let (line_items, orders) = query_4::load(conn).expect("Error loading Q4");
+    let baseline_merge_join = query_4::query_merge_join(line_items, orders, params);
+    let (line_items, orders) = query_4::load(conn).expect("Error loading Q4");
+    let baseline_bloom = query_4::query_bloom(line_items, orders, params);
+    let (line_items, orders) = query_4::load(conn).expect("Error loading Q4");
+    let hydroflow = super::query_4::query(line_items, orders, params);
+    let (line_items, orders) = query_4::load(conn).expect("Error loading Q4");
+    let hydroflow_set_join = super::query_4::query_set_join(line_items, orders, params);
+    let (line_items, orders) = query_4::load(conn).expect("Error loading Q4");
+    let hydroflow_merge_join = super::query_4::query_merge_join(line_items, orders, params);
+    let (line_items, orders) = query_4::load(conn).expect("Error loading Q4");
+    let hydroflow_bloom = super::query_4::query_bloom(line_items, orders, params);
+    let (line_items, orders) = query_4::load(conn).expect("Error loading Q4");
+    let hydroflow_partitioned = super::query_4::query_partitioned(line_items, orders, params, Parallelism::default().threads);
+    let hydroflow_scan = super::query_4::query_scan(conn, params);
+
+    vec![
+        compare("Q4", "duckdb", "baseline", &expected, &baseline, &tolerance),
+        compare("Q4", "duckdb", "baseline-parallel", &expected, &baseline_parallel, &tolerance),
+        compare("Q4", "duckdb", "baseline-merge-join", &expected, &baseline_merge_join, &tolerance),
+        compare("Q4", "duckdb", "baseline-bloom", &expected, &baseline_bloom, &tolerance),
+        compare("Q4", "duckdb", "hydroflow", &expected, &hydroflow, &tolerance),
+        compare("Q4", "duckdb", "hydroflow-set-join", &expected, &hydroflow_set_join, &tolerance),
+        compare("Q4", "duckdb", "hydroflow-merge-join", &expected, &hydroflow_merge_join, &tolerance),
+        compare("Q4", "duckdb", "hydroflow-bloom", &expected, &hydroflow_bloom, &tolerance),
+        compare("Q4", "duckdb", "hydroflow-partitioned", &expected, &hydroflow_partitioned, &tolerance),
+        compare("Q4", "duckdb", "hydroflow-scan", &expected, &hydroflow_scan, &tolerance),
+    ]
+}
+
+pub fn verify_query_5(conn: &Connection) -> Vec<Report<query_5::Q5Row>> {
+    let tolerance = Tolerance::default();
+    let expected = query_5::query_duckdb(conn, None);
+    let (customer, orders, line_items, supplier, nation, region) = query_5::load(conn);
+    let baseline = query_5::query(customer, orders, line_items, supplier, nation, region);
+    let (customer, orders, line_items, supplier, nation, region) = query_5::load(conn);
+    let hydroflow = super::query_5::query(customer, orders, line_items, supplier, nation, region);
+
+    vec![
+        compare("Q5", "duckdb", "baseline", &expected, &baseline, &tolerance),
+        compare("Q5", "duckdb", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn verify_query_6(conn: &Connection) -> Vec<Report<query_6::Q6Row>> {
+    let tolerance = Tolerance::default();
+    let expected = query_6::query_duckdb(conn, None);
+    let baseline = query_6::query(query_6::load(conn));
+    let baseline_chunks = query_6::query_chunks::<1024>(query_6::load(conn));
+    let hydroflow = super::query_6::query(query_6::load(conn));
+    let hydroflow_chunks = super::query_6::query_chunks::<1024>(query_6::load(conn));
+
+    vec![
+        compare("Q6", "duckdb", "baseline", &expected, &baseline, &tolerance),
+        compare("Q6", "duckdb", "baseline-chunks", &expected, &baseline_chunks, &tolerance),
+        compare("Q6", "duckdb", "hydroflow", &expected, &hydroflow, &tolerance),
+        compare("Q6", "duckdb", "hydroflow-chunks", &expected, &hydroflow_chunks, &tolerance),
+    ]
+}
+
+pub fn verify_query_10(conn: &Connection) -> Vec<Report<query_10::Q10Row>> {
+    let tolerance = Tolerance::default();
+    let expected = query_10::query_duckdb(conn, None);
+    let (customer, orders, line_items, nation) = query_10::load(conn);
+    let baseline = query_10::query(customer, orders, line_items, nation);
+    let (customer, orders, line_items, nation) = query_10::load(conn);
+    let hydroflow = super::query_10::query(customer, orders, line_items, nation);
+
+    vec![
+        compare("Q10", "duckdb", "baseline", &expected, &baseline, &tolerance),
+        compare("Q10", "duckdb", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn verify_query_12(conn: &Connection) -> Vec<Report<query_12::Q12Row>> {
+    let tolerance = Tolerance::default();
+    let expected = query_12::query_duckdb(conn, None);
+    let (orders, line_items) = query_12::load(conn);
+    let baseline = query_12::query(orders, line_items);
+    let (orders, line_items) = query_12::load(conn);
+    let baseline_chunks = query_12::query_chunks::<1024>(orders, line_items);
+    let (orders, line_items) = query_12::load(conn);
+    let hydroflow = super::query_12::query(orders, line_items);
+    let (orders, line_items) = query_12::load(conn);
+    let hydroflow_chunks = super::query_12::query_chunks::<1024>(orders, line_items);
+
+    vec![
+        compare("Q12", "duckdb", "baseline", &expected, &baseline, &tolerance),
+        compare("Q12", "duckdb", "baseline-chunks", &expected, &baseline_chunks, &tolerance),
+        compare("Q12", "duckdb", "hydroflow", &expected, &hydroflow, &tolerance),
+        compare("Q12", "duckdb", "hydroflow-chunks", &expected, &hydroflow_chunks, &tolerance),
+    ]
+}
+
+pub fn verify_query_13(conn: &Connection) -> Vec<Report<query_13::Q13Row>> {
+    let tolerance = Tolerance::default();
+    let expected = query_13::query_duckdb(conn, None);
+    let (customer, orders) = query_13::load(conn);
+    let baseline = query_13::query(customer, orders);
+    let (customer, orders) = query_13::load(conn);
+    let hydroflow = super::query_13::query(customer, orders);
+
+    vec![
+        compare("Q13", "duckdb", "baseline", &expected, &baseline, &tolerance),
+        compare("Q13", "duckdb", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn verify_query_14(conn: &Connection) -> Vec<Report<query_14::Q14Row>> {
+    let tolerance = Tolerance::default();
+    let expected = query_14::query_duckdb(conn, None);
+    let (line_items, part) = query_14::load(conn);
+    let baseline = query_14::query(line_items, part);
+    let (line_items, part) = query_14::load(conn);
+    let baseline_chunks = query_14::query_chunks::<1024>(line_items, part);
+    let (line_items, part) = query_14::load(conn);
+    let hydroflow = super::query_14::query(line_items, part);
+    let (line_items, part) = query_14::load(conn);
+    let hydroflow_chunks = super::query_14::query_chunks::<1024>(line_items, part);
+
+    vec![
+        compare("Q14", "duckdb", "baseline", &expected, &baseline, &tolerance),
+        compare("Q14", "duckdb", "baseline-chunks", &expected, &baseline_chunks, &tolerance),
+        compare("Q14", "duckdb", "hydroflow", &expected, &hydroflow, &tolerance),
+        compare("Q14", "duckdb", "hydroflow-chunks", &expected, &hydroflow_chunks, &tolerance),
+    ]
+}
+
+pub fn verify_query_16(conn: &Connection) -> Vec<Report<query_16::Q16Row>> {
+    let tolerance = Tolerance::default();
+    let expected = query_16::query_duckdb(conn, None);
+    let (partsupp, part, supplier) = query_16::load(conn);
+    let baseline = query_16::query(partsupp, part, supplier);
+    let (partsupp, part, supplier) = query_16::load(conn);
+    let hydroflow = super::query_16::query(partsupp, part, supplier);
+
+    vec![
+        compare("Q16", "duckdb", "baseline", &expected, &baseline, &tolerance),
+        compare("Q16", "duckdb", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn verify_query_17(conn: &Connection) -> Vec<Report<query_17::Q17Row>> {
+    let tolerance = Tolerance::default();
+    let expected = query_17::query_duckdb(conn, None);
+    let (line_items, part) = query_17::load(conn);
+    let baseline = query_17::query(line_items, part);
+    let (line_items, part) = query_17::load(conn);
+    let hydroflow = super::query_17::query(line_items, part);
+
+    vec![
+        compare("Q17", "duckdb", "baseline", &expected, &baseline, &tolerance),
+        compare("Q17", "duckdb", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn verify_query_18(conn: &Connection) -> Vec<Report<query_18::Q18Row>> {
+    let tolerance = Tolerance::default();
+    let expected = query_18::query_duckdb(conn, None);
+    let (customer, orders, line_items) = query_18::load(conn);
+    let baseline = query_18::query(customer, orders, line_items);
+    let (customer, orders, line_items) = query_18::load(conn);
+    let hydroflow = super::query_18::query(customer, orders, line_items);
+
+    vec![
+        compare("Q18", "duckdb", "baseline", &expected, &baseline, &tolerance),
+        compare("Q18", "duckdb", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn verify_query_19(conn: &Connection, params: &query_19::QueryParams) -> Vec<Report<query_19::Q19Row>> {
+    let tolerance = Tolerance::default();
+    let expected = query_19::query_duckdb(conn, None, params);
+    let (line_items, part) = query_19::load(conn).expect("Error loading Q19");
+    let baseline = query_19::query(line_items, part, params);
+    let (line_items, part) = query_19::load_columns(conn).expect("Error loading Q19");
+    let baseline_columns = query_19::query_columns::<1024>(line_items, part, params);
+    let (line_items, part) = query_19::load(conn).expect("Error loading Q19");
+    let baseline_parallel = query_19::query_parallel(line_items, part, params, &Parallelism::default());
+    let (line_items, part) = query_19::load(conn).expect("Error loading Q19");
+    let baseline_merge_join = query_19::query_merge_join(line_items, part, params);
+    let (line_items, part) = query_19::load(conn).expect("Error loading Q19");
+    let baseline_bloom = query_19::query_bloom(line_items, part, params);
+    let (line_items, part) = query_19::load(conn).expect("Error loading Q19");
+    let hydroflow = super::query_19::query(line_items, part, params);
+    let (line_items, part) = query_19::load_columns(conn).expect("Error loading Q19");
+    let hydroflow_columns = super::query_19::query_columns::<1024>(line_items, part, params);
+    let (line_items, part) = query_19::load(conn).expect("Error loading Q19");
+    let hydroflow_partitioned = super::query_19::query_partitioned(line_items, part, params, Parallelism::default().threads);
+    let (line_items, part) = query_19::load(conn).expect("Error loading Q19");
+    let hydroflow_merge_join = super::query_19::query_merge_join(line_items, part, params);
+    let (line_items, part) = query_19::load(conn).expect("Error loading Q19");
+    let hydroflow_bloom = super::query_19::query_bloom(line_items, part, params);
+    let hydroflow_scan = super::query_19::query_scan(conn, params);
+
+    vec![
+        compare("Q19", "duckdb", "baseline", &expected, &baseline, &tolerance),
+        compare("Q19", "duckdb", "baseline-columns", &expected, &baseline_columns, &tolerance),
+        compare("Q19", "duckdb", "baseline-parallel", &expected, &baseline_parallel, &tolerance),
+        compare("Q19", "duckdb", "baseline-merge-join", &expected, &baseline_merge_join, &tolerance),
+        compare("Q19", "duckdb", "baseline-bloom", &expected, &baseline_bloom, &tolerance),
+        compare("Q19", "duckdb", "hydroflow", &expected, &hydroflow, &tolerance),
+        compare("Q19", "duckdb", "hydroflow-columns", &expected, &hydroflow_columns, &tolerance),
+        compare("Q19", "duckdb", "hydroflow-partitioned", &expected, &hydroflow_partitioned, &tolerance),
+        compare("Q19", "duckdb", "hydroflow-merge-join", &expected, &hydroflow_merge_join, &tolerance),
+        compare("Q19", "duckdb", "hydroflow-bloom", &expected, &hydroflow_bloom, &tolerance),
+        compare("Q19", "duckdb", "hydroflow-scan", &expected, &hydroflow_scan, &tolerance),
+    ]
+}
+
+/// Same as `verify_query_19` with `Decimal` arithmetic, the revenue has to match DuckDB exactly.
+pub fn verify_query_19_decimal(conn: &Connection, params: &query_19::QueryParams) -> Vec<Report<query_19::Q19Row<Decimal>>> {
+    let tolerance = Tolerance::default();
+    let expected = query_19::query_duckdb_decimal(conn, None, params);
+    let (line_items, part) = query_19::load_decimal(conn).expect("Error loading Q19");
+    let baseline = query_19::query(line_items, part, params);
+    let (line_items, part) = query_19::load_decimal(conn).expect("Error loading Q19");
+    let hydroflow = super::query_19::query_decimal(line_items, part, params);
+
+    vec![
+        compare("Q19", "duckdb", "baseline-decimal", &expected, &baseline, &tolerance),
+        compare("Q19", "duckdb", "hydroflow-decimal", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn verify_query_20(conn: &Connection) -> Vec<Report<query_20::Q20Row>> {
+    let tolerance = Tolerance::default();
+    let expected = query_20::query_duckdb(conn, None);
+    let (supplier, nation, partsupp, part, line_items) = query_20::load(conn);
+    let baseline = query_20::query(supplier, nation, partsupp, part, line_items);
+    let (supplier, nation, partsupp, part, line_items) = query_20::load(conn);
+    let hydroflow = super::query_20::query(supplier, nation, partsupp, part, line_items);
+
+    vec![
+        compare("Q20", "duckdb", "baseline", &expected, &baseline, &tolerance),
+        compare("Q20", "duckdb", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn verify_query_21(conn: &Connection) -> Vec<Report<query_21::Q21Row>> {
+    let tolerance = Tolerance::default();
+    let expected = query_21::query_duckdb(conn, None);
+    let (supplier, line_items, orders, nation) = query_21::load(conn);
+    let baseline = query_21::query(supplier, line_items, orders, nation);
+    let (supplier, line_items, orders, nation) = query_21::load(conn);
+    let hydroflow = super::query_21::query(supplier, line_items, orders, nation);
+
+    vec![
+        compare("Q21", "duckdb", "baseline", &expected, &baseline, &tolerance),
+        compare("Q21", "duckdb", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn verify_query_22(conn: &Connection) -> Vec<Report<query_22::Q22Row>> {
+    let tolerance = Tolerance::default();
+    let expected = query_22::query_duckdb(conn, None);
+    let (customer, orders) = query_22::load(conn);
+    let baseline = query_22::query(customer, orders);
+    let (customer, orders) = query_22::load(conn);
+    let hydroflow = super::query_22::query(customer, orders);
+
+    vec![
+        compare("Q22", "duckdb", "baseline", &expected, &baseline, &tolerance),
+        compare("Q22", "duckdb", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+/**
+ * Check the baseline and the Hydroflow implementation against DuckDB's reference answers.
+ */
+pub fn validate_query_1(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_1::Q1Row>> {
+    let tolerance = answer_tolerance();
+    let expected = expected(conn, 1, scale_factor);
+    let params = query_1::QueryParams::default();
+    let baseline = query_1::query(query_1::load(conn).expect("Error loading Q1"), &params);
+    let hydroflow = super::query_1::query(query_1::load(conn).expect("Error loading Q1"), &params);
+
+    vec![
+        compare("Q1", "tpch_answers", "baseline", &expected, &baseline, &tolerance),
+        compare("Q1", "tpch_answers", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn validate_query_2(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_2::Q2Row>> {
+    let tolerance = answer_tolerance();
+    let expected = expected(conn, 2, scale_factor);
+    let (part, supplier, partsupp, nation, region) = query_2::load(conn);
+    let baseline = query_2::query(part, supplier, partsupp, nation, region);
+    let (part, supplier, partsupp, nation, region) = query_2::load(conn);
+    let hydroflow = super::query_2::query(part, supplier, partsupp, nation, region);
+
+    vec![
+        compare("Q2", "tpch_answers", "baseline", &expected, &baseline, &tolerance),
+        compare("Q2", "tpch_answers", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn validate_query_3(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_3::Q3Row>> {
+    let tolerance = answer_tolerance();
+    let expected = expected(conn, 3, scale_factor);
+    let (customer, orders, line_items) = query_3::load(conn);
+    let baseline = query_3::query(customer, orders, line_items);
+    let (customer, orders, line_items) = query_3::load(conn);
+    let hydroflow = super::query_3::query(customer, orders, line_items);
+
+    vec![
+        compare("Q3", "tpch_answers", "baseline", &expected, &baseline, &tolerance),
+        compare("Q3", "tpch_answers", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn validate_query_4(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_4::Q4Row>> {
+    let tolerance = answer_tolerance();
+    let expected = expected(conn, 4, scale_factor);
+    let params = query_4::QueryParams::default();
+    let (line_items, orders) = query_4::load(conn).expect("Error loading Q4");
+    let baseline = query_4::query(line_items, orders, &params);
+    let (line_items, orders) = query_4::load(conn).expect("Error loading Q4");
+    let hydroflow = super::query_4::query(line_items, orders, &params);
+
+    vec![
+        compare("Q4", "tpch_answers", "baseline", &expected, &baseline, &tolerance),
+        compare("Q4", "tpch_answers", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn validate_query_5(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_5::Q5Row>> {
+    let tolerance = answer_tolerance();
+    let expected = expected(conn, 5, scale_factor);
+    let (customer, orders, line_items, supplier, nation, region) = query_5::load(conn);
+    let baseline = query_5::query(customer, orders, line_items, supplier, nation, region);
+    let (customer, orders, line_items, supplier, nation, region) = query_5::load(conn);
+    let hydroflow = super::query_5::query(customer, orders, line_items, supplier, nation, region);
+
+    vec![
+        compare("Q5", "tpch_answers", "baseline", &expected, &baseline, &tolerance),
+        compare("Q5", "tpch_answers", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn validate_query_6(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_6::Q6Row>> {
+    let tolerance = answer_tolerance();
+    let expected = expected(conn, 6, scale_factor);
+    let baseline = query_6::query(query_6::load(conn));
+    let hydroflow = super::query_6::query(query_6::load(conn));
+
+    vec![
+        compare("Q6", "tpch_answers", "baseline", &expected, &baseline, &tolerance),
+        compare("Q6", "tpch_answers", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn validate_query_10(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_10::Q10Row>> {
+    let tolerance = answer_tolerance();
+    let expected = expected(conn, 10, scale_factor);
+    let (customer, orders, line_items, nation) = query_10::load(conn);
+    let baseline = query_10::query(customer, orders, line_items, nation);
+    let (customer, orders, line_items, nation) = query_10::load(conn);
+    let hydroflow = super::query_10::query(customer, orders, line_items, nation);
+
+    vec![
+        compare("Q10", "tpch_answers", "baseline", &expected, &baseline, &tolerance),
+        compare("Q10", "tpch_answers", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn validate_query_12(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_12::Q12Row>> {
+    let tolerance = answer_tolerance();
+    let expected = expected(conn, 12, scale_factor);
+    let (orders, line_items) = query_12::load(conn);
+    let baseline = query_12::query(orders, line_items);
+    let (orders, line_items) = query_12::load(conn);
+    let hydroflow = super::query_12::query(orders, line_items);
+
+    vec![
+        compare("Q12", "tpch_answers", "baseline", &expected, &baseline, &tolerance),
+        compare("Q12", "tpch_answers", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn validate_query_13(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_13::Q13Row>> {
+    let tolerance = answer_tolerance();
+    let expected = expected(conn, 13, scale_factor);
+    let (customer, orders) = query_13::load(conn);
+    let baseline = query_13::query(customer, orders);
+    let (customer, orders) = query_13::load(conn);
+    let hydroflow = super::query_13::query(customer, orders);
+
+    vec![
+        compare("Q13", "tpch_answers", "baseline", &expected, &baseline, &tolerance),
+        compare("Q13", "tpch_answers", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn validate_query_14(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_14::Q14Row>> {
+    let tolerance = answer_tolerance();
+    let expected = expected(conn, 14, scale_factor);
+    let (line_items, part) = query_14::load(conn);
+    let baseline = query_14::query(line_items, part);
+    let (line_items, part) = query_14::load(conn);
+    let hydroflow = super::query_14::query(line_items, part);
+
+    vec![
+        compare("Q14", "tpch_answers", "baseline", &expected, &baseline, &tolerance),
+        compare("Q14", "tpch_answers", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn validate_query_16(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_16::Q16Row>> {
+    let tolerance = answer_tolerance();
+    let expected = expected(conn, 16, scale_factor);
+    let (partsupp, part, supplier) = query_16::load(conn);
+    let baseline = query_16::query(partsupp, part, supplier);
+    let (partsupp, part, supplier) = query_16::load(conn);
+    let hydroflow = super::query_16::query(partsupp, part, supplier);
+
+    vec![
+        compare("Q16", "tpch_answers", "baseline", &expected, &baseline, &tolerance),
+        compare("Q16", "tpch_answers", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn validate_query_17(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_17::Q17Row>> {
+    let tolerance = answer_tolerance();
+    let expected = expected(conn, 17, scale_factor);
+    let (line_items, part) = query_17::load(conn);
+    let baseline = query_17::query(line_items, part);
+    let (line_items, part) = query_17::load(conn);
+    let hydroflow = super::query_17::query(line_items, part);
+
+    vec![
+        compare("Q17", "tpch_answers", "baseline", &expected, &baseline, &tolerance),
+        compare("Q17", "tpch_answers", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn validate_query_18(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_18::Q18Row>> {
+    let tolerance = answer_tolerance();
+    let expected = expected(conn, 18, scale_factor);
+    let (customer, orders, line_items) = query_18::load(conn);
+    let baseline = query_18::query(customer, orders, line_items);
+    let (customer, orders, line_items) = query_18::load(conn);
+    let hydroflow = super::query_18::query(customer, orders, line_items);
+
+    vec![
+        compare("Q18", "tpch_answers", "baseline", &expected, &baseline, &tolerance),
+        compare("Q18", "tpch_answers", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn validate_query_19(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_19::Q19Row>> {
+    let tolerance = answer_tolerance();
+    let expected = expected(conn, 19, scale_factor);
+    let params = query_19::QueryParams::default();
+    let (line_items, part) = query_19::load(conn).expect("Error loading Q19");
+    let baseline = query_19::query(line_items, part, &params);
+    let (line_items, part) = query_19::load(conn).expect("Error loading Q19");
+    let hydroflow = super::query_19::query(line_items, part, &params);
+
+    vec![
+        compare("Q19", "tpch_answers", "baseline", &expected, &baseline, &tolerance),
+        compare("Q19", "tpch_answers", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn validate_query_20(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_20::Q20Row>> {
+    let tolerance = answer_tolerance();
+    let expected = expected(conn, 20, scale_factor);
+    let (supplier, nation, partsupp, part, line_items) = query_20::load(conn);
+    let baseline = query_20::query(supplier, nation, partsupp, part, line_items);
+    let (supplier, nation, partsupp, part, line_items) = query_20::load(conn);
+    let hydroflow = super::query_20::query(supplier, nation, partsupp, part, line_items);
+
+    vec![
+        compare("Q20", "tpch_answers", "baseline", &expected, &baseline, &tolerance),
+        compare("Q20", "tpch_answers", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn validate_query_21(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_21::Q21Row>> {
+    let tolerance = answer_tolerance();
+    let expected = expected(conn, 21, scale_factor);
+    let (supplier, line_items, orders, nation) = query_21::load(conn);
+    let baseline = query_21::query(supplier, line_items, orders, nation);
+    let (supplier, line_items, orders, nation) = query_21::load(conn);
+    let hydroflow = super::query_21::query(supplier, line_items, orders, nation);
+
+    vec![
+        compare("Q21", "tpch_answers", "baseline", &expected, &baseline, &tolerance),
+        compare("Q21", "tpch_answers", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+pub fn validate_query_22(conn: &Connection, scale_factor: impl Into<ScaleFactor>) -> Vec<Report<query_22::Q22Row>> {
+    let tolerance = answer_tolerance();
+    let expected = expected(conn, 22, scale_factor);
+    let (customer, orders) = query_22::load(conn);
+    let baseline = query_22::query(customer, orders);
+    let (customer, orders) = query_22::load(conn);
+    let hydroflow = super::query_22::query(customer, orders);
+
+    vec![
+        compare("Q22", "tpch_answers", "baseline", &expected, &baseline, &tolerance),
+        compare("Q22", "tpch_answers", "hydroflow", &expected, &hydroflow, &tolerance),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base::tpch::initialize::initialize_database;
+    use base::tpch::verify::assert_match;
+
+    #[test]
+    fn test_verify() {
+        let conn = initialize_database(0.01);
+        assert_match(&verify_query_1(&conn, &query_1::QueryParams::default()));
+        assert_match(&verify_query_1_decimal(&conn, &query_1::QueryParams::default()));
+        assert_match(&verify_query_2(&conn));
+        assert_match(&verify_query_3(&conn));
+        assert_match(&verify_query_4(&conn, &query_4::QueryParams::default()));
+        assert_match(&verify_query_5(&conn));
+        assert_match(&verify_query_6(&conn));
+        assert_match(&verify_query_10(&conn));
+        assert_match(&verify_query_12(&conn));
+        assert_match(&verify_query_13(&conn));
+        assert_match(&verify_query_14(&conn));
+        assert_match(&verify_query_16(&conn));
+        assert_match(&verify_query_17(&conn));
+        assert_match(&verify_query_18(&conn));
+        assert_match(&verify_query_19(&conn, &query_19::QueryParams::default()));
+        assert_match(&verify_query_19_decimal(&conn, &query_19::QueryParams::default()));
+        assert_match(&verify_query_20(&conn));
+        assert_match(&verify_query_21(&conn));
+        assert_match(&verify_query_22(&conn));
+    }
+
+    #[test]
+    fn test_validate() {
+        let scale_factor = 1;
+        let conn = initialize_database(scale_factor);
+        assert_match(&validate_query_1(&conn, scale_factor));
+        assert_match(&validate_query_2(&conn, scale_factor));
+        assert_match(&validate_query_3(&conn, scale_factor));
+        assert_match(&validate_query_4(&conn, scale_factor));
+        assert_match(&validate_query_5(&conn, scale_factor));
+        assert_match(&validate_query_6(&conn, scale_factor));
+        assert_match(&validate_query_10(&conn, scale_factor));
+        assert_match(&validate_query_12(&conn, scale_factor));
+        assert_match(&validate_query_13(&conn, scale_factor));
+        assert_match(&validate_query_14(&conn, scale_factor));
+        assert_match(&validate_query_16(&conn, scale_factor));
+        assert_match(&validate_query_17(&conn, scale_factor));
+        assert_match(&validate_query_18(&conn, scale_factor));
+        assert_match(&validate_query_19(&conn, scale_factor));
+        assert_match(&validate_query_20(&conn, scale_factor));
+        assert_match(&validate_query_21(&conn, scale_factor));
+        assert_match(&validate_query_22(&conn, scale_factor));
+    }
+}